@@ -0,0 +1,71 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Allocations of the parser benchmarks
+//!
+//! Run with `cargo bench --bench allocations`. Each input of `benches/parse.rs`
+//! is parsed or lexed once, and the number of allocations and the allocated bytes
+//! are reported, which do not vary between runs as the time does.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use cqlparser::{Lexer, Parser};
+
+mod inputs;
+
+// Allocator that counts allocations
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn report(name: &str, cql: &str, run: impl Fn(&str)) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    run(black_box(cql));
+    println!(
+        "{:<30} {:>10} allocations  {:>10} KiB allocated  ({} KiB input)",
+        name,
+        ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        (ALLOCATED_BYTES.load(Ordering::Relaxed) - allocated_bytes) / 1024,
+        cql.len() / 1024
+    );
+}
+
+fn main() {
+    for (name, cql) in inputs::parser_inputs() {
+        report(&format!("parse/{}", name), &cql, |cql| {
+            black_box(Parser::new(cql).parse().unwrap());
+        });
+    }
+    for (name, cql) in inputs::lexer_inputs() {
+        report(&format!("lex/{}", name), &cql, |cql| {
+            black_box(Lexer::new(cql).count());
+        });
+    }
+}
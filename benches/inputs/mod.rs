@@ -0,0 +1,113 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Inputs shared by the benchmarks
+
+// Schema dump with many tables, types, indexes and views
+pub fn large_schema() -> String {
+    let mut cql = String::new();
+    for i in 0..2000 {
+        cql.push_str(&format!(
+            "CREATE TYPE IF NOT EXISTS ks{i}.address (street text, city text, zip_code int, phones set<text>);
+CREATE TABLE IF NOT EXISTS ks{i}.users (
+    id uuid,
+    bucket int,
+    name text,
+    \"Email\" text,
+    addresses map<text, frozen<address>>,
+    tags set<text>,
+    scores list<double>,
+    created_at timestamp STATIC,
+    PRIMARY KEY ((id, bucket), name)
+) WITH CLUSTERING ORDER BY (name DESC)
+    AND comment = 'users table {i}'
+    AND gc_grace_seconds = 864000
+    AND compaction = {{'class': 'SizeTieredCompactionStrategy', 'max_threshold': '32'}};
+CREATE INDEX IF NOT EXISTS users_tags_{i} ON ks{i}.users (VALUES(tags));
+CREATE MATERIALIZED VIEW IF NOT EXISTS ks{i}.users_by_name AS
+    SELECT * FROM ks{i}.users
+    WHERE name IS NOT NULL AND id IS NOT NULL AND bucket IS NOT NULL
+    PRIMARY KEY (name, id, bucket);
+",
+            i = i
+        ));
+    }
+    cql
+}
+
+// Many small DML statements
+pub fn small_statements() -> String {
+    let mut cql = String::new();
+    for i in 0..20000 {
+        cql.push_str(&format!(
+            "SELECT id, name, writetime(name) FROM ks.users WHERE id = {i} AND bucket IN (1, 2) LIMIT 10;
+INSERT INTO ks.users (id, bucket, name) VALUES (?, {i}, 'user{i}') USING TTL 86400;
+UPDATE ks.users SET name = :name, scores = scores + 1 WHERE id = {i} IF EXISTS;
+",
+            i = i
+        ));
+    }
+    cql
+}
+
+// Deeply nested and very long expressions
+pub fn pathological_expressions() -> String {
+    let depth = 100;
+    let nested = format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+    let long = vec!["a"; 10000].join(" + ");
+    let mut cql = String::new();
+    for _ in 0..100 {
+        cql.push_str(&format!("SELECT {} FROM t WHERE k = {};\n", long, nested));
+    }
+    cql
+}
+
+// Generated queries with a long IN list, and an INSERT of a long list literal
+pub fn long_lists() -> String {
+    let values: Vec<String> = (0..100_000).map(|i| i.to_string()).collect();
+    let values = values.join(", ");
+    format!(
+        "SELECT * FROM ks.users WHERE id IN ({});\nINSERT INTO ks.users (id, scores) VALUES (1, [{}]);\n",
+        values, values
+    )
+}
+
+// Values of every kind of literal, and identifiers starting with hex digits or `P`
+// that are lexed as UUIDs or durations up to some point
+pub fn literals() -> String {
+    let mut cql = String::new();
+    for i in 0..20000 {
+        cql.push_str(&format!(
+            "INSERT INTO ks.events (id, added_by, deadline, period, elapsed, flags, payload, ratio)
+    VALUES (67e55044-10b1-426f-9247-bb680e5fe0c8, bad_cafe_{i}, P0001-02-03T04:05:06, P1Y2M3DT4H,
+    1h30m{i}s, 0xcafe{i}, $$payload {i}$$, {i}.5e-3);
+",
+            i = i
+        ));
+    }
+    cql
+}
+
+/// Inputs of the parser benchmarks, by name
+pub fn parser_inputs() -> Vec<(&'static str, String)> {
+    vec![
+        ("large_schema", large_schema()),
+        ("small_statements", small_statements()),
+        ("pathological_expressions", pathological_expressions()),
+        ("long_lists", long_lists()),
+    ]
+}
+
+/// Inputs of the lexer benchmarks, by name
+pub fn lexer_inputs() -> Vec<(&'static str, String)> {
+    vec![("large_schema", large_schema()), ("literals", literals())]
+}
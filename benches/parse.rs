@@ -0,0 +1,59 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parser benchmarks
+//!
+//! Run with `cargo bench --bench parse`, or `cargo bench --bench parse -- <filter>`
+//! to run benchmarks whose names match the regular expression `<filter>`, such as `lex/`.
+//! Allocations per input are reported by `cargo bench --bench allocations`.
+use std::hint::black_box;
+use std::time::Duration;
+
+use criterion::measurement::WallTime;
+use criterion::{criterion_group, criterion_main, BenchmarkGroup, Criterion, Throughput};
+
+use cqlparser::{Lexer, Parser};
+
+mod inputs;
+
+// Parsing the largest inputs takes hundreds of milliseconds,
+// so fewer samples than the default are taken
+fn configure(group: &mut BenchmarkGroup<'_, WallTime>) {
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+}
+
+fn bench_parser(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    configure(&mut group);
+    for (name, cql) in inputs::parser_inputs() {
+        group.throughput(Throughput::Bytes(cql.len() as u64));
+        group.bench_function(name, |b| {
+            b.iter(|| Parser::new(black_box(&cql)).parse().unwrap())
+        });
+    }
+    group.finish();
+}
+
+// Lexes the whole input without parsing
+fn bench_lexer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lex");
+    configure(&mut group);
+    for (name, cql) in inputs::lexer_inputs() {
+        group.throughput(Throughput::Bytes(cql.len() as u64));
+        group.bench_function(name, |b| b.iter(|| Lexer::new(black_box(&cql)).count()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parser, bench_lexer);
+criterion_main!(benches);
@@ -10,27 +10,175 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use cqlparser::Parser;
-use std::io::{self, Result, Write};
+//! Interactive CQL parser
+//!
+//! Reads CQL from stdin until `;` that terminates the statement, and prints
+//! the result in the current output mode. Lines are read further while the input
+//! ends in the middle of a statement, such as after `;` in `BEGIN BATCH`.
+//! Commands change the output mode:
+//!
+//! - `:ast` prints the AST (default)
+//! - `:tokens` prints the tokens with their positions
+//! - `:format` prints the formatted CQL
+//! - `:lint` prints problems found by the built-in lints
+//! - `:quit` or `exit` quits
+//!
+//! CQL following a command, such as `:format select * from t`, is printed
+//! in the mode right away without waiting for `;`.
+
+use std::io::{self, BufRead, Result, Write};
+
+use cqlparser::lints::Linter;
+use cqlparser::{
+    format_statement, split_statements, FormatOptions, Lexer, ParseError, Parser, Position,
+    TokenType,
+};
+
+#[derive(Clone, Copy)]
+enum Mode {
+    Ast,
+    Tokens,
+    Format,
+    Lint,
+}
 
 pub fn main() -> Result<()> {
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut mode = Mode::Ast;
+    // lines of the statement not terminated by `;` yet
+    let mut buffer = String::new();
     loop {
-        print!("cql> ");
-        let _ = io::stdout().flush();
-
-        let stdin = io::stdin();
-        let mut raw_input = String::new();
-        match stdin.read_line(&mut raw_input) {
-            Ok(_) => {
-                let input = str::trim(&raw_input);
-                if input.eq_ignore_ascii_case("exit") {
-                    break;
+        print!("{}", if buffer.is_empty() { "cql> " } else { "...> " });
+        io::stdout().flush()?;
+
+        let line = match lines.next() {
+            Some(line) => line?,
+            // end of input
+            None => break,
+        };
+        if buffer.is_empty() {
+            let input = line.trim();
+            if input == ":quit" || input.eq_ignore_ascii_case("exit") {
+                break;
+            }
+            if let Some(command) = input.strip_prefix(':') {
+                let (name, cql) = command.split_once(' ').unwrap_or((command, ""));
+                mode = match name {
+                    "ast" => Mode::Ast,
+                    "tokens" => Mode::Tokens,
+                    "format" => Mode::Format,
+                    "lint" => Mode::Lint,
+                    _ => {
+                        println!("unknown command: {}", input);
+                        continue;
+                    }
+                };
+                if !cql.trim().is_empty() {
+                    print_result(cql, mode);
                 }
-                let p = Parser::new(input);
-                println!("{:?}", p.parse());
+                continue;
             }
-            Err(error) => println!("error: {}", error),
+        }
+        buffer.push_str(&line);
+        buffer.push('\n');
+        if is_terminated(&buffer) && !is_incomplete(&buffer) {
+            print_result(&buffer, mode);
+            buffer.clear();
+        } else if split_statements(&buffer).is_empty() {
+            // only whitespaces and comments
+            buffer.clear();
         }
     }
     Ok(())
 }
+
+// Returns true if the last statement in `cql` is followed by `;`
+fn is_terminated(cql: &str) -> bool {
+    let last = match split_statements(cql).last() {
+        Some(last) => *last,
+        None => return false,
+    };
+    // statements are slices of `cql`
+    let end = last.as_ptr() as usize - cql.as_ptr() as usize + last.len();
+    Lexer::new(&cql[end..]).any(|(_, t)| t.token_type == TokenType::SemiColon)
+}
+
+// Returns true if `cql` ends in the middle of a statement
+fn is_incomplete(cql: &str) -> bool {
+    matches!(Parser::new(cql).parse(), Err(error) if error.is_incomplete_input())
+}
+
+// Prints each statement in `cql` in given mode, stopping at the first error
+fn print_result(cql: &str, mode: Mode) {
+    if let Mode::Tokens = mode {
+        for (s, token) in Lexer::new(cql) {
+            if !matches!(token.token_type, TokenType::Whitespace) {
+                println!(
+                    "{}:{} {}..{} {:?} {:?}",
+                    token.line,
+                    token.column,
+                    token.offset,
+                    token.offset + token.length,
+                    token.token_type,
+                    s
+                );
+            }
+        }
+        return;
+    }
+    let mut rest = cql;
+    while !split_statements(rest).is_empty() {
+        let (statement, remaining) = match Parser::parse_single(rest) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                // positions of the error are relative to `rest`
+                let offset = rest.as_ptr() as usize - cql.as_ptr() as usize;
+                print_error(cql, offset, &error);
+                return;
+            }
+        };
+        match mode {
+            Mode::Format => match format_statement(&statement, &FormatOptions::default()) {
+                Some(formatted) => println!("{};", formatted),
+                None => println!("{:#?}", statement),
+            },
+            Mode::Lint => {
+                for diagnostic in Linter::with_default_lints().lint_statement(&statement) {
+                    println!("{}", diagnostic);
+                }
+            }
+            _ => println!("{:#?}", statement),
+        }
+        rest = remaining;
+    }
+}
+
+// Prints `error` in the statement starting at `offset` of `cql`,
+// with the line of the error and a caret under the column
+fn print_error(cql: &str, offset: usize, error: &ParseError) {
+    let position = match error.offset() {
+        Some(error_offset) => Position::from_offset(cql, offset + error_offset),
+        None => {
+            println!("error: {}", error);
+            return;
+        }
+    };
+    // replace the position in the message, which is relative to the statement
+    let message = error.to_string();
+    let relative = format!(
+        "{}:{}: ",
+        error.line().unwrap_or(0),
+        error.column().unwrap_or(0)
+    );
+    let message = message.strip_prefix(&relative).unwrap_or(&message);
+    // the message is followed by the context of the error, if any
+    let (message, context) = message.split_once('\n').unwrap_or((message, ""));
+    println!("error: {}:{}: {}", position.line, position.column, message);
+    let line = cql.lines().nth(position.line - 1).unwrap_or("");
+    println!("  {}", line);
+    println!("  {}^", " ".repeat(position.column - 1));
+    if !context.is_empty() {
+        println!("{}", context);
+    }
+}
@@ -10,48 +10,149 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+//! Parses schema files output from `desc keyspace` command
+//!
+//! Given a file, dumps parsed AST of the statements.
+//! Given a directory, prints JSON summary of the `.cql` files in the directory:
+//!
+//! ```json
+//! {
+//!   "files": [
+//!     {
+//!       "path": "schemas/users.cql",
+//!       "statements": 2,
+//!       "errors": [{"line": 3, "column": 8, "message": "..."}],
+//!       "tables": ["ks.users"]
+//!     }
+//!   ],
+//!   "errors": 1
+//! }
+//! ```
+//!
+//! Exits with status 1 when any file has errors, so that it can validate
+//! schema repositories in CI pipelines.
+
 use std::{
-    env,
-    fs::File,
-    io::{Read, Result},
-    path::Path,
+    env, fs,
+    io::Result,
+    path::{Path, PathBuf},
+    process,
 };
 
-use cqlparser::Parser;
+use cqlparser::{ast::CqlStatement, ParseError, Parser};
 
-/// Dumps parsed AST from the schema file output from `desc keyspace` command.
 pub fn main() -> Result<()> {
     let args: Vec<String> = env::args().collect();
+    if args.len() != 2 {
+        println!("usage: {} <path to schema cql file or directory>", args[0]);
+        process::exit(2);
+    }
 
-    match args.len() {
-        // no arguments passed
-        1 => {
-            println!("usage: {} <path to schema cql file>", args[0]);
-        }
-        // one argument passed
-        2 => {
-            // Create a path to the desired file
-            let path = Path::new(&args[1]);
-
-            // Open the path in read-only mode, returns `io::Result<File>`
-            let mut file = File::open(&path)?;
-
-            // Read the file contents into a string, returns `io::Result<usize>`
-            let mut s = String::new();
-            file.read_to_string(&mut s)?;
-            let parser = Parser::new(&s);
-            match parser.parse() {
-                Ok(stmts) => {
-                    for stmt in stmts.into_iter() {
-                        println!("{:?}", stmt);
-                    }
-                }
-                Err(e) => println!("Error: {:?}", e),
+    let path = Path::new(&args[1]);
+    let has_errors = if path.is_dir() {
+        summarize_directory(path)?
+    } else {
+        dump_file(path)?
+    };
+    if has_errors {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+// Dumps AST of the statements in the file, and returns true if parsing failed
+fn dump_file(path: &Path) -> Result<bool> {
+    let s = fs::read_to_string(path)?;
+    // Errors are reported with the path, such as `schema.cql:12:8: expected ...`
+    let parser = Parser::new(&s).with_source_name(path.display().to_string());
+    match parser.parse() {
+        Ok(stmts) => {
+            for stmt in stmts.into_iter() {
+                println!("{:?}", stmt);
             }
+            Ok(false)
         }
-        _ => {
-            println!("usage: {} <path to schema cql file>", args[0]);
+        Err(e) => {
+            println!("Error: {}", e);
+            Ok(true)
         }
     }
-    Ok(())
+}
+
+// Prints JSON summary of `.cql` files in the directory,
+// and returns true if any file has errors
+fn summarize_directory(dir: &Path) -> Result<bool> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<_>>()?;
+    paths.retain(|p| p.is_file() && p.extension() == Some("cql".as_ref()));
+    paths.sort();
+
+    let mut files = Vec::new();
+    let mut error_count = 0;
+    for path in paths {
+        let s = fs::read_to_string(&path)?;
+        // keep parsing after errors to report all of them
+        let (statements, errors) = Parser::new(&s).parse_lenient();
+        error_count += errors.len();
+        files.push(file_summary(&path, &statements, &errors));
+    }
+    println!("{{");
+    println!("  \"files\": [{}\n  ],", files.join(","));
+    println!("  \"errors\": {}", error_count);
+    println!("}}");
+    Ok(error_count > 0)
+}
+
+fn file_summary(path: &Path, statements: &[CqlStatement], errors: &[ParseError]) -> String {
+    let errors: Vec<String> = errors
+        .iter()
+        .map(|e| {
+            format!(
+                "{{\"line\": {}, \"column\": {}, \"message\": {}}}",
+                e.line().unwrap_or(0),
+                e.column().unwrap_or(0),
+                json_string(e.message())
+            )
+        })
+        .collect();
+    let tables: Vec<String> = statements
+        .iter()
+        .filter_map(|statement| match statement {
+            CqlStatement::CreateTable(table) => Some(json_string(&table.name.to_string())),
+            _ => None,
+        })
+        .collect();
+    format!(
+        "
+    {{
+      \"path\": {},
+      \"statements\": {},
+      \"errors\": [{}],
+      \"tables\": [{}]
+    }}",
+        json_string(&path.display().to_string()),
+        statements.len(),
+        errors.join(", "),
+        tables.join(", ")
+    )
+}
+
+// Quotes `s` as JSON string
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
 }
@@ -0,0 +1,50 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Feeds arbitrary UTF-8 to the parser. Any input must produce either
+//! statements or errors, never a panic.
+
+#![no_main]
+
+use cqlparser::ast::visit::{walk_literal, Visitor};
+use cqlparser::ast::Literal;
+use cqlparser::Parser;
+use libfuzzer_sys::fuzz_target;
+
+/// Exercises the constant accessors, which parse the literal text again
+struct ConstantChecker;
+
+impl Visitor for ConstantChecker {
+    fn visit_literal(&mut self, literal: &Literal) {
+        if let Literal::Constant(constant) = literal {
+            let _ = constant.as_f64();
+            let _ = constant.as_i64();
+            let _ = constant.as_uuid_bytes();
+            let _ = constant.as_duration();
+        }
+        walk_literal(self, literal);
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let cql = match std::str::from_utf8(data) {
+        Ok(cql) => cql,
+        Err(_) => return,
+    };
+    if let Ok(statements) = Parser::new(cql).parse() {
+        for statement in &statements {
+            ConstantChecker.visit_statement(statement);
+        }
+    }
+    // lenient mode recovers from errors, so it reaches statements after a failure
+    let _ = Parser::new(cql).parse_lenient();
+});
@@ -0,0 +1,275 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Analysis of statements against `Schema`
+//!
+//! ```
+//! use cqlparser::analysis::{classify_select, PartitionKeyRestriction};
+//! use cqlparser::ast::CqlStatement;
+//! use cqlparser::schema::Schema;
+//! use cqlparser::Parser;
+//!
+//! let schema = Schema::from_statements(
+//!     &Parser::new(
+//!         "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy'};
+//!          CREATE TABLE ks.events (id uuid, at timestamp, kind text, PRIMARY KEY (id, at))",
+//!     )
+//!     .parse()
+//!     .unwrap(),
+//! )
+//! .unwrap();
+//! let statements = Parser::new("SELECT * FROM ks.events WHERE kind = 'login'").parse().unwrap();
+//! if let CqlStatement::Select(select) = &statements[0] {
+//!     let shape = classify_select(select, &schema).unwrap();
+//!     assert_eq!(shape.partition_key, PartitionKeyRestriction::None);
+//!     assert!(shape.needs_filtering);
+//!     assert!(shape.is_full_scan());
+//! }
+//! ```
+
+use crate::ast::{IndexType, Operator, Relation, SelectStatement};
+use crate::schema::{Schema, SchemaError, Table};
+
+/// How a SELECT statement finds the rows to read, as Cassandra plans it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryShape {
+    pub partition_key: PartitionKeyRestriction,
+    /// Clustering columns restricted as a prefix of the clustering key, in order
+    ///
+    /// Only the last column can be restricted by a range, such as `c > 1`.
+    pub clustering_prefix: Vec<String>,
+    /// Secondary indexes used to find the rows
+    ///
+    /// Cassandra uses one index at most, except custom index expressions.
+    pub indexes: Vec<String>,
+    /// True if Cassandra rejects the query without `ALLOW FILTERING`
+    pub needs_filtering: bool,
+}
+
+impl QueryShape {
+    /// Returns true if the query reads every partition of the table,
+    /// that is, neither the partition key nor an index restricts the rows to read
+    pub fn is_full_scan(&self) -> bool {
+        !matches!(self.partition_key, PartitionKeyRestriction::Full) && self.indexes.is_empty()
+    }
+}
+
+/// Restriction on the partition key in WHERE clause
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PartitionKeyRestriction {
+    /// No partition key column is restricted
+    None,
+    /// Some of the partition key columns are restricted by `=` or `IN`
+    Partial,
+    /// Every partition key column is restricted by `=` or `IN`, or the token by `=`
+    Full,
+    /// The token of the partition key is restricted by a range, such as `token(k) > ?`
+    TokenRange,
+}
+
+/// Classifies the restrictions of `select` on the table defined in `schema`
+///
+/// Returns `SchemaError` if the table or restricted columns are not defined.
+pub fn classify_select(
+    select: &SelectStatement,
+    schema: &Schema,
+) -> Result<QueryShape, SchemaError> {
+    let table = schema.resolve_table(&select.table_name)?;
+    let relations = select
+        .selection
+        .as_ref()
+        .map(|where_clause| where_clause.relations())
+        .unwrap_or_default();
+    for column in relations.iter().flat_map(Relation::columns) {
+        if table.column(column).is_none() {
+            return Err(SchemaError::UnknownColumn {
+                keyspace: table.keyspace().to_owned(),
+                table: table.name().to_owned(),
+                column: column.to_owned(),
+            });
+        }
+    }
+
+    // relations the primary key serves, by index in `relations`
+    let mut served = vec![false; relations.len()];
+    let mut indexes = Vec::new();
+    let partition_key = classify_partition_key(table, relations, &mut served);
+    let clustering_prefix = clustering_prefix(table, relations, &mut served);
+    for (i, relation) in relations.iter().enumerate() {
+        match relation {
+            Relation::CustomIndex { index, .. } => {
+                indexes.push(index.clone());
+                served[i] = true;
+            }
+            // only in materialized views, which do not filter
+            Relation::SingleColumn {
+                operator: Operator::IsNot,
+                ..
+            } => served[i] = true,
+            _ => {}
+        }
+    }
+
+    // Cassandra uses one index for the first restriction it serves,
+    // and filters the others
+    if indexes.is_empty() {
+        let indexed = relations
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !served[*i])
+            .find_map(|(i, relation)| Some((i, index_for(table, relation)?)));
+        if let Some((i, index)) = indexed {
+            indexes.push(index.to_owned());
+            served[i] = true;
+        }
+    }
+
+    // clustering columns are restricted in each partition
+    let restricts_partitions = partition_key == PartitionKeyRestriction::Full;
+    let needs_filtering = served.iter().any(|served| !served)
+        || (!clustering_prefix.is_empty() && !restricts_partitions && indexes.is_empty());
+    Ok(QueryShape {
+        partition_key,
+        clustering_prefix,
+        indexes,
+        needs_filtering,
+    })
+}
+
+fn classify_partition_key(
+    table: &Table,
+    relations: &[Relation],
+    served: &mut [bool],
+) -> PartitionKeyRestriction {
+    let mut token_equal = false;
+    let mut token_range = false;
+    let mut restricted = Vec::new();
+    for (i, relation) in relations.iter().enumerate() {
+        match relation {
+            Relation::Token { operator, .. } => {
+                if *operator == Operator::Equal {
+                    token_equal = true;
+                } else {
+                    token_range = true;
+                }
+                served[i] = true;
+            }
+            Relation::SingleColumn {
+                column, operator, ..
+            } if is_equal_or_in(operator)
+                && table.partition_keys().iter().any(|k| **k == **column) =>
+            {
+                restricted.push(i);
+            }
+            _ => {}
+        }
+    }
+    let restricts_all = table.partition_keys().iter().all(|key| {
+        restricted
+            .iter()
+            .any(|i| relations[*i].columns().any(|c| c == key))
+    });
+    if token_equal || (restricts_all && !token_range) {
+        for i in restricted {
+            served[i] = true;
+        }
+        PartitionKeyRestriction::Full
+    } else if token_range {
+        PartitionKeyRestriction::TokenRange
+    } else if restricted.is_empty() {
+        PartitionKeyRestriction::None
+    } else {
+        // filtered unless an index serves them
+        PartitionKeyRestriction::Partial
+    }
+}
+
+// Returns clustering columns restricted as a prefix, marking the relations as served
+fn clustering_prefix(table: &Table, relations: &[Relation], served: &mut [bool]) -> Vec<String> {
+    let clustering_columns = table.clustering_columns();
+    let mut prefix = Vec::new();
+    while prefix.len() < clustering_columns.len() {
+        let next = &clustering_columns[prefix.len()];
+        let rest = &clustering_columns[prefix.len()..];
+        // relations restricting the next column, or the next columns as a tuple
+        let restrictions: Vec<usize> = relations
+            .iter()
+            .enumerate()
+            .filter(|(i, relation)| {
+                !served[*i]
+                    && match relation {
+                        Relation::SingleColumn {
+                            column, operator, ..
+                        } => **column == **next && *operator != Operator::IsNot,
+                        Relation::MultiColumn { columns, .. } => {
+                            columns.len() <= rest.len()
+                                && columns.iter().zip(rest).all(|(c, r)| **c == **r)
+                        }
+                        _ => false,
+                    }
+            })
+            .map(|(i, _)| i)
+            .collect();
+        let (columns, is_range) = match restrictions.first() {
+            Some(i) => (
+                relations[*i].columns().count(),
+                !matches!(relations[*i].operator(), Some(op) if is_equal_or_in(op)),
+            ),
+            None => break,
+        };
+        // both bounds of a range, such as `c > 1 AND c < 5`, are served
+        for i in restrictions {
+            if relations[i].columns().count() == columns {
+                served[i] = true;
+            }
+        }
+        prefix.extend(rest[..columns].iter().cloned());
+        if is_range {
+            break;
+        }
+    }
+    prefix
+}
+
+// Returns the name of the index that serves `relation`
+fn index_for<'a>(table: &'a Table, relation: &Relation) -> Option<&'a str> {
+    let (column, operator) = match relation {
+        Relation::SingleColumn {
+            column, operator, ..
+        } => (column, operator),
+        _ => return None,
+    };
+    table
+        .indexes()
+        .iter()
+        .find(|index| {
+            index.targets.iter().any(|(target, index_type)| {
+                **target == **column
+                    && (index.is_custom
+                        || match operator {
+                            Operator::Equal => {
+                                matches!(index_type, IndexType::Simple | IndexType::Full)
+                            }
+                            Operator::Contains => {
+                                matches!(index_type, IndexType::Simple | IndexType::Values)
+                            }
+                            Operator::ContainsKey => *index_type == IndexType::Keys,
+                            _ => false,
+                        })
+            })
+        })
+        .map(|index| index.name.as_str())
+}
+
+fn is_equal_or_in(operator: &Operator) -> bool {
+    matches!(operator, Operator::Equal | Operator::In)
+}
@@ -0,0 +1,171 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::visit::{walk_literal, Visitor};
+use super::{
+    CqlStatement, DeleteTarget, Expression, InsertMethod, Literal, Projection, WhereClause,
+};
+
+/// Bind marker (`?` or `:name`) found in a statement
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BindMarker {
+    /// Name of the bind marker, or `None` if positional (`?`)
+    pub name: Option<String>,
+    /// Clause the bind marker appears in
+    pub clause: BindMarkerClause,
+}
+
+/// Clause of the statement where a bind marker appears
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BindMarkerClause {
+    /// Selectors of SELECT statement
+    Projection,
+    /// VALUES of INSERT statement, or the JSON document of `INSERT JSON`
+    Values,
+    /// SET clause of UPDATE statement
+    Assignment,
+    /// Elements to delete in DELETE statement, such as `DELETE m[?] FROM t`
+    Deletion,
+    /// WHERE clause
+    Where,
+    /// IF clause
+    Condition,
+    /// LIMIT or PER PARTITION LIMIT clause
+    Limit,
+    /// USING TIMESTAMP or USING TTL clause
+    Using,
+}
+
+impl CqlStatement {
+    /// Returns bind markers in this statement, in the order of appearance
+    ///
+    /// ```
+    /// use cqlparser::ast::BindMarkerClause;
+    /// use cqlparser::Parser;
+    /// let statement = Parser::new("SELECT * FROM t WHERE k = :k LIMIT ?").parse().unwrap().remove(0);
+    /// let markers = statement.bind_markers();
+    /// assert_eq!(markers[0].name.as_deref(), Some("k"));
+    /// assert_eq!(markers[0].clause, BindMarkerClause::Where);
+    /// assert_eq!(markers[1].name, None);
+    /// assert_eq!(markers[1].clause, BindMarkerClause::Limit);
+    /// ```
+    pub fn bind_markers(&self) -> Vec<BindMarker> {
+        let mut collector = BindMarkerCollector {
+            clause: BindMarkerClause::Where,
+            markers: Vec::new(),
+        };
+        collector.collect_statement(self);
+        collector.markers
+    }
+}
+
+// Collects bind markers, keeping track of the clause being visited
+struct BindMarkerCollector {
+    clause: BindMarkerClause,
+    markers: Vec<BindMarker>,
+}
+
+impl BindMarkerCollector {
+    // Visits clauses in the order of appearance in CQL
+    fn collect_statement(&mut self, statement: &CqlStatement) {
+        match statement {
+            CqlStatement::Select(select) => {
+                if let Projection::Selectors(selectors) = &select.projection {
+                    self.clause = BindMarkerClause::Projection;
+                    selectors
+                        .iter()
+                        .for_each(|s| self.visit_expression(s.selectable()));
+                }
+                if let Some(selection) = &select.selection {
+                    self.collect_where_clause(selection);
+                }
+                self.collect_literal(BindMarkerClause::Limit, &select.per_partition_limit);
+                self.collect_literal(BindMarkerClause::Limit, &select.limit);
+            }
+            CqlStatement::Insert(insert) => {
+                self.clause = BindMarkerClause::Values;
+                match &insert.values {
+                    InsertMethod::Normal { values, .. } => {
+                        values.iter().for_each(|v| self.visit_expression(v))
+                    }
+                    InsertMethod::Json { value, .. } => self.visit_literal(value),
+                }
+                self.collect_literal(BindMarkerClause::Using, &insert.timestamp);
+                self.collect_literal(BindMarkerClause::Using, &insert.time_to_live);
+            }
+            CqlStatement::Update(update) => {
+                self.collect_literal(BindMarkerClause::Using, &update.timestamp);
+                self.collect_literal(BindMarkerClause::Using, &update.time_to_live);
+                self.clause = BindMarkerClause::Assignment;
+                update
+                    .assignments
+                    .iter()
+                    .for_each(|a| self.visit_expression(a));
+                self.collect_where_clause(&update.selection);
+                self.collect_expression(BindMarkerClause::Condition, &update.conditions);
+            }
+            CqlStatement::Batch(batch) => {
+                self.collect_literal(BindMarkerClause::Using, &batch.timestamp);
+                batch
+                    .statements
+                    .iter()
+                    .for_each(|s| self.collect_statement(s));
+            }
+            CqlStatement::Delete(delete) => {
+                self.clause = BindMarkerClause::Deletion;
+                for target in &delete.targets {
+                    if let DeleteTarget::Element { element, .. } = target {
+                        self.visit_expression(element);
+                    }
+                }
+                self.collect_literal(BindMarkerClause::Using, &delete.timestamp);
+                self.collect_where_clause(&delete.selection);
+                self.collect_expression(BindMarkerClause::Condition, &delete.conditions);
+            }
+            _ => {}
+        }
+    }
+
+    fn collect_where_clause(&mut self, where_clause: &WhereClause) {
+        self.clause = BindMarkerClause::Where;
+        where_clause
+            .relations()
+            .iter()
+            .for_each(|r| self.visit_relation(r));
+    }
+
+    fn collect_expression(&mut self, clause: BindMarkerClause, expression: &Option<Expression>) {
+        if let Some(expression) = expression {
+            self.clause = clause;
+            self.visit_expression(expression);
+        }
+    }
+
+    fn collect_literal(&mut self, clause: BindMarkerClause, literal: &Option<Literal>) {
+        if let Some(literal) = literal {
+            self.clause = clause;
+            self.visit_literal(literal);
+        }
+    }
+}
+
+impl Visitor for BindMarkerCollector {
+    fn visit_literal(&mut self, literal: &Literal) {
+        if let Literal::Binding(name) = literal {
+            self.markers.push(BindMarker {
+                name: name.clone(),
+                clause: self.clause,
+            });
+        }
+        walk_literal(self, literal);
+    }
+}
@@ -10,52 +10,387 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{CqlType, Expression, Projection, Property, QualifiedName};
+use std::fmt;
+
+use super::{
+    write_separated, Constant, CqlType, DisplayIdentifier, Expression, Identifier, Operator,
+    Projection, Property, PropertyValue, QualifiedName, Relation, Span, WhereClause,
+};
 
 /// CREATE KEYSPACE statement
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub struct CreateKeyspaceStatement {
     pub keyspace_name: String,
     pub attributes: Vec<Property>,
     pub if_not_exists: bool,
+    /// Position of the statement in the original CQL, not taken into account when comparing
+    pub span: Span,
 }
 
+impl_eq_ignoring_span!(CreateKeyspaceStatement {
+    keyspace_name,
+    attributes,
+    if_not_exists,
+});
+
 /// CREATE TABLE statement
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub struct CreateTableStatement {
     pub name: QualifiedName,
     pub if_not_exists: bool,
-    pub column_definitions: Vec<(String, CqlType)>,
-    pub static_columns: Vec<String>,
-    /// Partition keys here is defined as Vec<Vec<String>>,
+    pub column_definitions: Vec<ColumnDefinition>,
+    /// Partition keys here is defined as Vec<Vec<Identifier>>,
     /// since the statement can define partition keys in two
     /// different places: `column_name type PRIMARY KEY` and
     /// `PRIMARY KEY (...)`.
     ///
-    /// Only one partition keys should be defined,
-    /// so `partition_keys.len() > 1` is illegal.
-    pub partition_keys: Vec<Vec<String>>,
-    pub clustering_columns: Vec<String>,
+    /// The parser ensures that partition keys are defined exactly once,
+    /// so `partition_keys.len()` is always 1 for the parsed statements.
+    /// Use [`CreateTableStatement::primary_key`] to access the primary key.
+    pub partition_keys: Vec<Vec<Identifier>>,
+    pub clustering_columns: Vec<Identifier>,
     pub compact_storage: bool,
-    pub clustering_order: Vec<(String, bool)>,
+    pub clustering_order: Vec<(Identifier, bool)>,
     pub table_properties: Vec<Property>,
+    /// Position of the statement in the original CQL, not taken into account when comparing
+    pub span: Span,
 }
 
-/// CREATE (CUSTOM)? INDEX statement
+impl_eq_ignoring_span!(CreateTableStatement {
+    name,
+    if_not_exists,
+    column_definitions,
+    partition_keys,
+    clustering_columns,
+    compact_storage,
+    clustering_order,
+    table_properties,
+});
+
+impl CreateTableStatement {
+    /// Returns names of STATIC columns
+    pub fn static_columns(&self) -> impl Iterator<Item = &str> {
+        self.column_definitions
+            .iter()
+            .filter(|column| column.is_static)
+            .map(|column| &*column.name)
+    }
+
+    /// Returns (partition keys, clustering columns) pair of the primary key
+    ///
+    /// Returns `None` if the primary key is not defined exactly once.
+    pub fn primary_key(&self) -> Option<(&[Identifier], &[Identifier])> {
+        match self.partition_keys.as_slice() {
+            [partition_keys] => Some((partition_keys, &self.clustering_columns)),
+            _ => None,
+        }
+    }
+
+    /// Returns table properties to look up by name
+    pub fn properties(&self) -> TableProperties<'_> {
+        TableProperties::new(&self.table_properties)
+    }
+}
+
+/// Properties of a table or a materialized view, such as `comment = 'text'`,
+/// to look up by name
+///
+/// Names of well-known properties are defined as constants. Property names are
+/// case sensitive; unquoted names are lowercased by the parser.
+///
+/// ```
+/// use cqlparser::ast::{CqlStatement, TableProperties};
+/// use cqlparser::Parser;
+///
+/// let cql = "CREATE TABLE t (k int PRIMARY KEY) WITH comment = 'users' AND cdc = true";
+/// if let Some(CqlStatement::CreateTable(table)) = Parser::new(cql).parse().unwrap().pop() {
+///     let properties = table.properties();
+///     assert_eq!(properties.comment(), Some("users"));
+///     assert_eq!(properties.cdc(), Some(true));
+///     assert!(properties.get(TableProperties::COMPACTION).is_none());
+/// }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct TableProperties<'a> {
+    properties: &'a [Property],
+}
+
+impl<'a> TableProperties<'a> {
+    pub const CACHING: &'static str = "caching";
+    pub const CDC: &'static str = "cdc";
+    pub const COMMENT: &'static str = "comment";
+    pub const COMPACTION: &'static str = "compaction";
+    pub const GC_GRACE_SECONDS: &'static str = "gc_grace_seconds";
+    pub const ID: &'static str = "id";
+
+    pub fn new(properties: &'a [Property]) -> Self {
+        TableProperties { properties }
+    }
+
+    /// Returns the value of given property, or `None` if it is not specified
+    pub fn get(&self, key: &str) -> Option<&'a PropertyValue> {
+        self.properties
+            .iter()
+            .find(|property| property.key() == key)
+            .map(Property::value)
+    }
+
+    /// Returns `comment`, or `None` if it is not specified or not a string
+    pub fn comment(&self) -> Option<&'a str> {
+        match self.get(Self::COMMENT)? {
+            PropertyValue::Constant(Constant::StringLiteral(comment)) => Some(comment),
+            _ => None,
+        }
+    }
+
+    /// Returns `cdc`, or `None` if it is not specified or not a boolean
+    pub fn cdc(&self) -> Option<bool> {
+        match self.get(Self::CDC)? {
+            PropertyValue::Constant(Constant::Boolean(cdc)) => Some(*cdc),
+            _ => None,
+        }
+    }
+
+    /// Returns the table ID given by `ID = uuid`, or `None` if it is not specified or not a UUID
+    pub fn id(&self) -> Option<&'a str> {
+        match self.get(Self::ID)? {
+            PropertyValue::Constant(Constant::UUID(id)) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Returns `gc_grace_seconds`, or `None` if it is not specified or not an integer
+    pub fn gc_grace_seconds(&self) -> Option<i64> {
+        match self.get(Self::GC_GRACE_SECONDS)? {
+            PropertyValue::Constant(constant) => constant.as_i64(),
+            _ => None,
+        }
+    }
+}
+
+/// Formats CREATE TABLE statement as CQL, without trailing `;`
+///
+/// The primary key is written in the column definition if it is defined there,
+/// otherwise in `PRIMARY KEY (...)` after the columns.
+///
+/// ```
+/// use cqlparser::ast::CqlStatement;
+/// use cqlparser::Parser;
+///
+/// let cql = "CREATE TABLE t (k int, c text, v map<text, int>, PRIMARY KEY ((k), c))
+///            WITH CLUSTERING ORDER BY (c DESC) AND comment = 'it''s'";
+/// if let CqlStatement::CreateTable(table) = &Parser::new(cql).parse().unwrap()[0] {
+///     assert_eq!(
+///         table.to_string(),
+///         "CREATE TABLE t (k int, c text, v map<text, int>, PRIMARY KEY (k, c)) \
+///          WITH CLUSTERING ORDER BY (c DESC) AND comment = 'it''s'"
+///     );
+/// }
+/// ```
+impl fmt::Display for CreateTableStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CREATE TABLE ")?;
+        if self.if_not_exists {
+            f.write_str("IF NOT EXISTS ")?;
+        }
+        write!(f, "{} (", self.name)?;
+        write_separated(f, &self.column_definitions, ", ")?;
+        if let Some(primary_key) = self.primary_key_definition() {
+            write!(f, ", {}", primary_key)?;
+        }
+        f.write_str(")")?;
+
+        let options = table_options(
+            self.compact_storage,
+            &self.clustering_order,
+            &self.table_properties,
+        );
+        if !options.is_empty() {
+            f.write_str(" WITH ")?;
+            write_separated(f, options, " AND ")?;
+        }
+        Ok(())
+    }
+}
+
+impl CreateTableStatement {
+    // Returns `PRIMARY KEY (...)` to write after the columns,
+    // or `None` if the primary key is defined in the column definition
+    pub(crate) fn primary_key_definition(&self) -> Option<DisplayPrimaryKey<'_>> {
+        if self.column_definitions.iter().any(|c| c.is_primary_key) {
+            return None;
+        }
+        self.primary_key()
+            .map(|(partition_keys, clustering_columns)| DisplayPrimaryKey {
+                partition_keys,
+                clustering_columns,
+            })
+    }
+}
+
+// Displays `PRIMARY KEY (...)` of a table or a materialized view
+pub(crate) struct DisplayPrimaryKey<'a> {
+    pub(crate) partition_keys: &'a [Identifier],
+    pub(crate) clustering_columns: &'a [Identifier],
+}
+
+impl fmt::Display for DisplayPrimaryKey<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PRIMARY KEY (")?;
+        let is_composite = self.partition_keys.len() > 1;
+        if is_composite {
+            f.write_str("(")?;
+        }
+        write_separated(
+            f,
+            self.partition_keys.iter().map(|k| DisplayIdentifier(k)),
+            ", ",
+        )?;
+        if is_composite {
+            f.write_str(")")?;
+        }
+        for column in self.clustering_columns {
+            write!(f, ", {}", DisplayIdentifier(column))?;
+        }
+        f.write_str(")")
+    }
+}
+
+// Returns options in WITH clause of a table or a materialized view
+pub(crate) fn table_options(
+    compact_storage: bool,
+    clustering_order: &[(Identifier, bool)],
+    properties: &[Property],
+) -> Vec<String> {
+    let mut options = Vec::new();
+    if compact_storage {
+        options.push("COMPACT STORAGE".to_owned());
+    }
+    if !clustering_order.is_empty() {
+        let order = clustering_order
+            .iter()
+            .map(|(column, asc)| {
+                let order = if *asc { "ASC" } else { "DESC" };
+                format!("{} {}", DisplayIdentifier(column), order)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        options.push(format!("CLUSTERING ORDER BY ({})", order));
+    }
+    options.extend(properties.iter().map(Property::to_string));
+    options
+}
+
+/// Column definition in CREATE TABLE statement
+#[derive(Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
+pub struct ColumnDefinition {
+    pub name: Identifier,
+    pub data_type: CqlType,
+    pub is_static: bool,
+    pub is_primary_key: bool,
+    pub mask: Option<ColumnMask>,
+}
+
+impl ColumnDefinition {
+    /// Creates a regular column definition, which is not static, not a primary key
+    /// and not masked
+    pub fn new(name: String, data_type: CqlType) -> Self {
+        ColumnDefinition {
+            name: name.into(),
+            data_type,
+            is_static: false,
+            is_primary_key: false,
+            mask: None,
+        }
+    }
+}
+
+impl fmt::Display for ColumnDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", DisplayIdentifier(&self.name), self.data_type)?;
+        if self.is_static {
+            f.write_str(" STATIC")?;
+        }
+        if let Some(mask) = &self.mask {
+            write!(f, " MASKED WITH {}", mask)?;
+        }
+        if self.is_primary_key {
+            f.write_str(" PRIMARY KEY")?;
+        }
+        Ok(())
+    }
+}
+
+/// Dynamic data masking of a column: `MASKED WITH ...`
 #[derive(Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    serde(
+        tag = "type",
+        content = "value",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )
+)]
+pub enum ColumnMask {
+    /// `MASKED WITH DEFAULT`
+    Default,
+    /// `MASKED WITH function(args...)`, holding `Expression::Function`
+    Function(Expression),
+}
+
+impl fmt::Display for ColumnMask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnMask::Default => f.write_str("DEFAULT"),
+            ColumnMask::Function(function) => write!(f, "{}", function),
+        }
+    }
+}
+
+/// CREATE (CUSTOM)? INDEX statement
+#[derive(Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub struct CreateIndexStatement {
     pub index_name: Option<String>,
     pub table_name: QualifiedName,
     pub if_not_exists: bool,
     pub is_custom: bool,
-    pub index_targets: Vec<(String, IndexType)>,
+    pub index_targets: Vec<IndexTarget>,
+    /// Position of the statement in the original CQL, not taken into account when comparing
+    pub span: Span,
 }
 
+impl_eq_ignoring_span!(CreateIndexStatement {
+    index_name,
+    table_name,
+    if_not_exists,
+    is_custom,
+    index_targets,
+});
+
+/// Column to index, with the type of index
 #[derive(Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
+pub struct IndexTarget {
+    pub column: Identifier,
+    /// true if the column name is a quoted name
+    pub quoted: bool,
+    pub index_type: IndexType,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub enum IndexType {
     Simple,
     Values,
@@ -65,27 +400,312 @@ pub enum IndexType {
 }
 
 /// CREATE TYPE statement
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub struct CreateTypeStatement {
     pub name: QualifiedName,
     pub if_not_exists: bool,
     pub field_definitions: Vec<(String, CqlType)>,
+    /// Position of the statement in the original CQL, not taken into account when comparing
+    pub span: Span,
 }
 
-/// CREATE MATERIALIZED VIEW statement
+impl_eq_ignoring_span!(CreateTypeStatement {
+    name,
+    if_not_exists,
+    field_definitions,
+});
+
+/// ALTER TABLE statement
+#[derive(Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
+pub struct AlterTableStatement {
+    pub name: QualifiedName,
+    pub if_exists: bool,
+    pub operation: AlterTableOperation,
+    /// Position of the statement in the original CQL, not taken into account when comparing
+    pub span: Span,
+}
+
+impl_eq_ignoring_span!(AlterTableStatement {
+    name,
+    if_exists,
+    operation,
+});
+
+/// Operation of ALTER TABLE statement
+///
+/// `if_exists` and `if_not_exists` of each operation are the flags
+/// of the operation itself (Cassandra 4.1+), not the ones of the table.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    serde(
+        tag = "type",
+        content = "value",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )
+)]
+#[non_exhaustive]
+pub enum AlterTableOperation {
+    /// `ADD (IF NOT EXISTS)? column type` or `ADD (IF NOT EXISTS)? (column type, ...)`
+    Add {
+        if_not_exists: bool,
+        columns: Vec<ColumnDefinition>,
+    },
+    /// `DROP (IF EXISTS)? column` or `DROP (IF EXISTS)? (column, ...)`,
+    /// optionally followed by `USING TIMESTAMP n`
+    Drop {
+        if_exists: bool,
+        columns: Vec<Identifier>,
+        timestamp: Option<i64>,
+    },
+    /// `RENAME (IF EXISTS)? from TO to (AND from TO to)*`
+    Rename {
+        if_exists: bool,
+        columns: Vec<(Identifier, Identifier)>,
+    },
+    /// `ALTER column TYPE type`, removed in Cassandra 4.0
+    AlterType {
+        column: Identifier,
+        new_type: CqlType,
+    },
+    /// `DROP COMPACT STORAGE`
+    DropCompactStorage,
+    /// `WITH property (AND property)*`
+    WithProperties(Vec<Property>),
+}
+
+/// ALTER TYPE statement
+#[derive(Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
+pub struct AlterTypeStatement {
+    pub name: QualifiedName,
+    pub if_exists: bool,
+    pub operation: AlterTypeOperation,
+    /// Position of the statement in the original CQL, not taken into account when comparing
+    pub span: Span,
+}
+
+impl_eq_ignoring_span!(AlterTypeStatement {
+    name,
+    if_exists,
+    operation,
+});
+
+/// Operation of ALTER TYPE statement
+///
+/// `if_exists` and `if_not_exists` of each operation are the flags
+/// of the operation itself (Cassandra 4.1+), not the ones of the type.
 #[derive(Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    serde(
+        tag = "type",
+        content = "value",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )
+)]
+#[non_exhaustive]
+pub enum AlterTypeOperation {
+    /// `ADD (IF NOT EXISTS)? field type`
+    Add {
+        if_not_exists: bool,
+        field: String,
+        data_type: CqlType,
+    },
+    /// `RENAME (IF EXISTS)? from TO to (AND from TO to)*`
+    Rename {
+        if_exists: bool,
+        fields: Vec<(String, String)>,
+    },
+}
+
+/// ALTER KEYSPACE statement
+#[derive(Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
+pub struct AlterKeyspaceStatement {
+    pub keyspace_name: String,
+    pub if_exists: bool,
+    pub attributes: Vec<Property>,
+    /// Position of the statement in the original CQL, not taken into account when comparing
+    pub span: Span,
+}
+
+impl_eq_ignoring_span!(AlterKeyspaceStatement {
+    keyspace_name,
+    if_exists,
+    attributes,
+});
+
+/// DROP statement
+///
+/// New kinds of DROP statements can be added in the future, so matching on
+/// `DropStatement` outside of this crate needs a wildcard arm.
+///
+/// ```
+/// use cqlparser::ast::DropStatement;
+/// fn target(drop: &DropStatement) -> &'static str {
+///     match drop {
+///         DropStatement::Keyspace { .. } => "keyspace",
+///         DropStatement::Table { .. } | DropStatement::MaterializedView { .. } => "table",
+///         _ => "other",
+///     }
+/// }
+/// ```
+///
+/// Listing all the variants does not compile without the wildcard arm:
+///
+/// ```compile_fail,E0004
+/// use cqlparser::ast::DropStatement;
+/// fn target(drop: &DropStatement) -> &'static str {
+///     match drop {
+///         DropStatement::Aggregate => "aggregate",
+///         DropStatement::Function => "function",
+///         DropStatement::Index { .. } => "index",
+///         DropStatement::Keyspace { .. } => "keyspace",
+///         DropStatement::MaterializedView { .. } => "materialized view",
+///         DropStatement::Table { .. } => "table",
+///         DropStatement::Trigger => "trigger",
+///         DropStatement::Type { .. } => "type",
+///     }
+/// }
+/// ```
+#[derive(Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    serde(
+        tag = "type",
+        content = "value",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )
+)]
+#[non_exhaustive]
+pub enum DropStatement {
+    /// `DROP AGGREGATE`, not parsed yet
+    Aggregate,
+    /// `DROP FUNCTION`, not parsed yet
+    Function,
+    /// `DROP INDEX (IF EXISTS)? name`
+    Index {
+        name: QualifiedName,
+        if_exists: bool,
+    },
+    /// `DROP KEYSPACE (IF EXISTS)? name`
+    Keyspace { name: String, if_exists: bool },
+    /// `DROP MATERIALIZED VIEW (IF EXISTS)? name`
+    MaterializedView {
+        name: QualifiedName,
+        if_exists: bool,
+    },
+    /// `DROP TABLE (IF EXISTS)? name`
+    Table {
+        name: QualifiedName,
+        if_exists: bool,
+    },
+    /// `DROP TRIGGER`, not parsed yet
+    Trigger,
+    /// `DROP TYPE (IF EXISTS)? name`
+    Type {
+        name: QualifiedName,
+        if_exists: bool,
+    },
+}
+
+/// CREATE MATERIALIZED VIEW statement
+#[derive(Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub struct CreateMaterializedViewStatement {
     pub name: QualifiedName,
     pub base_table: QualifiedName,
     pub if_not_exists: bool,
     pub projection: Projection,
     /// WHERE clause
-    pub selection: Option<Expression>,
-    pub partition_keys: Vec<String>,
-    pub clustering_columns: Vec<String>,
+    pub selection: Option<WhereClause>,
+    pub partition_keys: Vec<Identifier>,
+    pub clustering_columns: Vec<Identifier>,
     pub compact_storage: bool,
-    pub clustering_order: Vec<(String, bool)>,
+    pub clustering_order: Vec<(Identifier, bool)>,
     pub view_properties: Vec<Property>,
+    /// Position of the statement in the original CQL, not taken into account when comparing
+    pub span: Span,
+}
+
+impl_eq_ignoring_span!(CreateMaterializedViewStatement {
+    name,
+    base_table,
+    if_not_exists,
+    projection,
+    selection,
+    partition_keys,
+    clustering_columns,
+    compact_storage,
+    clustering_order,
+    view_properties,
+});
+
+impl CreateMaterializedViewStatement {
+    /// Returns view properties to look up by name
+    pub fn properties(&self) -> TableProperties<'_> {
+        TableProperties::new(&self.view_properties)
+    }
+
+    /// Returns the columns of the base table selected by name,
+    /// or `None` if every column is selected with `*`
+    ///
+    /// Selectors other than column names, which Cassandra rejects, are skipped.
+    ///
+    /// ```
+    /// use cqlparser::ast::CqlStatement;
+    /// use cqlparser::Parser;
+    ///
+    /// let statements = Parser::new(
+    ///     "CREATE MATERIALIZED VIEW by_email AS SELECT email, \"Name\" FROM users
+    ///      WHERE email IS NOT NULL AND id IS NOT NULL PRIMARY KEY (email, id)",
+    /// )
+    /// .parse()
+    /// .unwrap();
+    /// if let CqlStatement::CreateMaterializedView(view) = &statements[0] {
+    ///     assert_eq!(view.selected_columns(), Some(vec!["email", "Name"]));
+    ///     assert_eq!(view.not_null_columns(), ["email", "id"]);
+    /// }
+    /// ```
+    pub fn selected_columns(&self) -> Option<Vec<&str>> {
+        match &self.projection {
+            Projection::Wildcard => None,
+            Projection::Selectors(selectors) => Some(
+                selectors
+                    .iter()
+                    .filter_map(|selector| selector.selectable().as_column())
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Returns the columns restricted by `IS NOT NULL` in WHERE clause
+    pub fn not_null_columns(&self) -> Vec<&str> {
+        self.selection
+            .iter()
+            .flat_map(WhereClause::relations)
+            .filter_map(|relation| match relation {
+                Relation::SingleColumn {
+                    column,
+                    operator: Operator::IsNot,
+                    ..
+                } => Some(&**column),
+                _ => None,
+            })
+            .collect()
+    }
 }
@@ -10,30 +10,98 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{Expression, Literal, QualifiedName};
+use std::convert::TryFrom;
+use std::fmt;
+
+use super::{
+    Constant, CqlStatement, DisplayIdentifier, Expression, Identifier, Literal, QualifiedName,
+    Span, WhereClause,
+};
 
 /// # INSERT statement
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub struct InsertStatement {
     pub table: QualifiedName,
     pub values: InsertMethod,
     pub if_not_exists: bool,
-    /// timestamp value
-    /// Can be `Literal::Integer` or `Literal::Binding`
+    /// `USING TIMESTAMP` value, which can be `Constant::Integer`, including negative ones such as `-1`,
+    /// or `Literal::Binding` of a bind marker such as `?` or `:ts`
     pub timestamp: Option<Literal>,
+    /// `USING TTL` value, which can be `Constant::Integer` or `Literal::Binding`
     pub time_to_live: Option<Literal>,
+    /// Position of the statement in the original CQL, not taken into account when comparing
+    pub span: Span,
+}
+
+impl_eq_ignoring_span!(InsertStatement {
+    table,
+    values,
+    if_not_exists,
+    timestamp,
+    time_to_live,
+});
+
+impl InsertStatement {
+    /// Returns the value of `USING TTL`, or `None` without TTL
+    ///
+    /// ```
+    /// use cqlparser::ast::{CqlStatement, TtlValue};
+    /// use cqlparser::Parser;
+    /// let cql = "INSERT INTO t (k) VALUES (1) USING TTL 0";
+    /// match &Parser::new(cql).parse().unwrap()[0] {
+    ///     CqlStatement::Insert(insert) => assert_eq!(insert.ttl_seconds(), Some(TtlValue::Constant(0))),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn ttl_seconds(&self) -> Option<TtlValue> {
+        TtlValue::from_literal(self.time_to_live.as_ref()?)
+    }
+}
+
+/// Value of `USING TTL` in INSERT and UPDATE statements
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TtlValue {
+    /// TTL in seconds, where `0` means that the data never expires
+    Constant(i32),
+    /// Bind marker, which is positional `?` if the name is `None`
+    Binding(Option<String>),
+}
+
+impl TtlValue {
+    // Returns `None` if `literal` is not a valid TTL
+    fn from_literal(literal: &Literal) -> Option<Self> {
+        match literal {
+            Literal::Constant(Constant::Integer(seconds)) => {
+                i32::try_from(*seconds).ok().map(TtlValue::Constant)
+            }
+            Literal::Binding(name) => Some(TtlValue::Binding(name.clone())),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    serde(
+        tag = "type",
+        content = "value",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )
+)]
 pub enum InsertMethod {
     Normal {
         columns: Vec<Expression>,
         values: Vec<Expression>,
     },
     Json {
-        value: String,
+        /// JSON document, either string literal whose value is unescaped
+        /// or `Literal::Binding`
+        value: Literal,
         default_behavior: JsonBehavior,
     },
 }
@@ -43,7 +111,7 @@ impl InsertMethod {
         InsertMethod::Normal { columns, values }
     }
 
-    pub fn json(value: String, default_behavior: JsonBehavior) -> Self {
+    pub fn json(value: Literal, default_behavior: JsonBehavior) -> Self {
         InsertMethod::Json {
             value,
             default_behavior,
@@ -54,21 +122,165 @@ impl InsertMethod {
 /// # Default Json behavior in `INSERT INTO tbl JSON` statement
 #[derive(Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub enum JsonBehavior {
     Unset,
     Null,
 }
 
 /// UPDATE statement
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub struct UpdateStatement {
     pub table: QualifiedName,
     pub if_exists: bool,
     pub assignments: Vec<Expression>,
-    pub selection: Expression,
-    /// timestamp value
-    /// Can be `Literal::Integer` or `Literal::Binding`
+    /// WHERE clause
+    pub selection: WhereClause,
+    /// Conditions in `IF` clause, such as `IF a != 1 AND b = 2`
+    pub conditions: Option<Expression>,
+    /// `USING TIMESTAMP` value, which can be `Constant::Integer`, including negative ones such as `-1`,
+    /// or `Literal::Binding` of a bind marker such as `?` or `:ts`
     pub timestamp: Option<Literal>,
+    /// `USING TTL` value, which can be `Constant::Integer` or `Literal::Binding`
     pub time_to_live: Option<Literal>,
+    /// Position of the statement in the original CQL, not taken into account when comparing
+    pub span: Span,
+}
+
+impl_eq_ignoring_span!(UpdateStatement {
+    table,
+    if_exists,
+    assignments,
+    selection,
+    conditions,
+    timestamp,
+    time_to_live,
+});
+
+impl UpdateStatement {
+    /// Returns the value of `USING TTL`, or `None` without TTL
+    pub fn ttl_seconds(&self) -> Option<TtlValue> {
+        TtlValue::from_literal(self.time_to_live.as_ref()?)
+    }
+}
+
+/// DELETE statement
+#[derive(Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
+pub struct DeleteStatement {
+    pub table: QualifiedName,
+    /// Columns, elements or fields to delete, which is empty when deleting whole rows
+    pub targets: Vec<DeleteTarget>,
+    /// `USING TIMESTAMP` value, which can be `Constant::Integer`, including negative ones such as `-1`,
+    /// or `Literal::Binding` of a bind marker such as `?` or `:ts`
+    pub timestamp: Option<Literal>,
+    /// WHERE clause
+    pub selection: WhereClause,
+    pub if_exists: bool,
+    /// Conditions in `IF` clause, such as `IF a != 1 AND b = 2`
+    pub conditions: Option<Expression>,
+    /// Position of the statement in the original CQL, not taken into account when comparing
+    pub span: Span,
+}
+
+impl_eq_ignoring_span!(DeleteStatement {
+    table,
+    targets,
+    timestamp,
+    selection,
+    if_exists,
+    conditions,
+});
+
+/// What to delete in DELETE statement
+#[derive(Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    serde(
+        tag = "type",
+        content = "value",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )
+)]
+pub enum DeleteTarget {
+    /// Whole column: `col`
+    Column(Identifier),
+    /// Element of a collection: `col[term]`,
+    /// which is a key of map, an index of list or an element of set
+    Element {
+        column: Identifier,
+        element: Expression,
+    },
+    /// Field of a non-frozen user defined type: `col.field`
+    Field {
+        column: Identifier,
+        field: Identifier,
+    },
+}
+
+impl DeleteTarget {
+    /// Returns the column that this target deletes from
+    pub fn column(&self) -> &Identifier {
+        match self {
+            DeleteTarget::Column(column)
+            | DeleteTarget::Element { column, .. }
+            | DeleteTarget::Field { column, .. } => column,
+        }
+    }
+}
+
+/// Formats target as written in CQL, such as `col['key']`
+impl fmt::Display for DeleteTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeleteTarget::Column(column) => write!(f, "{}", DisplayIdentifier(column)),
+            DeleteTarget::Element { column, element } => {
+                write!(f, "{}[{}]", DisplayIdentifier(column), element)
+            }
+            DeleteTarget::Field { column, field } => {
+                write!(
+                    f,
+                    "{}.{}",
+                    DisplayIdentifier(column),
+                    DisplayIdentifier(field)
+                )
+            }
+        }
+    }
+}
+
+/// BATCH statement
+#[derive(Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
+pub struct BatchStatement {
+    pub batch_type: BatchType,
+    /// `USING TIMESTAMP` value, which can be `Constant::Integer`, including negative ones such as `-1`,
+    /// or `Literal::Binding` of a bind marker such as `?` or `:ts`
+    pub timestamp: Option<Literal>,
+    /// INSERT, UPDATE or DELETE statements in the batch
+    pub statements: Vec<CqlStatement>,
+    /// Position of the statement in the original CQL, not taken into account when comparing
+    pub span: Span,
+}
+
+impl_eq_ignoring_span!(BatchStatement {
+    batch_type,
+    timestamp,
+    statements,
+});
+
+/// Type of BATCH statement: `BEGIN BATCH`, `BEGIN UNLOGGED BATCH` or `BEGIN COUNTER BATCH`
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
+pub enum BatchType {
+    Logged,
+    Unlogged,
+    Counter,
 }
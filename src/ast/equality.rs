@@ -0,0 +1,230 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{
+    AlterTableOperation, BindMarkerClause, ColumnDefinition, CqlStatement, CreateIndexStatement,
+    CreateMaterializedViewStatement, CreateTableStatement, InsertMethod, InsertStatement, Property,
+    PropertyValue,
+};
+
+impl CqlStatement {
+    /// Returns true if this statement means the same as `other`,
+    /// even if they are written differently
+    ///
+    /// The parser already drops the differences in whitespaces, comments, case of keywords,
+    /// `;`, redundant parentheses and the order of `USING TTL` and `TIMESTAMP`.
+    /// Unquoted identifiers are lowercased, so `T` and `"t"` are the same name,
+    /// and table names with the keyspace from `USE`, parsed with
+    /// `ParserOptions::resolve_use_keyspace`, equal the names written with the keyspace.
+    ///
+    /// In addition, this ignores:
+    /// - the order of properties in `WITH` clauses, and the order of entries
+    ///   of map properties such as `replication`
+    /// - the order of columns in `INSERT`, if the values are reordered accordingly,
+    ///   unless the values have positional bind markers (`?`) whose order changes
+    /// - quotes of the columns of `CREATE INDEX`, such as `("v")` and `(v)`
+    /// - whether the primary key of a single column is declared with the column,
+    ///   as in `k int PRIMARY KEY`, or in `PRIMARY KEY (k)`
+    ///
+    /// Values are compared as written, so `1` and `1.0` differ, and so do the statements
+    /// that differ only in optional clauses such as `ALLOW FILTERING` or the default
+    /// clustering order. Identifiers parsed with `ParserOptions::preserve_identifier_case`
+    /// are compared as written.
+    ///
+    /// ```
+    /// use cqlparser::Parser;
+    ///
+    /// let statements = Parser::new(
+    ///     "select * from T where k = 1;
+    ///      SELECT  *  FROM \"t\"  WHERE  K=1;
+    ///      SELECT * FROM t WHERE k = 2",
+    /// )
+    /// .parse()
+    /// .unwrap();
+    /// assert!(statements[0].normalized_eq(&statements[1]));
+    /// assert!(!statements[0].normalized_eq(&statements[2]));
+    /// ```
+    pub fn normalized_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (CqlStatement::Insert(a), CqlStatement::Insert(b)) => {
+                let ordered = [self, other].iter().any(|statement| {
+                    statement.bind_markers().iter().any(|marker| {
+                        marker.clause == BindMarkerClause::Values && marker.name.is_none()
+                    })
+                });
+                insert_eq(a, b, ordered)
+            }
+            (CqlStatement::CreateKeyspace(a), CqlStatement::CreateKeyspace(b)) => {
+                a.keyspace_name == b.keyspace_name
+                    && a.if_not_exists == b.if_not_exists
+                    && properties_eq(&a.attributes, &b.attributes)
+            }
+            (CqlStatement::AlterKeyspace(a), CqlStatement::AlterKeyspace(b)) => {
+                a.keyspace_name == b.keyspace_name
+                    && a.if_exists == b.if_exists
+                    && properties_eq(&a.attributes, &b.attributes)
+            }
+            (CqlStatement::CreateTable(a), CqlStatement::CreateTable(b)) => create_table_eq(a, b),
+            (CqlStatement::CreateMaterializedView(a), CqlStatement::CreateMaterializedView(b)) => {
+                create_view_eq(a, b)
+            }
+            (CqlStatement::AlterTable(a), CqlStatement::AlterTable(b)) => {
+                a.name == b.name
+                    && a.if_exists == b.if_exists
+                    && match (&a.operation, &b.operation) {
+                        (
+                            AlterTableOperation::WithProperties(a),
+                            AlterTableOperation::WithProperties(b),
+                        ) => properties_eq(a, b),
+                        (a, b) => a == b,
+                    }
+            }
+            (CqlStatement::CreateIndex(a), CqlStatement::CreateIndex(b)) => create_index_eq(a, b),
+            _ => self == other,
+        }
+    }
+}
+
+// `ordered` is true if the columns must be in the same order
+fn insert_eq(a: &InsertStatement, b: &InsertStatement, ordered: bool) -> bool {
+    let values_eq = match (&a.values, &b.values) {
+        (
+            InsertMethod::Normal { columns, values },
+            InsertMethod::Normal {
+                columns: other_columns,
+                values: other_values,
+            },
+        ) if !ordered => {
+            let pairs: Vec<_> = columns.iter().zip(values).collect();
+            let other_pairs: Vec<_> = other_columns.iter().zip(other_values).collect();
+            columns.len() == values.len()
+                && other_columns.len() == other_values.len()
+                && unordered_eq(&pairs, &other_pairs, |a, b| a == b)
+        }
+        (a, b) => a == b,
+    };
+    a.table == b.table
+        && values_eq
+        && a.if_not_exists == b.if_not_exists
+        && a.timestamp == b.timestamp
+        && a.time_to_live == b.time_to_live
+}
+
+fn create_table_eq(a: &CreateTableStatement, b: &CreateTableStatement) -> bool {
+    // destructured to compare new fields as they are added
+    let CreateTableStatement {
+        name,
+        if_not_exists,
+        column_definitions,
+        partition_keys,
+        clustering_columns,
+        compact_storage,
+        clustering_order,
+        table_properties,
+        span: _,
+    } = a;
+    *name == b.name
+        && *if_not_exists == b.if_not_exists
+        && column_definitions.len() == b.column_definitions.len()
+        && column_definitions
+            .iter()
+            .zip(&b.column_definitions)
+            .all(|(a, b)| column_definition_eq(a, b))
+        && *partition_keys == b.partition_keys
+        && *clustering_columns == b.clustering_columns
+        && *compact_storage == b.compact_storage
+        && *clustering_order == b.clustering_order
+        && properties_eq(table_properties, &b.table_properties)
+}
+
+// Primary key written with the column is also in `partition_keys`
+fn column_definition_eq(a: &ColumnDefinition, b: &ColumnDefinition) -> bool {
+    let ColumnDefinition {
+        name,
+        data_type,
+        is_static,
+        is_primary_key: _,
+        mask,
+    } = a;
+    *name == b.name && *data_type == b.data_type && *is_static == b.is_static && *mask == b.mask
+}
+
+fn create_view_eq(
+    a: &CreateMaterializedViewStatement,
+    b: &CreateMaterializedViewStatement,
+) -> bool {
+    let CreateMaterializedViewStatement {
+        name,
+        base_table,
+        if_not_exists,
+        projection,
+        selection,
+        partition_keys,
+        clustering_columns,
+        compact_storage,
+        clustering_order,
+        view_properties,
+        span: _,
+    } = a;
+    *name == b.name
+        && *base_table == b.base_table
+        && *if_not_exists == b.if_not_exists
+        && *projection == b.projection
+        && *selection == b.selection
+        && *partition_keys == b.partition_keys
+        && *clustering_columns == b.clustering_columns
+        && *compact_storage == b.compact_storage
+        && *clustering_order == b.clustering_order
+        && properties_eq(view_properties, &b.view_properties)
+}
+
+fn create_index_eq(a: &CreateIndexStatement, b: &CreateIndexStatement) -> bool {
+    let CreateIndexStatement {
+        index_name,
+        table_name,
+        if_not_exists,
+        is_custom,
+        index_targets,
+        span: _,
+    } = a;
+    *index_name == b.index_name
+        && *table_name == b.table_name
+        && *if_not_exists == b.if_not_exists
+        && *is_custom == b.is_custom
+        && index_targets.len() == b.index_targets.len()
+        && index_targets
+            .iter()
+            .zip(&b.index_targets)
+            .all(|(a, b)| a.column == b.column && a.index_type == b.index_type)
+}
+
+fn properties_eq(a: &[Property], b: &[Property]) -> bool {
+    unordered_eq(a, b, |a, b| {
+        a.key() == b.key() && property_value_eq(a.value(), b.value())
+    })
+}
+
+fn property_value_eq(a: &PropertyValue, b: &PropertyValue) -> bool {
+    match (a, b) {
+        (PropertyValue::Map(a), PropertyValue::Map(b)) => unordered_eq(a, b, |a, b| {
+            property_value_eq(&a.0, &b.0) && property_value_eq(&a.1, &b.1)
+        }),
+        (a, b) => a == b,
+    }
+}
+
+// Returns true if every item of `a` equals an item of `b`, and vice versa
+fn unordered_eq<T>(a: &[T], b: &[T], eq: impl Fn(&T, &T) -> bool) -> bool {
+    a.len() == b.len()
+        && a.iter().all(|x| b.iter().any(|y| eq(x, y)))
+        && b.iter().all(|y| a.iter().any(|x| eq(x, y)))
+}
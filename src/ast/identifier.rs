@@ -0,0 +1,91 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Column identifier or a component of `QualifiedName`
+///
+/// Identifiers produced by `Parser` are interned in `IdentifierCache`,
+/// so the same names share a single allocation.
+pub type Identifier = Arc<str>;
+
+/// Cache of interned identifiers
+///
+/// Each `Parser` has its own cache by default. The cache can be shared
+/// across parsers with `Parser::identifier_cache`, so that names
+/// in different inputs share allocations. Clones of the cache refer to the same names.
+///
+/// The cache only grows, so long-running services reusing it should
+/// `clear` it when the set of names changes.
+///
+/// ```
+/// use std::sync::Arc;
+/// use cqlparser::ast::{CqlStatement, IdentifierCache};
+/// use cqlparser::Parser;
+///
+/// let cache = IdentifierCache::new();
+/// let parse = |cql| {
+///     match Parser::new(cql).identifier_cache(cache.clone()).parse().unwrap().remove(0) {
+///         CqlStatement::Select(select) => select.table_name.name,
+///         _ => unreachable!(),
+///     }
+/// };
+/// assert!(Arc::ptr_eq(&parse("SELECT * FROM users"), &parse("SELECT a FROM users")));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct IdentifierCache {
+    names: Arc<Mutex<HashSet<Identifier>>>,
+}
+
+impl IdentifierCache {
+    /// Creates an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns interned identifier of given name
+    pub fn intern(&self, name: &str) -> Identifier {
+        let mut names = self.names.lock().unwrap_or_else(PoisonError::into_inner);
+        match names.get(name) {
+            Some(interned) => interned.clone(),
+            None => {
+                let interned: Identifier = Arc::from(name);
+                names.insert(interned.clone());
+                interned
+            }
+        }
+    }
+
+    /// Returns the number of interned identifiers
+    pub fn len(&self) -> usize {
+        self.names
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Returns true if no identifier is interned
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all interned identifiers
+    ///
+    /// Identifiers already returned stay valid.
+    pub fn clear(&self) {
+        self.names
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
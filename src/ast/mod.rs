@@ -25,6 +25,7 @@
 //! - Non-column identifier is generic identifier
 //! - Column identifier identifies a CQL column definition
 //!     - difference from Non-column identifier is that this can be cached using its name to save spaces ("interned")
+//!     - column identifiers and names in `QualifiedName` are `Identifier`s, interned in `IdentifierCache`
 //! - Field identifier identifies a field in UDT
 //!
 //! All of these are token types of:
@@ -36,13 +37,13 @@
 //! ### Simple term
 //!
 //! a `simple term` is one of:
-//! - value: `Expression::Value`
-//! - function: `Expression::Function`
-//! - type cast: `(type) simpleTerm` `Expression::Cast`
+//! - value: `ExpressionKind::Value`
+//! - function: `ExpressionKind::Function`
+//! - type hint: `(type) simpleTerm` `ExpressionKind::TypeCast`
 //!
 //! ### Value
 //!
-//! Implemented as `Expression::Value`
+//! Implemented as `ExpressionKind::Value`
 //!
 //! `value` is one of:
 //! - constant
@@ -56,19 +57,60 @@
 //!
 //! ### Function
 //!
-//! Implemented as `Expression::Function`
+//! Implemented as `ExpressionKind::Function`
+//!
+//! ## JSON representation
+//!
+//! The WebAssembly bindings serialize the AST with `serde`, in the shape
+//! that JavaScript consumers rely on:
+//!
+//! - struct fields and fields of enum variants are in camelCase, such as `tableName`
+//! - enums with data are objects of `type`, the variant name in camelCase,
+//!   and `value`, the data of the variant, such as
+//!   `{"type": "constant", "value": {"type": "integer", "value": 1}}`
+//! - enums without data are the variant names in camelCase, such as `"greaterThan"`,
+//!   except `NativeDataType`, which is the name of the type in CQL, such as `"bigint"`
+//! - `Expression` is the object of its kind with `span`, the byte offsets of the expression,
+//!   such as `{"type": "identifier", "value": "a", "span": {"start": 7, "end": 8}}`,
+//!   and the statement structs have `span` as well
+//!
+//! Renaming a type, a field or a variant changes the JSON, so `tests/wasm.rs`
+//! compares the JSON of representative statements with snapshots.
 
 use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
 
 use crate::error::ParseError;
-use crate::{Keyword, Token, TokenType};
+use crate::{Keyword, Parser, Token, TokenType};
+
+// Implements `PartialEq` of a statement, comparing all the fields except `span`
+macro_rules! impl_eq_ignoring_span {
+    ($statement:ident { $($field:ident),* $(,)? }) => {
+        impl PartialEq for $statement {
+            fn eq(&self, other: &Self) -> bool {
+                // destructured, so that a new field is not left out
+                let $statement { $($field,)* span: _ } = self;
+                $(*$field == other.$field)&&*
+            }
+        }
+    };
+}
 
+mod bind_marker;
 mod ddl;
 mod dml;
+mod equality;
+mod identifier;
 mod query;
+pub mod transform;
+pub mod visit;
 
+pub use bind_marker::*;
 pub use ddl::*;
 pub use dml::*;
+pub use identifier::*;
 pub use query::*;
 
 /// # Qualified name
@@ -77,21 +119,313 @@ pub use query::*;
 /// such as table name, index name, function names, etc.
 ///
 /// `keyspace` part can be omittedm, by providing `None` to `keyspace`.
-#[derive(Debug, PartialEq)]
+///
+/// Names are stored as interpreted by Cassandra: unquoted names are lowercased,
+/// and quoted names are kept as written without surrounding `"`.
+/// When parsed with `ParserOptions::preserve_identifier_case`, unquoted names keep
+/// their original case, and `normalized_keyspace` and `normalized_name` return
+/// the names as interpreted by Cassandra.
+/// Whether each part was quoted is kept in `keyspace_quoted` and `name_quoted`,
+/// but it does not affect equality, since `"tbl"` and `tbl` refer to the same table.
+///
+/// `Display` quotes the names only when needed, and `FromStr` parses names
+/// such as `ks.tbl` or `"Ks"."Tbl"`.
+/// Keyspace filled from `USE` with `ParserOptions::resolve_use_keyspace` is marked
+/// as `KeyspaceSource::Inherited`, and is not written by `Display`.
+///
+/// ```
+/// use cqlparser::ast::QualifiedName;
+/// let name: QualifiedName = "Ks.\"Tbl\"".parse().unwrap();
+/// assert_eq!(name.keyspace.as_deref(), Some("ks"));
+/// assert_eq!(&*name.name, "Tbl");
+/// assert!(name.name_quoted);
+/// assert_eq!(name.to_string(), "ks.\"Tbl\"");
+/// ```
+#[derive(Clone, Debug)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub struct QualifiedName {
-    pub keyspace: Option<String>,
-    pub name: String,
+    pub keyspace: Option<Identifier>,
+    pub name: Identifier,
+    /// True if keyspace name was quoted, and is case-sensitive
+    pub keyspace_quoted: bool,
+    /// True if name was quoted, and is case-sensitive
+    pub name_quoted: bool,
+    /// Whether `keyspace` was written in the name or inherited from `USE`
+    pub keyspace_source: KeyspaceSource,
 }
 
 impl QualifiedName {
     pub fn new(keyspace: Option<String>, name: String) -> Self {
-        QualifiedName { keyspace, name }
+        QualifiedName {
+            keyspace: keyspace.map(Identifier::from),
+            name: name.into(),
+            keyspace_quoted: false,
+            name_quoted: false,
+            keyspace_source: KeyspaceSource::Explicit,
+        }
+    }
+
+    /// Returns true if the keyspace is inherited from the preceding `USE` statement
+    pub fn is_keyspace_inherited(&self) -> bool {
+        self.keyspace.is_some() && self.keyspace_source == KeyspaceSource::Inherited
+    }
+
+    /// Returns keyspace name as interpreted by Cassandra,
+    /// lowercased unless quoted
+    pub fn normalized_keyspace(&self) -> Option<String> {
+        self.keyspace.as_ref().map(|keyspace| {
+            if self.keyspace_quoted {
+                keyspace.to_string()
+            } else {
+                keyspace.to_ascii_lowercase()
+            }
+        })
+    }
+
+    /// Returns name as interpreted by Cassandra, lowercased unless quoted
+    pub fn normalized_name(&self) -> String {
+        if self.name_quoted {
+            self.name.to_string()
+        } else {
+            self.name.to_ascii_lowercase()
+        }
+    }
+}
+
+impl PartialEq for QualifiedName {
+    fn eq(&self, other: &Self) -> bool {
+        self.keyspace == other.keyspace && self.name == other.name
+    }
+}
+
+impl fmt::Display for QualifiedName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.keyspace {
+            Some(keyspace) if !self.is_keyspace_inherited() => {
+                write_name(f, keyspace, self.keyspace_quoted)?;
+                f.write_str(".")?;
+            }
+            _ => {}
+        }
+        write_name(f, &self.name, self.name_quoted)
+    }
+}
+
+impl FromStr for QualifiedName {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Parser::parse_qualified_name_only(s)
+    }
+}
+
+/// Where the keyspace of `QualifiedName` comes from
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
+pub enum KeyspaceSource {
+    /// Written in the name, such as `ks.tbl`, or not given at all
+    #[default]
+    Explicit,
+    /// Filled from the preceding `USE` statement
+    /// with `ParserOptions::resolve_use_keyspace`
+    Inherited,
+}
+
+// Writes `name` as CQL identifier, surrounding with `"` if it cannot be written as is
+//
+// Names need to be quoted when they contain characters other than lowercase
+// letters, digits and `_`, or when they are keywords other than the basic unreserved
+// ones and `KEY`, which are allowed in any kind of names. For example, `count` and `int`
+// can be column names but not type names.
+// Uppercase letters are allowed in unquoted names, since they are only kept
+// when the original case is preserved.
+fn write_name(f: &mut fmt::Formatter<'_>, name: &str, quoted: bool) -> fmt::Result {
+    let is_letter = |c: &char| c.is_ascii_lowercase() || (!quoted && c.is_ascii_uppercase());
+    let mut chars = name.chars();
+    let is_plain = chars.next().is_some_and(|c| is_letter(&c))
+        && chars.all(|c| is_letter(&c) || c.is_ascii_digit() || c == '_')
+        && match Keyword::from_string(name) {
+            Some(k) => k.is_basic_unreserved_keyword() || k == Keyword::Key,
+            None => true,
+        };
+    if is_plain {
+        f.write_str(name)
+    } else {
+        write!(f, "\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+// Writes a part of user defined type name. Names such as `map` or `frozen` are quoted,
+// since they are read as the type keywords otherwise.
+fn write_type_name(f: &mut fmt::Formatter<'_>, name: &str, quoted: bool) -> fmt::Result {
+    match Keyword::from_string(name) {
+        Some(Keyword::Map | Keyword::List | Keyword::Set | Keyword::Tuple | Keyword::Frozen) => {
+            write!(f, "\"{}\"", name)
+        }
+        _ => write_name(f, name, quoted),
+    }
+}
+
+// Writes function name. Keywords allowed as function names, such as `token`
+// or `writetime`, are not quoted unless they were, since quoting them makes them
+// user defined functions. Other keywords are always quoted.
+fn write_function_name(f: &mut fmt::Formatter<'_>, name: &QualifiedName) -> fmt::Result {
+    if let Some(keyspace) = &name.keyspace {
+        write_name(f, keyspace, name.keyspace_quoted)?;
+        f.write_str(".")?;
+    }
+    match Keyword::from_string(&name.name) {
+        Some(k)
+            if !name.name_quoted
+                && (k.is_unreserved_for_function_name()
+                    || (name.keyspace.is_none()
+                        && matches!(k, Keyword::WriteTime | Keyword::Ttl))) =>
+        {
+            f.write_str(&name.name)
+        }
+        Some(_) => write!(f, "\"{}\"", name.name),
+        None => write_name(f, &name.name, name.name_quoted),
+    }
+}
+
+// Writes items separated by `separator`
+pub(crate) fn write_separated<I>(
+    f: &mut fmt::Formatter<'_>,
+    items: I,
+    separator: &str,
+) -> fmt::Result
+where
+    I: IntoIterator,
+    I::Item: fmt::Display,
+{
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            f.write_str(separator)?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+// Displays identifier stored as interpreted by Cassandra,
+// quoting it when it has uppercase letters or needs quotes otherwise
+pub(crate) struct DisplayIdentifier<'a>(pub(crate) &'a str);
+
+impl fmt::Display for DisplayIdentifier<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_name(f, self.0, true)
+    }
+}
+
+/// Returns identifier as interpreted by Cassandra
+///
+/// Quoted identifier is unquoted with `""` unescaped to `"`, and unquoted identifier
+/// is lowercased. This is meant for identifiers parsed with
+/// `ParserOptions::preserve_identifier_case`, which are kept as written in the source.
+///
+/// ```
+/// use cqlparser::ast::normalize_identifier;
+/// assert_eq!(normalize_identifier("ColA"), "cola");
+/// assert_eq!(normalize_identifier("\"ColA\""), "ColA");
+/// assert_eq!(normalize_identifier("\"a\"\"b\""), "a\"b");
+/// ```
+pub fn normalize_identifier(identifier: &str) -> String {
+    if identifier.len() >= 2 && identifier.starts_with('"') && identifier.ends_with('"') {
+        // remove surounding `"`, and replace `""` with single `"`
+        identifier[1..identifier.len() - 1].replace("\"\"", "\"")
+    } else {
+        identifier.to_ascii_lowercase()
+    }
+}
+
+/// # Span
+///
+/// Position of the AST node in the original CQL string,
+/// as a range of byte offsets from the beginning (`start..end`).
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    /// Length of the span in bytes
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns the span as a range of byte offsets, to slice the original CQL string
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+impl From<Span> for Range<usize> {
+    fn from(span: Span) -> Self {
+        span.range()
+    }
+}
+
+/// # Spanned node
+///
+/// AST node with its position in the original CQL string.
+///
+/// Two `Spanned` nodes are equal when their nodes are equal regardless of their spans
+/// and comments, so that the same statements written in different formats compare equal.
+#[derive(Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Span,
+    /// Comments preceding the node, including `--`, `//` or `/* */` markers.
+    ///
+    /// Only collected for statements when `ParserOptions::preserve_comments` is set.
+    pub leading_comments: Vec<String>,
+    /// Comment following the node on the same line, such as
+    /// `SELECT * FROM t; -- comment`.
+    ///
+    /// Only collected for statements when `ParserOptions::preserve_comments` is set.
+    pub trailing_comment: Option<String>,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(node: T, span: Span) -> Self {
+        Spanned {
+            node,
+            span,
+            leading_comments: Vec::new(),
+            trailing_comment: None,
+        }
+    }
+
+    /// Consumes this and returns the inner node
+    pub fn into_inner(self) -> T {
+        self.node
+    }
+}
+
+impl<T: PartialEq> PartialEq for Spanned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.node == other.node
     }
 }
 
 #[derive(Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub struct UnaryOp<A, R> {
     operator: R,
     operand: A,
@@ -101,10 +435,23 @@ impl<A, R> UnaryOp<A, R> {
     pub fn new(operator: R, operand: A) -> Self {
         UnaryOp { operator, operand }
     }
+
+    pub fn operator(&self) -> &R {
+        &self.operator
+    }
+
+    pub fn operand(&self) -> &A {
+        &self.operand
+    }
+
+    pub fn operand_mut(&mut self) -> &mut A {
+        &mut self.operand
+    }
 }
 
 #[derive(Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub struct BinaryOp<A, R> {
     left: A,
     operator: R,
@@ -119,11 +466,40 @@ impl<A, R> BinaryOp<A, R> {
             right,
         }
     }
+
+    pub fn left(&self) -> &A {
+        &self.left
+    }
+
+    pub fn operator(&self) -> &R {
+        &self.operator
+    }
+
+    pub fn right(&self) -> &A {
+        &self.right
+    }
+
+    pub fn left_mut(&mut self) -> &mut A {
+        &mut self.left
+    }
+
+    pub fn right_mut(&mut self) -> &mut A {
+        &mut self.right
+    }
 }
 
 /// Literal
 #[derive(Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    serde(
+        tag = "type",
+        content = "value",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )
+)]
 pub enum Literal {
     /// Constant literals
     Constant(Constant),
@@ -134,9 +510,19 @@ pub enum Literal {
     /// List collection literal
     List(Vec<Expression>),
 
+    /// ## List of constants of the same kind
+    ///
+    /// The parser holds list literals and values of `IN`, such as `IN (1, 2, 3)`,
+    /// as this compact form instead of `List` when every element is a constant
+    /// of the same kind, such as integers or string literals.
+    /// Written as a list literal, `[1, 2, 3]`.
+    ConstantList(Vec<Constant>),
+
     /// ## Set literal
     /// Example: {1, 2, 3}
-    Set,
+    ///
+    /// Empty `{}` is parsed as an empty `Map`. See `Literal::reinterpret_as`.
+    Set(Vec<Expression>),
 
     /// ## Map literal
     /// Example: {key1: 1, key2: 2}
@@ -156,11 +542,22 @@ pub enum Literal {
     Binding(Option<String>),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    serde(
+        tag = "type",
+        content = "value",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )
+)]
 pub enum Constant {
     StringLiteral(String),
-    Integer(u32),
+    /// ## Integer literal, which can be negative such as `-1`
+    Integer(i64),
+    /// ## Float literal as written, which can be negative such as `-1.5e-3`
     Float(String),
     Boolean(bool),
     Duration(String),
@@ -169,6 +566,7 @@ pub enum Constant {
     /// Note: This library does not convert UUID string to 128-bit UUID,
     /// and it may not be a valid UUID.
     /// It is a user's responsibility to parse UUID string.
+    #[cfg_attr(target_arch = "wasm32", serde(rename = "uuid"))]
     UUID(String),
     /// ## Binary data
     Bytes(Vec<u8>),
@@ -178,9 +576,216 @@ pub enum Constant {
     Infinity,
 }
 
+impl Literal {
+    /// Converts this literal to the kind of literal for given collection type,
+    /// and returns false if the literal cannot be a value of the type
+    ///
+    /// The parser does not know the types of columns, so `{}` is always parsed
+    /// as an empty map. Consumers that know the type can normalize it:
+    ///
+    /// ```
+    /// use cqlparser::ast::{CollectionType, CqlType, Literal, NativeDataType};
+    ///
+    /// let set_type = CollectionType::Set(Box::new(CqlType::Native(NativeDataType::Int)));
+    /// let mut literal = Literal::Map(Vec::new());
+    /// assert!(literal.reinterpret_as(&set_type));
+    /// assert_eq!(literal, Literal::Set(Vec::new()));
+    ///
+    /// let mut literal = Literal::List(Vec::new());
+    /// assert!(!literal.reinterpret_as(&set_type));
+    /// ```
+    ///
+    /// Empty maps and sets are converted to each other, as both are written as `{}`.
+    /// `NULL` and bind markers are values of any type, and are kept as they are.
+    pub fn reinterpret_as(&mut self, collection_type: &CollectionType) -> bool {
+        match (collection_type, &*self) {
+            (_, Literal::Null | Literal::Binding(_))
+            | (CollectionType::List(_), Literal::List(_) | Literal::ConstantList(_))
+            | (CollectionType::Set(_), Literal::Set(_))
+            | (CollectionType::Map { .. }, Literal::Map(_)) => true,
+            (CollectionType::Set(_), Literal::Map(entries)) if entries.is_empty() => {
+                *self = Literal::Set(Vec::new());
+                true
+            }
+            (CollectionType::Map { .. }, Literal::Set(elements)) if elements.is_empty() => {
+                *self = Literal::Map(Vec::new());
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Formats literal as written in CQL
+///
+/// `UserType` does not hold its fields, and is written as `{}`.
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, None)
+    }
+}
+
+impl Literal {
+    // Writes this literal, with its elements limited to `depth` levels
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: Option<usize>) -> fmt::Result {
+        let elements = |elements| {
+            <[Expression]>::iter(elements).map(move |expression| Limited { expression, depth })
+        };
+        match self {
+            Literal::Constant(constant) => write!(f, "{}", constant),
+            Literal::Null => f.write_str("NULL"),
+            Literal::List(list) => {
+                f.write_str("[")?;
+                write_separated(f, elements(list), ", ")?;
+                f.write_str("]")
+            }
+            Literal::ConstantList(constants) => {
+                f.write_str("[")?;
+                write_separated(f, constants, ", ")?;
+                f.write_str("]")
+            }
+            Literal::Set(set) => {
+                f.write_str("{")?;
+                write_separated(f, elements(set), ", ")?;
+                f.write_str("}")
+            }
+            Literal::UserType => f.write_str("{}"),
+            Literal::Map(entries) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    let key = Limited {
+                        expression: key,
+                        depth,
+                    };
+                    let value = Limited {
+                        expression: value,
+                        depth,
+                    };
+                    write!(f, "{}: {}", key, value)?;
+                }
+                f.write_str("}")
+            }
+            Literal::Tuple(tuple) => {
+                f.write_str("(")?;
+                write_separated(f, elements(tuple), ", ")?;
+                f.write_str(")")
+            }
+            Literal::Binding(None) => f.write_str("?"),
+            Literal::Binding(Some(name)) => write!(f, ":{}", DisplayIdentifier(name)),
+        }
+    }
+
+    // Returns true if this literal has no expressions inside
+    fn is_leaf(&self) -> bool {
+        !matches!(
+            self,
+            Literal::List(_)
+                | Literal::ConstantList(_)
+                | Literal::Set(_)
+                | Literal::Map(_)
+                | Literal::Tuple(_)
+        )
+    }
+}
+
+/// Formats constant as written in CQL, quoting string literals with `'`
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constant::StringLiteral(s) => write!(f, "'{}'", s.replace('\'', "''")),
+            Constant::Integer(i) => write!(f, "{}", i),
+            Constant::Float(value) | Constant::Duration(value) | Constant::UUID(value) => {
+                f.write_str(value)
+            }
+            Constant::Boolean(b) => write!(f, "{}", b),
+            Constant::Bytes(bytes) => {
+                f.write_str("0x")?;
+                bytes.iter().try_for_each(|b| write!(f, "{:02x}", b))
+            }
+            Constant::NaN => f.write_str("NaN"),
+            Constant::Infinity => f.write_str("Infinity"),
+        }
+    }
+}
+
+impl Constant {
+    /// Returns numeric value of this constant as `f64`.
+    ///
+    /// Returns `None` if this is not a numeric constant,
+    /// or the value is out of range of `f64`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Constant::Integer(i) => Some(*i as f64),
+            Constant::Float(f) => f.parse::<f64>().ok().filter(|f| f.is_finite()),
+            Constant::NaN => Some(f64::NAN),
+            Constant::Infinity => Some(f64::INFINITY),
+            _ => None,
+        }
+    }
+
+    /// Returns integer value of this constant as `i64`.
+    ///
+    /// Returns `None` if this is not an integer constant.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Constant::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// Returns 16 bytes of UUID.
+    ///
+    /// Returns `None` if this is not a UUID constant or not a valid UUID.
+    pub fn as_uuid_bytes(&self) -> Option<[u8; 16]> {
+        match self {
+            Constant::UUID(uuid) => crate::literal::parse_uuid_bytes(uuid),
+            _ => None,
+        }
+    }
+
+    /// Returns months, days, and nanoseconds of duration, as Cassandra stores them.
+    ///
+    /// Years are converted to months, weeks to days, and hours and smaller units to nanoseconds.
+    /// All the components of negative durations, such as `-3d`, are negative.
+    /// Returns `None` if this is not a duration constant or any component overflows.
+    ///
+    /// ```
+    /// use cqlparser::ast::Constant;
+    /// let duration = Constant::Duration("P1Y2M3DT4H".to_string());
+    /// assert_eq!(duration.as_duration(), Some((14, 3, 4 * 3_600_000_000_000)));
+    /// let duration = Constant::Duration("-1mo3d".to_string());
+    /// assert_eq!(duration.as_duration(), Some((-1, -3, 0)));
+    /// ```
+    pub fn as_duration(&self) -> Option<(i32, i32, i64)> {
+        match self {
+            Constant::Duration(duration) => crate::literal::parse_duration(duration),
+            _ => None,
+        }
+    }
+
+    /// Returns months component of duration. See [`Constant::as_duration`].
+    pub fn as_duration_months(&self) -> Option<i32> {
+        self.as_duration().map(|(months, _, _)| months)
+    }
+
+    /// Returns days component of duration. See [`Constant::as_duration`].
+    pub fn as_duration_days(&self) -> Option<i32> {
+        self.as_duration().map(|(_, days, _)| days)
+    }
+
+    /// Returns nanoseconds component of duration. See [`Constant::as_duration`].
+    pub fn as_duration_nanos(&self) -> Option<i64> {
+        self.as_duration().map(|(_, _, nanos)| nanos)
+    }
+}
+
 /// Operators
 #[derive(Eq, PartialEq, Debug)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub enum Operator {
     /// '+': arithmetic operator for addition
     Plus,
@@ -224,6 +829,32 @@ pub enum Operator {
     And,
 }
 
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Operator::Plus => "+",
+            Operator::Minus => "-",
+            Operator::Multiply => "*",
+            Operator::Divide => "/",
+            Operator::Modulus => "%",
+            Operator::Dot => ".",
+            Operator::LBracket => "[",
+            Operator::Equal => "=",
+            Operator::NotEqual => "!=",
+            Operator::LessThan => "<",
+            Operator::LessThanOrEqual => "<=",
+            Operator::GreaterThan => ">",
+            Operator::GreaterThanOrEqual => ">=",
+            Operator::In => "IN",
+            Operator::Contains => "CONTAINS",
+            Operator::ContainsKey => "CONTAINS KEY",
+            Operator::IsNot => "IS NOT",
+            Operator::Like => "LIKE",
+            Operator::And => "AND",
+        })
+    }
+}
+
 impl TryFrom<&Token> for Operator {
     type Error = ParseError;
 
@@ -241,6 +872,9 @@ impl TryFrom<&Token> for Operator {
             TokenType::Lt => Ok(Operator::LessThan),
             TokenType::Lte => Ok(Operator::LessThanOrEqual),
             TokenType::Keyword(Keyword::And) => Ok(Operator::And),
+            TokenType::Keyword(Keyword::In) => Ok(Operator::In),
+            TokenType::Keyword(Keyword::Contains) => Ok(Operator::Contains),
+            TokenType::Keyword(Keyword::Like) => Ok(Operator::Like),
             _ => Err(ParseError::with_message(format!(
                 "Cannot convert {:?} for operator!",
                 tt
@@ -264,13 +898,50 @@ impl TryFrom<&Token> for Operator {
 /// - `1 + 1`: binary operation
 /// - `udt.prop1`: UDT access
 /// - `map['key']: collection access
+///
+/// The kind of the expression with its operands is `ExpressionKind`.
+/// Span is not taken into account when comparing expressions.
+#[derive(Debug)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
+pub struct Expression {
+    #[cfg_attr(target_arch = "wasm32", serde(flatten))]
+    pub kind: ExpressionKind,
+    /// Position of the expression in the original CQL, including its parentheses if any
+    ///
+    /// Empty for expressions that are not parsed, such as the ones created with `Expression::new`.
+    pub span: Span,
+}
+
+impl PartialEq for Expression {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+    }
+}
+
+impl From<ExpressionKind> for Expression {
+    fn from(kind: ExpressionKind) -> Self {
+        Expression::new(kind)
+    }
+}
+
+/// Kind of `Expression`, with its operands
 #[derive(Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
-pub enum Expression {
+#[cfg_attr(
+    target_arch = "wasm32",
+    serde(
+        tag = "type",
+        content = "value",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )
+)]
+pub enum ExpressionKind {
     /// # Identifier
     ///
     /// In CQL, if the string is not quoted with `"`, case is not preserved.
-    Identifier(String),
+    Identifier(Identifier),
     /// Unary operation
     UnaryOp(UnaryOp<Box<Expression>, Operator>),
     /// Binary operation
@@ -288,15 +959,23 @@ pub enum Expression {
         ///
         /// Function name consists of optional keyspace name followed by `.`, and one of the followings:
         /// - Identifier
-        /// - Quoted string literal
+        /// - Quoted name
         /// - Unreserved keywords or native data type name
         /// - `TOKEN` keyword or `COUNT` keyword
-        name: Box<Expression>,
+        /// - `WRITETIME` or `TTL` keyword, without keyspace
+        ///
+        /// `keyspace` is `None` unless written, such as `system.tojson`,
+        /// since the keyspace from `USE` is not applied to functions.
+        name: QualifiedName,
         args: Vec<Expression>,
     },
+    /// `CAST(expr AS type)`, which converts the value to a native type
+    ///
     /// `cast` function is treated differently,
     /// since the argument is in the form of `xxx AS type`.
-    /// Another form of type cast is `(type) simple_term`.
+    /// Cassandra accepts it only in selectors.
+    Cast(CqlType, Box<Expression>),
+    /// Type hint of a term, `(type) simple_term`, such as `(int) ?`
     ///
     /// In CQL3 Parser, this is defined as one of simple terms.
     TypeCast(CqlType, Box<Expression>),
@@ -312,35 +991,499 @@ pub enum Expression {
 }
 
 impl Expression {
+    /// Creates new expression of given kind, without position
+    pub fn new(kind: ExpressionKind) -> Self {
+        Expression {
+            kind,
+            span: Span::default(),
+        }
+    }
+
+    /// Creates new expression with its position in the original CQL
+    pub fn with_span(kind: ExpressionKind, span: Span) -> Self {
+        Expression { kind, span }
+    }
+
     /// Expression is a "Simple Term" if it is one of:
     /// - Value
     /// - Function call
-    /// - Type cast
+    /// - Type cast or type hint
     pub fn is_simple_term(&self) -> bool {
+        matches!(
+            self.kind,
+            ExpressionKind::Value(_)
+                | ExpressionKind::Function { .. }
+                | ExpressionKind::Cast(_, _)
+                | ExpressionKind::TypeCast(_, _)
+        )
+    }
+
+    /// Returns the operands and the operator if this is a relation,
+    /// that is, a binary operation of a relational operator such as `a = 1`
+    ///
+    /// ```
+    /// use cqlparser::ast::{Constant, Operator};
+    /// use cqlparser::Parser;
+    ///
+    /// let expression = Parser::parse_expression_only("a >= 1").unwrap();
+    /// let (left, operator, right) = expression.as_relation().unwrap();
+    /// assert_eq!(left.as_column(), Some("a"));
+    /// assert_eq!(*operator, Operator::GreaterThanOrEqual);
+    /// assert_eq!(right.as_constant(), Some(&Constant::Integer(1)));
+    /// ```
+    pub fn as_relation(&self) -> Option<(&Expression, &Operator, &Expression)> {
+        match &self.kind {
+            ExpressionKind::BinaryOp(op) if op.operator().is_relational() => {
+                Some((op.left(), op.operator(), op.right()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the name if this is an identifier, such as a column name
+    pub fn as_column(&self) -> Option<&str> {
+        match &self.kind {
+            ExpressionKind::Identifier(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Returns the constant if this is a constant literal
+    pub fn as_constant(&self) -> Option<&Constant> {
+        match &self.kind {
+            ExpressionKind::Value(Literal::Constant(constant)) => Some(constant),
+            _ => None,
+        }
+    }
+
+    /// Returns the operands of `AND` in the order of the source,
+    /// flattening nested `AND`s such as `a = 1 AND (b = 2 AND c = 3)`
+    ///
+    /// Returns this expression alone if it is not `AND`.
+    pub fn and_operands(&self) -> Vec<&Expression> {
+        let mut operands = Vec::new();
+        // operands not visited yet, with the leftmost at the top
+        let mut stack = vec![self];
+        while let Some(expression) = stack.pop() {
+            match &expression.kind {
+                ExpressionKind::BinaryOp(op) if *op.operator() == Operator::And => {
+                    stack.push(op.right());
+                    stack.push(op.left());
+                }
+                _ => operands.push(expression),
+            }
+        }
+        operands
+    }
+}
+
+// Precedence of expressions when formatting, which follows the one of the parser.
+// Operands with lower precedence than their operator are parenthesized.
+const PRECEDENCE_PREFIX: u8 = 6;
+const PRECEDENCE_ATOM: u8 = 7;
+
+impl Operator {
+    /// Returns true if this is one of the arithmetic operators `+`, `-`, `*`, `/` and `%`
+    pub fn is_arithmetic(&self) -> bool {
+        matches!(
+            self,
+            Operator::Plus
+                | Operator::Minus
+                | Operator::Multiply
+                | Operator::Divide
+                | Operator::Modulus
+        )
+    }
+
+    /// Returns true if this operator relates two terms, such as `=`, `<`, `IN` or `CONTAINS`
+    pub fn is_relational(&self) -> bool {
+        matches!(
+            self,
+            Operator::Equal
+                | Operator::NotEqual
+                | Operator::LessThan
+                | Operator::LessThanOrEqual
+                | Operator::GreaterThan
+                | Operator::GreaterThanOrEqual
+                | Operator::In
+                | Operator::Contains
+                | Operator::ContainsKey
+                | Operator::IsNot
+                | Operator::Like
+        )
+    }
+
+    fn precedence(&self) -> u8 {
         match self {
-            Self::Value(_) | Self::Function { .. } | Self::TypeCast(_, _) => true,
+            Operator::And => 1,
+            Operator::LessThan
+            | Operator::LessThanOrEqual
+            | Operator::GreaterThan
+            | Operator::GreaterThanOrEqual => 3,
+            Operator::Plus | Operator::Minus => 4,
+            Operator::Multiply | Operator::Divide | Operator::Modulus => 5,
+            Operator::Dot | Operator::LBracket => PRECEDENCE_ATOM,
+            _ => 2,
+        }
+    }
+}
+
+impl Expression {
+    fn precedence(&self) -> u8 {
+        match &self.kind {
+            ExpressionKind::BinaryOp(op) => op.operator().precedence(),
+            ExpressionKind::UnaryOp(_) => PRECEDENCE_PREFIX,
+            ExpressionKind::TypeCast(_, _) => PRECEDENCE_PREFIX,
+            _ => PRECEDENCE_ATOM,
+        }
+    }
+
+    // Returns true if this expression is written starting with a number or `-`,
+    // such as `1[0]`, which becomes a negative constant when written after `-`
+    fn starts_with_number_or_minus(&self) -> bool {
+        let mut expression = self;
+        // receivers of `a[0][1]...` in a loop, which can be thousands deep
+        while let ExpressionKind::CollectionSubSelection { receiver, .. } = &expression.kind {
+            if receiver.precedence() < PRECEDENCE_ATOM {
+                return false;
+            }
+            expression = receiver;
+        }
+        matches!(
+            expression.kind,
+            ExpressionKind::UnaryOp(_)
+                | ExpressionKind::Value(Literal::Constant(
+                    Constant::Integer(_) | Constant::Float(_) | Constant::Duration(_),
+                ))
+        )
+    }
+
+    /// Returns this expression written as CQL in a single line, with subexpressions
+    /// nested deeper than `max_depth` levels written as `...`
+    ///
+    /// Unlike `Debug` output, the summary stays short for large expressions,
+    /// such as the ones in log messages. Identifiers and constants are always written.
+    ///
+    /// ```
+    /// use cqlparser::Parser;
+    ///
+    /// let expression = Parser::parse_expression_only("f(a + b * c, [1, 2]) = 1").unwrap();
+    /// assert_eq!(expression.summary(2), "f(..., ...) = 1");
+    /// assert_eq!(expression.summary(3), "f(a + ..., [1, 2]) = 1");
+    /// assert_eq!(expression.summary(4), expression.to_string());
+    /// ```
+    pub fn summary(&self, max_depth: usize) -> String {
+        Limited {
+            expression: self,
+            depth: Some(max_depth),
+        }
+        .to_string()
+    }
+
+    // Returns true if this expression has no subexpressions
+    fn is_leaf(&self) -> bool {
+        match &self.kind {
+            ExpressionKind::Identifier(_) => true,
+            ExpressionKind::Value(literal) => literal.is_leaf(),
             _ => false,
         }
     }
+
+    // Writes this expression, with subexpressions limited to `depth` levels below this one
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: Option<usize>) -> fmt::Result {
+        let child = |expression| Limited { expression, depth };
+        match &self.kind {
+            ExpressionKind::Identifier(name) => write!(f, "{}", DisplayIdentifier(name)),
+            ExpressionKind::UnaryOp(op) => {
+                write!(f, "{}", op.operator())?;
+                // avoid writing `--`, which starts a comment,
+                // and `-1` or `-1d`, which are negative constants
+                if op.operand().starts_with_number_or_minus() {
+                    f.write_str(" ")?;
+                }
+                child(op.operand()).write_operand(f, PRECEDENCE_PREFIX)
+            }
+            ExpressionKind::BinaryOp(_) | ExpressionKind::CollectionSubSelection { .. } => {
+                write_chain(f, self, depth)
+            }
+            ExpressionKind::Value(literal) => literal.write(f, depth),
+            ExpressionKind::Function { name, args } => {
+                write_function_name(f, name)?;
+                f.write_str("(")?;
+                write_separated(f, args.iter().map(child), ", ")?;
+                f.write_str(")")
+            }
+            ExpressionKind::Cast(cql_type, expr) => {
+                write!(f, "CAST({} AS {})", child(expr), cql_type)
+            }
+            ExpressionKind::TypeCast(cql_type, expr) => {
+                write!(f, "({}) ", cql_type)?;
+                child(expr).write_operand(f, PRECEDENCE_PREFIX)
+            }
+        }
+    }
+}
+
+// Expression written with its subexpressions limited to `depth` levels,
+// or without limit if `depth` is `None`
+//
+// Expressions below the limit are written as `...`, except identifiers and constants.
+struct Limited<'a> {
+    expression: &'a Expression,
+    depth: Option<usize>,
+}
+
+impl Limited<'_> {
+    fn is_elided(&self) -> bool {
+        self.depth == Some(0) && !self.expression.is_leaf()
+    }
+
+    // Writes the expression, parenthesized if it binds looser than `precedence`
+    fn write_operand(&self, f: &mut fmt::Formatter<'_>, precedence: u8) -> fmt::Result {
+        if !self.is_elided() && self.expression.precedence() < precedence {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+}
+
+impl fmt::Display for Limited<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_elided() {
+            return f.write_str("...");
+        }
+        // subexpressions are one level below
+        let depth = self.depth.map(|depth| depth.saturating_sub(1));
+        self.expression.write(f, depth)
+    }
+}
+
+// Operation written after its left operand, such as `+ b` of `a + b` or `[0]` of `m[0]`
+enum ChainLink<'a> {
+    BinaryOp(&'a BinaryOp<Box<Expression>, Operator>),
+    Subscript {
+        element: &'a Expression,
+        upto: Option<&'a Expression>,
+    },
+}
+
+impl<'a> ChainLink<'a> {
+    // Returns the operation of `expression` and its left operand
+    fn split(expression: &'a Expression) -> Option<(Self, &'a Expression)> {
+        match &expression.kind {
+            ExpressionKind::BinaryOp(op) => Some((ChainLink::BinaryOp(op), op.left())),
+            ExpressionKind::CollectionSubSelection {
+                receiver,
+                element,
+                upto,
+            } => Some((
+                ChainLink::Subscript {
+                    element,
+                    upto: upto.as_deref(),
+                },
+                receiver,
+            )),
+            _ => None,
+        }
+    }
+
+    // Precedence of the left operand written without parentheses
+    fn precedence(&self) -> u8 {
+        match self {
+            ChainLink::BinaryOp(op) => op.operator().precedence(),
+            ChainLink::Subscript { .. } => PRECEDENCE_ATOM,
+        }
+    }
+
+    // Writes the operation after the left operand, with the operands limited to `depth`
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: Option<usize>) -> fmt::Result {
+        match self {
+            ChainLink::BinaryOp(op) => {
+                // operators are left associative
+                let right_precedence = match op.operator() {
+                    // field of user defined type, which is always a name
+                    Operator::Dot => {
+                        f.write_str(".")?;
+                        PRECEDENCE_ATOM
+                    }
+                    operator => {
+                        write!(f, " {} ", operator)?;
+                        operator.precedence() + 1
+                    }
+                };
+                Limited {
+                    expression: op.right(),
+                    depth,
+                }
+                .write_operand(f, right_precedence)
+            }
+            ChainLink::Subscript { element, upto } => {
+                let element = Limited {
+                    expression: element,
+                    depth,
+                }
+                .to_string();
+                write!(f, "[{}", element)?;
+                if let Some(upto) = upto {
+                    // `3. ..4`, not `3...4`
+                    let separator = if element.ends_with('.') { " .." } else { ".." };
+                    let upto = Limited {
+                        expression: upto,
+                        depth,
+                    };
+                    write!(f, "{}{}", separator, upto)?;
+                }
+                f.write_str("]")
+            }
+        }
+    }
+}
+
+// Writes binary operation or collection sub selection limited to `depth` levels below it
+//
+// Operations on the left operands, such as `a + b + c + ...` parsed as `((a + b) + c) + ...`
+// or `m[0][1]...`, are written in a loop, so that long chains of thousands of operations
+// do not overflow the stack.
+fn write_chain(
+    f: &mut fmt::Formatter<'_>,
+    expression: &Expression,
+    depth: Option<usize>,
+) -> fmt::Result {
+    // depth of the operands of `chain[i]` is `below(i)`
+    let below = |i: usize| depth.map(|depth| depth.saturating_sub(i));
+    let mut chain = Vec::new();
+    let mut left = expression;
+    while let Some((link, next_left)) = ChainLink::split(left) {
+        if let Some(last) = chain.last() {
+            // parenthesized or elided operands are written by themselves
+            if below(chain.len() - 1) == Some(0) || left.precedence() < ChainLink::precedence(last)
+            {
+                break;
+            }
+        }
+        chain.push(link);
+        left = next_left;
+    }
+    let last = &chain[chain.len() - 1];
+    Limited {
+        expression: left,
+        depth: below(chain.len() - 1),
+    }
+    .write_operand(f, last.precedence())?;
+    for (i, link) in chain.iter().enumerate().rev() {
+        link.write(f, below(i))?;
+    }
+    Ok(())
+}
+
+/// Formats expression as written in CQL
+///
+/// Identifiers are quoted when needed, and operands are parenthesized
+/// only when their precedence requires.
+/// Type cast to a native type is written as `CAST(expr AS type)`,
+/// and others as `(type) expr`.
+///
+/// ```
+/// use cqlparser::ast::{CqlStatement, Projection};
+/// use cqlparser::Parser;
+///
+/// let statements = Parser::new("SELECT (a + \"B\") * -2 FROM t").parse().unwrap();
+/// if let CqlStatement::Select(select) = &statements[0] {
+///     if let Projection::Selectors(selectors) = &select.projection {
+///         assert_eq!(selectors[0].selectable().to_string(), "(a + \"B\") * -2");
+///     }
+/// }
+/// ```
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, None)
+    }
 }
 
 /// # Property
 ///
-/// Property value is one of the following:
-/// - Constant
-/// - Unreserved keyword
-/// - Map literal
-#[derive(Debug, PartialEq)]
+/// `key = value` pair in WITH clause, such as table properties.
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub struct Property {
     key: String,
-    value: Literal,
+    value: PropertyValue,
 }
 
 impl Property {
-    pub fn new(key: String, value: Literal) -> Self {
+    pub fn new(key: String, value: PropertyValue) -> Self {
         Property { key, value }
     }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn value(&self) -> &PropertyValue {
+        &self.value
+    }
+
+    pub fn value_mut(&mut self) -> &mut PropertyValue {
+        &mut self.value
+    }
+}
+
+/// Value of a property
+///
+/// Unlike `Literal`, property values cannot be NULL, bind markers,
+/// or expressions.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    serde(
+        tag = "type",
+        content = "value",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )
+)]
+pub enum PropertyValue {
+    /// Constant such as `'text'`, `10`, `0.01`, `true`
+    /// or UUID (`ID = 5a1c395e-b41f-11e5-9f22-ba0be0483c18`)
+    Constant(Constant),
+    /// Identifier or unreserved keyword as written,
+    /// such as `LeveledCompactionStrategy` or `ALWAYS`
+    Identifier(String),
+    /// Number followed by a unit that is not a duration, as written,
+    /// such as `99PERCENTILE` or `99.9p` for `speculative_retry`
+    NumberWithUnit(String),
+    /// Map literal such as `{'class': 'SimpleStrategy', 'replication_factor': 1}`
+    Map(Vec<(PropertyValue, PropertyValue)>),
+}
+
+/// Formats property as `key = value`
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", DisplayIdentifier(&self.key), self.value)
+    }
+}
+
+impl fmt::Display for PropertyValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PropertyValue::Constant(constant) => write!(f, "{}", constant),
+            PropertyValue::Identifier(identifier) => f.write_str(identifier),
+            PropertyValue::NumberWithUnit(value) => f.write_str(value),
+            PropertyValue::Map(entries) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
 }
 
 /// # CQL data types
@@ -351,8 +1494,17 @@ impl Property {
 /// - Tuple type
 /// - User defined type
 /// - Custom data type
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    serde(
+        tag = "type",
+        content = "value",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )
+)]
 pub enum CqlType {
     /// CQL native data types such as `text`, `int`, etc.
     Native(NativeDataType),
@@ -365,11 +1517,194 @@ pub enum CqlType {
     /// Custom data type.
     ///
     /// In CQL, custom type can be specified using string literal.
-    Custom(String),
+    Custom(CustomType),
 }
 
-#[derive(Debug, PartialEq)]
+/// Custom data type, which is the Java class of the type in a string literal
+/// with optional parameters, such as
+/// `'org.apache.cassandra.db.marshal.DynamicCompositeType(s=>UTF8Type)'`
+///
+/// Parameters are not parsed, and are kept as written inside the parentheses.
+/// `Display` writes the type as written, without quotes.
+///
+/// ```
+/// use cqlparser::ast::CustomType;
+///
+/// let custom_type = CustomType::parse("org.apache.cassandra.db.marshal.ReversedType(Int32Type)");
+/// assert_eq!(custom_type.class_name, "org.apache.cassandra.db.marshal.ReversedType");
+/// assert_eq!(custom_type.parameters.as_deref(), Some("Int32Type"));
+/// assert_eq!(custom_type.simple_class_name(), "ReversedType");
+/// assert_eq!(
+///     custom_type.to_string(),
+///     "org.apache.cassandra.db.marshal.ReversedType(Int32Type)"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
+pub struct CustomType {
+    /// Class name, which is fully qualified unless it is in
+    /// `org.apache.cassandra.db.marshal`
+    pub class_name: String,
+    /// Parameters inside the parentheses following the class name, if any
+    pub parameters: Option<String>,
+}
+
+impl CustomType {
+    /// Splits the string of a custom type into the class name and the parameters
+    ///
+    /// The string is kept as written, so that `to_string` returns it as it is.
+    /// Strings that do not end with the parameters in parentheses are class names.
+    pub fn parse(raw: &str) -> CustomType {
+        match raw.find('(') {
+            Some(open) if raw.ends_with(')') => CustomType {
+                class_name: raw[..open].to_owned(),
+                parameters: Some(raw[open + 1..raw.len() - 1].to_owned()),
+            },
+            _ => CustomType {
+                class_name: raw.to_owned(),
+                parameters: None,
+            },
+        }
+    }
+
+    /// Returns the class name without the package, such as `UTF8Type`
+    pub fn simple_class_name(&self) -> &str {
+        match self.class_name.rfind('.') {
+            Some(dot) => &self.class_name[dot + 1..],
+            None => &self.class_name,
+        }
+    }
+}
+
+/// Formats custom type as written in the string literal, without quotes
+impl fmt::Display for CustomType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.class_name)?;
+        if let Some(parameters) = &self.parameters {
+            write!(f, "({})", parameters)?;
+        }
+        Ok(())
+    }
+}
+
+impl CqlType {
+    /// Returns `true` if values of this type are frozen, which are stored as a whole
+    ///
+    /// Types are frozen with `frozen<...>`, and tuples are always frozen.
+    pub fn is_frozen(&self) -> bool {
+        matches!(self, CqlType::Frozen(_) | CqlType::Tuple(_))
+    }
+
+    /// Returns `true` if this type is `counter` or contains `counter`,
+    /// such as `list<counter>`
+    ///
+    /// Fields of user defined types are not looked into.
+    pub fn contains_counter(&self) -> bool {
+        match self {
+            CqlType::Native(native_type) => *native_type == NativeDataType::Counter,
+            CqlType::Collection(CollectionType::Map {
+                key_type,
+                value_type,
+            }) => key_type.contains_counter() || value_type.contains_counter(),
+            CqlType::Collection(CollectionType::List(element))
+            | CqlType::Collection(CollectionType::Set(element)) => element.contains_counter(),
+            CqlType::Tuple(types) => types.iter().any(CqlType::contains_counter),
+            CqlType::Frozen(inner) => inner.contains_counter(),
+            CqlType::UserDefinedType(_) | CqlType::Custom(_) => false,
+        }
+    }
+
+    /// Returns this type with the `frozen<...>` that Cassandra adds implicitly,
+    /// as the type is reported by the server such as in `system_schema.columns`
+    ///
+    /// Tuples are always frozen, and so are collections, tuples and user defined types
+    /// inside collections and tuples. Compare canonicalized types to tell if
+    /// a declared type is the same as the one reported by the server.
+    ///
+    /// ```
+    /// use cqlparser::Parser;
+    ///
+    /// let declared = Parser::parse_cql_type_only("map<text, tuple<int, list<int>>>").unwrap();
+    /// assert_eq!(
+    ///     declared.canonicalize().to_string(),
+    ///     "map<text, frozen<tuple<int, frozen<list<int>>>>>"
+    /// );
+    /// ```
+    pub fn canonicalize(&self) -> CqlType {
+        self.canonicalize_in(false)
+    }
+
+    // Canonicalizes this type, which must be frozen if `nested` in a collection or a tuple
+    fn canonicalize_in(&self, nested: bool) -> CqlType {
+        // `frozen<frozen<...>>` is the same as `frozen<...>`
+        let mut inner = self;
+        let mut frozen = false;
+        while let CqlType::Frozen(frozen_type) = inner {
+            inner = frozen_type;
+            frozen = true;
+        }
+        let canonical = match inner {
+            CqlType::Collection(CollectionType::Map {
+                key_type,
+                value_type,
+            }) => CqlType::Collection(CollectionType::Map {
+                key_type: Box::new(key_type.canonicalize_in(true)),
+                value_type: Box::new(value_type.canonicalize_in(true)),
+            }),
+            CqlType::Collection(CollectionType::List(element)) => CqlType::Collection(
+                CollectionType::List(Box::new(element.canonicalize_in(true))),
+            ),
+            CqlType::Collection(CollectionType::Set(element)) => {
+                CqlType::Collection(CollectionType::Set(Box::new(element.canonicalize_in(true))))
+            }
+            CqlType::Tuple(types) => {
+                CqlType::Tuple(types.iter().map(|t| t.canonicalize_in(true)).collect())
+            }
+            other => other.clone(),
+        };
+        let must_freeze = match inner {
+            CqlType::Tuple(_) => true,
+            CqlType::Collection(_) | CqlType::UserDefinedType(_) => nested,
+            _ => false,
+        };
+        if frozen || must_freeze {
+            CqlType::Frozen(Box::new(canonical))
+        } else {
+            canonical
+        }
+    }
+}
+
+/// Formats data type as written in CQL, such as `map<text, frozen<list<int>>>`
+impl fmt::Display for CqlType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CqlType::Native(native_type) => write!(f, "{}", native_type),
+            CqlType::Collection(collection_type) => write!(f, "{}", collection_type),
+            CqlType::Tuple(types) => {
+                f.write_str("tuple<")?;
+                write_separated(f, types, ", ")?;
+                f.write_str(">")
+            }
+            CqlType::UserDefinedType(name) => {
+                if let Some(keyspace) = &name.keyspace {
+                    write_type_name(f, keyspace, name.keyspace_quoted)?;
+                    f.write_str(".")?;
+                }
+                write_type_name(f, &name.name, name.name_quoted)
+            }
+            CqlType::Frozen(inner) => write!(f, "frozen<{}>", inner),
+            CqlType::Custom(custom_type) => {
+                write!(f, "'{}'", custom_type.to_string().replace('\'', "''"))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "lowercase"))]
 pub enum NativeDataType {
     Ascii,
     BigInt,
@@ -394,9 +1729,10 @@ pub enum NativeDataType {
     Time,
 }
 
-impl From<NativeDataType> for String {
-    fn from(nt: NativeDataType) -> Self {
-        (match nt {
+impl NativeDataType {
+    /// Returns the name of this type in CQL, in lowercase
+    pub fn as_str(&self) -> &'static str {
+        match self {
             NativeDataType::Ascii => "ascii",
             NativeDataType::BigInt => "bigint",
             NativeDataType::Blob => "blob",
@@ -418,13 +1754,33 @@ impl From<NativeDataType> for String {
             NativeDataType::TimeUUID => "timeuuid",
             NativeDataType::Date => "date",
             NativeDataType::Time => "time",
-        })
-        .to_owned()
+        }
     }
 }
 
-#[derive(Debug, PartialEq)]
+impl From<NativeDataType> for String {
+    fn from(nt: NativeDataType) -> Self {
+        nt.as_str().to_owned()
+    }
+}
+
+impl fmt::Display for NativeDataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    serde(
+        tag = "type",
+        content = "value",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )
+)]
 pub enum CollectionType {
     Map {
         key_type: Box<CqlType>,
@@ -434,17 +1790,45 @@ pub enum CollectionType {
     Set(Box<CqlType>),
 }
 
+impl fmt::Display for CollectionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CollectionType::Map {
+                key_type,
+                value_type,
+            } => write!(f, "map<{}, {}>", key_type, value_type),
+            CollectionType::List(element_type) => write!(f, "list<{}>", element_type),
+            CollectionType::Set(element_type) => write!(f, "set<{}>", element_type),
+        }
+    }
+}
+
 /// Statement
+///
+/// New statements can be added in the future, so matching on `CqlStatement` outside
+/// of this crate needs a wildcard arm. Use `is_ddl`, `is_dml` etc. to classify statements.
 #[derive(Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    serde(
+        tag = "type",
+        content = "value",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )
+)]
+#[non_exhaustive]
 pub enum CqlStatement {
     Select(SelectStatement),
     Insert(InsertStatement),
     Update(UpdateStatement),
-    Delete,
-    Batch,
-    Truncate,
-    Use,
+    Delete(DeleteStatement),
+    Batch(BatchStatement),
+    /// `TRUNCATE (TABLE)? name`, holding the table name
+    Truncate(QualifiedName),
+    /// `USE keyspace`, holding the keyspace name
+    Use(String),
     CreateAggregate,
     CreateFunction,
     CreateIndex(CreateIndexStatement),
@@ -453,18 +1837,25 @@ pub enum CqlStatement {
     CreateTrigger,
     CreateType(CreateTypeStatement),
     CreateMaterializedView(CreateMaterializedViewStatement),
-    AlterKeyspace,
-    AlterTable,
-    AlterType,
+    AlterKeyspace(AlterKeyspaceStatement),
+    AlterTable(AlterTableStatement),
+    AlterType(AlterTypeStatement),
     AlterView,
-    DropAggregate,
-    DropFunction,
-    DropIndex,
-    DropKeyspace,
-    DropTable,
-    DropTrigger,
-    DropType,
-    DropView,
+    /// `DROP` statements of schema objects, such as `DROP TABLE`
+    Drop(DropStatement),
+    /// Statements that manage roles, users or permissions
+    Auth(AuthStatement),
+}
+
+/// Statement that manages roles, users or permissions
+///
+/// These statements are not parsed yet. New statements can be added in the future,
+/// so matching on `AuthStatement` outside of this crate needs a wildcard arm.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
+#[non_exhaustive]
+pub enum AuthStatement {
     AlterRole,
     CreateRole,
     DropRole,
@@ -476,3 +1867,157 @@ pub enum CqlStatement {
     GrantPermissions,
     RevokePermissions,
 }
+
+// Category of statements, used to classify `CqlStatement`
+#[derive(PartialEq)]
+enum StatementCategory {
+    Query,
+    Dml,
+    Ddl,
+    Authz,
+    Other,
+}
+
+impl CqlStatement {
+    // Every statement must be categorized here,
+    // so do not add a wildcard arm.
+    fn category(&self) -> StatementCategory {
+        match self {
+            CqlStatement::Select(_) => StatementCategory::Query,
+            CqlStatement::Insert(_)
+            | CqlStatement::Update(_)
+            | CqlStatement::Delete(_)
+            | CqlStatement::Batch(_)
+            | CqlStatement::Truncate(_) => StatementCategory::Dml,
+            CqlStatement::CreateAggregate
+            | CqlStatement::CreateFunction
+            | CqlStatement::CreateIndex(_)
+            | CqlStatement::CreateKeyspace(_)
+            | CqlStatement::CreateTable(_)
+            | CqlStatement::CreateTrigger
+            | CqlStatement::CreateType(_)
+            | CqlStatement::CreateMaterializedView(_)
+            | CqlStatement::AlterKeyspace(_)
+            | CqlStatement::AlterTable(_)
+            | CqlStatement::AlterType(_)
+            | CqlStatement::AlterView
+            | CqlStatement::Drop(_) => StatementCategory::Ddl,
+            CqlStatement::Auth(_) => StatementCategory::Authz,
+            CqlStatement::Use(_) => StatementCategory::Other,
+        }
+    }
+
+    /// Returns true if this statement defines or changes schema,
+    /// such as CREATE TABLE or DROP KEYSPACE
+    pub fn is_ddl(&self) -> bool {
+        self.category() == StatementCategory::Ddl
+    }
+
+    /// Returns true if this statement modifies data,
+    /// which is one of INSERT, UPDATE, DELETE, BATCH or TRUNCATE
+    pub fn is_dml(&self) -> bool {
+        self.category() == StatementCategory::Dml
+    }
+
+    /// Returns true if this statement is SELECT
+    pub fn is_query(&self) -> bool {
+        self.category() == StatementCategory::Query
+    }
+
+    /// Returns true if this statement manages roles, users or permissions
+    pub fn is_authz(&self) -> bool {
+        self.category() == StatementCategory::Authz
+    }
+
+    /// Returns true if this statement changes schema, same as `is_ddl`
+    pub fn modifies_schema(&self) -> bool {
+        self.is_ddl()
+    }
+
+    /// Returns true if this statement is a conditional update (lightweight transaction),
+    /// which has `IF NOT EXISTS`, `IF EXISTS` or `IF` conditions
+    ///
+    /// `IF NOT EXISTS` in DDL statements, such as CREATE TABLE, does not count.
+    pub fn is_conditional(&self) -> bool {
+        match self {
+            CqlStatement::Insert(insert) => insert.if_not_exists,
+            CqlStatement::Update(update) => update.if_exists || update.conditions.is_some(),
+            CqlStatement::Delete(delete) => delete.if_exists || delete.conditions.is_some(),
+            CqlStatement::Batch(batch) => batch.statements.iter().any(|s| s.is_conditional()),
+            _ => false,
+        }
+    }
+
+    /// Returns the names of tables and materialized views referenced by this statement
+    ///
+    /// For CREATE MATERIALIZED VIEW, the view name is followed by the base table.
+    /// Statements without tables, such as CREATE KEYSPACE or DROP INDEX, return an empty `Vec`.
+    ///
+    /// ```
+    /// use cqlparser::Parser;
+    /// let statement = Parser::new("SELECT * FROM ks.tbl").parse().unwrap().remove(0);
+    /// let tables = statement.table_references();
+    /// assert_eq!(tables.len(), 1);
+    /// assert_eq!(tables[0].to_string(), "ks.tbl");
+    /// ```
+    pub fn table_references(&self) -> Vec<&QualifiedName> {
+        match self {
+            CqlStatement::Select(select) => vec![&select.table_name],
+            CqlStatement::Insert(insert) => vec![&insert.table],
+            CqlStatement::Update(update) => vec![&update.table],
+            CqlStatement::Delete(delete) => vec![&delete.table],
+            CqlStatement::Truncate(name) => vec![name],
+            CqlStatement::Batch(batch) => batch
+                .statements
+                .iter()
+                .flat_map(|s| s.table_references())
+                .collect(),
+            CqlStatement::CreateIndex(create_index) => vec![&create_index.table_name],
+            CqlStatement::CreateTable(create_table) => vec![&create_table.name],
+            CqlStatement::AlterTable(alter_table) => vec![&alter_table.name],
+            CqlStatement::CreateMaterializedView(create_view) => {
+                vec![&create_view.name, &create_view.base_table]
+            }
+            CqlStatement::Drop(
+                DropStatement::Table { name, .. } | DropStatement::MaterializedView { name, .. },
+            ) => vec![name],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the names of keyspaces explicitly referenced by this statement
+    ///
+    /// These are the keyspaces of the tables returned by `table_references`,
+    /// the keyspace of CREATE, ALTER or DROP KEYSPACE and USE, and the keyspace of
+    /// the index of DROP INDEX, in the order of appearance without duplicates.
+    /// Tables without keyspace names are in the session's current keyspace,
+    /// which is not included.
+    pub fn keyspace_references(&self) -> Vec<&str> {
+        let mut keyspaces = Vec::new();
+        match self {
+            CqlStatement::CreateKeyspace(create_keyspace) => {
+                keyspaces.push(create_keyspace.keyspace_name.as_str());
+            }
+            CqlStatement::AlterKeyspace(alter_keyspace) => {
+                keyspaces.push(alter_keyspace.keyspace_name.as_str());
+            }
+            CqlStatement::Drop(DropStatement::Keyspace { name, .. }) | CqlStatement::Use(name) => {
+                keyspaces.push(name.as_str());
+            }
+            CqlStatement::Drop(DropStatement::Index { name, .. }) => {
+                keyspaces.extend(name.keyspace.as_deref());
+            }
+            _ => {}
+        }
+        for keyspace in self
+            .table_references()
+            .into_iter()
+            .filter_map(|table| table.keyspace.as_deref())
+        {
+            if !keyspaces.contains(&keyspace) {
+                keyspaces.push(keyspace);
+            }
+        }
+        keyspaces
+    }
+}
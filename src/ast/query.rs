@@ -10,16 +10,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{Expression, Literal, QualifiedName};
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
+use super::{
+    write_separated, BinaryOp, DisplayIdentifier, Expression, ExpressionKind, Identifier, Literal,
+    Operator, QualifiedName, Span,
+};
+
+#[derive(Debug)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub struct SelectStatement {
     /// FROM table name
     pub table_name: QualifiedName,
     pub projection: Projection,
     /// WHERE clause
-    pub selection: Option<Expression>,
+    pub selection: Option<WhereClause>,
+    /// GROUP BY clause, which is empty if not specified
+    ///
+    /// Elements are column names, or a function call over a column,
+    /// such as `floor(ts, 1h)`, for the last element.
+    pub group_by: Vec<Expression>,
     /// true when the SELECT statement begins with `SELECT JSON columns...`
     pub is_json: bool,
     /// true when the SELECT statement contains `DISTINCT`
@@ -30,10 +41,77 @@ pub struct SelectStatement {
     pub limit: Option<Literal>,
     /// true when the SELECT statement contains `ALLOW FILTERING`
     pub allow_filtering: bool,
+    /// Position of the statement in the original CQL, not taken into account when comparing
+    pub span: Span,
+}
+
+impl_eq_ignoring_span!(SelectStatement {
+    table_name,
+    projection,
+    selection,
+    group_by,
+    is_json,
+    is_distinct,
+    per_partition_limit,
+    limit,
+    allow_filtering,
+});
+
+/// Formats SELECT statement as CQL, without trailing `;`
+///
+/// ```
+/// use cqlparser::ast::CqlStatement;
+/// use cqlparser::Parser;
+///
+/// let cql = "select a AS \"A\" from ks.tbl where k in (1, 2) and c > 'x' limit 10";
+/// if let CqlStatement::Select(select) = &Parser::new(cql).parse().unwrap()[0] {
+///     assert_eq!(
+///         select.to_string(),
+///         "SELECT a AS \"A\" FROM ks.tbl WHERE k IN (1, 2) AND c > 'x' LIMIT 10"
+///     );
+/// }
+/// ```
+impl fmt::Display for SelectStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SELECT ")?;
+        if self.is_json {
+            f.write_str("JSON ")?;
+        }
+        if self.is_distinct {
+            f.write_str("DISTINCT ")?;
+        }
+        write!(f, "{} FROM {}", self.projection, self.table_name)?;
+        if let Some(selection) = &self.selection {
+            write!(f, " WHERE {}", selection)?;
+        }
+        if !self.group_by.is_empty() {
+            f.write_str(" GROUP BY ")?;
+            write_separated(f, &self.group_by, ", ")?;
+        }
+        if let Some(limit) = &self.per_partition_limit {
+            write!(f, " PER PARTITION LIMIT {}", limit)?;
+        }
+        if let Some(limit) = &self.limit {
+            write!(f, " LIMIT {}", limit)?;
+        }
+        if self.allow_filtering {
+            f.write_str(" ALLOW FILTERING")?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    serde(
+        tag = "type",
+        content = "value",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )
+)]
 pub enum Projection {
     /// Wildcard(`*`) projection
     Wildcard,
@@ -41,25 +119,355 @@ pub enum Projection {
     Selectors(Vec<Selector>),
 }
 
+impl fmt::Display for Projection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Projection::Wildcard => f.write_str("*"),
+            Projection::Selectors(selectors) => write_separated(f, selectors, ", "),
+        }
+    }
+}
+
 /// Selector is an expression in SELECT clause to be selected for the result set.
-#[derive(Debug, PartialEq)]
+///
+/// Span is not taken into account when comparing selectors.
+#[derive(Debug)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub struct Selector {
     selectable: Expression,
     /// alias name if any
     alias: Option<String>,
+    /// position of the selector including its alias
+    span: Span,
+}
+
+impl PartialEq for Selector {
+    fn eq(&self, other: &Self) -> bool {
+        self.selectable == other.selectable && self.alias == other.alias
+    }
 }
 
 impl Selector {
     /// Creates new selector with given selectable and optional alias name
     pub fn new(selectable: Expression, alias: Option<String>) -> Self {
-        Selector { selectable, alias }
+        Selector {
+            selectable,
+            alias,
+            span: Span::default(),
+        }
+    }
+
+    /// Creates new selector with its position in the original CQL
+    pub fn with_span(selectable: Expression, alias: Option<String>, span: Span) -> Self {
+        Selector {
+            selectable,
+            alias,
+            span,
+        }
+    }
+
+    /// Returns the position of this selector in the original CQL
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Returns the expression to be selected
+    pub fn selectable(&self) -> &Expression {
+        &self.selectable
+    }
+
+    /// Returns the mutable reference of the expression to be selected
+    pub fn selectable_mut(&mut self) -> &mut Expression {
+        &mut self.selectable
+    }
+
+    /// Returns alias name if any
+    pub fn alias(&self) -> Option<&str> {
+        self.alias.as_deref()
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.selectable)?;
+        if let Some(alias) = &self.alias {
+            write!(f, " AS {}", DisplayIdentifier(alias))?;
+        }
+        Ok(())
     }
 }
 
+/// WHERE clause, which consists of relations joined by `AND`
+///
+/// The clause can be converted back to a single `Expression` of AND-joined relations
+/// with `Expression::from`.
 #[derive(Debug, PartialEq)]
 #[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(target_arch = "wasm32", serde(rename_all = "camelCase"))]
 pub struct WhereClause {
-    relations: Vec<()>,
-    custom_index_expressions: Vec<String>,
+    relations: Vec<Relation>,
+}
+
+impl WhereClause {
+    pub fn new(relations: Vec<Relation>) -> Self {
+        WhereClause { relations }
+    }
+
+    /// Returns relations in the order of appearance
+    pub fn relations(&self) -> &[Relation] {
+        &self.relations
+    }
+
+    /// Returns the mutable reference of relations
+    pub fn relations_mut(&mut self) -> &mut Vec<Relation> {
+        &mut self.relations
+    }
+
+    /// Returns relations that restrict given `column`,
+    /// including token and multi-column relations containing the column
+    pub fn restrictions<'a>(&'a self, column: &'a str) -> impl Iterator<Item = &'a Relation> {
+        self.relations
+            .iter()
+            .filter(move |r| r.columns().any(|c| c == column))
+    }
+}
+
+/// Formats relations joined by `AND`, without the `WHERE` keyword
+impl fmt::Display for WhereClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_separated(f, &self.relations, " AND ")
+    }
+}
+
+impl From<WhereClause> for Expression {
+    fn from(where_clause: WhereClause) -> Self {
+        where_clause
+            .relations
+            .into_iter()
+            .map(Expression::from)
+            .reduce(|left, right| {
+                ExpressionKind::BinaryOp(BinaryOp::new(
+                    Box::new(left),
+                    Operator::And,
+                    Box::new(right),
+                ))
+                .into()
+            })
+            .unwrap_or_else(|| ExpressionKind::Value(Literal::Null).into())
+    }
+}
+
+/// Relation in WHERE clause
+#[derive(Debug, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    target_arch = "wasm32",
+    serde(
+        tag = "type",
+        content = "value",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )
+)]
+pub enum Relation {
+    /// Relation on a single column, such as `a = 1`, `b IN (1, 2)`,
+    /// `c CONTAINS 'x'` or `d IS NOT NULL`
+    ///
+    /// Values of `IN` are held as `Literal::List`, or `Literal::ConstantList` if they are
+    /// constants of the same kind, unless bound with a bind marker,
+    /// and `IS NOT NULL` has `Literal::Null` as its value.
+    SingleColumn {
+        column: Identifier,
+        operator: Operator,
+        value: Expression,
+    },
+    /// Relation on the token of partition key columns, such as `token(a, b) > 0`
+    Token {
+        columns: Vec<Identifier>,
+        operator: Operator,
+        value: Expression,
+    },
+    /// Relation on a tuple of columns, such as `(a, b) > (1, 2)`
+    MultiColumn {
+        columns: Vec<Identifier>,
+        operator: Operator,
+        value: Expression,
+    },
+    /// Custom index expression, `expr(index_name, 'expression')`
+    CustomIndex { index: String, value: Expression },
+}
+
+impl Relation {
+    /// Returns the columns restricted by this relation
+    ///
+    /// Custom index expression does not restrict any column.
+    pub fn columns(&self) -> impl Iterator<Item = &str> {
+        let columns = match self {
+            Relation::SingleColumn { column, .. } => std::slice::from_ref(column),
+            Relation::Token { columns, .. } | Relation::MultiColumn { columns, .. } => columns,
+            Relation::CustomIndex { .. } => &[],
+        };
+        columns.iter().map(|column| &**column)
+    }
+
+    /// Returns the operator of this relation, or `None` for custom index expression
+    pub fn operator(&self) -> Option<&Operator> {
+        match self {
+            Relation::SingleColumn { operator, .. }
+            | Relation::Token { operator, .. }
+            | Relation::MultiColumn { operator, .. } => Some(operator),
+            Relation::CustomIndex { .. } => None,
+        }
+    }
+
+    /// Returns the value compared with the columns
+    pub fn value(&self) -> &Expression {
+        match self {
+            Relation::SingleColumn { value, .. }
+            | Relation::Token { value, .. }
+            | Relation::MultiColumn { value, .. }
+            | Relation::CustomIndex { value, .. } => value,
+        }
+    }
+
+    /// Returns the mutable reference of the value compared with the columns
+    pub fn value_mut(&mut self) -> &mut Expression {
+        match self {
+            Relation::SingleColumn { value, .. }
+            | Relation::Token { value, .. }
+            | Relation::MultiColumn { value, .. }
+            | Relation::CustomIndex { value, .. } => value,
+        }
+    }
+}
+
+impl From<Relation> for Expression {
+    fn from(relation: Relation) -> Self {
+        let identifiers = |columns: Vec<Identifier>| {
+            columns
+                .into_iter()
+                .map(|column| ExpressionKind::Identifier(column).into())
+                .collect()
+        };
+        let (left, operator, value) = match relation {
+            Relation::SingleColumn {
+                column,
+                operator,
+                value,
+            } => (ExpressionKind::Identifier(column), operator, value),
+            Relation::Token {
+                columns,
+                operator,
+                value,
+            } => (
+                ExpressionKind::Function {
+                    name: QualifiedName::new(None, "token".to_owned()),
+                    args: identifiers(columns),
+                },
+                operator,
+                value,
+            ),
+            Relation::MultiColumn {
+                columns,
+                operator,
+                value,
+            } => (
+                ExpressionKind::Value(Literal::Tuple(identifiers(columns))),
+                operator,
+                value,
+            ),
+            Relation::CustomIndex { index, value } => {
+                return ExpressionKind::Function {
+                    name: QualifiedName::new(None, "expr".to_owned()),
+                    args: vec![ExpressionKind::Identifier(index.into()).into(), value],
+                }
+                .into();
+            }
+        };
+        ExpressionKind::BinaryOp(BinaryOp::new(
+            Box::new(left.into()),
+            operator,
+            Box::new(value),
+        ))
+        .into()
+    }
+}
+
+impl fmt::Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (operator, value) = match self {
+            Relation::SingleColumn {
+                column,
+                operator,
+                value,
+            } => {
+                write!(f, "{}", DisplayIdentifier(column))?;
+                (operator, value)
+            }
+            Relation::Token {
+                columns,
+                operator,
+                value,
+            } => {
+                f.write_str("token")?;
+                write_columns(f, columns)?;
+                (operator, value)
+            }
+            Relation::MultiColumn {
+                columns,
+                operator,
+                value,
+            } => {
+                write_columns(f, columns)?;
+                (operator, value)
+            }
+            Relation::CustomIndex { index, value } => {
+                return write!(f, "expr({}, {})", DisplayIdentifier(index), value);
+            }
+        };
+        match (operator, &value.kind) {
+            (Operator::IsNot, _) => f.write_str(" IS NOT NULL"),
+            // values of IN are written in parentheses
+            (Operator::In, ExpressionKind::Value(Literal::List(values))) => {
+                f.write_str(" IN (")?;
+                write_separated(f, values, ", ")?;
+                f.write_str(")")
+            }
+            (Operator::In, ExpressionKind::Value(Literal::ConstantList(values))) => {
+                f.write_str(" IN (")?;
+                write_separated(f, values, ", ")?;
+                f.write_str(")")
+            }
+            // `CONTAINS key...` is read as `CONTAINS KEY`, so the identifier is quoted
+            (Operator::Contains, _) if starts_with_key(value) => {
+                let value = value.to_string();
+                match value.strip_prefix("key") {
+                    Some(rest) => write!(f, " CONTAINS \"key\"{}", rest),
+                    // parenthesized
+                    None => write!(f, " CONTAINS {}", value),
+                }
+            }
+            _ => write!(f, " {} {}", operator, value),
+        }
+    }
+}
+
+// Returns true if the expression is written starting with identifier `key`
+fn starts_with_key(expression: &Expression) -> bool {
+    match &expression.kind {
+        ExpressionKind::Identifier(name) => &**name == "key",
+        ExpressionKind::BinaryOp(op) => starts_with_key(op.left()),
+        // function named `key` is quoted
+        ExpressionKind::Function { name, .. } => name.keyspace.as_deref() == Some("key"),
+        ExpressionKind::CollectionSubSelection { receiver, .. } => starts_with_key(receiver),
+        _ => false,
+    }
+}
+
+// Writes parenthesized column names, such as `(a, b)`
+fn write_columns(f: &mut fmt::Formatter<'_>, columns: &[Identifier]) -> fmt::Result {
+    f.write_str("(")?;
+    write_separated(f, columns.iter().map(|c| DisplayIdentifier(c)), ", ")?;
+    f.write_str(")")
 }
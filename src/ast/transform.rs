@@ -0,0 +1,69 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # AST transformers
+//!
+//! Built-in rewrites of the AST, implemented on top of `visit::VisitorMut`.
+
+use super::visit::{walk_expression_chain_mut, walk_literal_mut, VisitorMut};
+use super::{CqlStatement, Expression, ExpressionKind, Literal};
+
+/// Replaces every constant literal in the statement with positional binding variable (`?`).
+///
+/// This is useful to anonymize queries, or to group queries that only differ in their values.
+///
+/// ## Example
+///
+/// ```
+/// use cqlparser::ast::transform::parameterize;
+/// use cqlparser::ast::{CqlStatement, ExpressionKind, Literal, Operator, Relation, WhereClause};
+/// use cqlparser::Parser;
+///
+/// let mut statements = Parser::new("SELECT * FROM tbl WHERE k = 5").parse().unwrap();
+/// parameterize(&mut statements[0]);
+/// if let CqlStatement::Select(select) = &statements[0] {
+///     assert_eq!(
+///         select.selection,
+///         Some(WhereClause::new(vec![Relation::SingleColumn {
+///             column: "k".into(),
+///             operator: Operator::Equal,
+///             value: ExpressionKind::Value(Literal::Binding(None)).into(),
+///         }]))
+///     );
+/// }
+/// ```
+pub fn parameterize(statement: &mut CqlStatement) {
+    Parameterizer.visit_statement_mut(statement);
+}
+
+struct Parameterizer;
+
+impl VisitorMut for Parameterizer {
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        walk_expression_chain_mut(self, expression);
+    }
+
+    fn visit_literal_mut(&mut self, literal: &mut Literal) {
+        match literal {
+            Literal::Constant(_) => *literal = Literal::Binding(None),
+            // a binding variable for each constant
+            Literal::ConstantList(constants) => {
+                let bindings = constants
+                    .iter()
+                    .map(|_| ExpressionKind::Value(Literal::Binding(None)).into())
+                    .collect();
+                *literal = Literal::List(bindings);
+            }
+            _ => walk_literal_mut(self, literal),
+        }
+    }
+}
@@ -0,0 +1,657 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # AST traversal
+//!
+//! `Visitor` walks the AST in the order the elements appear in the source CQL.
+//! Each `visit_*` method defaults to the corresponding `walk_*` function,
+//! which recurses into the children of the node.
+//! Override only the methods for the nodes you are interested in,
+//! and call `walk_*` from the overridden method to keep descending.
+//!
+//! `VisitorMut` is the mutable counterpart of `Visitor`, which receives
+//! `&mut` references so that nodes can be replaced in place.
+//!
+//! ## Example
+//!
+//! Collecting all identifiers used in a SELECT statement:
+//!
+//! ```
+//! use cqlparser::ast::visit::{walk_expression, Visitor};
+//! use cqlparser::ast::{Expression, ExpressionKind, Identifier};
+//! use cqlparser::Parser;
+//!
+//! #[derive(Default)]
+//! struct IdentifierCollector {
+//!     identifiers: Vec<String>,
+//! }
+//!
+//! impl Visitor for IdentifierCollector {
+//!     fn visit_expression(&mut self, expression: &Expression) {
+//!         if let ExpressionKind::Identifier(name) = &expression.kind {
+//!             self.identifiers.push(name.to_string());
+//!         }
+//!         walk_expression(self, expression);
+//!     }
+//!
+//!     // columns of WHERE relations are not expressions
+//!     fn visit_column(&mut self, column: &Identifier) {
+//!         self.identifiers.push(column.to_string());
+//!     }
+//! }
+//!
+//! let statements = Parser::new("SELECT a, b + 1 FROM tbl WHERE c = 1").parse().unwrap();
+//! let mut collector = IdentifierCollector::default();
+//! collector.visit_statement(&statements[0]);
+//! assert_eq!(collector.identifiers, vec!["a", "b", "c"]);
+//! ```
+
+use super::*;
+
+/// Read-only visitor of the AST
+pub trait Visitor: Sized {
+    fn visit_statement(&mut self, statement: &CqlStatement) {
+        walk_statement(self, statement)
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        walk_expression(self, expression)
+    }
+
+    fn visit_literal(&mut self, literal: &Literal) {
+        walk_literal(self, literal)
+    }
+
+    fn visit_type(&mut self, cql_type: &CqlType) {
+        walk_type(self, cql_type)
+    }
+
+    fn visit_relation(&mut self, relation: &Relation) {
+        walk_relation(self, relation)
+    }
+
+    /// Column restricted by a relation, such as `a` of `a = 1` or `token(a, b) > 0`
+    fn visit_column(&mut self, _column: &Identifier) {}
+
+    fn visit_qualified_name(&mut self, _name: &QualifiedName) {}
+
+    fn visit_property(&mut self, _property: &Property) {}
+}
+
+/// Visits the children of given statement
+pub fn walk_statement<V: Visitor>(visitor: &mut V, statement: &CqlStatement) {
+    match statement {
+        CqlStatement::Select(select) => {
+            walk_projection(visitor, &select.projection);
+            visitor.visit_qualified_name(&select.table_name);
+            if let Some(selection) = &select.selection {
+                walk_where_clause(visitor, selection);
+            }
+            select
+                .group_by
+                .iter()
+                .for_each(|e| visitor.visit_expression(e));
+            if let Some(limit) = &select.per_partition_limit {
+                visitor.visit_literal(limit);
+            }
+            if let Some(limit) = &select.limit {
+                visitor.visit_literal(limit);
+            }
+        }
+        CqlStatement::Insert(insert) => {
+            visitor.visit_qualified_name(&insert.table);
+            match &insert.values {
+                InsertMethod::Normal { columns, values } => {
+                    columns.iter().for_each(|c| visitor.visit_expression(c));
+                    values.iter().for_each(|v| visitor.visit_expression(v));
+                }
+                InsertMethod::Json { value, .. } => visitor.visit_literal(value),
+            }
+            walk_using_clause(visitor, &insert.timestamp, &insert.time_to_live);
+        }
+        CqlStatement::Update(update) => {
+            visitor.visit_qualified_name(&update.table);
+            walk_using_clause(visitor, &update.timestamp, &update.time_to_live);
+            update
+                .assignments
+                .iter()
+                .for_each(|a| visitor.visit_expression(a));
+            walk_where_clause(visitor, &update.selection);
+            if let Some(conditions) = &update.conditions {
+                visitor.visit_expression(conditions);
+            }
+        }
+        CqlStatement::Batch(batch) => {
+            if let Some(timestamp) = &batch.timestamp {
+                visitor.visit_literal(timestamp);
+            }
+            batch
+                .statements
+                .iter()
+                .for_each(|s| visitor.visit_statement(s));
+        }
+        CqlStatement::Delete(delete) => {
+            for target in &delete.targets {
+                if let DeleteTarget::Element { element, .. } = target {
+                    visitor.visit_expression(element);
+                }
+            }
+            visitor.visit_qualified_name(&delete.table);
+            if let Some(timestamp) = &delete.timestamp {
+                visitor.visit_literal(timestamp);
+            }
+            walk_where_clause(visitor, &delete.selection);
+            if let Some(conditions) = &delete.conditions {
+                visitor.visit_expression(conditions);
+            }
+        }
+        CqlStatement::Truncate(name) => visitor.visit_qualified_name(name),
+        CqlStatement::CreateKeyspace(create_keyspace) => {
+            walk_properties(visitor, &create_keyspace.attributes);
+        }
+        CqlStatement::CreateTable(create_table) => {
+            visitor.visit_qualified_name(&create_table.name);
+            for column in &create_table.column_definitions {
+                visitor.visit_type(&column.data_type);
+                if let Some(ColumnMask::Function(mask)) = &column.mask {
+                    visitor.visit_expression(mask);
+                }
+            }
+            walk_properties(visitor, &create_table.table_properties);
+        }
+        CqlStatement::CreateIndex(create_index) => {
+            visitor.visit_qualified_name(&create_index.table_name);
+        }
+        CqlStatement::AlterTable(alter_table) => {
+            visitor.visit_qualified_name(&alter_table.name);
+            match &alter_table.operation {
+                AlterTableOperation::Add { columns, .. } => {
+                    for column in columns {
+                        visitor.visit_type(&column.data_type);
+                        if let Some(ColumnMask::Function(mask)) = &column.mask {
+                            visitor.visit_expression(mask);
+                        }
+                    }
+                }
+                AlterTableOperation::AlterType { new_type, .. } => visitor.visit_type(new_type),
+                AlterTableOperation::WithProperties(properties) => {
+                    walk_properties(visitor, properties);
+                }
+                _ => {}
+            }
+        }
+        CqlStatement::CreateType(create_type) => {
+            visitor.visit_qualified_name(&create_type.name);
+            create_type
+                .field_definitions
+                .iter()
+                .for_each(|(_, cql_type)| visitor.visit_type(cql_type));
+        }
+        CqlStatement::AlterType(alter_type) => {
+            visitor.visit_qualified_name(&alter_type.name);
+            if let AlterTypeOperation::Add { data_type, .. } = &alter_type.operation {
+                visitor.visit_type(data_type);
+            }
+        }
+        CqlStatement::CreateMaterializedView(create_view) => {
+            visitor.visit_qualified_name(&create_view.name);
+            walk_projection(visitor, &create_view.projection);
+            visitor.visit_qualified_name(&create_view.base_table);
+            if let Some(selection) = &create_view.selection {
+                walk_where_clause(visitor, selection);
+            }
+            walk_properties(visitor, &create_view.view_properties);
+        }
+        CqlStatement::AlterKeyspace(alter_keyspace) => {
+            walk_properties(visitor, &alter_keyspace.attributes);
+        }
+        CqlStatement::Drop(
+            DropStatement::Table { name, .. }
+            | DropStatement::Type { name, .. }
+            | DropStatement::MaterializedView { name, .. },
+        ) => {
+            visitor.visit_qualified_name(name);
+        }
+        _ => {}
+    }
+}
+
+/// Visits the children of given expression
+pub fn walk_expression<V: Visitor>(visitor: &mut V, expression: &Expression) {
+    match &expression.kind {
+        ExpressionKind::Identifier(_) => {}
+        ExpressionKind::UnaryOp(op) => visitor.visit_expression(op.operand()),
+        ExpressionKind::BinaryOp(op) => {
+            visitor.visit_expression(op.left());
+            visitor.visit_expression(op.right());
+        }
+        ExpressionKind::Value(literal) => visitor.visit_literal(literal),
+        ExpressionKind::Function { name, args } => {
+            visitor.visit_qualified_name(name);
+            args.iter().for_each(|arg| visitor.visit_expression(arg));
+        }
+        ExpressionKind::Cast(cql_type, expr) | ExpressionKind::TypeCast(cql_type, expr) => {
+            visitor.visit_type(cql_type);
+            visitor.visit_expression(expr);
+        }
+        ExpressionKind::CollectionSubSelection {
+            receiver,
+            element,
+            upto,
+        } => {
+            visitor.visit_expression(receiver);
+            visitor.visit_expression(element);
+            if let Some(upto) = upto {
+                visitor.visit_expression(upto);
+            }
+        }
+    }
+}
+
+/// Visits the columns and the value of given relation
+pub fn walk_relation<V: Visitor>(visitor: &mut V, relation: &Relation) {
+    match relation {
+        Relation::SingleColumn { column, .. } => visitor.visit_column(column),
+        Relation::Token { columns, .. } | Relation::MultiColumn { columns, .. } => {
+            columns.iter().for_each(|c| visitor.visit_column(c));
+        }
+        Relation::CustomIndex { .. } => {}
+    }
+    visitor.visit_expression(relation.value());
+}
+
+/// Visits the children of given literal
+pub fn walk_literal<V: Visitor>(visitor: &mut V, literal: &Literal) {
+    match literal {
+        Literal::List(elements) | Literal::Set(elements) | Literal::Tuple(elements) => {
+            elements.iter().for_each(|e| visitor.visit_expression(e));
+        }
+        Literal::Map(entries) => {
+            for (key, value) in entries {
+                visitor.visit_expression(key);
+                visitor.visit_expression(value);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Visits the children of given data type
+pub fn walk_type<V: Visitor>(visitor: &mut V, cql_type: &CqlType) {
+    match cql_type {
+        CqlType::Collection(CollectionType::Map {
+            key_type,
+            value_type,
+        }) => {
+            visitor.visit_type(key_type);
+            visitor.visit_type(value_type);
+        }
+        CqlType::Collection(CollectionType::List(inner))
+        | CqlType::Collection(CollectionType::Set(inner))
+        | CqlType::Frozen(inner) => visitor.visit_type(inner),
+        CqlType::Tuple(types) => types.iter().for_each(|t| visitor.visit_type(t)),
+        CqlType::UserDefinedType(name) => visitor.visit_qualified_name(name),
+        CqlType::Native(_) | CqlType::Custom(_) => {}
+    }
+}
+
+fn walk_projection<V: Visitor>(visitor: &mut V, projection: &Projection) {
+    if let Projection::Selectors(selectors) = projection {
+        selectors
+            .iter()
+            .for_each(|s| visitor.visit_expression(s.selectable()));
+    }
+}
+
+fn walk_where_clause<V: Visitor>(visitor: &mut V, where_clause: &WhereClause) {
+    where_clause
+        .relations()
+        .iter()
+        .for_each(|r| visitor.visit_relation(r));
+}
+
+fn walk_properties<V: Visitor>(visitor: &mut V, properties: &[Property]) {
+    properties.iter().for_each(|p| visitor.visit_property(p));
+}
+
+fn walk_using_clause<V: Visitor>(
+    visitor: &mut V,
+    timestamp: &Option<Literal>,
+    time_to_live: &Option<Literal>,
+) {
+    if let Some(timestamp) = timestamp {
+        visitor.visit_literal(timestamp);
+    }
+    if let Some(ttl) = time_to_live {
+        visitor.visit_literal(ttl);
+    }
+}
+
+/// Visitor that can modify the AST in place
+///
+/// Same as `Visitor`, except that each method receives `&mut` reference of the node,
+/// and `walk_*_mut` functions are used to descend into the children.
+pub trait VisitorMut: Sized {
+    fn visit_statement_mut(&mut self, statement: &mut CqlStatement) {
+        walk_statement_mut(self, statement)
+    }
+
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        walk_expression_mut(self, expression)
+    }
+
+    fn visit_literal_mut(&mut self, literal: &mut Literal) {
+        walk_literal_mut(self, literal)
+    }
+
+    fn visit_type_mut(&mut self, cql_type: &mut CqlType) {
+        walk_type_mut(self, cql_type)
+    }
+
+    fn visit_relation_mut(&mut self, relation: &mut Relation) {
+        walk_relation_mut(self, relation)
+    }
+
+    fn visit_column_mut(&mut self, _column: &mut Identifier) {}
+
+    fn visit_qualified_name_mut(&mut self, _name: &mut QualifiedName) {}
+
+    fn visit_property_mut(&mut self, _property: &mut Property) {}
+}
+
+/// Visits the children of given statement mutably
+pub fn walk_statement_mut<V: VisitorMut>(visitor: &mut V, statement: &mut CqlStatement) {
+    match statement {
+        CqlStatement::Select(select) => {
+            walk_projection_mut(visitor, &mut select.projection);
+            visitor.visit_qualified_name_mut(&mut select.table_name);
+            if let Some(selection) = &mut select.selection {
+                walk_where_clause_mut(visitor, selection);
+            }
+            select
+                .group_by
+                .iter_mut()
+                .for_each(|e| visitor.visit_expression_mut(e));
+            if let Some(limit) = &mut select.per_partition_limit {
+                visitor.visit_literal_mut(limit);
+            }
+            if let Some(limit) = &mut select.limit {
+                visitor.visit_literal_mut(limit);
+            }
+        }
+        CqlStatement::Insert(insert) => {
+            visitor.visit_qualified_name_mut(&mut insert.table);
+            match &mut insert.values {
+                InsertMethod::Normal { columns, values } => {
+                    columns
+                        .iter_mut()
+                        .for_each(|c| visitor.visit_expression_mut(c));
+                    values
+                        .iter_mut()
+                        .for_each(|v| visitor.visit_expression_mut(v));
+                }
+                InsertMethod::Json { value, .. } => visitor.visit_literal_mut(value),
+            }
+            walk_using_clause_mut(visitor, &mut insert.timestamp, &mut insert.time_to_live);
+        }
+        CqlStatement::Update(update) => {
+            visitor.visit_qualified_name_mut(&mut update.table);
+            walk_using_clause_mut(visitor, &mut update.timestamp, &mut update.time_to_live);
+            update
+                .assignments
+                .iter_mut()
+                .for_each(|a| visitor.visit_expression_mut(a));
+            walk_where_clause_mut(visitor, &mut update.selection);
+            if let Some(conditions) = &mut update.conditions {
+                visitor.visit_expression_mut(conditions);
+            }
+        }
+        CqlStatement::Batch(batch) => {
+            if let Some(timestamp) = &mut batch.timestamp {
+                visitor.visit_literal_mut(timestamp);
+            }
+            batch
+                .statements
+                .iter_mut()
+                .for_each(|s| visitor.visit_statement_mut(s));
+        }
+        CqlStatement::Delete(delete) => {
+            for target in &mut delete.targets {
+                if let DeleteTarget::Element { element, .. } = target {
+                    visitor.visit_expression_mut(element);
+                }
+            }
+            visitor.visit_qualified_name_mut(&mut delete.table);
+            if let Some(timestamp) = &mut delete.timestamp {
+                visitor.visit_literal_mut(timestamp);
+            }
+            walk_where_clause_mut(visitor, &mut delete.selection);
+            if let Some(conditions) = &mut delete.conditions {
+                visitor.visit_expression_mut(conditions);
+            }
+        }
+        CqlStatement::Truncate(name) => visitor.visit_qualified_name_mut(name),
+        CqlStatement::CreateKeyspace(create_keyspace) => {
+            walk_properties_mut(visitor, &mut create_keyspace.attributes);
+        }
+        CqlStatement::CreateTable(create_table) => {
+            visitor.visit_qualified_name_mut(&mut create_table.name);
+            for column in &mut create_table.column_definitions {
+                visitor.visit_type_mut(&mut column.data_type);
+                if let Some(ColumnMask::Function(mask)) = &mut column.mask {
+                    visitor.visit_expression_mut(mask);
+                }
+            }
+            walk_properties_mut(visitor, &mut create_table.table_properties);
+        }
+        CqlStatement::CreateIndex(create_index) => {
+            visitor.visit_qualified_name_mut(&mut create_index.table_name);
+        }
+        CqlStatement::AlterTable(alter_table) => {
+            visitor.visit_qualified_name_mut(&mut alter_table.name);
+            match &mut alter_table.operation {
+                AlterTableOperation::Add { columns, .. } => {
+                    for column in columns {
+                        visitor.visit_type_mut(&mut column.data_type);
+                        if let Some(ColumnMask::Function(mask)) = &mut column.mask {
+                            visitor.visit_expression_mut(mask);
+                        }
+                    }
+                }
+                AlterTableOperation::AlterType { new_type, .. } => visitor.visit_type_mut(new_type),
+                AlterTableOperation::WithProperties(properties) => {
+                    walk_properties_mut(visitor, properties);
+                }
+                _ => {}
+            }
+        }
+        CqlStatement::CreateType(create_type) => {
+            visitor.visit_qualified_name_mut(&mut create_type.name);
+            create_type
+                .field_definitions
+                .iter_mut()
+                .for_each(|(_, cql_type)| visitor.visit_type_mut(cql_type));
+        }
+        CqlStatement::AlterType(alter_type) => {
+            visitor.visit_qualified_name_mut(&mut alter_type.name);
+            if let AlterTypeOperation::Add { data_type, .. } = &mut alter_type.operation {
+                visitor.visit_type_mut(data_type);
+            }
+        }
+        CqlStatement::CreateMaterializedView(create_view) => {
+            visitor.visit_qualified_name_mut(&mut create_view.name);
+            walk_projection_mut(visitor, &mut create_view.projection);
+            visitor.visit_qualified_name_mut(&mut create_view.base_table);
+            if let Some(selection) = &mut create_view.selection {
+                walk_where_clause_mut(visitor, selection);
+            }
+            walk_properties_mut(visitor, &mut create_view.view_properties);
+        }
+        CqlStatement::AlterKeyspace(alter_keyspace) => {
+            walk_properties_mut(visitor, &mut alter_keyspace.attributes);
+        }
+        CqlStatement::Drop(
+            DropStatement::Table { name, .. }
+            | DropStatement::Type { name, .. }
+            | DropStatement::MaterializedView { name, .. },
+        ) => {
+            visitor.visit_qualified_name_mut(name);
+        }
+        _ => {}
+    }
+}
+
+/// Visits the children of given expression mutably
+pub fn walk_expression_mut<V: VisitorMut>(visitor: &mut V, expression: &mut Expression) {
+    match &mut expression.kind {
+        ExpressionKind::Identifier(_) => {}
+        ExpressionKind::UnaryOp(op) => visitor.visit_expression_mut(op.operand_mut()),
+        ExpressionKind::BinaryOp(op) => {
+            visitor.visit_expression_mut(op.left_mut());
+            visitor.visit_expression_mut(op.right_mut());
+        }
+        ExpressionKind::Value(literal) => visitor.visit_literal_mut(literal),
+        ExpressionKind::Function { name, args } => {
+            visitor.visit_qualified_name_mut(name);
+            args.iter_mut()
+                .for_each(|arg| visitor.visit_expression_mut(arg));
+        }
+        ExpressionKind::Cast(cql_type, expr) | ExpressionKind::TypeCast(cql_type, expr) => {
+            visitor.visit_type_mut(cql_type);
+            visitor.visit_expression_mut(expr);
+        }
+        ExpressionKind::CollectionSubSelection {
+            receiver,
+            element,
+            upto,
+        } => {
+            visitor.visit_expression_mut(receiver);
+            visitor.visit_expression_mut(element);
+            if let Some(upto) = upto {
+                visitor.visit_expression_mut(upto);
+            }
+        }
+    }
+}
+
+// Same as `walk_expression_mut`, except that the left operands of chains such as
+// `a + b + c` or `m[0][1]` are walked in a loop instead of visited, so that long chains
+// do not overflow the stack. Only for visitors whose `visit_expression_mut` calls this,
+// as the left operands are not passed to `visit_expression_mut`.
+pub(crate) fn walk_expression_chain_mut<V: VisitorMut>(
+    visitor: &mut V,
+    mut expression: &mut Expression,
+) {
+    loop {
+        match expression.kind {
+            ExpressionKind::BinaryOp(ref mut op) => {
+                visitor.visit_expression_mut(op.right_mut());
+                expression = op.left_mut();
+            }
+            ExpressionKind::CollectionSubSelection {
+                ref mut receiver,
+                ref mut element,
+                ref mut upto,
+            } => {
+                visitor.visit_expression_mut(element);
+                if let Some(upto) = upto {
+                    visitor.visit_expression_mut(upto);
+                }
+                expression = receiver;
+            }
+            _ => return walk_expression_mut(visitor, expression),
+        }
+    }
+}
+
+/// Visits the columns and the value of given relation mutably
+pub fn walk_relation_mut<V: VisitorMut>(visitor: &mut V, relation: &mut Relation) {
+    match relation {
+        Relation::SingleColumn { column, .. } => visitor.visit_column_mut(column),
+        Relation::Token { columns, .. } | Relation::MultiColumn { columns, .. } => {
+            columns.iter_mut().for_each(|c| visitor.visit_column_mut(c));
+        }
+        Relation::CustomIndex { .. } => {}
+    }
+    visitor.visit_expression_mut(relation.value_mut());
+}
+
+/// Visits the children of given literal mutably
+pub fn walk_literal_mut<V: VisitorMut>(visitor: &mut V, literal: &mut Literal) {
+    match literal {
+        Literal::List(elements) | Literal::Set(elements) | Literal::Tuple(elements) => {
+            elements
+                .iter_mut()
+                .for_each(|e| visitor.visit_expression_mut(e));
+        }
+        Literal::Map(entries) => {
+            for (key, value) in entries {
+                visitor.visit_expression_mut(key);
+                visitor.visit_expression_mut(value);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Visits the children of given data type mutably
+pub fn walk_type_mut<V: VisitorMut>(visitor: &mut V, cql_type: &mut CqlType) {
+    match cql_type {
+        CqlType::Collection(CollectionType::Map {
+            key_type,
+            value_type,
+        }) => {
+            visitor.visit_type_mut(key_type);
+            visitor.visit_type_mut(value_type);
+        }
+        CqlType::Collection(CollectionType::List(inner))
+        | CqlType::Collection(CollectionType::Set(inner))
+        | CqlType::Frozen(inner) => visitor.visit_type_mut(inner),
+        CqlType::Tuple(types) => types.iter_mut().for_each(|t| visitor.visit_type_mut(t)),
+        CqlType::UserDefinedType(name) => visitor.visit_qualified_name_mut(name),
+        CqlType::Native(_) | CqlType::Custom(_) => {}
+    }
+}
+
+fn walk_projection_mut<V: VisitorMut>(visitor: &mut V, projection: &mut Projection) {
+    if let Projection::Selectors(selectors) = projection {
+        selectors
+            .iter_mut()
+            .for_each(|s| visitor.visit_expression_mut(s.selectable_mut()));
+    }
+}
+
+fn walk_where_clause_mut<V: VisitorMut>(visitor: &mut V, where_clause: &mut WhereClause) {
+    where_clause
+        .relations_mut()
+        .iter_mut()
+        .for_each(|r| visitor.visit_relation_mut(r));
+}
+
+fn walk_properties_mut<V: VisitorMut>(visitor: &mut V, properties: &mut [Property]) {
+    properties
+        .iter_mut()
+        .for_each(|p| visitor.visit_property_mut(p));
+}
+
+fn walk_using_clause_mut<V: VisitorMut>(
+    visitor: &mut V,
+    timestamp: &mut Option<Literal>,
+    time_to_live: &mut Option<Literal>,
+) {
+    if let Some(timestamp) = timestamp {
+        visitor.visit_literal_mut(timestamp);
+    }
+    if let Some(ttl) = time_to_live {
+        visitor.visit_literal_mut(ttl);
+    }
+}
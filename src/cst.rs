@@ -0,0 +1,174 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Concrete syntax tree, which keeps every token of the source along with the AST
+//!
+//! `Parser::parse_syntax_tree` parses statements as usual, and attaches the tokens
+//! of the source to them, including whitespaces, comments and `;`.
+//! `SyntaxTree::unparse` writes the tokens back, which reproduces the source
+//! byte for byte unless the tokens are edited. Tools such as formatters can edit
+//! only the tokens they need to change, finding them by the spans of AST nodes.
+//!
+//! ```
+//! use cqlparser::ast::{CqlStatement, Projection};
+//! use cqlparser::Parser;
+//!
+//! let cql = "-- events\nselect  name,age FROM events ;\n";
+//! let mut tree = Parser::new(cql).parse_syntax_tree().unwrap();
+//! assert_eq!(tree.unparse(), cql);
+//!
+//! // uppercase the keywords of the first statement, keeping everything else
+//! for token in &mut tree.statements[0].tokens {
+//!     if token.is_keyword() {
+//!         token.text = token.text.to_uppercase().into();
+//!     }
+//! }
+//! assert_eq!(tree.unparse(), "-- events\nSELECT  name,age FROM events ;\n");
+//!
+//! // tokens of a selector
+//! let statement = &tree.statements[0];
+//! if let CqlStatement::Select(select) = &statement.statement.node {
+//!     if let Projection::Selectors(selectors) = &select.projection {
+//!         let tokens: Vec<_> = statement.tokens_in(selectors[1].span()).collect();
+//!         assert_eq!(tokens.len(), 1);
+//!         assert_eq!(tokens[0].text, "age");
+//!     }
+//! }
+//! ```
+
+use std::borrow::Cow;
+
+use crate::ast::{CqlStatement, Span, Spanned};
+use crate::lexer::{Lexer, Token, TokenType, BOM};
+
+/// Statements parsed from the source, with every token of the source
+#[derive(Debug)]
+pub struct SyntaxTree<'a> {
+    /// True if the source starts with UTF-8 byte order mark, which is not a token
+    pub byte_order_mark: bool,
+    pub statements: Vec<SyntaxStatement<'a>>,
+    /// Tokens after the last statement, such as whitespaces and comments
+    pub trailing_tokens: Vec<SyntaxToken<'a>>,
+}
+
+/// Statement with the tokens of its source
+#[derive(Debug)]
+pub struct SyntaxStatement<'a> {
+    pub statement: Spanned<CqlStatement>,
+    /// Tokens between the previous statement and this one,
+    /// such as whitespaces, comments and extra `;`
+    pub leading_tokens: Vec<SyntaxToken<'a>>,
+    /// Tokens in the span of the statement, including whitespaces and comments
+    /// inside it and the trailing `;` if present
+    pub tokens: Vec<SyntaxToken<'a>>,
+}
+
+/// Token with its text, which can be edited before `SyntaxTree::unparse`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyntaxToken<'a> {
+    /// Token as lexed from the source, whose position is not updated by edits
+    pub token: Token,
+    pub text: Cow<'a, str>,
+}
+
+impl SyntaxToken<'_> {
+    /// Returns the position of this token in the source
+    pub fn span(&self) -> Span {
+        Span::new(self.token.offset, self.token.offset + self.token.length)
+    }
+
+    /// Returns true if this token is a whitespace or a comment
+    pub fn is_trivia(&self) -> bool {
+        matches!(
+            self.token.token_type,
+            TokenType::Whitespace | TokenType::Comment(_)
+        )
+    }
+
+    /// Returns true if this token is a keyword
+    ///
+    /// Keywords used as names, such as `key` in `SELECT key FROM t`, are also keywords here.
+    pub fn is_keyword(&self) -> bool {
+        matches!(self.token.token_type, TokenType::Keyword(_))
+    }
+}
+
+impl<'a> SyntaxTree<'a> {
+    // Attaches the tokens of `cql` to `statements` parsed from it
+    pub(crate) fn new(cql: &'a str, statements: Vec<Spanned<CqlStatement>>) -> Self {
+        // whitespaces and comments are included by default
+        let mut tokens = Lexer::new(cql)
+            .map(|(text, token)| SyntaxToken {
+                token,
+                text: Cow::Borrowed(text),
+            })
+            .peekable();
+        let mut syntax_statements = Vec::with_capacity(statements.len());
+        for statement in statements {
+            let span = statement.span;
+            let mut leading_tokens = Vec::new();
+            while let Some(token) = tokens.next_if(|t| t.token.offset < span.start) {
+                leading_tokens.push(token);
+            }
+            let mut statement_tokens = Vec::new();
+            while let Some(token) = tokens.next_if(|t| t.token.offset < span.end) {
+                statement_tokens.push(token);
+            }
+            syntax_statements.push(SyntaxStatement {
+                statement,
+                leading_tokens,
+                tokens: statement_tokens,
+            });
+        }
+        SyntaxTree {
+            byte_order_mark: cql.starts_with(BOM),
+            statements: syntax_statements,
+            trailing_tokens: tokens.collect(),
+        }
+    }
+
+    /// Returns every token in the order of the source
+    pub fn tokens(&self) -> impl Iterator<Item = &SyntaxToken<'a>> {
+        self.statements
+            .iter()
+            .flat_map(|statement| statement.leading_tokens.iter().chain(&statement.tokens))
+            .chain(&self.trailing_tokens)
+    }
+
+    /// Writes the tokens back as CQL
+    ///
+    /// Gives the original source if no token is edited.
+    pub fn unparse(&self) -> String {
+        let mut output = String::new();
+        if self.byte_order_mark {
+            output.push(BOM);
+        }
+        for token in self.tokens() {
+            output.push_str(&token.text);
+        }
+        output
+    }
+}
+
+impl<'a> SyntaxStatement<'a> {
+    /// Returns the tokens in `span`, such as the span of an AST node in this statement
+    pub fn tokens_in(&self, span: Span) -> impl Iterator<Item = &SyntaxToken<'a>> {
+        self.tokens.iter().filter(move |token| {
+            token.token.offset >= span.start && token.token.offset + token.token.length <= span.end
+        })
+    }
+
+    /// Writes the tokens of this statement back as CQL, without leading tokens
+    pub fn unparse(&self) -> String {
+        self.tokens.iter().map(|token| &*token.text).collect()
+    }
+}
@@ -10,28 +10,298 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+use std::fmt;
+
+use crate::lexer::BOM;
+
+/// Error returned when parsing CQL fails
+///
+/// Details are boxed to keep `Result<_, ParseError>` small,
+/// since parser functions return it everywhere.
+#[derive(Clone, Eq, Hash, PartialEq)]
 pub struct ParseError {
-    path: Option<String>,
-    line: String,
+    detail: Box<ErrorDetail>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct ErrorDetail {
+    /// Name of the source, such as the file path, given by `Parser::with_source_name`
+    source_name: Option<String>,
+    message: String,
     continued_line: Option<String>,
+    /// Descriptions of the tokens or syntax elements that were expected
+    expected: Vec<String>,
+    /// The token actually found, or `None` at the end of the input
+    found: Option<String>,
+    /// Byte offset where the error occurred
+    offset: Option<usize>,
+    /// Position resolved from `offset`
+    position: Option<Position>,
+    /// What was being parsed when the error occurred, from the innermost
+    context: Vec<String>,
+    /// True if the input ended before the statement was complete
+    incomplete: bool,
+}
+
+/// Position in the original CQL string
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position {
+    /// Position in bytes from the beginning
+    pub offset: usize,
+    /// Line number, starting from 1
+    pub line: usize,
+    /// Column number in chars, starting from 1
+    pub column: usize,
+}
+
+impl Position {
+    /// Calculates line and column of given byte `offset` in `cql`
+    pub fn from_offset(cql: &str, offset: usize) -> Self {
+        let before = cql.get(..offset).unwrap_or(cql);
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let mut line_before = &before[line_start..];
+        if line_start == 0 {
+            // byte order mark is skipped by `Lexer`
+            line_before = line_before.strip_prefix(BOM).unwrap_or(line_before);
+        }
+        let column = line_before.chars().count() + 1;
+        Position {
+            offset,
+            line,
+            column,
+        }
+    }
+}
+
+impl Default for ParseError {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ParseError {
     pub fn new() -> Self {
-        ParseError {
-            path: None,
-            line: String::from(""),
-            continued_line: None,
-        }
+        Self::with_message(String::from(""))
     }
 
     pub fn with_message(message: String) -> Self {
         ParseError {
-            path: None,
-            line: message,
-            continued_line: None,
+            detail: Box::new(ErrorDetail {
+                source_name: None,
+                message,
+                continued_line: None,
+                expected: Vec::new(),
+                found: None,
+                offset: None,
+                position: None,
+                context: Vec::new(),
+                incomplete: false,
+            }),
+        }
+    }
+
+    /// Creates an error saying that one of `expected` was expected, but `found` was found
+    ///
+    /// `found` is `None` when the input ended unexpectedly.
+    pub fn unexpected(expected: Vec<String>, found: Option<String>) -> Self {
+        let expected_message = match expected.split_last() {
+            Some((last, rest)) if !rest.is_empty() => format!("{} or {}", rest.join(", "), last),
+            Some((last, _)) => last.clone(),
+            None => String::from("nothing"),
+        };
+        let found_message = match &found {
+            // string literals and quoted names are already quoted
+            Some(f) if f.starts_with(['\'', '"']) || f.starts_with("$$") => f.clone(),
+            Some(f) => format!("'{}'", f),
+            None => String::from("end of input"),
+        };
+        let mut error = Self::with_message(format!(
+            "expected {}, found {}",
+            expected_message, found_message
+        ));
+        error.detail.incomplete = found.is_none();
+        error.detail.expected = expected;
+        error.detail.found = found;
+        error
+    }
+
+    /// Creates an error saying that one of `expected` was expected,
+    /// but `found` could not be tokenized because of `reason`
+    pub(crate) fn invalid_token(expected: Vec<String>, found: String, reason: &str) -> Self {
+        Self::unexpected(expected, Some(found)).with_reason(reason)
+    }
+
+    /// Appends why this error occurred to the message, such as `expected WITH, found ';' (reason)`
+    pub(crate) fn with_reason(mut self, reason: &str) -> Self {
+        self.detail.message = format!("{} ({})", self.detail.message, reason);
+        self
+    }
+
+    /// Marks this error as caused by the end of the input, such as in an unterminated string
+    pub(crate) fn at_end_of_input(mut self) -> Self {
+        self.detail.incomplete = true;
+        self
+    }
+
+    /// Sets the byte offset where this error occurred if not set yet
+    pub(crate) fn or_offset(mut self, offset: usize) -> Self {
+        self.detail.offset.get_or_insert(offset);
+        self
+    }
+
+    /// Adds what was being parsed when this error occurred,
+    /// such as `column definition v`, outside of the frames added so far
+    pub(crate) fn in_context(mut self, frame: String) -> Self {
+        self.detail.context.push(frame);
+        self
+    }
+
+    /// Resolves line and column of this error in given CQL string
+    ///
+    /// Line and column are calculated lazily here, since errors are created
+    /// and thrown away frequently while trying alternatives during parsing.
+    pub(crate) fn locate(mut self, cql: &str) -> Self {
+        if let Some(offset) = self.detail.offset {
+            self.detail.position = Some(Position::from_offset(cql, offset));
+        }
+        self
+    }
+
+    /// Sets the name of the source where this error occurred
+    pub(crate) fn in_source(mut self, source_name: &str) -> Self {
+        self.detail.source_name = Some(source_name.to_owned());
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.detail.message
+    }
+
+    /// Returns the descriptions of what was expected where this error occurred
+    pub fn expected(&self) -> &[String] {
+        &self.detail.expected
+    }
+
+    /// Returns the token found where this error occurred,
+    /// or `None` if the input ended unexpectedly
+    pub fn found(&self) -> Option<&str> {
+        self.detail.found.as_deref()
+    }
+
+    /// Returns the name of the source, such as the file path, where this error occurred
+    ///
+    /// This is the name given by `Parser::with_source_name`.
+    pub fn source_name(&self) -> Option<&str> {
+        self.detail.source_name.as_deref()
+    }
+
+    /// Returns what was being parsed when this error occurred, from the innermost,
+    /// such as `["column definition v", "CREATE TABLE t"]`
+    pub fn context(&self) -> &[String] {
+        &self.detail.context
+    }
+
+    /// Returns the position where this error occurred, if known
+    pub fn position(&self) -> Option<Position> {
+        self.detail.position
+    }
+
+    /// Returns the byte offset where this error occurred, if known
+    pub fn offset(&self) -> Option<usize> {
+        self.detail.offset
+    }
+
+    /// Returns the line number (starting from 1) where this error occurred, if known
+    pub fn line(&self) -> Option<usize> {
+        self.detail.position.map(|p| p.line)
+    }
+
+    /// Returns the column number (starting from 1) where this error occurred, if known
+    pub fn column(&self) -> Option<usize> {
+        self.detail.position.map(|p| p.column)
+    }
+
+    /// Returns true if the input ended in the middle of a statement,
+    /// such as `SELECT * FROM` or `SELECT * FROM t WHERE a = 'abc`
+    ///
+    /// Interactive callers can read more lines and parse again,
+    /// instead of reporting the error.
+    ///
+    /// ```
+    /// use cqlparser::Parser;
+    ///
+    /// let error = Parser::new("SELECT * FROM").parse().unwrap_err();
+    /// assert!(error.is_incomplete_input());
+    /// let error = Parser::new("SELECT * FROM ;").parse().unwrap_err();
+    /// assert!(!error.is_incomplete_input());
+    /// ```
+    pub fn is_incomplete_input(&self) -> bool {
+        self.detail.incomplete
+    }
+}
+
+impl fmt::Debug for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParseError")
+            .field("source_name", &self.detail.source_name)
+            .field("message", &self.detail.message)
+            .field("expected", &self.detail.expected)
+            .field("found", &self.detail.found)
+            .field("position", &self.detail.position)
+            .field("context", &self.detail.context)
+            .field("incomplete", &self.detail.incomplete)
+            .finish()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(source_name) = &self.detail.source_name {
+            write!(f, "{}:", source_name)?;
+            if self.detail.position.is_none() {
+                write!(f, " ")?;
+            }
+        }
+        if let Some(position) = self.detail.position {
+            write!(f, "{}:{}: ", position.line, position.column)?;
+        }
+        if self.detail.message.is_empty() {
+            write!(f, "syntax error")?;
+        } else {
+            write!(f, "{}", self.detail.message)?;
         }
+        for frame in &self.detail.context {
+            write!(f, "\n  in {}", frame)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Serializes the error as a flat object of `message`, `expected`, `found`,
+/// `offset`, `line`, `column`, `context` and `incomplete`, for consumers such as JavaScript
+#[cfg(target_arch = "wasm32")]
+impl serde::Serialize for ParseError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let message = if self.detail.message.is_empty() {
+            "syntax error"
+        } else {
+            &self.detail.message
+        };
+        let mut s = serializer.serialize_struct("ParseError", 8)?;
+        s.serialize_field("message", message)?;
+        s.serialize_field("expected", &self.detail.expected)?;
+        s.serialize_field("found", &self.detail.found)?;
+        s.serialize_field("offset", &self.detail.offset)?;
+        s.serialize_field("line", &self.line())?;
+        s.serialize_field("column", &self.column())?;
+        s.serialize_field("context", &self.detail.context)?;
+        s.serialize_field("incomplete", &self.detail.incomplete)?;
+        s.end()
     }
 }
@@ -0,0 +1,125 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fingerprints of queries that identify the shape of the queries
+
+use crate::ast::transform::parameterize;
+use crate::ast::visit::{walk_expression_chain_mut, walk_literal_mut, VisitorMut};
+use crate::ast::{Expression, ExpressionKind, Literal};
+use crate::format::format_single_line;
+use crate::{ParseError, Parser};
+
+/// Returns the fingerprint of CQL statements, which is the same for queries
+/// of the same shape
+///
+/// Statements are normalized as follows, and written in a single line
+/// in the same way as `format`:
+///
+/// - constants, such as `1` or `'text'`, are replaced with `?`
+/// - named bind markers are replaced with `?`
+/// - lists, sets and maps of `?`, such as `IN (?, ?, ?)`, are collapsed into a single element,
+///   and so are lists of tuples of `?`, such as `(a, b) IN ((?, ?), (?, ?))`
+/// - whitespaces and comments are removed, keywords are uppercased
+///   and unquoted names are lowercased
+///
+/// The order of columns and relations is kept, so queries that select columns
+/// in different order have different fingerprints. Multiple statements are joined
+/// with `; `.
+///
+/// ```
+/// use cqlparser::fingerprint;
+/// assert_eq!(
+///     fingerprint("select a, b from Users where id in (1, 2, 3) and v = 'x'").unwrap(),
+///     "SELECT a, b FROM users WHERE id IN (?) AND v = ?"
+/// );
+/// assert_eq!(
+///     fingerprint("SELECT a,b FROM users WHERE id IN (4) AND v = :v").unwrap(),
+///     "SELECT a, b FROM users WHERE id IN (?) AND v = ?"
+/// );
+/// ```
+pub fn fingerprint(cql: &str) -> Result<String, ParseError> {
+    let statements = Parser::new(cql).parse_spanned()?;
+    let fingerprints: Vec<String> = statements
+        .into_iter()
+        .map(|mut statement| {
+            parameterize(&mut statement.node);
+            Normalizer.visit_statement_mut(&mut statement.node);
+            format_single_line(&statement.node).unwrap_or_else(|| {
+                // statements without AST are kept as written, in a single line
+                let source = cql[statement.span.range()].trim_end_matches(';');
+                source.split_whitespace().collect::<Vec<_>>().join(" ")
+            })
+        })
+        .collect();
+    Ok(fingerprints.join("; "))
+}
+
+/// Returns 64-bit hash of `fingerprint(cql)`
+///
+/// The hash is FNV-1a of the fingerprint in UTF-8, which does not change
+/// across processes or versions of Rust, so it can be stored or sent
+/// to other processes.
+///
+/// ```
+/// use cqlparser::fingerprint_hash;
+/// assert_eq!(
+///     fingerprint_hash("SELECT * FROM t WHERE k = 1").unwrap(),
+///     fingerprint_hash("select * from t where k=2").unwrap()
+/// );
+/// ```
+pub fn fingerprint_hash(cql: &str) -> Result<u64, ParseError> {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let hash = fingerprint(cql)?.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    });
+    Ok(hash)
+}
+
+// Replaces named bind markers with `?`, and collapses lists, sets and maps of `?`
+// and lists of tuples of `?`, which `parameterize` leaves, into a single element
+struct Normalizer;
+
+impl VisitorMut for Normalizer {
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        walk_expression_chain_mut(self, expression);
+    }
+
+    fn visit_literal_mut(&mut self, literal: &mut Literal) {
+        walk_literal_mut(self, literal);
+        match literal {
+            Literal::Binding(name) => *name = None,
+            Literal::List(elements) | Literal::Set(elements)
+                if elements.iter().all(is_binding) || elements.iter().all(is_tuple_of_bindings) =>
+            {
+                elements.truncate(1)
+            }
+            Literal::Map(entries)
+                if entries
+                    .iter()
+                    .all(|(key, value)| is_binding(key) && is_binding(value)) =>
+            {
+                entries.truncate(1)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_binding(expression: &Expression) -> bool {
+    matches!(expression.kind, ExpressionKind::Value(Literal::Binding(_)))
+}
+
+// Values of multi-column IN, such as `(?, ?)` of `(a, b) IN ((?, ?), (?, ?))`
+fn is_tuple_of_bindings(expression: &Expression) -> bool {
+    matches!(&expression.kind, ExpressionKind::Value(Literal::Tuple(elements)) if elements.iter().all(is_binding))
+}
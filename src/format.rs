@@ -0,0 +1,674 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Opinionated formatting of CQL statements
+
+use crate::ast::{
+    table_options, AlterKeyspaceStatement, AlterTableOperation, AlterTableStatement,
+    AlterTypeOperation, AlterTypeStatement, BatchStatement, BatchType, CqlStatement,
+    CreateIndexStatement, CreateKeyspaceStatement, CreateMaterializedViewStatement,
+    CreateTableStatement, CreateTypeStatement, DeleteStatement, DisplayIdentifier,
+    DisplayPrimaryKey, DropStatement, IndexTarget, IndexType, InsertMethod, InsertStatement,
+    JsonBehavior, Literal, Property, SelectStatement, Spanned, UpdateStatement, WhereClause,
+};
+use crate::highlight::{highlight, SemanticTokenKind};
+use crate::lexer::{Keyword, Lexer, TokenType};
+use crate::parser::{Parser, ParserOptions};
+use crate::ParseError;
+
+/// Options of `format`
+#[derive(Clone, Debug)]
+pub struct FormatOptions {
+    /// Write keywords in uppercase, otherwise in lowercase
+    pub uppercase_keywords: bool,
+    /// Number of spaces to indent continued lines
+    pub indent_width: usize,
+    /// Write each column of CREATE TABLE and each field of CREATE TYPE on its own line
+    pub column_per_line: bool,
+    /// WHERE clauses longer than this are split into one relation per line
+    pub max_line_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            uppercase_keywords: true,
+            indent_width: 4,
+            column_per_line: true,
+            max_line_width: 80,
+        }
+    }
+}
+
+/// Formats CQL statements
+///
+/// Each clause of a statement starts on its own line, and statements are separated
+/// by a blank line. Comments are kept: the ones before or inside a statement are written
+/// above the statement, and the one following a statement on the same line stays there.
+/// Unquoted names are written as interpreted by Cassandra, that is, column names
+/// are lowercased.
+///
+/// Formatting the output again gives the same output.
+///
+/// ```
+/// use cqlparser::{format, FormatOptions};
+/// let formatted = format(
+///     "select a,b from ks.t where k=1;  -- first",
+///     FormatOptions::default(),
+/// );
+/// assert_eq!(
+///     formatted.unwrap(),
+///     "SELECT a, b\nFROM ks.t\nWHERE k = 1; -- first\n"
+/// );
+/// ```
+pub fn format(cql: &str, options: FormatOptions) -> Result<String, ParseError> {
+    let parser_options = ParserOptions {
+        preserve_comments: true,
+        ..ParserOptions::default()
+    };
+    let statements = Parser::with_options(cql, parser_options).parse_spanned()?;
+    let formatter = Formatter { options: &options };
+
+    let mut blocks: Vec<String> = statements
+        .iter()
+        .map(|statement| formatter.format_spanned(cql, statement))
+        .collect();
+    // comments after the last statement
+    let end = statements.last().map_or(0, |statement| statement.span.end);
+    let skip = match statements.last() {
+        Some(Spanned {
+            trailing_comment: Some(_),
+            ..
+        }) => 1,
+        _ => 0,
+    };
+    let rest: Vec<String> = comments(&cql[end..]).skip(skip).collect();
+    if !rest.is_empty() {
+        blocks.push(rest.join("\n"));
+    }
+    if blocks.is_empty() {
+        return Ok(String::new());
+    }
+
+    let mut output = blocks.join("\n\n");
+    output.push('\n');
+    if !options.uppercase_keywords {
+        output = lowercase_keywords(&output);
+    }
+    Ok(output)
+}
+
+/// Formats a statement in the same way as `format`, without trailing `;`
+///
+/// This is meant for statements built as AST, such as the ones returned by
+/// `SchemaChange::to_statement`. Returns `None` for statements that `Parser`
+/// does not support yet.
+///
+/// ```
+/// use cqlparser::{format_statement, FormatOptions, Parser};
+/// let statement = Parser::new("drop table if exists ks.t").parse().unwrap().remove(0);
+/// assert_eq!(
+///     format_statement(&statement, &FormatOptions::default()).unwrap(),
+///     "DROP TABLE IF EXISTS ks.t"
+/// );
+/// ```
+pub fn format_statement(statement: &CqlStatement, options: &FormatOptions) -> Option<String> {
+    let formatted = Formatter { options }.format_statement(statement)?;
+    if options.uppercase_keywords {
+        Some(formatted)
+    } else {
+        Some(lowercase_keywords(&formatted))
+    }
+}
+
+// Formats a statement in a single line, with keywords in uppercase
+pub(crate) fn format_single_line(statement: &CqlStatement) -> Option<String> {
+    let options = FormatOptions {
+        uppercase_keywords: true,
+        indent_width: 0,
+        column_per_line: false,
+        max_line_width: usize::MAX,
+    };
+    let lines = Formatter { options: &options }.statement_lines(statement)?;
+    Some(lines.join(" "))
+}
+
+// Returns comments in `cql`, without trailing whitespaces
+fn comments(cql: &str) -> impl Iterator<Item = String> + '_ {
+    Lexer::new(cql)
+        .filter(|(_, token)| matches!(token.token_type, TokenType::Comment(_)))
+        .map(|(text, _)| text.trim_end().to_owned())
+}
+
+// Lowercases keywords that are not used as names
+fn lowercase_keywords(cql: &str) -> String {
+    let mut output = String::with_capacity(cql.len());
+    let mut offset = 0;
+    for token in highlight(cql) {
+        if token.kind == SemanticTokenKind::Keyword {
+            output.push_str(&cql[offset..token.span.start]);
+            output.push_str(&cql[token.span.range()].to_ascii_lowercase());
+            offset = token.span.end;
+        }
+    }
+    output.push_str(&cql[offset..]);
+    output
+}
+
+struct Formatter<'a> {
+    options: &'a FormatOptions,
+}
+
+impl Formatter<'_> {
+    // Formats statement with its comments
+    fn format_spanned(&self, cql: &str, statement: &Spanned<CqlStatement>) -> String {
+        let mut output = String::new();
+        let inner_comments = comments(&cql[statement.span.range()]);
+        for comment in statement
+            .leading_comments
+            .iter()
+            .cloned()
+            .chain(inner_comments)
+        {
+            output.push_str(comment.trim_end());
+            output.push('\n');
+        }
+        match self.format_statement(&statement.node) {
+            Some(formatted) => output.push_str(&formatted),
+            // write statements without AST as is
+            None => output.push_str(cql[statement.span.range()].trim_end_matches(';').trim()),
+        }
+        output.push(';');
+        if let Some(comment) = &statement.trailing_comment {
+            output.push(' ');
+            output.push_str(comment.trim_end());
+        }
+        output
+    }
+
+    fn format_statement(&self, statement: &CqlStatement) -> Option<String> {
+        self.statement_lines(statement)
+            .map(|lines| lines.join("\n"))
+    }
+
+    fn statement_lines(&self, statement: &CqlStatement) -> Option<Vec<String>> {
+        let lines = match statement {
+            CqlStatement::Select(select) => self.select(select),
+            CqlStatement::Insert(insert) => self.insert(insert),
+            CqlStatement::Update(update) => self.update(update),
+            CqlStatement::Delete(delete) => self.delete(delete),
+            CqlStatement::Batch(batch) => self.batch(batch)?,
+            CqlStatement::Truncate(name) => vec![format!("TRUNCATE {}", name)],
+            CqlStatement::CreateKeyspace(keyspace) => self.create_keyspace(keyspace),
+            CqlStatement::CreateTable(table) => self.create_table(table),
+            CqlStatement::CreateIndex(index) => self.create_index(index),
+            CqlStatement::CreateType(user_type) => self.create_type(user_type),
+            CqlStatement::CreateMaterializedView(view) => self.create_materialized_view(view),
+            CqlStatement::AlterTable(alter) => self.alter_table(alter),
+            CqlStatement::AlterType(alter) => self.alter_type(alter),
+            CqlStatement::AlterKeyspace(alter) => self.alter_keyspace(alter),
+            CqlStatement::Drop(drop) => vec![drop_statement(drop)?],
+            CqlStatement::Use(keyspace) => vec![format!("USE {}", DisplayIdentifier(keyspace))],
+            _ => return None,
+        };
+        Some(lines)
+    }
+
+    fn indent(&self, level: usize) -> String {
+        " ".repeat(level * self.options.indent_width)
+    }
+
+    fn select(&self, select: &SelectStatement) -> Vec<String> {
+        let mut line = "SELECT ".to_owned();
+        if select.is_json {
+            line.push_str("JSON ");
+        }
+        if select.is_distinct {
+            line.push_str("DISTINCT ");
+        }
+        line.push_str(&select.projection.to_string());
+        let mut lines = vec![line, format!("FROM {}", select.table_name)];
+        if let Some(selection) = &select.selection {
+            lines.extend(self.where_clause(selection, 0));
+        }
+        if !select.group_by.is_empty() {
+            lines.push(format!("GROUP BY {}", join(&select.group_by)));
+        }
+        if let Some(limit) = &select.per_partition_limit {
+            lines.push(format!("PER PARTITION LIMIT {}", limit));
+        }
+        if let Some(limit) = &select.limit {
+            lines.push(format!("LIMIT {}", limit));
+        }
+        if select.allow_filtering {
+            lines.push("ALLOW FILTERING".to_owned());
+        }
+        lines
+    }
+
+    // Writes WHERE clause in a line, or one relation per line if it is too long
+    fn where_clause(&self, selection: &WhereClause, level: usize) -> Vec<String> {
+        let indent = self.indent(level);
+        let line = format!("{}WHERE {}", indent, selection);
+        let relations = selection.relations();
+        if line.chars().count() <= self.options.max_line_width || relations.len() < 2 {
+            return vec![line];
+        }
+        let mut lines = vec![format!("{}WHERE {}", indent, relations[0])];
+        let indent = self.indent(level + 1);
+        lines.extend(
+            relations[1..]
+                .iter()
+                .map(|relation| format!("{}AND {}", indent, relation)),
+        );
+        lines
+    }
+
+    // Appends WITH clause to the last line, continued with one option per line
+    fn with_clause<I>(&self, lines: &mut Vec<String>, options: I)
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let indent = self.indent(1);
+        for (i, option) in options.into_iter().enumerate() {
+            if i == 0 {
+                let last = lines.last_mut().expect("statement has at least one line");
+                last.push_str(" WITH ");
+                last.push_str(&option);
+            } else {
+                lines.push(format!("{}AND {}", indent, option));
+            }
+        }
+    }
+
+    fn insert(&self, insert: &InsertStatement) -> Vec<String> {
+        let mut lines = match &insert.values {
+            InsertMethod::Normal { columns, values } => vec![
+                format!("INSERT INTO {} ({})", insert.table, join(columns)),
+                format!("VALUES ({})", join(values)),
+            ],
+            InsertMethod::Json {
+                value,
+                default_behavior,
+            } => {
+                let mut line = format!("INSERT INTO {} JSON {}", insert.table, value);
+                if *default_behavior == JsonBehavior::Null {
+                    line.push_str(" DEFAULT NULL");
+                }
+                vec![line]
+            }
+        };
+        if insert.if_not_exists {
+            lines.push("IF NOT EXISTS".to_owned());
+        }
+        lines.extend(using_clause(&insert.time_to_live, &insert.timestamp));
+        lines
+    }
+
+    fn update(&self, update: &UpdateStatement) -> Vec<String> {
+        let mut lines = vec![format!("UPDATE {}", update.table)];
+        lines.extend(using_clause(&update.time_to_live, &update.timestamp));
+        lines.push(format!("SET {}", join(&update.assignments)));
+        lines.extend(self.where_clause(&update.selection, 0));
+        if update.if_exists {
+            lines.push("IF EXISTS".to_owned());
+        }
+        if let Some(conditions) = &update.conditions {
+            lines.push(format!("IF {}", conditions));
+        }
+        lines
+    }
+
+    fn delete(&self, delete: &DeleteStatement) -> Vec<String> {
+        let mut lines = Vec::new();
+        if delete.targets.is_empty() {
+            lines.push(format!("DELETE FROM {}", delete.table));
+        } else {
+            lines.push(format!("DELETE {}", join(&delete.targets)));
+            lines.push(format!("FROM {}", delete.table));
+        }
+        lines.extend(using_clause(&None, &delete.timestamp));
+        lines.extend(self.where_clause(&delete.selection, 0));
+        if delete.if_exists {
+            lines.push("IF EXISTS".to_owned());
+        }
+        if let Some(conditions) = &delete.conditions {
+            lines.push(format!("IF {}", conditions));
+        }
+        lines
+    }
+
+    // Statements in the batch are indented, and terminated by `;`
+    fn batch(&self, batch: &BatchStatement) -> Option<Vec<String>> {
+        let batch_type = match batch.batch_type {
+            BatchType::Logged => "",
+            BatchType::Unlogged => "UNLOGGED ",
+            BatchType::Counter => "COUNTER ",
+        };
+        let mut line = format!("BEGIN {}BATCH", batch_type);
+        if let Some(using) = using_clause(&None, &batch.timestamp) {
+            line.push(' ');
+            line.push_str(&using);
+        }
+        let mut lines = vec![line];
+        let indent = self.indent(1);
+        for statement in &batch.statements {
+            let mut statement_lines = self.statement_lines(statement)?;
+            if let Some(last) = statement_lines.last_mut() {
+                last.push(';');
+            }
+            lines.extend(
+                statement_lines
+                    .into_iter()
+                    .map(|line| format!("{}{}", indent, line)),
+            );
+        }
+        lines.push("APPLY BATCH".to_owned());
+        Some(lines)
+    }
+
+    fn create_keyspace(&self, keyspace: &CreateKeyspaceStatement) -> Vec<String> {
+        let mut lines = vec![format!(
+            "CREATE KEYSPACE {}{}",
+            if_not_exists(keyspace.if_not_exists),
+            DisplayIdentifier(&keyspace.keyspace_name)
+        )];
+        self.with_clause(&mut lines, property_list(&keyspace.attributes));
+        lines
+    }
+
+    fn alter_keyspace(&self, keyspace: &AlterKeyspaceStatement) -> Vec<String> {
+        let mut lines = vec![format!(
+            "ALTER KEYSPACE {}{}",
+            if_exists(keyspace.if_exists),
+            DisplayIdentifier(&keyspace.keyspace_name)
+        )];
+        self.with_clause(&mut lines, property_list(&keyspace.attributes));
+        lines
+    }
+
+    fn create_table(&self, table: &CreateTableStatement) -> Vec<String> {
+        let mut definitions: Vec<String> = table
+            .column_definitions
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        definitions.extend(
+            table
+                .primary_key_definition()
+                .as_ref()
+                .map(DisplayPrimaryKey::to_string),
+        );
+        let header = format!(
+            "CREATE TABLE {}{}",
+            if_not_exists(table.if_not_exists),
+            table.name
+        );
+        let mut lines = self.definitions(header, definitions);
+        let options = table_options(
+            table.compact_storage,
+            &table.clustering_order,
+            &table.table_properties,
+        );
+        self.with_clause(&mut lines, options);
+        lines
+    }
+
+    fn create_type(&self, user_type: &CreateTypeStatement) -> Vec<String> {
+        let fields = user_type
+            .field_definitions
+            .iter()
+            .map(|(name, data_type)| format!("{} {}", DisplayIdentifier(name), data_type))
+            .collect();
+        let header = format!(
+            "CREATE TYPE {}{}",
+            if_not_exists(user_type.if_not_exists),
+            user_type.name
+        );
+        self.definitions(header, fields)
+    }
+
+    // Writes `header (definitions...)`, one definition per line if configured so
+    fn definitions(&self, header: String, definitions: Vec<String>) -> Vec<String> {
+        if !self.options.column_per_line {
+            return vec![format!("{} ({})", header, definitions.join(", "))];
+        }
+        let indent = self.indent(1);
+        let count = definitions.len();
+        let mut lines = vec![format!("{} (", header)];
+        lines.extend(definitions.into_iter().enumerate().map(|(i, definition)| {
+            let separator = if i + 1 < count { "," } else { "" };
+            format!("{}{}{}", indent, definition, separator)
+        }));
+        lines.push(")".to_owned());
+        lines
+    }
+
+    fn create_index(&self, index: &CreateIndexStatement) -> Vec<String> {
+        let mut line = "CREATE ".to_owned();
+        if index.is_custom {
+            line.push_str("CUSTOM ");
+        }
+        line.push_str("INDEX ");
+        line.push_str(if_not_exists(index.if_not_exists));
+        if let Some(name) = &index.index_name {
+            line.push_str(&format!("{} ", DisplayIdentifier(name)));
+        }
+        let targets: Vec<String> = index.index_targets.iter().map(index_target).collect();
+        line.push_str(&format!("ON {} ({})", index.table_name, targets.join(", ")));
+        vec![line]
+    }
+
+    fn create_materialized_view(&self, view: &CreateMaterializedViewStatement) -> Vec<String> {
+        let indent = self.indent(1);
+        let mut lines = vec![
+            format!(
+                "CREATE MATERIALIZED VIEW {}{} AS",
+                if_not_exists(view.if_not_exists),
+                view.name
+            ),
+            format!("{}SELECT {}", indent, view.projection),
+            format!("{}FROM {}", indent, view.base_table),
+        ];
+        if let Some(selection) = &view.selection {
+            lines.extend(self.where_clause(selection, 1));
+        }
+        let primary_key = DisplayPrimaryKey {
+            partition_keys: &view.partition_keys,
+            clustering_columns: &view.clustering_columns,
+        };
+        lines.push(format!("{}{}", indent, primary_key));
+        let options = table_options(
+            view.compact_storage,
+            &view.clustering_order,
+            &view.view_properties,
+        );
+        // WITH clause starts on its own line, so as not to be read as a part of SELECT
+        let mut options = options.into_iter();
+        if let Some(first) = options.next() {
+            lines.push(format!("WITH {}", first));
+            lines.extend(options.map(|option| format!("{}AND {}", indent, option)));
+        }
+        lines
+    }
+
+    fn alter_table(&self, alter: &AlterTableStatement) -> Vec<String> {
+        let mut line = format!("ALTER TABLE {}{} ", if_exists(alter.if_exists), alter.name);
+        match &alter.operation {
+            AlterTableOperation::Add {
+                if_not_exists: flag,
+                columns,
+            } => {
+                line.push_str("ADD ");
+                line.push_str(if_not_exists(*flag));
+                line.push_str(&parenthesize(columns.iter().map(ToString::to_string)));
+            }
+            AlterTableOperation::Drop {
+                if_exists: flag,
+                columns,
+                timestamp,
+            } => {
+                line.push_str("DROP ");
+                line.push_str(if_exists(*flag));
+                line.push_str(&parenthesize(
+                    columns.iter().map(|c| DisplayIdentifier(c).to_string()),
+                ));
+                if let Some(timestamp) = timestamp {
+                    line.push_str(&format!(" USING TIMESTAMP {}", timestamp));
+                }
+            }
+            AlterTableOperation::Rename {
+                if_exists: flag,
+                columns,
+            } => {
+                line.push_str("RENAME ");
+                line.push_str(if_exists(*flag));
+                line.push_str(&renames(columns.iter().map(|(from, to)| (&**from, &**to))));
+            }
+            AlterTableOperation::AlterType { column, new_type } => {
+                line.push_str(&format!(
+                    "ALTER {} TYPE {}",
+                    DisplayIdentifier(column),
+                    new_type
+                ));
+            }
+            AlterTableOperation::DropCompactStorage => line.push_str("DROP COMPACT STORAGE"),
+            AlterTableOperation::WithProperties(properties) => {
+                let mut lines = vec![line.trim_end().to_owned()];
+                self.with_clause(&mut lines, property_list(properties));
+                return lines;
+            }
+        }
+        vec![line]
+    }
+
+    fn alter_type(&self, alter: &AlterTypeStatement) -> Vec<String> {
+        let mut line = format!("ALTER TYPE {}{} ", if_exists(alter.if_exists), alter.name);
+        match &alter.operation {
+            AlterTypeOperation::Add {
+                if_not_exists: flag,
+                field,
+                data_type,
+            } => {
+                line.push_str("ADD ");
+                line.push_str(if_not_exists(*flag));
+                line.push_str(&format!("{} {}", DisplayIdentifier(field), data_type));
+            }
+            AlterTypeOperation::Rename {
+                if_exists: flag,
+                fields,
+            } => {
+                line.push_str("RENAME ");
+                line.push_str(if_exists(*flag));
+                line.push_str(&renames(fields.iter().map(|(from, to)| (&**from, &**to))));
+            }
+        }
+        vec![line]
+    }
+}
+
+fn if_not_exists(flag: bool) -> &'static str {
+    if flag {
+        "IF NOT EXISTS "
+    } else {
+        ""
+    }
+}
+
+fn if_exists(flag: bool) -> &'static str {
+    if flag {
+        "IF EXISTS "
+    } else {
+        ""
+    }
+}
+
+fn drop_statement(drop: &DropStatement) -> Option<String> {
+    let (target, name, flag) = match drop {
+        DropStatement::Keyspace { name, if_exists } => {
+            ("KEYSPACE", DisplayIdentifier(name).to_string(), if_exists)
+        }
+        DropStatement::Table { name, if_exists } => ("TABLE", name.to_string(), if_exists),
+        DropStatement::Index { name, if_exists } => ("INDEX", name.to_string(), if_exists),
+        DropStatement::Type { name, if_exists } => ("TYPE", name.to_string(), if_exists),
+        DropStatement::MaterializedView { name, if_exists } => {
+            ("MATERIALIZED VIEW", name.to_string(), if_exists)
+        }
+        _ => return None,
+    };
+    Some(format!("DROP {} {}{}", target, if_exists(*flag), name))
+}
+
+fn join<T: ToString>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Writes a single item as is, and multiple items in parentheses
+fn parenthesize<I: Iterator<Item = String>>(items: I) -> String {
+    let items: Vec<String> = items.collect();
+    match items.as_slice() {
+        [item] => item.clone(),
+        _ => format!("({})", items.join(", ")),
+    }
+}
+
+fn renames<'a, I: Iterator<Item = (&'a str, &'a str)>>(renames: I) -> String {
+    renames
+        .map(|(from, to)| format!("{} TO {}", DisplayIdentifier(from), DisplayIdentifier(to)))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+fn property_list(properties: &[Property]) -> impl Iterator<Item = String> + '_ {
+    properties.iter().map(ToString::to_string)
+}
+
+// `USING TTL ... AND TIMESTAMP ...` if any
+fn using_clause(time_to_live: &Option<Literal>, timestamp: &Option<Literal>) -> Option<String> {
+    let parameters: Vec<String> = time_to_live
+        .iter()
+        .map(|ttl| format!("TTL {}", ttl))
+        .chain(
+            timestamp
+                .iter()
+                .map(|timestamp| format!("TIMESTAMP {}", timestamp)),
+        )
+        .collect();
+    if parameters.is_empty() {
+        None
+    } else {
+        Some(format!("USING {}", parameters.join(" AND ")))
+    }
+}
+
+fn index_target(target: &IndexTarget) -> String {
+    // keep quoted names quoted, as the quotes are a part of the AST
+    let column = if target.quoted {
+        format!("\"{}\"", target.column.replace('"', "\"\""))
+    } else if Keyword::from_string(&target.column).is_some_and(|k| k.is_unreserved_keyword()) {
+        // unquoted keyword such as `(count)`, which would be a quoted name if quoted
+        target.column.to_string()
+    } else {
+        DisplayIdentifier(&target.column).to_string()
+    };
+    match target.index_type {
+        IndexType::Simple => column,
+        IndexType::Values => format!("values({})", column),
+        IndexType::Keys => format!("keys({})", column),
+        IndexType::KeysAndValues => format!("entries({})", column),
+        IndexType::Full => format!("full({})", column),
+    }
+}
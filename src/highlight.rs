@@ -0,0 +1,135 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Classification of tokens for editors
+
+use std::collections::HashMap;
+
+use crate::ast::Span;
+use crate::lexer::{Keyword, Lexer, TokenType};
+use crate::parser::Parser;
+
+/// Kind of a token in the context of the statement it belongs to
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+pub enum SemanticTokenKind {
+    /// Keyword that is not used as a name
+    Keyword,
+    /// Native type, collection type or user defined type name
+    Type,
+    Keyspace,
+    /// Table or materialized view name
+    Table,
+    Column,
+    Function,
+    /// Alias of a selector: `AS alias`
+    Alias,
+    Index,
+    /// Name of a property in WITH clause
+    Property,
+    /// Field of a user defined type
+    Field,
+    /// Name whose role is unknown, in a statement that cannot be parsed
+    Identifier,
+    String,
+    /// Integer or floating point number, including `NaN` and `Infinity`
+    Number,
+    Boolean,
+    Duration,
+    Uuid,
+    Blob,
+    /// `?` or `:name`
+    BindMarker,
+    Operator,
+    Punctuation,
+    Comment,
+    /// Token that cannot be tokenized
+    Error,
+}
+
+/// Token classified by `highlight`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+pub struct SemanticToken {
+    pub span: Span,
+    pub kind: SemanticTokenKind,
+}
+
+/// Classifies tokens in CQL for syntax highlighting
+///
+/// Names are classified by the role they play in the statement, such as table or column,
+/// by parsing the statements. In a statement that fails to parse, tokens after the error
+/// fall back to the classification by their token types. Whitespaces are not included.
+///
+/// ```
+/// use cqlparser::{highlight, SemanticTokenKind};
+/// let cql = "SELECT v FROM ks.t";
+/// let kinds: Vec<_> = highlight(cql).into_iter().map(|t| t.kind).collect();
+/// assert_eq!(
+///     kinds,
+///     [
+///         SemanticTokenKind::Keyword,
+///         SemanticTokenKind::Column,
+///         SemanticTokenKind::Keyword,
+///         SemanticTokenKind::Keyspace,
+///         SemanticTokenKind::Punctuation,
+///         SemanticTokenKind::Table,
+///     ]
+/// );
+/// ```
+pub fn highlight(cql: &str) -> Vec<SemanticToken> {
+    let mut parser = Parser::new(cql).lenient().record_roles();
+    for _ in parser.by_ref() {}
+    // roles recorded later override the earlier ones for the same token
+    let roles: HashMap<usize, SemanticTokenKind> = parser.take_roles().into_iter().collect();
+
+    Lexer::new(cql)
+        .filter(|(_, token)| token.token_type != TokenType::Whitespace)
+        .map(|(_, token)| SemanticToken {
+            span: Span::new(token.offset, token.offset + token.length),
+            kind: roles
+                .get(&token.offset)
+                .copied()
+                .unwrap_or_else(|| lexical_kind(&token.token_type)),
+        })
+        .collect()
+}
+
+// Classification of tokens by their token types
+fn lexical_kind(token_type: &TokenType) -> SemanticTokenKind {
+    match token_type {
+        TokenType::Keyword(Keyword::NaN | Keyword::Infinity) => SemanticTokenKind::Number,
+        TokenType::Keyword(_) => SemanticTokenKind::Keyword,
+        TokenType::StringLiteral => SemanticTokenKind::String,
+        TokenType::Identifier | TokenType::QuotedName => SemanticTokenKind::Identifier,
+        TokenType::Integer | TokenType::Float => SemanticTokenKind::Number,
+        TokenType::Boolean => SemanticTokenKind::Boolean,
+        TokenType::Duration => SemanticTokenKind::Duration,
+        TokenType::Hexnumber => SemanticTokenKind::Blob,
+        TokenType::UUID => SemanticTokenKind::Uuid,
+        TokenType::Qmark => SemanticTokenKind::BindMarker,
+        TokenType::Comment(_) => SemanticTokenKind::Comment,
+        TokenType::MalformedNumber | TokenType::Error(_) => SemanticTokenKind::Error,
+        TokenType::Equal
+        | TokenType::NotEqual
+        | TokenType::Gt
+        | TokenType::Gte
+        | TokenType::Lt
+        | TokenType::Lte
+        | TokenType::Plus
+        | TokenType::Minus
+        | TokenType::Asterisk
+        | TokenType::Slash
+        | TokenType::Percent => SemanticTokenKind::Operator,
+        _ => SemanticTokenKind::Punctuation,
+    }
+}
@@ -17,7 +17,7 @@ use std::str::Chars;
 use crate::literal::*;
 
 /// CQL Tokens
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Token {
     /// Type of this token, as defined in `TokenType`.
     pub token_type: TokenType,
@@ -25,18 +25,31 @@ pub struct Token {
     pub offset: usize,
     /// Length of token in bytes.
     pub length: usize,
+    /// Line number of the beginning of token, starting from 1.
+    pub line: u32,
+    /// Column number in chars of the beginning of token, starting from 1.
+    pub column: u32,
 }
 
 impl Token {
-    /// Create new Token with given type, offset and length.
+    /// Create new Token with given type and position.
     ///
     /// `offset` is a position in bytes in original CQL from the beginning.
     /// `length` is a length of token in bytes.
-    pub fn new(token_type: TokenType, offset: usize, length: usize) -> Self {
+    /// `line` and `column` are 1-based, and `column` is counted in chars.
+    pub fn new(
+        token_type: TokenType,
+        offset: usize,
+        length: usize,
+        line: u32,
+        column: u32,
+    ) -> Self {
         Token {
             token_type,
             offset,
             length,
+            line,
+            column,
         }
     }
 
@@ -44,6 +57,22 @@ impl Token {
     pub fn is_type(&self, token_type: TokenType) -> bool {
         self.token_type == token_type
     }
+
+    /// Returns the text of this token in `source`, the CQL this token was produced from
+    ///
+    /// Returns an empty string if `source` does not contain the range of this token.
+    ///
+    /// ```
+    /// use cqlparser::Lexer;
+    /// let cql = "SELECT 'café'";
+    /// let tokens = Lexer::new(cql).tokenize_all();
+    /// assert_eq!(tokens[2].text(cql), "'café'");
+    /// ```
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        source
+            .get(self.offset..self.offset + self.length)
+            .unwrap_or_default()
+    }
 }
 
 /*
@@ -55,7 +84,11 @@ impl Display for Token {
 */
 
 /// Token types
+///
+/// New token types can be added in the future, so matching on `TokenType` outside
+/// of this crate needs a wildcard arm.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum TokenType {
     /// CQL Keywords
     /// (https://cassandra.apache.org/doc/latest/cql/appendices.html#appendix-a-cql-keywords)
@@ -96,6 +129,10 @@ pub enum TokenType {
     /// A UUID constant is defined by hex{8}-hex{4}-hex{4}-hex{4}-hex{12} where
     /// hex is an hexadecimal character, e.g. [0-9a-fA-F] and {4} is the number of such characters.
     UUID,
+    /// Token that begins with a digit but is not a valid constant,
+    /// such as `99percentile`, `99.9PERCENTILE` or `123abc`.
+    /// [0-9][a-zA-Z0-9_]* ('.' [a-zA-Z0-9_]*)?
+    MalformedNumber,
     /// Whitespace
     /// (' ' | '\t' | '\n' | '\r')+
     Whitespace,
@@ -154,8 +191,124 @@ pub enum TokenType {
     RBrace,
     /// EOF
     EOF,
-    /// Error token
-    Error,
+    /// Error token, with the reason why it cannot be tokenized
+    Error(LexErrorKind),
+}
+
+/// Reasons of `TokenType::Error`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LexErrorKind {
+    /// String literal without closing `'` or `$$`
+    UnterminatedString,
+    /// Quoted name without closing `"`
+    UnterminatedQuotedName,
+    /// Multi-line comment without closing `*/`
+    UnterminatedComment,
+    /// Character that cannot start any token
+    InvalidCharacter,
+    /// Non-ASCII character outside of quoted names and string literals,
+    /// such as `é` in unquoted `café`
+    NonAsciiCharacter,
+    /// Token beginning with a digit that is not a valid constant
+    InvalidNumber,
+    /// Token beginning with an alphabet that looks like, but is not
+    /// a valid UUID or duration
+    InvalidLiteral,
+}
+
+impl std::fmt::Display for LexErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            LexErrorKind::UnterminatedString => "unterminated string literal",
+            LexErrorKind::UnterminatedQuotedName => "unterminated quoted name",
+            LexErrorKind::UnterminatedComment => "unterminated comment",
+            LexErrorKind::InvalidCharacter => "invalid character",
+            LexErrorKind::NonAsciiCharacter => {
+                "non-ASCII character is only allowed inside quoted identifiers or string literals"
+            }
+            LexErrorKind::InvalidNumber => "invalid number",
+            LexErrorKind::InvalidLiteral => "invalid literal",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+impl TokenType {
+    /// Describes the token type for error messages
+    ///
+    /// Same as `Display`, except that punctuations and operators are quoted,
+    /// such as `')'`, so that they can be told from the words around them.
+    ///
+    /// ```
+    /// use cqlparser::{Keyword, TokenType};
+    /// assert_eq!(TokenType::RParen.describe(), "')'");
+    /// assert_eq!(TokenType::Keyword(Keyword::From).describe(), "FROM");
+    /// assert_eq!(TokenType::QuotedName.describe(), "quoted name");
+    /// ```
+    pub fn describe(&self) -> String {
+        match self.symbol() {
+            Some(symbol) => format!("'{}'", symbol),
+            None => self.to_string(),
+        }
+    }
+
+    // Returns the text of punctuations and operators
+    fn symbol(&self) -> Option<&'static str> {
+        let symbol = match self {
+            TokenType::Equal => "=",
+            TokenType::NotEqual => "!=",
+            TokenType::Gt => ">",
+            TokenType::Gte => ">=",
+            TokenType::Lt => "<",
+            TokenType::Lte => "<=",
+            TokenType::Plus => "+",
+            TokenType::Minus => "-",
+            TokenType::Asterisk => "*",
+            TokenType::Slash => "/",
+            TokenType::Percent => "%",
+            TokenType::Dot => ".",
+            TokenType::Range => "..",
+            TokenType::SemiColon => ";",
+            TokenType::Colon => ":",
+            TokenType::Comma => ",",
+            TokenType::LParen => "(",
+            TokenType::RParen => ")",
+            TokenType::LBracket => "[",
+            TokenType::RBracket => "]",
+            TokenType::Ampersand => "&",
+            TokenType::Qmark => "?",
+            TokenType::LBrace => "{",
+            TokenType::RBrace => "}",
+            _ => return None,
+        };
+        Some(symbol)
+    }
+}
+
+/// Writes keywords in uppercase, punctuations and operators as written,
+/// and the other token types by what they are, such as `string literal`
+impl std::fmt::Display for TokenType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let description = match self {
+            TokenType::Keyword(keyword) => keyword.as_str(),
+            TokenType::StringLiteral => "string literal",
+            TokenType::Identifier => "identifier",
+            TokenType::QuotedName => "quoted name",
+            TokenType::Integer => "integer",
+            TokenType::Float => "float",
+            TokenType::Boolean => "boolean",
+            TokenType::Duration => "duration",
+            TokenType::Hexnumber => "blob",
+            TokenType::UUID => "UUID",
+            TokenType::MalformedNumber => "malformed number",
+            TokenType::Whitespace => "whitespace",
+            TokenType::Comment(_) => "comment",
+            TokenType::EOF => "end of input",
+            TokenType::Error(kind) => return write!(f, "invalid token ({})", kind),
+            _ => self.symbol().unwrap_or_default(),
+        };
+        f.write_str(description)
+    }
 }
 
 /// CQL keywords
@@ -167,8 +320,11 @@ pub enum TokenType {
 /// | t=native_type              { $str = t.toString(); }
 /// | k=(K_TTL | K_COUNT | K_WRITETIME | K_KEY | K_CAST | K_JSON | K_DISTINCT) { $str = $k.text; }
 /// ;
-
+///
+/// New keywords are added as CQL evolves, so matching on `Keyword` outside
+/// of this crate needs a wildcard arm.
 #[derive(Clone, Eq, PartialEq, Debug)]
+#[non_exhaustive]
 pub enum Keyword {
     Select,
     From,
@@ -313,167 +469,518 @@ pub enum Keyword {
     Default,
     Unset,
     Like,
+    Masked,
+}
+
+// Length of the longest keyword, `COLUMNFAMILIES`
+const MAX_KEYWORD_LENGTH: usize = 14;
+
+// Keywords and their aliases in uppercase, sorted for binary search
+const KEYWORDS: &[(&str, Keyword)] = &[
+    ("ACCESS", Keyword::Access),
+    ("ADD", Keyword::Add),
+    ("AGGREGATE", Keyword::Aggregate),
+    ("AGGREGATES", Keyword::Aggregates),
+    ("ALL", Keyword::All),
+    ("ALLOW", Keyword::Allow),
+    ("ALTER", Keyword::Alter),
+    ("AND", Keyword::And),
+    ("APPLY", Keyword::Apply),
+    ("AS", Keyword::As),
+    ("ASC", Keyword::Asc),
+    ("ASCII", Keyword::Ascii),
+    ("AUTHORIZE", Keyword::Authorize),
+    ("BATCH", Keyword::Batch),
+    ("BEGIN", Keyword::Begin),
+    ("BIGINT", Keyword::Bigint),
+    ("BLOB", Keyword::Blob),
+    ("BOOLEAN", Keyword::Boolean),
+    ("BY", Keyword::By),
+    ("CALLED", Keyword::Called),
+    ("CAST", Keyword::Cast),
+    ("CLUSTER", Keyword::Cluster),
+    ("CLUSTERING", Keyword::Clustering),
+    ("COLUMNFAMILIES", Keyword::Tables),
+    ("COLUMNFAMILY", Keyword::Table),
+    ("COMPACT", Keyword::Compact),
+    ("CONTAINS", Keyword::Contains),
+    ("COUNT", Keyword::Count),
+    ("COUNTER", Keyword::Counter),
+    ("CREATE", Keyword::Create),
+    ("CUSTOM", Keyword::Custom),
+    ("DATACENTERS", Keyword::Datacenters),
+    ("DATE", Keyword::Date),
+    ("DECIMAL", Keyword::Decimal),
+    ("DEFAULT", Keyword::Default),
+    ("DELETE", Keyword::Delete),
+    ("DESC", Keyword::Desc),
+    ("DESCRIBE", Keyword::Describe),
+    ("DISTINCT", Keyword::Distinct),
+    ("DOUBLE", Keyword::Double),
+    ("DROP", Keyword::Drop),
+    ("DURATION", Keyword::Duration),
+    ("ENTRIES", Keyword::Entries),
+    ("EXECUTE", Keyword::Execute),
+    ("EXISTS", Keyword::Exists),
+    ("FILTERING", Keyword::Filtering),
+    ("FINALFUNC", Keyword::FinalFunc),
+    ("FLOAT", Keyword::Float),
+    ("FROM", Keyword::From),
+    ("FROZEN", Keyword::Frozen),
+    ("FULL", Keyword::Full),
+    ("FUNCTION", Keyword::Function),
+    ("FUNCTIONS", Keyword::Functions),
+    ("GRANT", Keyword::Grant),
+    ("GROUP", Keyword::Group),
+    ("IF", Keyword::If),
+    ("IN", Keyword::In),
+    ("INDEX", Keyword::Index),
+    ("INET", Keyword::Inet),
+    ("INFINITY", Keyword::Infinity),
+    ("INITCOND", Keyword::InitCond),
+    ("INPUT", Keyword::Input),
+    ("INSERT", Keyword::Insert),
+    ("INT", Keyword::Int),
+    ("INTERNALS", Keyword::Internals),
+    ("INTO", Keyword::Into),
+    ("IS", Keyword::Is),
+    ("JSON", Keyword::Json),
+    ("KEY", Keyword::Key),
+    ("KEYS", Keyword::Keys),
+    ("KEYSPACE", Keyword::Keyspace),
+    ("KEYSPACES", Keyword::Keyspaces),
+    ("LANGUAGE", Keyword::Language),
+    ("LIKE", Keyword::Like),
+    ("LIMIT", Keyword::Limit),
+    ("LIST", Keyword::List),
+    ("LOGIN", Keyword::Login),
+    ("MAP", Keyword::Map),
+    ("MASKED", Keyword::Masked),
+    ("MATERIALIZED", Keyword::Materialized),
+    ("MBEAN", Keyword::MBean),
+    ("MBEANS", Keyword::MBeans),
+    ("MODIFY", Keyword::Modify),
+    ("NAN", Keyword::NaN),
+    ("NOLOGIN", Keyword::NoLogin),
+    ("NORECURSIVE", Keyword::NoRecursive),
+    ("NOSUPERUSER", Keyword::NoSuperuser),
+    ("NOT", Keyword::Not),
+    ("NULL", Keyword::Null),
+    ("OF", Keyword::Of),
+    ("ON", Keyword::On),
+    ("ONLY", Keyword::Only),
+    ("OPTIONS", Keyword::Options),
+    ("OR", Keyword::Or),
+    ("ORDER", Keyword::Order),
+    ("PARTITION", Keyword::Partition),
+    ("PASSWORD", Keyword::Password),
+    ("PER", Keyword::Per),
+    ("PERMISSION", Keyword::Permission),
+    ("PERMISSIONS", Keyword::Permissions),
+    ("PRIMARY", Keyword::Primary),
+    ("RENAME", Keyword::Rename),
+    ("REPLACE", Keyword::Replace),
+    ("RETURNS", Keyword::Returns),
+    ("REVOKE", Keyword::Revoke),
+    ("ROLE", Keyword::Role),
+    ("ROLES", Keyword::Roles),
+    ("SCHEMA", Keyword::Schema),
+    ("SELECT", Keyword::Select),
+    ("SET", Keyword::Set),
+    ("SFUNC", Keyword::SFunc),
+    ("SMALLINT", Keyword::SmallInt),
+    ("STATIC", Keyword::Static),
+    ("STORAGE", Keyword::Storage),
+    ("STYPE", Keyword::SType),
+    ("SUPERUSER", Keyword::Superuser),
+    ("TABLE", Keyword::Table),
+    ("TABLES", Keyword::Tables),
+    ("TEXT", Keyword::Text),
+    ("TIME", Keyword::Time),
+    ("TIMESTAMP", Keyword::Timestamp),
+    ("TIMEUUID", Keyword::TimeUUID),
+    ("TINYINT", Keyword::TinyInt),
+    ("TO", Keyword::To),
+    ("TOKEN", Keyword::Token),
+    ("TRIGGER", Keyword::Trigger),
+    ("TRUNCATE", Keyword::Truncate),
+    ("TTL", Keyword::Ttl),
+    ("TUPLE", Keyword::Tuple),
+    ("TYPE", Keyword::Type),
+    ("TYPES", Keyword::Types),
+    ("UNLOGGED", Keyword::Unlogged),
+    ("UNSET", Keyword::Unset),
+    ("UPDATE", Keyword::Update),
+    ("USE", Keyword::Use),
+    ("USER", Keyword::User),
+    ("USERS", Keyword::Users),
+    ("USING", Keyword::Using),
+    ("UUID", Keyword::UUID),
+    ("VALUES", Keyword::Values),
+    ("VARCHAR", Keyword::Varchar),
+    ("VARINT", Keyword::VarInt),
+    ("VIEW", Keyword::View),
+    ("WHERE", Keyword::Where),
+    ("WITH", Keyword::With),
+    ("WRITETIME", Keyword::WriteTime),
+];
+
+// Defines `Keyword::ALL`, which fails to compile unless every keyword is listed
+macro_rules! all_keywords {
+    ($($keyword:ident,)*) => {
+        impl Keyword {
+            /// Every keyword, such as for completion of keywords
+            ///
+            /// ```
+            /// use cqlparser::Keyword;
+            /// let keywords: Vec<_> = Keyword::iter()
+            ///     .map(Keyword::as_str)
+            ///     .filter(|k| k.starts_with("SEL"))
+            ///     .collect();
+            /// assert_eq!(keywords, ["SELECT"]);
+            /// ```
+            pub const ALL: &'static [Keyword] = &[$(Keyword::$keyword),*];
+        }
+
+        const _: fn(&Keyword) = |keyword| match keyword {
+            $(Keyword::$keyword)|* => {}
+        };
+    };
+}
+
+// in the order of declaration; new keywords are also added to `KEYWORDS`
+all_keywords! {
+    Select,
+    From,
+    As,
+    Where,
+    And,
+    Key,
+    Keys,
+    Entries,
+    Full,
+    Insert,
+    Update,
+    With,
+    Limit,
+    Per,
+    Partition,
+    Using,
+    Use,
+    Distinct,
+    Count,
+    Set,
+    Begin,
+    Unlogged,
+    Batch,
+    Apply,
+    Truncate,
+    Delete,
+    In,
+    Create,
+    Schema,
+    Keyspace,
+    Keyspaces,
+    Table,
+    Tables,
+    Materialized,
+    View,
+    Index,
+    Custom,
+    On,
+    To,
+    Drop,
+    Primary,
+    Into,
+    Values,
+    Timestamp,
+    Ttl,
+    Cast,
+    Alter,
+    Rename,
+    Add,
+    Type,
+    Types,
+    Compact,
+    Storage,
+    Order,
+    By,
+    Asc,
+    Desc,
+    Allow,
+    Filtering,
+    If,
+    Is,
+    Contains,
+    Group,
+    Cluster,
+    Internals,
+    Only,
+    Grant,
+    All,
+    Permission,
+    Permissions,
+    Of,
+    Revoke,
+    Modify,
+    Authorize,
+    Describe,
+    Execute,
+    NoRecursive,
+    MBean,
+    MBeans,
+    User,
+    Users,
+    Role,
+    Roles,
+    Superuser,
+    NoSuperuser,
+    Password,
+    Login,
+    NoLogin,
+    Options,
+    Access,
+    Datacenters,
+    Clustering,
+    Ascii,
+    Bigint,
+    Blob,
+    Boolean,
+    Counter,
+    Decimal,
+    Double,
+    Duration,
+    Float,
+    Inet,
+    Int,
+    SmallInt,
+    TinyInt,
+    Text,
+    UUID,
+    Varchar,
+    VarInt,
+    TimeUUID,
+    Token,
+    WriteTime,
+    Date,
+    Time,
+    Null,
+    Not,
+    Exists,
+    Map,
+    List,
+    NaN,
+    Infinity,
+    Tuple,
+    Trigger,
+    Static,
+    Frozen,
+    Function,
+    Functions,
+    Aggregate,
+    Aggregates,
+    SFunc,
+    SType,
+    FinalFunc,
+    InitCond,
+    Returns,
+    Called,
+    Input,
+    Language,
+    Or,
+    Replace,
+    Json,
+    Default,
+    Unset,
+    Like,
+    Masked,
 }
 
 impl Keyword {
+    /// Returns an iterator over every keyword in `Keyword::ALL`
+    pub fn iter() -> std::slice::Iter<'static, Keyword> {
+        Keyword::ALL.iter()
+    }
+
     /// Returns `Some(Keyword)` if given `s` is a keyword
     /// Otherwise, returns `None`.
+    ///
+    /// Keywords are matched case-insensitively.
     pub fn from_string(s: &str) -> Option<Self> {
-        match s.to_uppercase().as_ref() {
-            "SELECT" => Some(Keyword::Select),
-            "FROM" => Some(Keyword::From),
-            "AS" => Some(Keyword::As),
-            "WHERE" => Some(Keyword::Where),
-            "AND" => Some(Keyword::And),
-            "KEY" => Some(Keyword::Key),
-            "KEYS" => Some(Keyword::Keys),
-            "ENTRIES" => Some(Keyword::Entries),
-            "FULL" => Some(Keyword::Full),
-            "INSERT" => Some(Keyword::Insert),
-            "UPDATE" => Some(Keyword::Update),
-            "WITH" => Some(Keyword::With),
-            "LIMIT" => Some(Keyword::Limit),
-            "PER" => Some(Keyword::Per),
-            "PARTITION" => Some(Keyword::Partition),
-            "USING" => Some(Keyword::Using),
-            "USE" => Some(Keyword::Use),
-            "DISTINCT" => Some(Keyword::Distinct),
-            "COUNT" => Some(Keyword::Count),
-            "SET" => Some(Keyword::Set),
-            "BEGIN" => Some(Keyword::Begin),
-            "UNLOGGED" => Some(Keyword::Unlogged),
-            "BATCH" => Some(Keyword::Batch),
-            "APPLY" => Some(Keyword::Apply),
-            "TRUNCATE" => Some(Keyword::Truncate),
-            "DELETE" => Some(Keyword::Delete),
-            "IN" => Some(Keyword::In),
-            "CREATE" => Some(Keyword::Create),
-            "SCHEMA" => Some(Keyword::Schema),
-            "KEYSPACE" => Some(Keyword::Keyspace),
-            "KEYSPACES" => Some(Keyword::Keyspaces),
-            "COLUMNFAMILY" | "TABLE" => Some(Keyword::Table),
-            "COLUMNFAMILIES" | "TABLES" => Some(Keyword::Tables),
-            "MATERIALIZED" => Some(Keyword::Materialized),
-            "VIEW" => Some(Keyword::View),
-            "INDEX" => Some(Keyword::Index),
-            "CUSTOM" => Some(Keyword::Custom),
-            "ON" => Some(Keyword::On),
-            "TO" => Some(Keyword::To),
-            "DROP" => Some(Keyword::Drop),
-            "PRIMARY" => Some(Keyword::Primary),
-            "INTO" => Some(Keyword::Into),
-            "VALUES" => Some(Keyword::Values),
-            "TIMESTAMP" => Some(Keyword::Timestamp),
-            "TTL" => Some(Keyword::Ttl),
-            "CAST" => Some(Keyword::Cast),
-            "ALTER" => Some(Keyword::Alter),
-            "RENAME" => Some(Keyword::Rename),
-            "ADD" => Some(Keyword::Add),
-            "TYPE" => Some(Keyword::Type),
-            "TYPES" => Some(Keyword::Types),
-            "COMPACT" => Some(Keyword::Compact),
-            "STORAGE" => Some(Keyword::Storage),
-            "ORDER" => Some(Keyword::Order),
-            "BY" => Some(Keyword::By),
-            "ASC" => Some(Keyword::Asc),
-            "DESC" => Some(Keyword::Desc),
-            "ALLOW" => Some(Keyword::Allow),
-            "FILTERING" => Some(Keyword::Filtering),
-            "IF" => Some(Keyword::If),
-            "IS" => Some(Keyword::Is),
-            "CONTAINS" => Some(Keyword::Contains),
-            "GROUP" => Some(Keyword::Group),
-            "CLUSTER" => Some(Keyword::Cluster),
-            "INTERNALS" => Some(Keyword::Internals),
-            "ONLY" => Some(Keyword::Only),
-
-            "GRANT" => Some(Keyword::Grant),
-            "ALL" => Some(Keyword::All),
-            "PERMISSION" => Some(Keyword::Permission),
-            "PERMISSIONS" => Some(Keyword::Permissions),
-            "OF" => Some(Keyword::Of),
-            "REVOKE" => Some(Keyword::Revoke),
-            "MODIFY" => Some(Keyword::Modify),
-            "AUTHORIZE" => Some(Keyword::Authorize),
-            "DESCRIBE" => Some(Keyword::Describe),
-            "EXECUTE" => Some(Keyword::Execute),
-            "NORECURSIVE" => Some(Keyword::NoRecursive),
-            "MBEAN" => Some(Keyword::MBean),
-            "MBEANS" => Some(Keyword::MBeans),
-
-            "USER" => Some(Keyword::User),
-            "USERS" => Some(Keyword::Users),
-            "ROLE" => Some(Keyword::Role),
-            "ROLES" => Some(Keyword::Roles),
-            "SUPERUSER" => Some(Keyword::Superuser),
-            "NOSUPERUSER" => Some(Keyword::NoSuperuser),
-            "PASSWORD" => Some(Keyword::Password),
-            "LOGIN" => Some(Keyword::Login),
-            "NOLOGIN" => Some(Keyword::NoLogin),
-            "OPTIONS" => Some(Keyword::Options),
-            "ACCESS" => Some(Keyword::Access),
-            "DATACENTERS" => Some(Keyword::Datacenters),
-
-            "CLUSTERING" => Some(Keyword::Clustering),
-            "ASCII" => Some(Keyword::Ascii),
-            "BIGINT" => Some(Keyword::Bigint),
-            "BLOB" => Some(Keyword::Blob),
-            "BOOLEAN" => Some(Keyword::Boolean),
-            "COUNTER" => Some(Keyword::Counter),
-            "DECIMAL" => Some(Keyword::Decimal),
-            "DOUBLE" => Some(Keyword::Double),
-            "DURATION" => Some(Keyword::Duration),
-            "FLOAT" => Some(Keyword::Float),
-            "INET" => Some(Keyword::Inet),
-            "INT" => Some(Keyword::Int),
-            "SMALLINT" => Some(Keyword::SmallInt),
-            "TINYINT" => Some(Keyword::TinyInt),
-            "TEXT" => Some(Keyword::Text),
-            "UUID" => Some(Keyword::UUID),
-            "VARCHAR" => Some(Keyword::Varchar),
-            "VARINT" => Some(Keyword::VarInt),
-            "TIMEUUID" => Some(Keyword::TimeUUID),
-            "TOKEN" => Some(Keyword::Token),
-            "WRITETIME" => Some(Keyword::WriteTime),
-            "DATE" => Some(Keyword::Date),
-            "TIME" => Some(Keyword::Time),
-
-            "NULL" => Some(Keyword::Null),
-            "NOT" => Some(Keyword::Not),
-            "EXISTS" => Some(Keyword::Exists),
-
-            "MAP" => Some(Keyword::Map),
-            "LIST" => Some(Keyword::List),
-            "TUPLE" => Some(Keyword::Tuple),
-
-            // these are kind of float
-            "NAN" => Some(Keyword::NaN),
-            "INFINITY" => Some(Keyword::Infinity),
-
-            "TRIGGER" => Some(Keyword::Trigger),
-            "STATIC" => Some(Keyword::Static),
-            "FROZEN" => Some(Keyword::Frozen),
-
-            "FUNCTION" => Some(Keyword::Function),
-            "FUNCTIONS" => Some(Keyword::Functions),
-            "AGGREGATE" => Some(Keyword::Aggregate),
-            "AGGREGATES" => Some(Keyword::Aggregates),
-            "SFUNC" => Some(Keyword::SFunc),
-            "STYPE" => Some(Keyword::SType),
-            "FINALFUNC" => Some(Keyword::FinalFunc),
-            "INITCOND" => Some(Keyword::InitCond),
-            "RETURNS" => Some(Keyword::Returns),
-            "CALLED" => Some(Keyword::Called),
-            "INPUT" => Some(Keyword::Input),
-            "LANGUAGE" => Some(Keyword::Language),
-            "OR" => Some(Keyword::Or),
-            "REPLACE" => Some(Keyword::Replace),
-
-            "JSON" => Some(Keyword::Json),
-            "DEFAULT" => Some(Keyword::Default),
-            "UNSET" => Some(Keyword::Unset),
-            "LIKE" => Some(Keyword::Like),
-            _ => None,
+        if s.len() > MAX_KEYWORD_LENGTH {
+            return None;
+        }
+        // compared without uppercasing `s` into a new string,
+        // since this is called for every identifier
+        KEYWORDS
+            .binary_search_by(|(keyword, _)| {
+                keyword
+                    .bytes()
+                    .cmp(s.bytes().map(|b| b.to_ascii_uppercase()))
+            })
+            .ok()
+            .map(|i| KEYWORDS[i].1.clone())
+    }
+
+    /// Returns the keyword in uppercase, as written in CQL
+    ///
+    /// `Keyword::from_string` returns the same keyword for the returned string.
+    /// Keywords with aliases return their current names, such as `TABLE`
+    /// for `COLUMNFAMILY`.
+    ///
+    /// ```
+    /// use cqlparser::Keyword;
+    /// assert_eq!(Keyword::Select.as_str(), "SELECT");
+    /// assert_eq!(Keyword::from_string("ColumnFamily").unwrap().as_str(), "TABLE");
+    /// ```
+    pub fn as_str(&self) -> &'static str {
+        // Every keyword must be written here,
+        // so do not add a wildcard arm.
+        match self {
+            Keyword::Select => "SELECT",
+            Keyword::From => "FROM",
+            Keyword::As => "AS",
+            Keyword::Where => "WHERE",
+            Keyword::And => "AND",
+            Keyword::Key => "KEY",
+            Keyword::Keys => "KEYS",
+            Keyword::Entries => "ENTRIES",
+            Keyword::Full => "FULL",
+            Keyword::Insert => "INSERT",
+            Keyword::Update => "UPDATE",
+            Keyword::With => "WITH",
+            Keyword::Limit => "LIMIT",
+            Keyword::Per => "PER",
+            Keyword::Partition => "PARTITION",
+            Keyword::Using => "USING",
+            Keyword::Use => "USE",
+            Keyword::Distinct => "DISTINCT",
+            Keyword::Count => "COUNT",
+            Keyword::Set => "SET",
+            Keyword::Begin => "BEGIN",
+            Keyword::Unlogged => "UNLOGGED",
+            Keyword::Batch => "BATCH",
+            Keyword::Apply => "APPLY",
+            Keyword::Truncate => "TRUNCATE",
+            Keyword::Delete => "DELETE",
+            Keyword::In => "IN",
+            Keyword::Create => "CREATE",
+            Keyword::Schema => "SCHEMA",
+            Keyword::Keyspace => "KEYSPACE",
+            Keyword::Keyspaces => "KEYSPACES",
+            Keyword::Table => "TABLE",
+            Keyword::Tables => "TABLES",
+            Keyword::Materialized => "MATERIALIZED",
+            Keyword::View => "VIEW",
+            Keyword::Index => "INDEX",
+            Keyword::Custom => "CUSTOM",
+            Keyword::On => "ON",
+            Keyword::To => "TO",
+            Keyword::Drop => "DROP",
+            Keyword::Primary => "PRIMARY",
+            Keyword::Into => "INTO",
+            Keyword::Values => "VALUES",
+            Keyword::Timestamp => "TIMESTAMP",
+            Keyword::Ttl => "TTL",
+            Keyword::Cast => "CAST",
+            Keyword::Alter => "ALTER",
+            Keyword::Rename => "RENAME",
+            Keyword::Add => "ADD",
+            Keyword::Type => "TYPE",
+            Keyword::Types => "TYPES",
+            Keyword::Compact => "COMPACT",
+            Keyword::Storage => "STORAGE",
+            Keyword::Order => "ORDER",
+            Keyword::By => "BY",
+            Keyword::Asc => "ASC",
+            Keyword::Desc => "DESC",
+            Keyword::Allow => "ALLOW",
+            Keyword::Filtering => "FILTERING",
+            Keyword::If => "IF",
+            Keyword::Is => "IS",
+            Keyword::Contains => "CONTAINS",
+            Keyword::Group => "GROUP",
+            Keyword::Cluster => "CLUSTER",
+            Keyword::Internals => "INTERNALS",
+            Keyword::Only => "ONLY",
+            Keyword::Grant => "GRANT",
+            Keyword::All => "ALL",
+            Keyword::Permission => "PERMISSION",
+            Keyword::Permissions => "PERMISSIONS",
+            Keyword::Of => "OF",
+            Keyword::Revoke => "REVOKE",
+            Keyword::Modify => "MODIFY",
+            Keyword::Authorize => "AUTHORIZE",
+            Keyword::Describe => "DESCRIBE",
+            Keyword::Execute => "EXECUTE",
+            Keyword::NoRecursive => "NORECURSIVE",
+            Keyword::MBean => "MBEAN",
+            Keyword::MBeans => "MBEANS",
+            Keyword::User => "USER",
+            Keyword::Users => "USERS",
+            Keyword::Role => "ROLE",
+            Keyword::Roles => "ROLES",
+            Keyword::Superuser => "SUPERUSER",
+            Keyword::NoSuperuser => "NOSUPERUSER",
+            Keyword::Password => "PASSWORD",
+            Keyword::Login => "LOGIN",
+            Keyword::NoLogin => "NOLOGIN",
+            Keyword::Options => "OPTIONS",
+            Keyword::Access => "ACCESS",
+            Keyword::Datacenters => "DATACENTERS",
+            Keyword::Clustering => "CLUSTERING",
+            Keyword::Ascii => "ASCII",
+            Keyword::Bigint => "BIGINT",
+            Keyword::Blob => "BLOB",
+            Keyword::Boolean => "BOOLEAN",
+            Keyword::Counter => "COUNTER",
+            Keyword::Decimal => "DECIMAL",
+            Keyword::Double => "DOUBLE",
+            Keyword::Duration => "DURATION",
+            Keyword::Float => "FLOAT",
+            Keyword::Inet => "INET",
+            Keyword::Int => "INT",
+            Keyword::SmallInt => "SMALLINT",
+            Keyword::TinyInt => "TINYINT",
+            Keyword::Text => "TEXT",
+            Keyword::UUID => "UUID",
+            Keyword::Varchar => "VARCHAR",
+            Keyword::VarInt => "VARINT",
+            Keyword::TimeUUID => "TIMEUUID",
+            Keyword::Token => "TOKEN",
+            Keyword::WriteTime => "WRITETIME",
+            Keyword::Date => "DATE",
+            Keyword::Time => "TIME",
+            Keyword::Null => "NULL",
+            Keyword::Not => "NOT",
+            Keyword::Exists => "EXISTS",
+            Keyword::Map => "MAP",
+            Keyword::List => "LIST",
+            Keyword::NaN => "NAN",
+            Keyword::Infinity => "INFINITY",
+            Keyword::Tuple => "TUPLE",
+            Keyword::Trigger => "TRIGGER",
+            Keyword::Static => "STATIC",
+            Keyword::Frozen => "FROZEN",
+            Keyword::Function => "FUNCTION",
+            Keyword::Functions => "FUNCTIONS",
+            Keyword::Aggregate => "AGGREGATE",
+            Keyword::Aggregates => "AGGREGATES",
+            Keyword::SFunc => "SFUNC",
+            Keyword::SType => "STYPE",
+            Keyword::FinalFunc => "FINALFUNC",
+            Keyword::InitCond => "INITCOND",
+            Keyword::Returns => "RETURNS",
+            Keyword::Called => "CALLED",
+            Keyword::Input => "INPUT",
+            Keyword::Language => "LANGUAGE",
+            Keyword::Or => "OR",
+            Keyword::Replace => "REPLACE",
+            Keyword::Json => "JSON",
+            Keyword::Default => "DEFAULT",
+            Keyword::Unset => "UNSET",
+            Keyword::Like => "LIKE",
+            Keyword::Masked => "MASKED",
         }
     }
 
@@ -559,16 +1066,16 @@ impl Keyword {
     pub fn is_unreserved_keyword(&self) -> bool {
         self.is_basic_unreserved_keyword()
             | self.is_native_type()
-            | match self {
+            | matches!(
+                self,
                 Keyword::Ttl
-                | Keyword::Count
-                | Keyword::WriteTime
-                | Keyword::Key
-                | Keyword::Cast
-                | Keyword::Json
-                | Keyword::Distinct => true,
-                _ => false,
-            }
+                    | Keyword::Count
+                    | Keyword::WriteTime
+                    | Keyword::Key
+                    | Keyword::Cast
+                    | Keyword::Json
+                    | Keyword::Distinct
+            )
     }
 
     /// Returns true if this keyword can be used as function name,
@@ -579,129 +1086,219 @@ impl Keyword {
     pub fn is_unreserved_for_function_name(&self) -> bool {
         self.is_basic_unreserved_keyword()
             | self.is_native_type()
-            | match self {
-                Keyword::Token | Keyword::Count => true,
-                _ => false,
-            }
+            | matches!(self, Keyword::Token | Keyword::Count)
     }
 
     /// Returns true if this keyword describes CQL3 native data type.
     pub fn is_native_type(&self) -> bool {
-        match self {
+        matches!(
+            self,
             Keyword::Ascii
-            | Keyword::Bigint
-            | Keyword::Blob
-            | Keyword::Boolean
-            | Keyword::Counter
-            | Keyword::Decimal
-            | Keyword::Double
-            | Keyword::Duration
-            | Keyword::Float
-            | Keyword::Inet
-            | Keyword::Int
-            | Keyword::SmallInt
-            | Keyword::Text
-            | Keyword::Timestamp
-            | Keyword::TinyInt
-            | Keyword::UUID
-            | Keyword::Varchar
-            | Keyword::VarInt
-            | Keyword::TimeUUID
-            | Keyword::Date
-            | Keyword::Time => true,
-            _ => false,
-        }
+                | Keyword::Bigint
+                | Keyword::Blob
+                | Keyword::Boolean
+                | Keyword::Counter
+                | Keyword::Decimal
+                | Keyword::Double
+                | Keyword::Duration
+                | Keyword::Float
+                | Keyword::Inet
+                | Keyword::Int
+                | Keyword::SmallInt
+                | Keyword::Text
+                | Keyword::Timestamp
+                | Keyword::TinyInt
+                | Keyword::UUID
+                | Keyword::Varchar
+                | Keyword::VarInt
+                | Keyword::TimeUUID
+                | Keyword::Date
+                | Keyword::Time
+        )
     }
 
     pub fn is_basic_unreserved_keyword(&self) -> bool {
-        match self {
+        matches!(
+            self,
             Keyword::Keys
-            | Keyword::As
-            | Keyword::Cluster
-            | Keyword::Clustering
-            | Keyword::Compact
-            | Keyword::Storage
-            | Keyword::Tables
-            | Keyword::Type
-            | Keyword::Types
-            | Keyword::Values
-            | Keyword::Map
-            | Keyword::List
-            | Keyword::Filtering
-            | Keyword::Permission
-            | Keyword::Permissions
-            | Keyword::Keyspaces
-            | Keyword::All
-            | Keyword::User
-            | Keyword::Users
-            | Keyword::Role
-            | Keyword::Roles
-            | Keyword::Superuser
-            | Keyword::NoSuperuser
-            | Keyword::Login
-            | Keyword::NoLogin
-            | Keyword::Options
-            | Keyword::Password
-            | Keyword::Exists
-            | Keyword::Custom
-            | Keyword::Trigger
-            | Keyword::Contains
-            | Keyword::Internals
-            | Keyword::Only
-            | Keyword::Static
-            | Keyword::Frozen
-            | Keyword::Tuple
-            | Keyword::Function
-            | Keyword::Functions
-            | Keyword::Aggregate
-            | Keyword::Aggregates
-            | Keyword::SFunc
-            | Keyword::SType
-            | Keyword::FinalFunc
-            | Keyword::InitCond
-            | Keyword::Returns
-            | Keyword::Language
-            | Keyword::Called
-            | Keyword::Input
-            | Keyword::Like
-            | Keyword::Per
-            | Keyword::Partition
-            | Keyword::Group
-            | Keyword::Datacenters
-            | Keyword::Access
-            | Keyword::Default
-            | Keyword::MBean
-            | Keyword::MBeans
-            | Keyword::Replace
-            | Keyword::Unset => true,
-            _ => false,
-        }
+                | Keyword::As
+                | Keyword::Cluster
+                | Keyword::Clustering
+                | Keyword::Compact
+                | Keyword::Storage
+                | Keyword::Tables
+                | Keyword::Type
+                | Keyword::Types
+                | Keyword::Values
+                | Keyword::Map
+                | Keyword::List
+                | Keyword::Filtering
+                | Keyword::Permission
+                | Keyword::Permissions
+                | Keyword::Keyspaces
+                | Keyword::All
+                | Keyword::User
+                | Keyword::Users
+                | Keyword::Role
+                | Keyword::Roles
+                | Keyword::Superuser
+                | Keyword::NoSuperuser
+                | Keyword::Login
+                | Keyword::NoLogin
+                | Keyword::Options
+                | Keyword::Password
+                | Keyword::Exists
+                | Keyword::Custom
+                | Keyword::Trigger
+                | Keyword::Contains
+                | Keyword::Internals
+                | Keyword::Only
+                | Keyword::Static
+                | Keyword::Frozen
+                | Keyword::Tuple
+                | Keyword::Function
+                | Keyword::Functions
+                | Keyword::Aggregate
+                | Keyword::Aggregates
+                | Keyword::SFunc
+                | Keyword::SType
+                | Keyword::FinalFunc
+                | Keyword::InitCond
+                | Keyword::Returns
+                | Keyword::Language
+                | Keyword::Called
+                | Keyword::Input
+                | Keyword::Like
+                | Keyword::Per
+                | Keyword::Partition
+                | Keyword::Group
+                | Keyword::Datacenters
+                | Keyword::Access
+                | Keyword::Default
+                | Keyword::MBean
+                | Keyword::MBeans
+                | Keyword::Replace
+                | Keyword::Unset
+                | Keyword::Masked
+        )
     }
 }
 
+/// Writes the keyword in uppercase, as `Keyword::as_str`
+impl std::fmt::Display for Keyword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Options to change tokens produced by `Lexer`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LexerOptions {
+    /// Produce `TokenType::EOF` with zero length once at the end of input
+    pub emit_eof: bool,
+    /// Do not produce whitespaces and comments
+    pub skip_trivia: bool,
+}
+
+/// UTF-8 byte order mark
+pub(crate) const BOM: char = '\u{FEFF}';
+
 /// CQL Lexer
 ///
 /// Tokenize CQL
 /// Implements iterator to produce `Token`s
-#[derive(Debug)]
+///
+/// ## Example
+///
+/// ```
+/// use cqlparser::{Lexer, LexerOptions, TokenType};
+/// let options = LexerOptions {
+///     emit_eof: true,
+///     skip_trivia: true,
+/// };
+/// let tokens: Vec<_> = Lexer::with_options("SELECT * -- all", options)
+///     .map(|(_, t)| t.token_type)
+///     .collect();
+/// assert_eq!(tokens.len(), 3);
+/// assert_eq!(tokens[2], TokenType::EOF);
+/// ```
+#[derive(Clone, Debug)]
 pub struct Lexer<'a> {
     original: &'a str,
+    options: LexerOptions,
+    /// Set after EOF token is produced
+    eof_emitted: bool,
     cql: Peekable<Chars<'a>>,
     token_start: usize,
     token_end: usize,
+    /// Line and column of the beginning of the current token
+    token_line: u32,
+    token_column: u32,
+    /// Line and column of the next char
+    line: u32,
+    column: u32,
 }
 
 impl<'a> Lexer<'a> {
     /// Create new lexer for given CQL string.
+    ///
+    /// A leading UTF-8 byte order mark is skipped, as files saved by some editors have one.
     pub fn new(cql: &'a str) -> Self {
+        Lexer::with_options(cql, LexerOptions::default())
+    }
+
+    /// Create new lexer for given CQL string, with options to change produced tokens.
+    pub fn with_options(cql: &'a str, options: LexerOptions) -> Self {
+        // offsets of tokens are still counted from the beginning of `cql`
+        let bom = if cql.starts_with(BOM) {
+            BOM.len_utf8()
+        } else {
+            0
+        };
         Lexer {
             original: cql,
-            cql: cql.chars().peekable(),
-            token_start: 0,
-            token_end: 0,
+            options,
+            eof_emitted: false,
+            cql: cql[bom..].chars().peekable(),
+            token_start: bom,
+            token_end: bom,
+            token_line: 1,
+            token_column: 1,
+            line: 1,
+            column: 1,
         }
     }
 
+    /// Tokenize the whole input
+    ///
+    /// Whitespaces and comments are included unless `LexerOptions::skip_trivia` is set,
+    /// so that the tokens cover the whole input, for example to highlight syntax.
+    ///
+    /// ```
+    /// use cqlparser::{Keyword, Lexer, TokenType};
+    /// let tokens = Lexer::new("SELECT *").tokenize_all();
+    /// assert_eq!(tokens.len(), 3);
+    /// assert_eq!(tokens[0].token_type, TokenType::Keyword(Keyword::Select));
+    /// assert_eq!(tokens[1].token_type, TokenType::Whitespace);
+    /// ```
+    pub fn tokenize_all(self) -> Vec<Token> {
+        self.map(|(_, token)| token).collect()
+    }
+
+    /// Tokenize `cql` into tokens with their owned text, which can outlive `cql`
+    ///
+    /// ```
+    /// use cqlparser::{Lexer, TokenType};
+    /// let tokens = Lexer::tokenize("SELECT *");
+    /// assert_eq!(tokens[2].0, "*");
+    /// assert_eq!(tokens[2].1.token_type, TokenType::Asterisk);
+    /// ```
+    pub fn tokenize(cql: &str) -> Vec<(String, Token)> {
+        Lexer::new(cql)
+            .map(|(s, token)| (s.to_owned(), token))
+            .collect()
+    }
+
     fn consume_and_create_token(&mut self, token_type: TokenType) -> (&'a str, Token) {
         self.advance();
         self.create_token(token_type)
@@ -716,6 +1313,8 @@ impl<'a> Lexer<'a> {
                 token_type,
                 self.token_start,
                 self.token_end - self.token_start,
+                self.token_line,
+                self.token_column,
             ),
         )
     }
@@ -724,10 +1323,24 @@ impl<'a> Lexer<'a> {
         let c = self.cql.next();
         if let Some(ch) = c {
             self.token_end += ch.len_utf8();
+            // '\r' of CRLF is reset here by the following '\n'
+            if ch == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
         }
         c
     }
 
+    // Returns the char after the next one without consuming
+    fn peek_second(&self) -> Option<char> {
+        let mut ahead = self.cql.clone();
+        ahead.next();
+        ahead.next()
+    }
+
     // String literal
     // - Quoted string literal
     // 'abc'
@@ -752,7 +1365,7 @@ impl<'a> Lexer<'a> {
             }
         }
         let token_type = if in_string {
-            TokenType::Error
+            TokenType::Error(LexErrorKind::UnterminatedString)
         } else {
             TokenType::StringLiteral
         };
@@ -775,7 +1388,7 @@ impl<'a> Lexer<'a> {
             }
         }
         let token_type = if in_string {
-            TokenType::Error
+            TokenType::Error(LexErrorKind::UnterminatedString)
         } else {
             TokenType::StringLiteral
         };
@@ -789,7 +1402,15 @@ impl<'a> Lexer<'a> {
     // - Duration (ISO8601 format)
     // - One of reserved keywords
     // - Identifier
+    //
+    // Parsers of UUID and durations run along with identifier only while they accept
+    // the characters, so most of the identifiers are scanned without them.
     fn parse_alphabet(&mut self) -> (&'a str, Token) {
+        // only UUIDs start with hex digits, and durations with `P`
+        if !matches!(self.cql.peek(), Some(c) if c.is_ascii_hexdigit() || *c == 'P') {
+            self.identifier_chars();
+            return self.identifier_or_keyword();
+        }
         let mut uuid = UUIDParser::new();
         let mut duration = Iso8601Parser::new();
         let mut duration_alt = Iso8601AlternativeParser::new();
@@ -803,10 +1424,7 @@ impl<'a> Lexer<'a> {
                         0 => uuid.accept(&c),
                         1 => duration.accept(&c),
                         2 => duration_alt.accept(&c),
-                        3 => match c {
-                            '0'..='9' | 'A'..='Z' | 'a'..='z' | '_' => true,
-                            _ => false,
-                        },
+                        3 => is_identifier_char(c),
                         _ => unreachable!(),
                     };
                     if accept[i] {
@@ -814,11 +1432,15 @@ impl<'a> Lexer<'a> {
                     }
                 }
             }
-            if accept.iter().any(|b| *b) {
-                self.advance();
-            } else {
+            if !accept.iter().any(|b| *b) {
                 break;
             }
+            self.advance();
+            // identifier is the longest once the others reject
+            if accept[..3] == [false; 3] {
+                self.identifier_chars();
+                return self.identifier_or_keyword();
+            }
         }
         // max length of chars
         let max = length.iter().max().unwrap();
@@ -831,25 +1453,39 @@ impl<'a> Lexer<'a> {
                 {
                     return self.create_token(TokenType::Duration);
                 } else if idx == 3 {
-                    let token_type = match self
-                        .original
-                        .get(self.token_start..self.token_end)
-                        .map(str::to_ascii_uppercase)
-                    {
-                        Some(s) => match s.as_str() {
-                            "TRUE" | "FALSE" => TokenType::Boolean,
-                            _ => Keyword::from_string(&s)
-                                // .filter(Keyword::is_reserved)
-                                .map(TokenType::Keyword)
-                                .unwrap_or(TokenType::Identifier),
-                        },
-                        _ => TokenType::Error,
-                    };
-                    return self.create_token(token_type);
+                    return self.identifier_or_keyword();
                 }
             }
         }
-        self.create_token(TokenType::Error)
+        self.create_token(TokenType::Error(LexErrorKind::InvalidLiteral))
+    }
+
+    // Advance over the following identifier characters
+    fn identifier_chars(&mut self) {
+        let length = self.original.as_bytes()[self.token_end..]
+            .iter()
+            .take_while(|b| is_identifier_char(char::from(**b)))
+            .count();
+        if length > 0 {
+            // identifier characters are ASCII, one byte each and no line break
+            self.token_end += length;
+            self.column += length as u32;
+            self.cql = self.original[self.token_end..].chars().peekable();
+        }
+    }
+
+    // Token of the identifier characters consumed so far
+    fn identifier_or_keyword(&self) -> (&'a str, Token) {
+        let token_type = match self.original.get(self.token_start..self.token_end) {
+            Some(s) if s.eq_ignore_ascii_case("TRUE") => TokenType::Boolean,
+            Some(s) if s.eq_ignore_ascii_case("FALSE") => TokenType::Boolean,
+            Some(s) => Keyword::from_string(s)
+                // .filter(Keyword::is_reserved)
+                .map(TokenType::Keyword)
+                .unwrap_or(TokenType::Identifier),
+            _ => TokenType::Error(LexErrorKind::InvalidLiteral),
+        };
+        self.create_token(token_type)
     }
 
     // Quoted Identifier
@@ -872,13 +1508,29 @@ impl<'a> Lexer<'a> {
             }
         }
         let token_type = if in_quote {
-            TokenType::Error
+            TokenType::Error(LexErrorKind::UnterminatedQuotedName)
         } else {
             TokenType::QuotedName
         };
         self.create_token(token_type)
     }
 
+    // Non-ASCII character outside of quoted names and string literals.
+    //
+    // The rest of the word is included in the error token,
+    // so that the parser does not report it again as another token.
+    fn non_ascii(&mut self) -> (&'a str, Token) {
+        self.advance();
+        while let Some(c) = self.cql.peek() {
+            if c.is_alphanumeric() || *c == '_' {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+        self.create_token(TokenType::Error(LexErrorKind::NonAsciiCharacter))
+    }
+
     // Catch all for token that begins with ascii digit character.
     //
     // The token can be either
@@ -887,15 +1539,23 @@ impl<'a> Lexer<'a> {
     // - UUID
     // - Float
     // - Integer
+    // - MalformedNumber, if none of above matches the longest run of
+    //   identifier characters
     fn parse_digit(&mut self) -> (&'a str, Token) {
         let mut duration = DurationUnitParser::new();
         let mut uuid = UUIDParser::new();
         let mut hexnumber = HexnumberParser::new();
         let mut numeric = NumberParser::new();
 
-        let mut accept = [true; 4];
-        let mut length = [0u64; 4];
+        let mut accept = [true; 5];
+        let mut length = [0u64; 5];
+        // malformed number may contain one `.`, such as `99.9PERCENTILE`
+        let mut malformed_dot = false;
         while let Some(&c) = self.cql.peek() {
+            // `..` is a range token, as in `100..200`, not a part of the number
+            if c == '.' && self.peek_second() == Some('.') {
+                break;
+            }
             for i in 0..accept.len() {
                 if accept[i] {
                     accept[i] = match i {
@@ -903,6 +1563,13 @@ impl<'a> Lexer<'a> {
                         1 => uuid.accept(&c),
                         2 => hexnumber.accept(&c),
                         3 => numeric.accept(&c),
+                        4 => match c {
+                            '.' if !malformed_dot => {
+                                malformed_dot = true;
+                                true
+                            }
+                            c => is_identifier_char(c),
+                        },
                         _ => unreachable!(),
                     };
                     if accept[i] {
@@ -910,11 +1577,22 @@ impl<'a> Lexer<'a> {
                     }
                 }
             }
-            if accept.iter().any(|b| *b) {
-                self.advance();
-            } else {
+            if !accept.iter().any(|b| *b) {
                 break;
             }
+            self.advance();
+            // malformed number is the longest once the others reject
+            if accept[..4] == [false; 4] {
+                self.identifier_chars();
+                let dot = !malformed_dot
+                    && self.cql.peek() == Some(&'.')
+                    && self.peek_second() != Some('.');
+                if dot {
+                    self.advance();
+                    self.identifier_chars();
+                }
+                return self.create_token(TokenType::MalformedNumber);
+            }
         }
         // max length of chars
         let max = length.iter().max().unwrap();
@@ -933,10 +1611,29 @@ impl<'a> Lexer<'a> {
                     } else {
                         self.create_token(TokenType::Integer)
                     };
+                } else if idx == 4 && !(malformed_dot && length[3] == *max) {
+                    // incomplete float such as `1.5e` is an invalid number
+                    return self.create_token(TokenType::MalformedNumber);
                 }
             }
         }
-        self.create_token(TokenType::Error)
+        self.create_token(TokenType::Error(LexErrorKind::InvalidNumber))
+    }
+
+    // `-` directly followed by a duration, such as `-3d` or `-P1D`, is a negative duration
+    // as in Cassandra. Otherwise, `-` alone is a token, such as the minus of `-1`.
+    fn negative_duration(&mut self) -> (&'a str, Token) {
+        let minus = self.clone();
+        let (s, token) = match self.cql.peek() {
+            Some('P') => self.parse_alphabet(),
+            _ => self.parse_digit(),
+        };
+        if token.token_type == TokenType::Duration {
+            (s, token)
+        } else {
+            *self = minus;
+            self.create_token(TokenType::Minus)
+        }
     }
 
     // Whitespace
@@ -986,21 +1683,61 @@ impl<'a> Lexer<'a> {
                 _ => continue,
             }
         }
-        self.create_token(TokenType::Error)
+        self.create_token(TokenType::Error(LexErrorKind::UnterminatedComment))
     }
 }
 
+// Characters of unquoted identifiers and keywords
+fn is_identifier_char(c: char) -> bool {
+    matches!(c, '0'..='9' | 'A'..='Z' | 'a'..='z' | '_')
+}
+
 impl<'a> Iterator for Lexer<'a> {
     type Item = (&'a str, Token);
 
     fn next(&mut self) -> Option<(&'a str, Token)> {
+        while let Some(next) = self.next_token() {
+            match next.1.token_type {
+                TokenType::Whitespace | TokenType::Comment(_) if self.options.skip_trivia => {
+                    continue
+                }
+                _ => return Some(next),
+            }
+        }
+        if self.options.emit_eof && !self.eof_emitted {
+            self.eof_emitted = true;
+            // `token_start` is at the end of input here
+            return Some(self.create_token(TokenType::EOF));
+        }
+        None
+    }
+}
+
+impl<'a> Lexer<'a> {
+    // Produce the next token, including whitespaces and comments
+    fn next_token(&mut self) -> Option<(&'a str, Token)> {
         self.token_start = self.token_end;
+        self.token_line = self.line;
+        self.token_column = self.column;
         if let Some(c) = self.cql.peek() {
             match c {
                 ' ' | '\t' | '\n' | '\r' => Some(self.whitespace()),
                 '+' => Some(self.consume_and_create_token(TokenType::Plus)),
                 '*' => Some(self.consume_and_create_token(TokenType::Asterisk)),
+                '%' => Some(self.consume_and_create_token(TokenType::Percent)),
                 '=' => Some(self.consume_and_create_token(TokenType::Equal)),
+                '!' => {
+                    self.advance();
+                    match self.cql.peek() {
+                        Some('=') => {
+                            self.advance();
+                            Some(self.create_token(TokenType::NotEqual))
+                        }
+                        _ => Some(
+                            self.create_token(TokenType::Error(LexErrorKind::InvalidCharacter)),
+                        ),
+                    }
+                }
                 ';' => Some(self.consume_and_create_token(TokenType::SemiColon)),
                 ':' => Some(self.consume_and_create_token(TokenType::Colon)),
                 ',' => Some(self.consume_and_create_token(TokenType::Comma)),
@@ -1010,6 +1747,8 @@ impl<'a> Iterator for Lexer<'a> {
                 ']' => Some(self.consume_and_create_token(TokenType::RBracket)),
                 '{' => Some(self.consume_and_create_token(TokenType::LBrace)),
                 '}' => Some(self.consume_and_create_token(TokenType::RBrace)),
+                '?' => Some(self.consume_and_create_token(TokenType::Qmark)),
+                '&' => Some(self.consume_and_create_token(TokenType::Ampersand)),
                 '.' => {
                     self.advance();
                     match self.cql.peek() {
@@ -1045,7 +1784,10 @@ impl<'a> Iterator for Lexer<'a> {
                     self.advance();
                     match self.cql.peek() {
                         Some('$') => Some(self.pg_string_literal()),
-                        _ => Some(self.create_token(TokenType::StringLiteral)), //TODO maybe emit single char ('$')
+                        // lone '$' is not a valid token
+                        _ => Some(
+                            self.create_token(TokenType::Error(LexErrorKind::InvalidCharacter)),
+                        ),
                     }
                 }
                 '"' => Some(self.quoted_identifier()),
@@ -1070,15 +1812,48 @@ impl<'a> Iterator for Lexer<'a> {
                             self.advance();
                             Some(self.singleline_comment())
                         }
+                        Some('0'..='9' | 'P') => Some(self.negative_duration()),
                         _ => Some(self.create_token(TokenType::Minus)),
                     }
                 }
                 c if c.is_ascii_digit() => Some(self.parse_digit()),
                 c if c.is_ascii_alphabetic() => Some(self.parse_alphabet()),
-                _ => Some(self.consume_and_create_token(TokenType::Error)),
+                c if !c.is_ascii() => Some(self.non_ascii()),
+                _ => Some(
+                    self.consume_and_create_token(TokenType::Error(LexErrorKind::InvalidCharacter)),
+                ),
             }
         } else {
             None
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_keywords_are_sorted() {
+        for pair in KEYWORDS.windows(2) {
+            assert!(pair[0].0 < pair[1].0, "{} and {}", pair[0].0, pair[1].0);
+        }
+        for (s, _) in KEYWORDS {
+            assert!(s.len() <= MAX_KEYWORD_LENGTH, "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_keywords_cover_all() {
+        for keyword in Keyword::ALL {
+            assert!(
+                KEYWORDS.contains(&(keyword.as_str(), keyword.clone())),
+                "{}",
+                keyword.as_str()
+            );
+        }
+        for (s, keyword) in KEYWORDS {
+            assert!(Keyword::ALL.contains(keyword), "{}", s);
+        }
+    }
+}
@@ -19,24 +19,33 @@
 //#![warn(missing_docs)]
 //#![warn(missing_doc_code_examples)]
 
+pub mod analysis;
 pub mod ast;
+pub mod cst;
 mod error;
+mod fingerprint;
+mod format;
+mod highlight;
 mod lexer;
+pub mod lints;
 mod literal;
 mod parser;
-
-pub use error::ParseError;
-pub use lexer::{Keyword, Lexer, Token, TokenType};
-pub use parser::Parser;
-
+pub mod schema;
+mod split;
+mod version;
+mod warning;
 #[cfg(target_arch = "wasm32")]
-use wasm_bindgen::prelude::*;
+mod wasm;
+
+pub use error::{ParseError, Position};
+pub use fingerprint::{fingerprint, fingerprint_hash};
+pub use format::{format, format_statement, FormatOptions};
+pub use highlight::{highlight, SemanticToken, SemanticTokenKind};
+pub use lexer::{Keyword, LexErrorKind, Lexer, LexerOptions, Token, TokenType};
+pub use parser::{OwnedParser, Parser, ParserOptions};
+pub use split::split_statements;
+pub use version::CqlVersion;
+pub use warning::{ParseWarning, WarningKind};
 
 #[cfg(target_arch = "wasm32")]
-#[wasm_bindgen]
-pub fn parse(s: &str) -> Result<JsValue, JsValue> {
-    match Parser::new(s).parse() {
-        Ok(stmts) => Ok(serde_wasm_bindgen::to_value(&stmts)?),
-        Err(e) => Err(serde_wasm_bindgen::to_value(&e)?),
-    }
-}
+pub use wasm::{parse, parse_with_options, tokenize};
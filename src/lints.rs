@@ -0,0 +1,222 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Lints over parsed statements
+//!
+//! `Linter` runs a set of `Lint`s over statements, which can be the built-in lints
+//! of this module or lints implemented outside of this crate.
+//!
+//! ```
+//! use cqlparser::ast::CqlStatement;
+//! use cqlparser::lints::{Diagnostic, Lint, Linter};
+//! use cqlparser::Parser;
+//!
+//! // every table must be created in a keyspace explicitly
+//! struct QualifiedTable;
+//!
+//! impl Lint for QualifiedTable {
+//!     fn name(&self) -> &str {
+//!         "qualified-table"
+//!     }
+//!
+//!     fn check(&self, statement: &CqlStatement) -> Vec<Diagnostic> {
+//!         match statement {
+//!             CqlStatement::CreateTable(table) if table.name.keyspace.is_none() => {
+//!                 vec![Diagnostic::new(format!("Keyspace of {} is not specified", table.name))]
+//!             }
+//!             _ => Vec::new(),
+//!         }
+//!     }
+//! }
+//!
+//! let statements = Parser::new("CREATE TABLE t (k int PRIMARY KEY); SELECT * FROM t")
+//!     .parse_spanned()
+//!     .unwrap();
+//! let diagnostics = Linter::with_default_lints()
+//!     .with(QualifiedTable)
+//!     .lint(&statements);
+//! let lints: Vec<_> = diagnostics.iter().map(|d| d.lint.as_str()).collect();
+//! assert_eq!(lints, ["missing-comment", "qualified-table", "select-star", "unbounded-select"]);
+//! ```
+
+use std::fmt;
+
+use crate::ast::{CqlStatement, Projection, Span, Spanned};
+
+/// Check over a statement
+pub trait Lint {
+    /// Name of the lint in kebab-case, such as `select-star`
+    fn name(&self) -> &str;
+
+    /// Returns problems found in `statement`
+    fn check(&self, statement: &CqlStatement) -> Vec<Diagnostic>;
+}
+
+/// Problem found by a `Lint`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    /// Name of the lint that found the problem, set by `Linter`
+    pub lint: String,
+    pub message: String,
+    /// Span of the statement, set by `Linter::lint`
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn new(message: impl Into<String>) -> Self {
+        Diagnostic {
+            lint: String::new(),
+            message: message.into(),
+            span: Span::default(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}]", self.message, self.lint)
+    }
+}
+
+/// Runs lints over statements
+#[derive(Default)]
+pub struct Linter {
+    lints: Vec<Box<dyn Lint>>,
+}
+
+impl Linter {
+    /// Creates `Linter` without any lints
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates `Linter` with the built-in lints:
+    /// `MissingComment`, `SelectStar` and `UnboundedSelect`
+    pub fn with_default_lints() -> Self {
+        Self::new()
+            .with(MissingComment)
+            .with(SelectStar)
+            .with(UnboundedSelect)
+    }
+
+    /// Adds `lint` to run
+    pub fn with(mut self, lint: impl Lint + 'static) -> Self {
+        self.lints.push(Box::new(lint));
+        self
+    }
+
+    /// Removes the lint named `name`
+    pub fn without(mut self, name: &str) -> Self {
+        self.lints.retain(|lint| lint.name() != name);
+        self
+    }
+
+    /// Returns the names of the lints to run, in the order of running
+    pub fn lint_names(&self) -> impl Iterator<Item = &str> {
+        self.lints.iter().map(|lint| lint.name())
+    }
+
+    /// Runs the lints over `statement`
+    pub fn lint_statement(&self, statement: &CqlStatement) -> Vec<Diagnostic> {
+        self.lints
+            .iter()
+            .flat_map(|lint| {
+                lint.check(statement).into_iter().map(move |mut d| {
+                    d.lint = lint.name().to_owned();
+                    d
+                })
+            })
+            .collect()
+    }
+
+    /// Runs the lints over `statements`, setting the span of each statement
+    /// to its diagnostics
+    pub fn lint(&self, statements: &[Spanned<CqlStatement>]) -> Vec<Diagnostic> {
+        statements
+            .iter()
+            .flat_map(|statement| {
+                self.lint_statement(&statement.node)
+                    .into_iter()
+                    .map(move |mut d| {
+                        d.span = statement.span;
+                        d
+                    })
+            })
+            .collect()
+    }
+}
+
+/// `missing-comment`: CREATE TABLE without `comment` property
+pub struct MissingComment;
+
+impl Lint for MissingComment {
+    fn name(&self) -> &str {
+        "missing-comment"
+    }
+
+    fn check(&self, statement: &CqlStatement) -> Vec<Diagnostic> {
+        match statement {
+            CqlStatement::CreateTable(table) if table.properties().comment().is_none() => {
+                vec![Diagnostic::new(format!(
+                    "Table {} has no comment",
+                    table.name
+                ))]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// `select-star`: SELECT of all the columns with `*`, which breaks
+/// when columns are added or dropped
+pub struct SelectStar;
+
+impl Lint for SelectStar {
+    fn name(&self) -> &str {
+        "select-star"
+    }
+
+    fn check(&self, statement: &CqlStatement) -> Vec<Diagnostic> {
+        match statement {
+            CqlStatement::Select(select) if select.projection == Projection::Wildcard => {
+                vec![Diagnostic::new(format!(
+                    "SELECT * from {}, instead of listing the columns",
+                    select.table_name
+                ))]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// `unbounded-select`: SELECT without WHERE clause nor LIMIT, which reads the whole table
+pub struct UnboundedSelect;
+
+impl Lint for UnboundedSelect {
+    fn name(&self) -> &str {
+        "unbounded-select"
+    }
+
+    fn check(&self, statement: &CqlStatement) -> Vec<Diagnostic> {
+        match statement {
+            CqlStatement::Select(select)
+                if select.selection.is_none() && select.limit.is_none() =>
+            {
+                vec![Diagnostic::new(format!(
+                    "SELECT from {} without WHERE clause nor LIMIT reads the whole table",
+                    select.table_name
+                ))]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
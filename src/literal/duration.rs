@@ -12,6 +12,7 @@
 
 //! - Number with duration unit (case insensitive)
 //!     - `\d+(Y|MO|W|D|H|M|S|MS|US|\u{00B5}S|NS)(\d+(Y|MO|W|D|H|M|S|MS|US|\u{00B5}S|NS))*`
+//!     - Units must be in descending order without repetition, such as `1h30m`
 //! - ISO8601 Duration
 //!     - Three supported formats:
 //!         - Format with designators
@@ -21,6 +22,8 @@
 //!         - Alternative format
 //!             - `P\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}`
 
+use std::convert::TryFrom;
+
 use super::{StateMachine, StateTransition};
 
 // States for parsing duration unit format
@@ -42,13 +45,30 @@ enum DurationUnitParseState {
     NanosecondParsed,
 }
 
-impl StateTransition for DurationUnitParseState {
-    fn is_final(&self) -> bool {
+impl DurationUnitParseState {
+    // Returns the order of the unit just parsed, from years (1) to nanoseconds (10),
+    // or `None` if the unit is not parsed yet
+    fn unit_order(&self) -> Option<u8> {
         match self {
-            Self::Initial | Self::ParseDigit => false,
-            _ => true,
+            Self::YearParsed => Some(1),
+            Self::MonthParsed => Some(2),
+            Self::WeekParsed => Some(3),
+            Self::DayParsed => Some(4),
+            Self::HourParsed => Some(5),
+            Self::MinuteParsed => Some(6),
+            Self::SecondParsed => Some(7),
+            Self::MillisecondParsed => Some(8),
+            Self::MicrosecondParsed => Some(9),
+            Self::NanosecondParsed => Some(10),
+            _ => None,
         }
     }
+}
+
+impl StateTransition for DurationUnitParseState {
+    fn is_final(&self) -> bool {
+        !matches!(self, Self::Initial | Self::ParseDigit)
+    }
 
     fn next_state(&self, c: &char) -> Result<Self, ()> {
         match self {
@@ -103,8 +123,13 @@ impl StateTransition for DurationUnitParseState {
 ///
 /// - Number with duration unit (case insensitive)
 ///     - `\d+(Y|MO|W|D|H|M|S|MS|US|\u{00B5}S|NS)(\d+(Y|MO|W|D|H|M|S|MS|US|\u{00B5}S|NS))*`
+///
+/// As in Cassandra, units must be in descending order without repetition,
+/// so `1h30m` is accepted but `30m1h` and `1h1h` are not.
 pub struct DurationUnitParser {
     state: StateMachine<DurationUnitParseState>,
+    /// Order of the last unit followed by another component, 0 if none
+    last_unit: u8,
 }
 
 impl DurationUnitParser {
@@ -112,16 +137,31 @@ impl DurationUnitParser {
     pub fn new() -> Self {
         Self {
             state: StateMachine::new(DurationUnitParseState::Initial),
+            last_unit: 0,
         }
     }
 
     /// Returns true if given char is accepted by the current state, and advances the state.
     pub fn accept(&mut self, c: &char) -> bool {
+        // a digit after a unit starts the next component, so the unit is complete here
+        if c.is_ascii_digit() {
+            if let Some(unit) = self.state.state.unit_order() {
+                if unit <= self.last_unit {
+                    return false;
+                }
+                self.last_unit = unit;
+            }
+        }
         self.state.accept(c)
     }
 
     pub fn is_valid(&self) -> bool {
         self.state.is_final()
+            && self
+                .state
+                .state
+                .unit_order()
+                .is_some_and(|unit| unit > self.last_unit)
     }
 }
 
@@ -147,18 +187,18 @@ enum Iso8601ParseState {
 
 impl StateTransition for Iso8601ParseState {
     fn is_final(&self) -> bool {
-        match self {
+        matches!(
+            self,
             Self::ParseStart
-            | Self::ParseTime
-            | Self::YearParsed
-            | Self::MonthParsed
-            | Self::DayParsed
-            | Self::HourParsed
-            | Self::MinuteParsed
-            | Self::SecondParsed
-            | Self::WeekParsed => true,
-            _ => false,
-        }
+                | Self::ParseTime
+                | Self::YearParsed
+                | Self::MonthParsed
+                | Self::DayParsed
+                | Self::HourParsed
+                | Self::MinuteParsed
+                | Self::SecondParsed
+                | Self::WeekParsed
+        )
     }
 
     fn next_state(&self, c: &char) -> Result<Self, ()> {
@@ -372,3 +412,366 @@ impl Iso8601AlternativeParser {
         self.state.is_final()
     }
 }
+
+const NANOS_PER_MICRO: i64 = 1_000;
+const NANOS_PER_MILLI: i64 = 1_000 * NANOS_PER_MICRO;
+const NANOS_PER_SECOND: i64 = 1_000 * NANOS_PER_MILLI;
+const NANOS_PER_MINUTE: i64 = 60 * NANOS_PER_SECOND;
+const NANOS_PER_HOUR: i64 = 60 * NANOS_PER_MINUTE;
+
+// Accumulates duration components with overflow checks
+#[derive(Default)]
+struct DurationBuilder {
+    months: i32,
+    days: i32,
+    nanoseconds: i64,
+    /// Order of the last unit added, to reject units out of order
+    last_unit: u8,
+}
+
+impl DurationBuilder {
+    // Makes sure that units are added in descending order without repetition
+    fn unit(&mut self, order: u8) -> Option<()> {
+        if order <= self.last_unit {
+            return None;
+        }
+        self.last_unit = order;
+        Some(())
+    }
+
+    fn add_months(&mut self, n: u64, multiplier: i32) -> Option<()> {
+        let n = i32::try_from(n).ok()?.checked_mul(multiplier)?;
+        self.months = self.months.checked_add(n)?;
+        Some(())
+    }
+
+    fn add_days(&mut self, n: u64, multiplier: i32) -> Option<()> {
+        let n = i32::try_from(n).ok()?.checked_mul(multiplier)?;
+        self.days = self.days.checked_add(n)?;
+        Some(())
+    }
+
+    fn add_nanoseconds(&mut self, n: u64, multiplier: i64) -> Option<()> {
+        let n = i64::try_from(n).ok()?.checked_mul(multiplier)?;
+        self.nanoseconds = self.nanoseconds.checked_add(n)?;
+        Some(())
+    }
+
+    fn build(self) -> (i32, i32, i64) {
+        (self.months, self.days, self.nanoseconds)
+    }
+}
+
+/// Converts duration literal to `(months, days, nanoseconds)`
+/// the same way Cassandra does.
+///
+/// Leading `-` negates all the components.
+/// Returns `None` if the literal is not valid or any of the components overflows.
+pub(crate) fn parse_duration(s: &str) -> Option<(i32, i32, i64)> {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let mut builder = DurationBuilder::default();
+    match s.strip_prefix(['P', 'p']) {
+        Some(rest) if rest.contains(['-', ':']) => parse_iso8601_alternative(rest, &mut builder)?,
+        Some(rest) => parse_iso8601(rest, &mut builder)?,
+        None => parse_duration_unit(s, &mut builder)?,
+    }
+    let (months, days, nanoseconds) = builder.build();
+    // components are not negative, so they do not overflow
+    if negative {
+        Some((-months, -days, -nanoseconds))
+    } else {
+        Some((months, days, nanoseconds))
+    }
+}
+
+// Splits leading digits from the rest and parses them
+fn split_number(s: &str) -> Option<(u64, &str)> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    if end == 0 {
+        return None;
+    }
+    Some((s[..end].parse().ok()?, &s[end..]))
+}
+
+// Units of the duration unit format, in the order they must be written
+const UNIT_ORDER: [&str; 10] = ["y", "mo", "w", "d", "h", "m", "s", "ms", "us", "ns"];
+
+/// Returns why `s` is not a duration if its units are out of order or repeated,
+/// such as `1m2h` or `1h2h`, or `None` otherwise
+pub(crate) fn unit_order_error(s: &str) -> Option<String> {
+    let mut s = s.strip_prefix('-').unwrap_or(s);
+    let mut last = None;
+    let mut error = None;
+    while !s.is_empty() {
+        let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        if digits == 0 {
+            return None;
+        }
+        let rest = &s[digits..];
+        let end = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let unit = rest[..end].to_lowercase().replace('\u{00B5}', "u");
+        let order = UNIT_ORDER.iter().position(|u| *u == unit)?;
+        if error.is_none() {
+            error = match last {
+                Some(last) if order == last => Some("repeated"),
+                Some(last) if order < last => Some("out of order"),
+                _ => None,
+            }
+            .map(|problem| {
+                format!(
+                    "duration unit '{}' is {}, units must be in the order {}",
+                    &rest[..end],
+                    problem,
+                    UNIT_ORDER.join(", ")
+                )
+            });
+        }
+        last = Some(order);
+        s = &rest[end..];
+    }
+    error
+}
+
+fn parse_duration_unit(mut s: &str, builder: &mut DurationBuilder) -> Option<()> {
+    if s.is_empty() {
+        return None;
+    }
+    while !s.is_empty() {
+        let (n, rest) = split_number(s)?;
+        let end = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let unit = rest[..end].to_lowercase().replace('\u{00B5}', "u");
+        builder.unit(UNIT_ORDER.iter().position(|u| *u == unit)? as u8 + 1)?;
+        match unit.as_str() {
+            "y" => builder.add_months(n, 12)?,
+            "mo" => builder.add_months(n, 1)?,
+            "w" => builder.add_days(n, 7)?,
+            "d" => builder.add_days(n, 1)?,
+            "h" => builder.add_nanoseconds(n, NANOS_PER_HOUR)?,
+            "m" => builder.add_nanoseconds(n, NANOS_PER_MINUTE)?,
+            "s" => builder.add_nanoseconds(n, NANOS_PER_SECOND)?,
+            "ms" => builder.add_nanoseconds(n, NANOS_PER_MILLI)?,
+            "us" => builder.add_nanoseconds(n, NANOS_PER_MICRO)?,
+            "ns" => builder.add_nanoseconds(n, 1)?,
+            _ => return None,
+        }
+        s = &rest[end..];
+    }
+    Some(())
+}
+
+fn parse_iso8601(s: &str, builder: &mut DurationBuilder) -> Option<()> {
+    let (date, time) = match s.split_once(['T', 't']) {
+        Some((_, "")) => return None,
+        Some((date, time)) => (date, Some(time)),
+        None => (s, None),
+    };
+    if date.is_empty() && time.is_none() {
+        return None;
+    }
+    let mut date = date;
+    while !date.is_empty() {
+        let (n, rest) = split_number(date)?;
+        let mut chars = rest.chars();
+        let designator = chars.next()?.to_ascii_uppercase();
+        builder.unit(match designator {
+            'Y' => 1,
+            'M' => 2,
+            'W' => 3,
+            'D' => 4,
+            _ => return None,
+        })?;
+        match designator {
+            'Y' => builder.add_months(n, 12)?,
+            'M' => builder.add_months(n, 1)?,
+            'W' => builder.add_days(n, 7)?,
+            'D' => builder.add_days(n, 1)?,
+            _ => return None,
+        }
+        date = chars.as_str();
+    }
+    let mut time = time.unwrap_or_default();
+    while !time.is_empty() {
+        let (n, rest) = split_number(time)?;
+        let mut chars = rest.chars();
+        let designator = chars.next()?.to_ascii_uppercase();
+        builder.unit(match designator {
+            'H' => 5,
+            'M' => 6,
+            'S' => 7,
+            _ => return None,
+        })?;
+        match designator {
+            'H' => builder.add_nanoseconds(n, NANOS_PER_HOUR)?,
+            'M' => builder.add_nanoseconds(n, NANOS_PER_MINUTE)?,
+            'S' => builder.add_nanoseconds(n, NANOS_PER_SECOND)?,
+            _ => return None,
+        }
+        time = chars.as_str();
+    }
+    Some(())
+}
+
+fn parse_iso8601_alternative(s: &str, builder: &mut DurationBuilder) -> Option<()> {
+    let (date, time) = s.split_once(['T', 't'])?;
+    let mut date = date.split('-');
+    let mut time = time.split(':');
+    let next = |part: Option<&str>, len: usize| -> Option<u64> {
+        part.filter(|p| p.len() == len && p.bytes().all(|b| b.is_ascii_digit()))?
+            .parse()
+            .ok()
+    };
+    let (years, months, days) = (
+        next(date.next(), 4)?,
+        next(date.next(), 2)?,
+        next(date.next(), 2)?,
+    );
+    let (hours, minutes, seconds) = (
+        next(time.next(), 2)?,
+        next(time.next(), 2)?,
+        next(time.next(), 2)?,
+    );
+    if date.next().is_some() || time.next().is_some() {
+        return None;
+    }
+    builder.add_months(years, 12)?;
+    builder.add_months(months, 1)?;
+    builder.add_days(days, 1)?;
+    builder.add_nanoseconds(hours, NANOS_PER_HOUR)?;
+    builder.add_nanoseconds(minutes, NANOS_PER_MINUTE)?;
+    builder.add_nanoseconds(seconds, NANOS_PER_SECOND)?;
+    Some(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_unit_order_error() {
+        assert_eq!(
+            unit_order_error("1m2h").as_deref(),
+            Some("duration unit 'h' is out of order, units must be in the order y, mo, w, d, h, m, s, ms, us, ns")
+        );
+        assert_eq!(
+            unit_order_error("-1H30M2Y").as_deref(),
+            Some("duration unit 'Y' is out of order, units must be in the order y, mo, w, d, h, m, s, ms, us, ns")
+        );
+        assert_eq!(
+            unit_order_error("3mo2mo").as_deref(),
+            Some("duration unit 'mo' is repeated, units must be in the order y, mo, w, d, h, m, s, ms, us, ns")
+        );
+        for s in [
+            "1h30m",
+            "1y2mo3w4d5h6m7s8ms9us10ns",
+            "1m2",
+            "1m2x",
+            "m2h",
+            "99PERCENTILE",
+            "",
+        ] {
+            assert_eq!(unit_order_error(s), None, "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("1h30m"), Some((0, 0, 90 * NANOS_PER_MINUTE)));
+        assert_eq!(parse_duration("1y2mo3w4d"), Some((14, 25, 0)));
+        assert_eq!(parse_duration("1MS2us4ns"), Some((0, 0, 1_002_004)));
+        assert_eq!(parse_duration("3\u{00B5}s"), Some((0, 0, 3_000)));
+        assert_eq!(parse_duration("P1Y"), Some((12, 0, 0)));
+        assert_eq!(parse_duration("P1M"), Some((1, 0, 0)));
+        assert_eq!(parse_duration("PT1M"), Some((0, 0, NANOS_PER_MINUTE)));
+        assert_eq!(
+            parse_duration("P1Y2M3DT4H5M6S"),
+            Some((
+                14,
+                3,
+                4 * NANOS_PER_HOUR + 5 * NANOS_PER_MINUTE + 6 * NANOS_PER_SECOND
+            ))
+        );
+        assert_eq!(parse_duration("P2W"), Some((0, 14, 0)));
+        assert_eq!(
+            parse_duration("P0001-02-03T04:05:06"),
+            Some((
+                14,
+                3,
+                4 * NANOS_PER_HOUR + 5 * NANOS_PER_MINUTE + 6 * NANOS_PER_SECOND
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_duration_out_of_range() {
+        assert_eq!(parse_duration("178956970y"), Some((i32::MAX - 7, 0, 0)));
+        assert_eq!(parse_duration("178956971y"), None);
+        assert_eq!(parse_duration("2147483647mo1mo"), None);
+        assert_eq!(
+            parse_duration("2562047h"),
+            Some((0, 0, 2562047 * NANOS_PER_HOUR))
+        );
+        assert_eq!(parse_duration("2562048h"), None);
+        assert_eq!(parse_duration("99999999999999999999ns"), None);
+    }
+
+    #[test]
+    fn test_parse_invalid_duration() {
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("1"), None);
+        assert_eq!(parse_duration("1x"), None);
+        assert_eq!(parse_duration("P"), None);
+        assert_eq!(parse_duration("P1YT"), None);
+        assert_eq!(parse_duration("P1H"), None);
+        assert_eq!(parse_duration("P01-02-03T04:05:06"), None);
+    }
+
+    #[test]
+    fn test_parse_duration_unit_order() {
+        assert_eq!(
+            parse_duration("1d2h3m4s"),
+            Some((
+                0,
+                1,
+                2 * NANOS_PER_HOUR + 3 * NANOS_PER_MINUTE + 4 * NANOS_PER_SECOND
+            ))
+        );
+        assert_eq!(parse_duration("1h1h"), None);
+        assert_eq!(parse_duration("3mo2mo"), None);
+        assert_eq!(parse_duration("30m1h"), None);
+        assert_eq!(parse_duration("1ms1s"), None);
+        assert_eq!(parse_duration("1us1\u{00B5}s"), None);
+        assert_eq!(parse_duration("P1D1Y"), None);
+        assert_eq!(parse_duration("PT1S1M"), None);
+    }
+
+    #[test]
+    fn test_parse_negative_duration() {
+        assert_eq!(parse_duration("-3d"), Some((0, -3, 0)));
+        assert_eq!(parse_duration("-1y1h"), Some((-12, 0, -NANOS_PER_HOUR)));
+        assert_eq!(parse_duration("-P1Y2D"), Some((-12, -2, 0)));
+        assert_eq!(parse_duration("-P0001-00-00T00:00:00"), Some((-12, 0, 0)));
+        assert_eq!(parse_duration("--3d"), None);
+    }
+
+    #[test]
+    fn test_duration_unit_parser() {
+        let lex = |s: &str| {
+            let mut parser = DurationUnitParser::new();
+            s.chars().all(|c| parser.accept(&c)) && parser.is_valid()
+        };
+        assert!(lex("1h30m45s"));
+        assert!(lex("1Y2MO3W4D5H6M7S8MS9US10NS"));
+        assert!(lex("1mo1m"));
+        assert!(!lex("1m1mo"));
+        assert!(!lex("3mo2mo2mo"));
+        assert!(!lex("1s1h"));
+        assert!(!lex("1ms1ms"));
+    }
+}
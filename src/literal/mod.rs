@@ -28,6 +28,9 @@ pub use duration::{DurationUnitParser, Iso8601AlternativeParser, Iso8601Parser};
 pub use numeric::{HexnumberParser, NumberParser};
 pub use uuid::UUIDParser;
 
+pub(crate) use duration::{parse_duration, unit_order_error};
+pub(crate) use uuid::parse_uuid_bytes;
+
 /// Trait that define transition of states.
 pub trait StateTransition: Sized {
     /// Returns next state when given input
@@ -21,23 +21,17 @@ enum NumericState {
     Exponent,
     PlusMinus,
     ExponentDigit,
-    /// INTEGER '..' case
-    IntegerRange,
-    /// INTEGER '.' '..' case
-    FloatRange,
 }
 
 impl StateTransition for NumericState {
     fn is_final(&self) -> bool {
-        match self {
+        matches!(
+            self,
             NumericState::Integer
-            | NumericState::FloatingPoint
-            | NumericState::Float
-            | NumericState::ExponentDigit
-            | NumericState::IntegerRange
-            | NumericState::FloatRange => true,
-            _ => false,
-        }
+                | NumericState::FloatingPoint
+                | NumericState::Float
+                | NumericState::ExponentDigit
+        )
     }
 
     fn next_state(&self, c: &char) -> Result<Self, ()> {
@@ -52,9 +46,10 @@ impl StateTransition for NumericState {
                 'E' | 'e' => Ok(Self::Exponent),
                 _ => Err(()),
             },
+            // `..` following an integer is a range, which the lexer splits
+            // before it reaches here
             Self::FloatingPoint => match c {
                 '0'..='9' => Ok(Self::Float),
-                '.' => Ok(Self::IntegerRange),
                 _ => Err(()),
             },
             Self::Float => match c {
@@ -75,11 +70,6 @@ impl StateTransition for NumericState {
                 '0'..='9' => Ok(Self::ExponentDigit),
                 _ => Err(()),
             },
-            Self::IntegerRange => match c {
-                '.' => Ok(Self::FloatRange),
-                _ => Err(()),
-            },
-            _ => Err(()),
         }
     }
 }
@@ -102,12 +92,10 @@ impl NumberParser {
 
     pub fn is_float(&self) -> bool {
         self.state.is_final()
-            && match self.state.state {
-                NumericState::FloatingPoint | NumericState::Float | NumericState::ExponentDigit => {
-                    true
-                }
-                _ => false,
-            }
+            && matches!(
+                self.state.state,
+                NumericState::FloatingPoint | NumericState::Float | NumericState::ExponentDigit
+            )
     }
 
     pub fn is_valid(&self) -> bool {
@@ -144,8 +132,12 @@ impl StateTransition for HexnumberState {
         }
     }
 
+    // `0x` without digits is an empty blob
     fn is_final(&self) -> bool {
-        *self == HexnumberState::HexParsing
+        matches!(
+            self,
+            HexnumberState::PrefixParsed | HexnumberState::HexParsing
+        )
     }
 }
 
@@ -215,9 +207,10 @@ mod test {
         assert!("100.0e+1".parse::<NumberParser>().unwrap().is_valid());
         assert!("100.0e+1".parse::<NumberParser>().unwrap().is_float());
 
-        // with ranges
-        assert!("100..".parse::<NumberParser>().unwrap().is_valid());
-        assert!("100...".parse::<NumberParser>().unwrap().is_valid());
+        // the second `.` is not a part of numbers, as in the range `100..200`
+        let mut parser = NumberParser::new();
+        assert!("100.".chars().all(|c| parser.accept(&c)));
+        assert!(!parser.accept(&'.'));
 
         assert!("abc".parse::<NumberParser>().is_err());
     }
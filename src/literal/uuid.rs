@@ -121,6 +121,22 @@ impl UUIDParser {
     }
 }
 
+/// Converts UUID string to 16 bytes.
+///
+/// Returns `None` if the string is not a valid UUID.
+pub(crate) fn parse_uuid_bytes(s: &str) -> Option<[u8; 16]> {
+    let mut parser = UUIDParser::new();
+    if !s.chars().all(|c| parser.accept(&c)) || !parser.is_valid() {
+        return None;
+    }
+    let hex = s.replace('-', "");
+    let mut bytes = [0u8; 16];
+    for (i, b) in bytes.iter_mut().enumerate() {
+        *b = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
 #[cfg(test)]
 impl std::str::FromStr for UUIDParser {
     type Err = ();
@@ -142,7 +158,7 @@ impl std::str::FromStr for UUIDParser {
 
 #[cfg(test)]
 mod test {
-    use super::UUIDParser;
+    use super::{parse_uuid_bytes, UUIDParser};
 
     #[test]
     fn test_uuid() {
@@ -160,4 +176,24 @@ mod test {
             .parse::<UUIDParser>()
             .is_err());
     }
+
+    #[test]
+    fn test_parse_uuid_bytes() {
+        assert_eq!(
+            parse_uuid_bytes("67e55044-10b1-426f-9247-bb680e5fe0c8"),
+            Some([
+                0x67, 0xe5, 0x50, 0x44, 0x10, 0xb1, 0x42, 0x6f, 0x92, 0x47, 0xbb, 0x68, 0x0e, 0x5f,
+                0xe0, 0xc8
+            ])
+        );
+        assert_eq!(
+            parse_uuid_bytes("67e55044-10b1-426f-9247-bb680e5fe0c"),
+            None
+        );
+        assert_eq!(
+            parse_uuid_bytes("67e55044-10b1-426f-9247-bb680e5fe0c8a"),
+            None
+        );
+        assert_eq!(parse_uuid_bytes("67e5504410b1426f9247bb680e5fe0c8"), None);
+    }
 }
@@ -10,17 +10,50 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::iter::Peekable;
+use std::mem;
+use std::ops::Range;
 use std::result::Result;
 
 use super::ast::*;
+use super::cst::SyntaxTree;
 use super::error::ParseError;
+use super::highlight::SemanticTokenKind;
 use super::lexer::*;
+use super::literal::unit_order_error;
+use super::version::CqlVersion;
+use super::warning::{
+    check_type, check_view_relation, check_view_selector, ParseWarning, WarningKind,
+};
 use super::TokenType;
 
 pub type CqlResult = Result<CqlStatement, ParseError>;
 
+/// (compact storage, clustering order, properties) in WITH clause of CREATE TABLE
+type TableOptions = (bool, Vec<(Identifier, bool)>, Vec<Property>);
+
+/// Keywords that can start a statement
+const STATEMENT_KEYWORDS: &[&str] = &[
+    "SELECT",
+    "INSERT",
+    "UPDATE",
+    "DELETE",
+    "BEGIN BATCH",
+    "CREATE",
+    "ALTER",
+    "DROP",
+    "USE",
+    "TRUNCATE",
+];
+
+/// Maximum TTL in seconds (20 years) accepted by Cassandra
+const MAX_TTL: i64 = 20 * 365 * 24 * 60 * 60;
+
+/// Keywords that can start a statement inside BATCH
+const BATCH_STATEMENT_KEYWORDS: &[&str] = &["INSERT", "UPDATE", "DELETE", "APPLY BATCH"];
+
 /// Operator precedence
 #[derive(Debug, PartialEq, PartialOrd)]
 enum Precedence {
@@ -52,13 +85,76 @@ impl From<&Token> for Precedence {
             }
             TokenType::Plus | TokenType::Minus => Precedence::Addition,
             TokenType::Asterisk | TokenType::Slash | TokenType::Percent => Precedence::Product,
-            TokenType::LParen => Precedence::Call,
-            TokenType::Keyword(Keyword::And) => Precedence::And,
+            TokenType::LBracket | TokenType::Dot => Precedence::Call,
+            // OR is parsed only to be rejected
+            TokenType::Keyword(Keyword::And | Keyword::Or) => Precedence::And,
             _ => Precedence::Min,
         }
     }
 }
 
+/// Limits applied while parsing, to protect the parser from malicious inputs
+///
+/// ## Example
+///
+/// ```
+/// use cqlparser::{Parser, ParserOptions};
+/// let options = ParserOptions {
+///     max_expression_depth: 2,
+///     ..ParserOptions::default()
+/// };
+/// let parser = Parser::with_options("SELECT * FROM test WHERE a = (((1)))", options);
+/// assert!(parser.parse().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParserOptions {
+    /// Maximum nesting depth of expressions and data types
+    pub max_expression_depth: usize,
+    /// Maximum depth of operators in an expression, such as the length of
+    /// `a + b + c` or `m[0][1]`, which are not nested but chained
+    pub max_operator_depth: usize,
+    /// Maximum number of selectors in a projection
+    pub max_selectors: usize,
+    /// Maximum number of statements in the input
+    pub max_statements: usize,
+    /// Maximum number of terms in a list, such as values of `IN` or `VALUES`,
+    /// and collection and tuple literals
+    pub max_terms_per_list: usize,
+    /// Cassandra version to follow, or `None` to accept the grammar of any version
+    pub version: Option<CqlVersion>,
+    /// Keep comments around statements in `Spanned` returned from `Parser::parse_spanned`
+    pub preserve_comments: bool,
+    /// Keep identifiers as written in the source, instead of lowercasing unquoted ones
+    ///
+    /// Identifiers keep surrounding `"` if quoted, so that they can be normalized later
+    /// with `ast::normalize_identifier`. Names in `QualifiedName` are kept without `"`,
+    /// and normalized with `QualifiedName::normalized_name`.
+    pub preserve_identifier_case: bool,
+    /// Fill the keyspace of table, type and view names without keyspace
+    /// from the preceding `USE` statement
+    ///
+    /// The keyspace is marked as `KeyspaceSource::Inherited`, and names are still
+    /// written without the keyspace. Function names are not resolved, since
+    /// native functions such as `now()` do not belong to the keyspace.
+    pub resolve_use_keyspace: bool,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        ParserOptions {
+            max_expression_depth: 128,
+            max_operator_depth: 10_000,
+            max_selectors: usize::MAX,
+            max_statements: usize::MAX,
+            max_terms_per_list: usize::MAX,
+            version: None,
+            preserve_comments: false,
+            preserve_identifier_case: false,
+            resolve_use_keyspace: false,
+        }
+    }
+}
+
 /// Apache Cassandra CQL Parser
 ///
 /// ## Example
@@ -68,72 +164,487 @@ impl From<&Token> for Precedence {
 /// let parser = Parser::new("SELECT * FROM test;");
 /// assert!(parser.parse().is_ok());
 /// ```
+///
+/// `Parser` is also an iterator that parses statements lazily one at a time.
+/// By default, the iteration stops after the first error.
+///
+/// ```
+/// use cqlparser::Parser;
+/// let mut parser = Parser::new("SELECT * FROM t1; SELECT * FROM t2;");
+/// assert!(parser.next().unwrap().is_ok());
+/// ```
+///
+/// `Parser` borrows the CQL string. Use `OwnedParser` to keep the CQL string
+/// with the parser, for example to send it to another thread.
+#[derive(Debug)]
 pub struct Parser<'a> {
+    cql: &'a str,
     lexer: Peekable<Lexer<'a>>,
+    /// End offset of the last consumed token
+    last_token_end: usize,
+    /// Start offset of the last consumed token
+    last_token_offset: usize,
+    /// Line of the last consumed token
+    last_token_line: u32,
+    /// Comments skipped so far, as (offset, line, text),
+    /// which are not attached to statements yet
+    comments: Vec<(usize, u32, String)>,
+    /// Continue iterating statements after errors
+    lenient: bool,
+    /// Set when iteration stopped by an error
+    failed: bool,
+    options: ParserOptions,
+    /// Current nesting depth of expressions and data types
+    depth: usize,
+    /// Depth of operators in the expressions parsed last, checked with
+    /// `ParserOptions::max_operator_depth`
+    operator_depth: usize,
+    /// Number of statements parsed so far
+    statements: usize,
+    /// Cache of column identifiers and names
+    identifiers: IdentifierCache,
+    /// Roles of consumed tokens as (offset, kind), only recorded for `highlight`
+    roles: Option<Vec<(usize, SemanticTokenKind)>>,
+    /// Name of the source set to errors, such as the file path
+    source_name: Option<String>,
+    /// Keyspace of the last `USE` statement and whether it was quoted,
+    /// only tracked with `ParserOptions::resolve_use_keyspace`
+    use_keyspace: Option<(Identifier, bool)>,
+    /// Warnings about the statements parsed so far
+    warnings: Vec<ParseWarning>,
 }
 
 impl<'a> Parser<'a> {
     /// Create new `Parser` of given CQL string
     pub fn new(cql: &'a str) -> Self {
+        Parser::with_options(cql, ParserOptions::default())
+    }
+
+    /// Create new `Parser` of given CQL string, with limits applied while parsing
+    pub fn with_options(cql: &'a str, options: ParserOptions) -> Self {
         Parser {
-            lexer: Lexer::new(cql).peekable(),
+            cql,
+            // whitespaces and comments are not used in parsing
+            lexer: Lexer::with_options(
+                cql,
+                LexerOptions {
+                    skip_trivia: !options.preserve_comments,
+                    ..LexerOptions::default()
+                },
+            )
+            .peekable(),
+            last_token_end: 0,
+            last_token_offset: 0,
+            last_token_line: 0,
+            comments: Vec::new(),
+            lenient: false,
+            failed: false,
+            options,
+            depth: 0,
+            operator_depth: 0,
+            statements: 0,
+            identifiers: IdentifierCache::new(),
+            roles: None,
+            source_name: None,
+            use_keyspace: None,
+            warnings: Vec::new(),
         }
     }
 
+    /// Create new `OwnedParser`, which owns given CQL string
+    ///
+    /// ```
+    /// use cqlparser::Parser;
+    /// let parser = Parser::from_string(String::from("SELECT * FROM t"));
+    /// let handle = std::thread::spawn(move || parser.parse());
+    /// assert!(handle.join().unwrap().is_ok());
+    /// ```
+    pub fn from_string(cql: String) -> OwnedParser {
+        OwnedParser::new(cql)
+    }
+
+    /// Create new `Parser` of given CQL string, which rejects the grammar
+    /// not supported in given Cassandra version
+    ///
+    /// ```
+    /// use cqlparser::{CqlVersion, Parser};
+    /// let cql = "SELECT * FROM test PER PARTITION LIMIT 1";
+    /// assert!(Parser::with_version(cql, CqlVersion::V3_6).parse().is_ok());
+    /// assert!(Parser::with_version(cql, CqlVersion::V3_0).parse().is_err());
+    /// ```
+    pub fn with_version(cql: &'a str, version: CqlVersion) -> Self {
+        let options = ParserOptions {
+            version: Some(version),
+            ..ParserOptions::default()
+        };
+        Parser::with_options(cql, options)
+    }
+
+    /// Make this parser continue iterating statements after errors
+    ///
+    /// When a statement cannot be parsed, the parser skips to the next `;`
+    /// outside of parentheses, brackets and braces to resume parsing
+    /// from the following statement.
+    pub fn lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+
+    /// Make this parser set given name of the source, such as the file path, to errors
+    ///
+    /// The name is prepended to the position when errors are displayed.
+    ///
+    /// ```
+    /// use cqlparser::Parser;
+    /// let error = Parser::new("SELECT * FROM")
+    ///     .with_source_name("schema/users.cql")
+    ///     .parse()
+    ///     .unwrap_err();
+    /// assert_eq!(error.source_name(), Some("schema/users.cql"));
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "schema/users.cql:1:14: expected identifier, found end of input"
+    /// );
+    /// ```
+    pub fn with_source_name(mut self, name: impl Into<String>) -> Self {
+        self.source_name = Some(name.into());
+        self
+    }
+
+    /// Make this parser intern identifiers in given cache
+    ///
+    /// Parsers sharing the cache share the same allocations for the same names.
+    /// See `IdentifierCache`.
+    pub fn identifier_cache(mut self, cache: IdentifierCache) -> Self {
+        self.identifiers = cache;
+        self
+    }
+
+    // Make this parser record the roles of consumed tokens, for `highlight`
+    pub(crate) fn record_roles(mut self) -> Self {
+        self.roles = Some(Vec::new());
+        self
+    }
+
+    // Takes the roles recorded so far, in the order of recording
+    pub(crate) fn take_roles(&mut self) -> Vec<(usize, SemanticTokenKind)> {
+        self.roles.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
     /// Parse CQL statements
     ///
     /// If `Parser` only parses `&str` that contains a single CQL statement,
     /// `;` at the end of the statement can be omitted.
-    pub fn parse(mut self) -> Result<Vec<CqlStatement>, ParseError> {
+    pub fn parse(self) -> Result<Vec<CqlStatement>, ParseError> {
+        self.collect()
+    }
+
+    /// Parse CQL statements, along with their positions in the original CQL
+    ///
+    /// The span of each statement covers from the first token of the statement
+    /// to the trailing `;` if present.
+    pub fn parse_spanned(mut self) -> Result<Vec<Spanned<CqlStatement>>, ParseError> {
         let mut statements = Vec::new();
-        while self.peek().is_some() {
-            // Skip `;` between statements
-            while self.expect(TokenType::SemiColon).is_ok() {}
+        while let Some(statement) = self.next_statement() {
+            statements.push(statement?);
+        }
+        Ok(statements)
+    }
 
-            // at the end of the input
-            if self.peek().is_none() {
-                break;
+    /// Parse CQL statements, along with every token of the source
+    ///
+    /// `SyntaxTree::unparse` reproduces the source byte for byte. See `cqlparser::cst`.
+    pub fn parse_syntax_tree(self) -> Result<SyntaxTree<'a>, ParseError> {
+        let cql = self.cql;
+        Ok(SyntaxTree::new(cql, self.parse_spanned()?))
+    }
+
+    /// Parse CQL statements, along with the ranges of their source text
+    ///
+    /// Each range covers from the first token of the statement to the trailing `;`,
+    /// or to the last token if `;` is omitted, so that slicing the original CQL
+    /// by the range gives the statement as written.
+    pub fn parse_with_spans(self) -> Result<Vec<(Range<usize>, CqlStatement)>, ParseError> {
+        Ok(self
+            .parse_spanned()?
+            .into_iter()
+            .map(|s| (s.span.range(), s.node))
+            .collect())
+    }
+
+    /// Parse CQL statements, along with warnings about CQL that parses,
+    /// but is likely to be rejected by Cassandra
+    ///
+    /// ```
+    /// use cqlparser::{Parser, WarningKind};
+    /// let cql = "CREATE TABLE t (k int PRIMARY KEY, v list<counter>)";
+    /// let (statements, warnings) = Parser::new(cql).parse_with_warnings().unwrap();
+    /// assert_eq!(statements.len(), 1);
+    /// assert_eq!(warnings[0].kind, WarningKind::CounterInCollection);
+    /// assert_eq!(&cql[warnings[0].span.range()], "list<counter>");
+    /// ```
+    pub fn parse_with_warnings(
+        mut self,
+    ) -> Result<(Vec<CqlStatement>, Vec<ParseWarning>), ParseError> {
+        let mut statements = Vec::new();
+        while let Some(statement) = self.next_statement() {
+            statements.push(statement?.node);
+        }
+        Ok((statements, self.warnings))
+    }
+
+    /// Parse CQL statements, continuing after statements that fail to parse
+    ///
+    /// When a statement cannot be parsed, the error is recorded and the parser
+    /// skips to the next `;` outside of parentheses, brackets and braces
+    /// to resume parsing from the following statement.
+    ///
+    /// Returns successfully parsed statements, and errors in the order of appearance.
+    pub fn parse_lenient(self) -> (Vec<CqlStatement>, Vec<ParseError>) {
+        let mut statements = Vec::new();
+        let mut errors = Vec::new();
+        for result in self.lenient() {
+            match result {
+                Ok(statement) => statements.push(statement),
+                Err(e) => errors.push(e),
             }
+        }
+        (statements, errors)
+    }
 
-            statements.push(self.parse_statement()?);
+    /// Parse CQL statements along with their positions in the original CQL,
+    /// continuing after statements that fail to parse
+    ///
+    /// Works as `Parser::parse_lenient`, but returns statements as `Parser::parse_spanned` does.
+    pub fn parse_spanned_lenient(mut self) -> (Vec<Spanned<CqlStatement>>, Vec<ParseError>) {
+        self.lenient = true;
+        let mut statements = Vec::new();
+        let mut errors = Vec::new();
+        while let Some(result) = self.next_spanned() {
+            match result {
+                Ok(statement) => statements.push(statement),
+                Err(e) => errors.push(e),
+            }
+        }
+        (statements, errors)
+    }
+
+    /// Parse the first statement of the input, and return it with the rest of the input
+    ///
+    /// The statement must be terminated by `;` or the end of the input.
+    /// The rest starts right after `;`, so it can be empty, only whitespaces and comments,
+    /// or the following statements, which is useful to parse input line by line.
+    ///
+    /// ```
+    /// use cqlparser::Parser;
+    /// let (_, rest) = Parser::parse_single("SELECT * FROM t1; SELECT * FROM t2").unwrap();
+    /// assert_eq!(rest, " SELECT * FROM t2");
+    /// assert!(Parser::parse_single("SELECT * FROM t1 garbage").is_err());
+    /// ```
+    pub fn parse_single(cql: &str) -> Result<(CqlStatement, &str), ParseError> {
+        let mut parser = Parser::new(cql);
+        let statement = match parser.next_statement() {
+            Some(statement) => statement?,
+            None => {
+                let error = parser.unexpected(STATEMENT_KEYWORDS);
+                return Err(parser.locate(error));
+            }
+        };
+        let end = statement.span.end;
+        if !cql[..end].ends_with(';') && parser.peek().is_some() {
+            let error = parser.unexpected(&[&TokenType::SemiColon.describe(), "end of input"]);
+            return Err(parser.locate(error));
+        }
+        Ok((statement.node, &cql[end..]))
+    }
+
+    /// Parse a single expression, such as the body of WHERE clause
+    ///
+    /// The whole input must be one expression, otherwise `ParseError` is returned.
+    ///
+    /// ```
+    /// use cqlparser::Parser;
+    /// assert!(Parser::parse_expression_only("a = 1 AND b > 2").is_ok());
+    /// assert!(Parser::parse_expression_only("a = 1 b").is_err());
+    /// ```
+    pub fn parse_expression_only(cql: &str) -> Result<Expression, ParseError> {
+        Parser::new(cql).parse_all(|p| p.parse_expression(Precedence::Min))
+    }
+
+    /// Parse a single CQL data type, such as `map<text, frozen<list<int>>>`
+    ///
+    /// The whole input must be one data type, otherwise `ParseError` is returned.
+    pub fn parse_cql_type_only(cql: &str) -> Result<CqlType, ParseError> {
+        Parser::new(cql).parse_all(|p| p.parse_data_type())
+    }
+
+    /// Parse a single qualified name, such as `ks.tbl`
+    ///
+    /// Used to implement `FromStr` for `QualifiedName`.
+    pub(crate) fn parse_qualified_name_only(cql: &str) -> Result<QualifiedName, ParseError> {
+        Parser::new(cql).parse_all(|p| p.parse_qualified_name())
+    }
+
+    // Parse the input with given function, and make sure that there is no remaining token
+    fn parse_all<T, F>(&mut self, f: F) -> Result<T, ParseError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, ParseError>,
+    {
+        f(self)
+            .and_then(|parsed| match self.peek() {
+                Some(_) => Err(self.unexpected(&["end of input"])),
+                None => Ok(parsed),
+            })
+            .map_err(|e| self.locate(e))
+    }
+
+    // Parse the next statement, or returns `None` at the end of the input
+    fn next_statement(&mut self) -> Option<Result<Spanned<CqlStatement>, ParseError>> {
+        // Skip `;` between statements
+        while self.consume(TokenType::SemiColon) {}
+
+        // at the end of the input
+        self.peek()?;
+
+        if self.statements >= self.options.max_statements {
+            // do not try to parse the rest of the input
+            self.failed = true;
+            let error = ParseError::with_message(format!(
+                "Too many statements (max: {})",
+                self.options.max_statements
+            ));
+            return Some(Err(self.locate(error)));
+        }
+        self.statements += 1;
+
+        let start = self.next_token_offset();
+        let warnings = self.warnings.len();
+        let leading_comments = self.comments.drain(..).map(|(_, _, c)| c).collect();
+        let result = match self.parse_statement() {
+            Ok(statement) => {
+                // include trailing `;` in the span of the statement
+                self.consume(TokenType::SemiColon);
+                let mut spanned = Spanned::new(statement, Span::new(start, self.last_token_end));
+                spanned.leading_comments = leading_comments;
+                spanned.trailing_comment = self.take_trailing_comment();
+                Ok(spanned)
+            }
+            Err(e) => {
+                // warnings about the failed statement are dropped with it
+                self.warnings.truncate(warnings);
+                self.comments.clear();
+                Err(self.locate(e))
+            }
+        };
+        Some(result)
+    }
+
+    // Parses the next statement, then skips to the next statement in lenient mode
+    // or stops parsing otherwise if it failed
+    fn next_spanned(&mut self) -> Option<Result<Spanned<CqlStatement>, ParseError>> {
+        if self.failed {
+            return None;
+        }
+        let result = self.next_statement()?;
+        if result.is_err() {
+            if self.lenient {
+                self.skip_statement();
+            } else {
+                self.failed = true;
+            }
+        }
+        Some(result)
+    }
+
+    // Drops comments inside the last statement, and takes the comment
+    // following the statement on the same line if any
+    fn take_trailing_comment(&mut self) -> Option<String> {
+        // collect comments up to the next token
+        self.peek();
+        let end = self.last_token_end;
+        self.comments.retain(|(offset, _, _)| *offset >= end);
+        match self.comments.first() {
+            Some((_, line, _)) if *line == self.last_token_line => Some(self.comments.remove(0).2),
+            _ => None,
         }
-        Ok(statements)
+    }
+
+    // Skip tokens until the next `;` outside of parentheses, brackets and braces
+    fn skip_statement(&mut self) {
+        let mut depth = 0usize;
+        while let Some((_, token)) = self.advance() {
+            match token.token_type {
+                TokenType::LParen | TokenType::LBracket | TokenType::LBrace => depth += 1,
+                TokenType::RParen | TokenType::RBracket | TokenType::RBrace => {
+                    depth = depth.saturating_sub(1)
+                }
+                TokenType::SemiColon if depth == 0 => break,
+                _ => {}
+            }
+        }
+    }
+
+    // Fills the position of the error with the current position if unknown,
+    // and resolves its line and column, and sets the source name if any
+    fn locate(&mut self, error: ParseError) -> ParseError {
+        let offset = self.next_token_offset();
+        let error = error.or_offset(offset).locate(self.cql);
+        match &self.source_name {
+            Some(source_name) => error.in_source(source_name),
+            None => error,
+        }
+    }
+
+    // Returns the offset of the next token,
+    // or the end of the last consumed token if there is no more token
+    fn next_token_offset(&mut self) -> usize {
+        let last_token_end = self.last_token_end;
+        self.peek().map(|(_, t)| t.offset).unwrap_or(last_token_end)
     }
 
     // Peek next token, ignoring whitespaces and comments
-    fn peek(&mut self) -> Option<&(&str, Token)> {
-        loop {
-            if let Some((_, next)) = self.lexer.peek() {
-                match next.token_type {
-                    // Skip whitespaces and comments
-                    TokenType::Whitespace | TokenType::Comment(_) => {
-                        self.lexer.next();
-                    }
-                    _ => break,
+    //
+    // Whitespaces and comments are only produced by the lexer
+    // when comments are preserved.
+    fn peek(&mut self) -> Option<&(&'a str, Token)> {
+        while let Some((s, next)) = self.lexer.peek() {
+            match next.token_type {
+                TokenType::Whitespace => {}
+                TokenType::Comment(_) => {
+                    let comment = s.trim_end_matches(['\r', '\n']).to_owned();
+                    self.comments.push((next.offset, next.line, comment));
                 }
-            } else {
-                break;
+                _ => break,
             }
+            self.lexer.next();
         }
         self.lexer.peek()
     }
 
+    // Peek the type of the token after the next token, ignoring whitespaces and comments
+    fn peek_second(&mut self) -> Option<TokenType> {
+        self.peek()?;
+        let mut lexer = self.lexer.clone();
+        lexer.next();
+        lexer
+            .map(|(_, t)| t.token_type)
+            .find(|t| !matches!(t, TokenType::Whitespace | TokenType::Comment(_)))
+    }
+
     // Advance to the next token, ignoring whitespaces and comments
-    fn advance(&mut self) -> Option<(&str, Token)> {
-        while let Some(next) = self.lexer.next() {
-            match next.1.token_type {
-                // Skip whitespaces and comments
-                TokenType::Whitespace | TokenType::Comment(_) => continue,
-                _ => return Some(next),
-            }
-        }
-        None
+    fn advance(&mut self) -> Option<(&'a str, Token)> {
+        self.peek()?;
+        let next = self.lexer.next()?;
+        self.last_token_offset = next.1.offset;
+        self.last_token_end = next.1.offset + next.1.length;
+        self.last_token_line = next.1.line;
+        Some(next)
     }
 
     fn advance_if<P: FnOnce(&&(&str, Token)) -> bool>(
         &mut self,
         predicate: P,
-    ) -> Option<(&str, Token)> {
+    ) -> Option<(&'a str, Token)> {
         if self.peek().filter(predicate).is_some() {
             self.advance()
         } else {
@@ -144,41 +655,160 @@ impl<'a> Parser<'a> {
     // Advance to next token if it matches given token type
     // Otherwise, return `ParseError`.
     fn expect(&mut self, token_type: TokenType) -> Result<(&str, Token), ParseError> {
-        let next_token = self.peek();
-        // save next token as String for parse error message
-        let next_token_string = next_token
-            .map(|(s, _)| String::from(*s))
-            .unwrap_or(String::new());
-
-        let advanced = if next_token
+        if self
+            .peek()
             .filter(|(_, t)| t.token_type == token_type)
             .is_some()
         {
-            self.advance()
+            // `advance` never fails after successful `peek`
+            Ok(self.advance().unwrap())
         } else {
-            None
-        };
-        advanced.ok_or(ParseError::with_message(format!(
-            "Expected {:?}, but was {:?}",
-            &token_type, next_token_string
-        )))
+            Err(self.unexpected(&[&token_type.describe()]))
+        }
+    }
+
+    // Advance to next token if it matches given token type, and return true.
+    // Otherwise, return false without advancing.
+    //
+    // Unlike `expect`, this does not build `ParseError`, so this should be used
+    // for optional tokens.
+    fn consume(&mut self, token_type: TokenType) -> bool {
+        self.advance_if(|(_, t)| t.token_type == token_type)
+            .is_some()
+    }
+
+    // Record the role of the token at `offset`
+    fn mark(&mut self, offset: usize, kind: SemanticTokenKind) {
+        if let Some(roles) = &mut self.roles {
+            roles.push((offset, kind));
+        }
+    }
+
+    // Record the role of the last consumed token
+    fn mark_last(&mut self, kind: SemanticTokenKind) {
+        self.mark(self.last_token_offset, kind);
+    }
+
+    // Change the role recorded last, when it turns out to be different
+    // by the tokens that follow, such as a column name followed by `(`
+    fn remark_last(&mut self, kind: SemanticTokenKind) {
+        if let Some((_, last)) = self.roles.as_mut().and_then(|roles| roles.last_mut()) {
+            *last = kind;
+        }
     }
 
     /// Parse a single CQL statement
     fn parse_statement(&mut self) -> CqlResult {
-        while let Some((_, next)) = self.peek() {
-            match &next.token_type {
-                TokenType::Keyword(kw) => match kw {
-                    Keyword::Select => return self.parse_select_statement(),
-                    Keyword::Insert => return self.parse_insert_statement(),
-                    Keyword::Update => return self.parse_update_statement(),
-                    Keyword::Create => return self.create_statement(),
-                    _ => return Err(ParseError::new()),
+        match self.peek().map(|(s, t)| (*s, t.token_type.clone())) {
+            Some((_, TokenType::Keyword(kw))) => match kw {
+                Keyword::Select => self.parse_select_statement(),
+                Keyword::Insert => self.parse_insert_statement(),
+                Keyword::Update => self.parse_update_statement(),
+                Keyword::Delete => self.parse_delete_statement(),
+                Keyword::Begin => self.parse_batch_statement(),
+                Keyword::Create => self.create_statement(),
+                Keyword::Alter => self.alter_statement(),
+                Keyword::Drop => self.drop_statement(),
+                Keyword::Use => self.parse_use_statement(),
+                Keyword::Truncate => self.parse_truncate_statement(),
+                _ => Err(self.unexpected(STATEMENT_KEYWORDS)),
+            },
+            // maybe a misspelled keyword, such as `SELCT`
+            Some((s, TokenType::Identifier)) => {
+                let error = self.unexpected(STATEMENT_KEYWORDS);
+                match suggest_statement_keyword(s) {
+                    Some(keyword) => Err(error.with_reason(&format!("did you mean {}?", keyword))),
+                    None => Err(error),
+                }
+            }
+            Some(_) => Err(self.unexpected(&["a CQL statement"])),
+            None => Err(self.unexpected(STATEMENT_KEYWORDS)),
+        }
+    }
+
+    // Creates an error saying that one of `expected` was expected at the next token
+    fn unexpected(&mut self, expected: &[&str]) -> ParseError {
+        let offset = self.next_token_offset();
+        let expected = expected.iter().map(|e| e.to_string()).collect();
+        let error = match self.peek() {
+            Some((
+                s,
+                Token {
+                    token_type: TokenType::Error(kind),
+                    ..
                 },
-                _ => break,
+            )) => {
+                let reason = match kind {
+                    LexErrorKind::NonAsciiCharacter => format!(
+                        "non-ASCII character '{}' is only allowed inside quoted identifiers or string literals",
+                        s.chars().next().unwrap_or_default()
+                    ),
+                    _ => kind.to_string(),
+                };
+                let error = ParseError::invalid_token(expected, String::from(*s), &reason);
+                match kind {
+                    // unterminated tokens continue to the end of the input
+                    LexErrorKind::UnterminatedString
+                    | LexErrorKind::UnterminatedQuotedName
+                    | LexErrorKind::UnterminatedComment => error.at_end_of_input(),
+                    _ => error,
+                }
+            }
+            Some((s, token)) => {
+                let error = ParseError::unexpected(expected, Some(String::from(*s)));
+                // durations with units out of order, such as `1m2h`, are lexed as malformed numbers
+                match unit_order_error(s) {
+                    Some(reason) if token.token_type == TokenType::MalformedNumber => {
+                        error.with_reason(&reason)
+                    }
+                    _ => error,
+                }
             }
+            None => ParseError::unexpected(expected, None),
+        };
+        error.or_offset(offset)
+    }
+
+    // Run given parse function one level deeper,
+    // or return `ParseError` if nested deeper than the limit
+    fn nested<T, F>(&mut self, f: F) -> Result<T, ParseError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, ParseError>,
+    {
+        if self.depth >= self.options.max_expression_depth {
+            let offset = self.next_token_offset();
+            return Err(ParseError::with_message(format!(
+                "Nested too deeply (max depth: {})",
+                self.options.max_expression_depth
+            ))
+            .or_offset(offset));
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    // Records a warning about the tokens from `start` to the last consumed token
+    fn warn(&mut self, start: usize, kind: WarningKind, message: String) {
+        let span = Span::new(start, self.last_token_end);
+        self.warnings.push(ParseWarning::new(span, kind, message));
+    }
+
+    // Returns `ParseError` if `feature` starting at `offset` is not supported
+    // in the target version
+    fn check_version<F>(&self, feature: &str, offset: usize, supported: F) -> Result<(), ParseError>
+    where
+        F: FnOnce(CqlVersion) -> bool,
+    {
+        match self.options.version {
+            Some(version) if !supported(version) => Err(ParseError::with_message(format!(
+                "{} is not supported in Cassandra {}",
+                feature, version
+            ))
+            .or_offset(offset)),
+            _ => Ok(()),
         }
-        Err(ParseError::new())
     }
 
     // Parse expression
@@ -189,30 +819,112 @@ impl<'a> Parser<'a> {
     //    - -1, -cast(col as int)
     // - Relationship
     //    - col_a > 10
+    //
+    // Nested expressions, such as the ones in parentheses, are parsed recursively
+    // through this function, so the nesting depth is limited here.
     fn parse_expression(&mut self, precedence: Precedence) -> Result<Expression, ParseError> {
-        // parse prefix
-        let mut left = self.parse_prefix()?;
+        self.nested(|p| {
+            // operators of the expressions parsed before this, such as the previous arguments
+            let previous = std::mem::replace(&mut p.operator_depth, 0);
+            // parse prefix
+            let left = p.parse_prefix()?;
+            let expression = p.parse_infixes(left, precedence)?;
+            p.operator_depth = p.operator_depth.max(previous);
+            Ok(expression)
+        })
+    }
 
+    // Creates an expression of `kind` from `start` to the end of the last consumed token
+    fn spanned(&self, start: usize, kind: ExpressionKind) -> Expression {
+        Expression::with_span(kind, Span::new(start, self.last_token_end))
+    }
+
+    // Converts the data type parsed in parentheses back to identifier
+    fn data_type_to_identifier(&self, cql_type: CqlType) -> Result<ExpressionKind, ParseError> {
+        match cql_type {
+            CqlType::UserDefinedType(n) => Ok(ExpressionKind::Identifier(n.name)),
+            CqlType::Native(nt) => Ok(ExpressionKind::Identifier(self.intern(nt.as_str()))),
+            _ => Err(ParseError::with_message(format!(
+                "{:?} cannot be an identifier",
+                cql_type
+            ))),
+        }
+    }
+
+    // Returns true if the next token can start a term, such as the operand of type cast
+    fn next_starts_term(&mut self) -> bool {
+        if self.next_is_constant() {
+            return true;
+        }
+        match self.peek() {
+            Some((_, t)) => match &t.token_type {
+                TokenType::Identifier
+                | TokenType::QuotedName
+                | TokenType::LParen
+                | TokenType::LBrace
+                | TokenType::LBracket
+                | TokenType::Qmark
+                | TokenType::Colon
+                | TokenType::Minus => true,
+                // `AS` follows selectors
+                TokenType::Keyword(Keyword::As) => false,
+                TokenType::Keyword(k) => {
+                    k.is_unreserved_keyword()
+                        || matches!(k, Keyword::Null | Keyword::Token | Keyword::Count)
+                }
+                _ => false,
+            },
+            None => false,
+        }
+    }
+
+    // Parses infix operators following `left` while they bind tighter than `precedence`
+    //
+    // Chains of operators such as `a + b + c` are parsed in a loop, not recursively,
+    // but make the tree as deep as they are long, so their depth is limited here.
+    // `operator_depth` is the depth of operators in `left` on entry,
+    // and the one in the result on return.
+    fn parse_infixes(
+        &mut self,
+        mut left: Expression,
+        precedence: Precedence,
+    ) -> Result<Expression, ParseError> {
+        let mut depth = self.operator_depth;
         while let Some((_, next_token)) = self.peek() {
             let next_precedence = Precedence::from(next_token);
             if precedence < next_precedence {
                 // if next precedence is higher, then try to parse infix
+                let offset = self.next_token_offset();
+                self.operator_depth = 0;
                 left = self.parse_infix(left)?;
+                // the right operand can be deeper than the left one
+                depth = depth.max(self.operator_depth) + 1;
+                if depth > self.options.max_operator_depth {
+                    return Err(ParseError::with_message(format!(
+                        "Operators chained too deeply (max depth: {})",
+                        self.options.max_operator_depth
+                    ))
+                    .or_offset(offset));
+                }
             } else {
                 break;
             }
         }
+        self.operator_depth = depth;
         Ok(left)
     }
 
     fn parse_prefix(&mut self) -> Result<Expression, ParseError> {
+        let start = self.next_token_offset();
         // Literal constant
-        let maybe_literal_constant = self
-            .parse_constant()
-            .map(Literal::Constant)
-            .map(Expression::Value);
-        if maybe_literal_constant.is_ok() {
-            return maybe_literal_constant;
+        if self.next_is_constant() {
+            let constant = self.parse_constant()?;
+            return Ok(self.spanned(start, ExpressionKind::Value(Literal::Constant(constant))));
+        }
+
+        // Function call, whose name can be a keyword such as `token` or `int`
+        if self.next_is_function_call() {
+            return self.parse_function_call();
         }
 
         if let Some((_, next)) = self.peek() {
@@ -221,26 +933,31 @@ impl<'a> Parser<'a> {
                     // NULL
                     Keyword::Null => {
                         self.advance();
-                        Ok(Expression::Value(Literal::Null))
+                        Ok(self.spanned(start, ExpressionKind::Value(Literal::Null)))
                     }
-                    // TOKEN and COUNT keywords are allowed for function name
-                    Keyword::Token | Keyword::Count => {
-                        self.advance();
-                        Ok(Expression::Value(Literal::Null))
+                    // `cast` without `(` is a column name
+                    Keyword::Cast => {
+                        if self.peek_second() == Some(TokenType::LParen) {
+                            self.parse_cast()
+                        } else {
+                            self.parse_identifier()
+                        }
                     }
-                    Keyword::Cast => self.parse_cast(),
                     _ => self.parse_identifier(),
                 },
-                TokenType::Identifier => {
-                    // Maybe function
-                    let maybe_function_name = self.parse_function_name();
-                    if maybe_function_name.is_ok() {
-                        Ok(Expression::Identifier(maybe_function_name?.name))
-                    } else {
-                        self.parse_identifier()
-                    }
+                TokenType::Identifier | TokenType::QuotedName => self.parse_identifier(),
+                TokenType::Qmark | TokenType::Colon => {
+                    let marker = self.parse_bind_marker()?;
+                    Ok(self.spanned(start, ExpressionKind::Value(marker)))
+                }
+                TokenType::LBrace => {
+                    let literal = self.parse_brace_literal()?;
+                    Ok(self.spanned(start, ExpressionKind::Value(literal)))
+                }
+                TokenType::LBracket => {
+                    let literal = self.parse_list_literal()?;
+                    Ok(self.spanned(start, ExpressionKind::Value(literal)))
                 }
-                TokenType::QuotedName => self.parse_identifier(),
 
                 // There are several cases here:
                 // - type cast: `(cql_type) simple_term`
@@ -248,34 +965,66 @@ impl<'a> Parser<'a> {
                 TokenType::LParen => {
                     self.expect(TokenType::LParen)?;
                     // Empty tuple(`()`)?
-                    if self.expect(TokenType::RParen).is_ok() {
-                        return Ok(Expression::Value(Literal::Tuple(Vec::new())));
+                    if self.consume(TokenType::RParen) {
+                        return Ok(
+                            self.spanned(start, ExpressionKind::Value(Literal::Tuple(Vec::new())))
+                        );
                     }
-                    let maybe_cql_type = self.parse_data_type();
-                    // can be type cast: `(type) expr`
-                    let in_paren = if maybe_cql_type.is_ok() {
-                        // if next token is ')', this is type cast
-                        if self.expect(TokenType::RParen).is_ok() {
-                            // We need to exit in paren here
-                            Ok(Expression::TypeCast(
-                                maybe_cql_type?,
-                                Box::new(self.parse_expression(Precedence::Min)?),
-                            ))
-                        } else {
-                            // this cql type is just an identifier
-                            maybe_cql_type.and_then(|cql_type| match cql_type {
-                                CqlType::Custom(s) => Ok(Expression::Identifier(s)),
-                                CqlType::UserDefinedType(n) => Ok(Expression::Identifier(n.name)),
-                                CqlType::Native(nt) => Ok(Expression::Identifier(String::from(nt))),
-                                _ => Err(ParseError::with_message(format!(
-                                    "{:?} cannot be an identifier",
-                                    cql_type
-                                ))),
-                            })
-                        }
+                    // data type starts with identifier or keyword,
+                    // but collection keywords such as `map` are identifiers unless followed by `<`,
+                    // and names followed by `(` are functions such as `(int(a))`
+                    let next_is_type = !self.next_is_function_call()
+                        && match self.peek().map(|(_, t)| t.token_type.clone()) {
+                            Some(TokenType::Keyword(
+                                Keyword::Map
+                                | Keyword::List
+                                | Keyword::Set
+                                | Keyword::Tuple
+                                | Keyword::Frozen,
+                            )) => self.peek_second() == Some(TokenType::Lt),
+                            Some(
+                                TokenType::Identifier
+                                | TokenType::QuotedName
+                                | TokenType::Keyword(_),
+                            ) => true,
+                            _ => false,
+                        };
+                    let type_start = self.next_token_offset();
+                    let maybe_cql_type = if next_is_type {
+                        self.parse_data_type()
                     } else {
-                        self.parse_expression(Precedence::Min)
+                        Err(ParseError::with_message(String::new()))
                     };
+                    // can be type cast: `(type) expr`
+                    let mut in_paren = match maybe_cql_type {
+                        Ok(cql_type) => {
+                            let type_end = self.last_token_end;
+                            if self.consume(TokenType::RParen) {
+                                // the closing paren is consumed, so return here.
+                                // cast binds as tight as prefix operators: `(int) a + 1` is `((int) a) + 1`
+                                if self.next_starts_term() {
+                                    let operand = self.parse_expression(Precedence::Prefix)?;
+                                    return Ok(self.spanned(
+                                        start,
+                                        ExpressionKind::TypeCast(cql_type, Box::new(operand)),
+                                    ));
+                                }
+                                // otherwise, this is just a parenthesized identifier such as `(a)`
+                                self.mark(type_start, SemanticTokenKind::Column);
+                                let identifier = self.data_type_to_identifier(cql_type)?;
+                                return Ok(self.spanned(start, identifier));
+                            }
+                            // this cql type is just an identifier,
+                            // which can be followed by operators such as `(a + 1)`
+                            self.mark(type_start, SemanticTokenKind::Column);
+                            let identifier = Expression::with_span(
+                                self.data_type_to_identifier(cql_type)?,
+                                Span::new(type_start, type_end),
+                            );
+                            self.parse_infixes(identifier, Precedence::Min)
+                        }
+                        Err(_) => self.parse_expression(Precedence::Min),
+                    }?;
                     if self
                         .peek()
                         .filter(|(_, t)| t.token_type == TokenType::Comma)
@@ -283,31 +1032,40 @@ impl<'a> Parser<'a> {
                     {
                         // tuple
                         let mut values = Vec::new();
-                        values.push(in_paren?);
-                        while self.expect(TokenType::Comma).is_ok() {
-                            values.push(self.parse_expression(Precedence::Min)?);
+                        values.push(in_paren);
+                        while self.consume(TokenType::Comma) {
+                            let start = self.next_token_offset();
+                            let value = self.parse_expression(Precedence::Min)?;
+                            self.check_terms(values.len(), start)?;
+                            values.push(value);
                         }
                         self.expect(TokenType::RParen)?;
-                        return Ok(Expression::Value(Literal::Tuple(values)));
+                        return Ok(
+                            self.spanned(start, ExpressionKind::Value(Literal::Tuple(values)))
+                        );
                     }
                     self.expect(TokenType::RParen)?;
-                    in_paren
+                    // parenthesized expression spans the parentheses
+                    in_paren.span = Span::new(start, self.last_token_end);
+                    Ok(in_paren)
                 }
                 TokenType::Minus => {
                     self.advance();
-                    Ok(Expression::UnaryOp(UnaryOp::new(
-                        Operator::Minus,
-                        Box::new(self.parse_expression(Precedence::Prefix)?),
-                    )))
+                    let operand = self.parse_expression(Precedence::Prefix)?;
+                    Ok(self.spanned(
+                        start,
+                        ExpressionKind::UnaryOp(UnaryOp::new(Operator::Minus, Box::new(operand))),
+                    ))
                 }
-                _ => Err(ParseError::new()),
+                _ => Err(self.unexpected(&["expression"])),
             }
         } else {
-            Err(ParseError::new())
+            Err(self.unexpected(&["expression"]))
         }
     }
 
     fn parse_infix(&mut self, left: Expression) -> Result<Expression, ParseError> {
+        let start = left.span.start;
         if let Some((_, next)) = self.peek() {
             match &next.token_type {
                 TokenType::Plus
@@ -322,50 +1080,46 @@ impl<'a> Parser<'a> {
                 | TokenType::Lt
                 | TokenType::Lte
                 | TokenType::Keyword(Keyword::And) => self.parse_binary_operator(left),
+                // only AND joins relations
+                TokenType::Keyword(Keyword::Or) => Err(self.or_not_supported()),
                 TokenType::Keyword(Keyword::Is) => {
                     self.expect(TokenType::Keyword(Keyword::Is))?;
                     self.expect(TokenType::Keyword(Keyword::Not))?;
-                    Ok(Expression::BinaryOp(BinaryOp::new(
-                        Box::new(left),
-                        Operator::IsNot,
-                        Box::new(self.parse_expression(Precedence::Equal)?),
-                    )))
+                    let right = self.parse_expression(Precedence::Equal)?;
+                    Ok(self.spanned(
+                        start,
+                        ExpressionKind::BinaryOp(BinaryOp::new(
+                            Box::new(left),
+                            Operator::IsNot,
+                            Box::new(right),
+                        )),
+                    ))
                 }
                 // Collection sub selection
                 TokenType::LBracket => self.parse_collection_subselection(left),
-                TokenType::LParen => {
+                // Field of user defined type, such as `address.city`
+                TokenType::Dot => {
                     self.advance();
-                    // Function argments
-                    let mut args = Vec::new();
-                    // can be empty
-                    if self
-                        .peek()
-                        .filter(|(_, t)| t.token_type != TokenType::RParen)
-                        .is_some()
-                    {
-                        loop {
-                            let value = self.parse_expression(Precedence::Min)?;
-                            args.push(value);
-                            if self.expect(TokenType::Comma).is_err() {
-                                break;
-                            }
-                        }
-                    }
-                    self.expect(TokenType::RParen)?;
-                    Ok(Expression::Function {
-                        name: Box::new(left),
-                        args,
-                    })
+                    let field = self.parse_identifier()?;
+                    Ok(self.spanned(
+                        start,
+                        ExpressionKind::BinaryOp(BinaryOp::new(
+                            Box::new(left),
+                            Operator::Dot,
+                            Box::new(field),
+                        )),
+                    ))
                 }
-                _ => Err(ParseError::new()),
+                _ => Err(self.unexpected(&["operator"])),
             }
         } else {
-            Err(ParseError::new())
+            Err(self.unexpected(&["operator"]))
         }
     }
 
     // Parse CQL's Cast function: `cast(expr AS native_type)`
     fn parse_cast(&mut self) -> Result<Expression, ParseError> {
+        let start = self.next_token_offset();
         self.expect(TokenType::Keyword(Keyword::Cast))?;
         self.expect(TokenType::LParen)?;
         let expr = self.parse_expression(Precedence::Min)?;
@@ -373,45 +1127,179 @@ impl<'a> Parser<'a> {
         let target_type = self.parse_native_data_type()?;
         self.expect(TokenType::RParen)?;
 
-        Ok(Expression::TypeCast(target_type, Box::new(expr)))
+        Ok(self.spanned(start, ExpressionKind::Cast(target_type, Box::new(expr))))
+    }
+
+    // Returns true if the next tokens are a function name followed by `(`,
+    // such as `now(`, `int(` or `ks.func(`
+    fn next_is_function_call(&mut self) -> bool {
+        self.peek();
+        let mut tokens = self
+            .lexer
+            .clone()
+            .map(|(_, t)| t.token_type)
+            .filter(|t| !matches!(t, TokenType::Whitespace | TokenType::Comment(_)));
+        let is_name = |t: &TokenType, keyword_filter: fn(&Keyword) -> bool| match t {
+            TokenType::Identifier | TokenType::QuotedName => true,
+            TokenType::Keyword(k) => keyword_filter(k),
+            _ => false,
+        };
+        match (tokens.next(), tokens.next()) {
+            // `WRITETIME` and `TTL` selectors are written as functions without keyspace
+            (
+                Some(TokenType::Keyword(Keyword::WriteTime | Keyword::Ttl)),
+                Some(TokenType::LParen),
+            ) => true,
+            (Some(name), Some(TokenType::LParen)) => {
+                is_name(&name, Keyword::is_unreserved_for_function_name)
+            }
+            (Some(keyspace), Some(TokenType::Dot)) => {
+                is_name(&keyspace, Keyword::is_unreserved_keyword)
+                    && matches!((tokens.next(), tokens.next()), (Some(name), Some(TokenType::LParen))
+                        if is_name(&name, Keyword::is_unreserved_for_function_name))
+            }
+            _ => false,
+        }
+    }
+
+    // functionCall := functionName '(' (term (',' term)*)? ')'
+    fn parse_function_call(&mut self) -> Result<Expression, ParseError> {
+        let start = self.next_token_offset();
+        let writetime = self.advance_if(|(_, t)| {
+            matches!(
+                t.token_type,
+                TokenType::Keyword(Keyword::WriteTime | Keyword::Ttl)
+            )
+        });
+        let name = match writetime {
+            Some((s, _)) => {
+                self.mark_last(SemanticTokenKind::Function);
+                QualifiedName {
+                    keyspace: None,
+                    name: self.intern(&s.to_ascii_lowercase()),
+                    keyspace_quoted: false,
+                    name_quoted: false,
+                    keyspace_source: KeyspaceSource::Explicit,
+                }
+            }
+            None => {
+                let mut name = self.parse_function_name()?;
+                // keyspace from `USE` is not applied to functions
+                if name.is_keyspace_inherited() {
+                    name.keyspace = None;
+                    name.keyspace_quoted = false;
+                    name.keyspace_source = KeyspaceSource::Explicit;
+                }
+                name
+            }
+        };
+        self.expect(TokenType::LParen)?;
+        let mut args = Vec::new();
+        // can be empty
+        if !self.consume(TokenType::RParen) {
+            loop {
+                args.push(self.parse_expression(Precedence::Min)?);
+                if !self.consume(TokenType::Comma) {
+                    break;
+                }
+            }
+            self.expect(TokenType::RParen)?;
+        }
+        Ok(self.spanned(start, ExpressionKind::Function { name, args }))
     }
 
     fn parse_identifier(&mut self) -> Result<Expression, ParseError> {
-        let value = self.parse_ident().ok_or(ParseError::new())?;
-        Ok(Expression::Identifier(value))
+        let start = self.next_token_offset();
+        let value = self
+            .parse_column_ident()
+            .ok_or_else(|| self.unexpected(&["identifier"]))?;
+        Ok(self.spanned(start, ExpressionKind::Identifier(value)))
     }
 
     fn parse_string_literal(&mut self) -> Result<Constant, ParseError> {
-        let (value, _) = self.expect(TokenType::StringLiteral)?;
-        // Remove surrounding `'` or `$$`
-        let string_value = if value.starts_with('\'') {
-            // regular string literal
-            value[1..value.len() - 1].to_owned()
-        } else if value.starts_with('$') {
-            // PG style string literal
-            value[2..value.len() - 2].to_owned()
-        } else {
-            unreachable!();
-        };
+        self.parse_string().map(Constant::StringLiteral)
+    }
 
-        Ok(Constant::StringLiteral(string_value))
+    // Parses string literal, and returns its value
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        let (value, token) = self.expect(TokenType::StringLiteral)?;
+        // Remove surrounding `'` or `$$`, and unescape `''` in regular string literal.
+        // Delimiters are ASCII, so stripping them never splits multi-byte characters.
+        let string_value =
+            if let Some(s) = value.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+                s.replace("''", "'")
+            } else if let Some(s) = value.strip_prefix("$$").and_then(|s| s.strip_suffix("$$")) {
+                // PG style string literal
+                s.to_owned()
+            } else {
+                // `Lexer` only emits terminated string literals
+                return Err(
+                    ParseError::with_message(format!("Invalid string literal {}", value))
+                        .or_offset(token.offset),
+                );
+            };
+
+        Ok(string_value)
     }
 
+    // Parses integer, optionally negative such as `-1`
     fn parse_integer(&mut self) -> Result<Constant, ParseError> {
+        let start = self.next_token_offset();
+        let negative = self.next_is_negative_number() && self.consume(TokenType::Minus);
         let (value, _) = self.expect(TokenType::Integer)?;
-        // TODO value greater than 32 bit (long, bigint)
-        let int_value = value.parse::<u32>().map_err(|_| ParseError::new())?;
+        let value: Cow<str> = if negative {
+            format!("-{}", value).into()
+        } else {
+            value.into()
+        };
+        // TODO value greater than 64 bit (varint)
+        let int_value = value.parse::<i64>().map_err(|e| {
+            ParseError::with_message(format!("Invalid integer {}: {}", value, e)).or_offset(start)
+        })?;
         Ok(Constant::Integer(int_value))
     }
 
+    // Parse bind marker, `?` or `:name`
+    fn parse_bind_marker(&mut self) -> Result<Literal, ParseError> {
+        if self.consume(TokenType::Qmark) {
+            return Ok(Literal::Binding(None));
+        }
+        self.expect(TokenType::Colon)?;
+        self.mark_last(SemanticTokenKind::BindMarker);
+        let name = self
+            .parse_ident()
+            .ok_or_else(|| self.unexpected(&["bind marker name"]))?;
+        self.mark_last(SemanticTokenKind::BindMarker);
+        Ok(Literal::Binding(Some(name)))
+    }
+
+    // Parse integer constant or bind marker, used in LIMIT and USING clauses
+    fn parse_integer_or_bind_marker(&mut self) -> Result<Literal, ParseError> {
+        match self.peek() {
+            Some((_, t)) if matches!(t.token_type, TokenType::Qmark | TokenType::Colon) => {
+                self.parse_bind_marker()
+            }
+            _ => self.parse_integer().map(Literal::Constant),
+        }
+    }
+
+    // Parses float, optionally negative such as `-1.5`
     fn parse_float(&mut self) -> Result<Constant, ParseError> {
+        let negative = self.next_is_negative_number() && self.consume(TokenType::Minus);
         let (value, _) = self.expect(TokenType::Float)?;
-        Ok(Constant::Float(value.to_owned()))
+        if negative {
+            Ok(Constant::Float(format!("-{}", value)))
+        } else {
+            Ok(Constant::Float(value.to_owned()))
+        }
     }
 
     fn parse_boolean(&mut self) -> Result<Constant, ParseError> {
         let (value, _) = self.expect(TokenType::Boolean)?;
-        let bool_value = value.parse::<bool>().map_err(|_| ParseError::new())?;
+        let bool_value = value
+            .to_ascii_lowercase()
+            .parse::<bool>()
+            .map_err(|e| ParseError::with_message(format!("Invalid boolean {}: {}", value, e)))?;
         Ok(Constant::Boolean(bool_value))
     }
 
@@ -445,36 +1333,147 @@ impl<'a> Parser<'a> {
         Ok(Constant::Bytes(blob))
     }
 
-    fn parse_map_literal(&mut self) -> Result<Literal, ParseError> {
+    // Map, set or user type literal in braces, where `{}` is an empty map
+    fn parse_brace_literal(&mut self) -> Result<Literal, ParseError> {
         self.expect(TokenType::LBrace)?;
-        let mut map = Vec::new();
-        // can be empty
+        if self.consume(TokenType::RBrace) {
+            return Ok(Literal::Map(Vec::new()));
+        }
+        let mut key = self.parse_expression(Precedence::Min)?;
         if self
             .peek()
-            .filter(|(_, t)| t.token_type != TokenType::RBrace)
-            .is_some()
+            .filter(|(_, t)| t.token_type == TokenType::Colon)
+            .is_none()
         {
-            loop {
-                let key = self.parse_expression(Precedence::Min)?;
-                self.expect(TokenType::Colon)?;
-                let value = self.parse_expression(Precedence::Min)?;
-                map.push((key, value));
-                if self.expect(TokenType::Comma).is_err() {
-                    break;
-                }
+            let mut elements = vec![key];
+            while self.consume(TokenType::Comma) {
+                let start = self.next_token_offset();
+                let element = self.parse_expression(Precedence::Min)?;
+                self.check_terms(elements.len(), start)?;
+                elements.push(element);
+            }
+            self.expect(TokenType::RBrace)?;
+            return Ok(Literal::Set(elements));
+        }
+        let mut map = Vec::new();
+        loop {
+            // names are the fields of user type literal
+            if let ExpressionKind::Identifier(_) = key.kind {
+                self.remark_last(SemanticTokenKind::Field);
+            }
+            self.expect(TokenType::Colon)?;
+            let value = self.parse_expression(Precedence::Min)?;
+            map.push((key, value));
+            if !self.consume(TokenType::Comma) {
+                break;
             }
+            let start = self.next_token_offset();
+            key = self.parse_expression(Precedence::Min)?;
+            self.check_terms(map.len(), start)?;
         }
         self.expect(TokenType::RBrace)?;
         Ok(Literal::Map(map))
     }
 
+    // List literal, `[term, ...]` or `[]`
+    fn parse_list_literal(&mut self) -> Result<Literal, ParseError> {
+        self.expect(TokenType::LBracket)?;
+        // can be empty
+        if self.consume(TokenType::RBracket) {
+            return Ok(Literal::List(Vec::new()));
+        }
+        let list = self.parse_list_elements()?;
+        self.expect(TokenType::RBracket)?;
+        Ok(list)
+    }
+
+    // Comma separated terms of list literal or values of IN, which are kept
+    // as `Literal::ConstantList` while they are constants of the same kind,
+    // so that long lists of generated queries take less memory
+    fn parse_list_elements(&mut self) -> Result<Literal, ParseError> {
+        let mut constants: Vec<Constant> = Vec::new();
+        // spans of the constants, to make them expressions again
+        let mut spans: Vec<Span> = Vec::new();
+        // every element once any of them is not such a constant
+        let mut expressions: Vec<Expression> = Vec::new();
+        loop {
+            let start = self.next_token_offset();
+            let element = self.parse_expression(Precedence::Min)?;
+            self.check_terms(constants.len() + expressions.len(), start)?;
+            match element.kind {
+                ExpressionKind::Value(Literal::Constant(constant))
+                    if expressions.is_empty()
+                        && match constants.first() {
+                            Some(first) => mem::discriminant(first) == mem::discriminant(&constant),
+                            None => true,
+                        } =>
+                {
+                    constants.push(constant);
+                    spans.push(element.span);
+                }
+                kind => {
+                    if expressions.is_empty() {
+                        expressions.reserve(constants.len() + 1);
+                        expressions.extend(constants.drain(..).zip(spans.drain(..)).map(
+                            |(constant, span)| {
+                                Expression::with_span(
+                                    ExpressionKind::Value(Literal::Constant(constant)),
+                                    span,
+                                )
+                            },
+                        ));
+                    }
+                    expressions.push(Expression::with_span(kind, element.span));
+                }
+            }
+            if !self.consume(TokenType::Comma) {
+                break;
+            }
+        }
+        if expressions.is_empty() {
+            Ok(Literal::ConstantList(constants))
+        } else {
+            Ok(Literal::List(expressions))
+        }
+    }
+
+    // Returns `ParseError` if a term starting at `start` is added to a list of `count` terms
+    // beyond `max_terms_per_list`
+    fn check_terms(&self, count: usize, start: usize) -> Result<(), ParseError> {
+        if count >= self.options.max_terms_per_list {
+            return Err(ParseError::with_message(format!(
+                "Too many terms in list (max: {})",
+                self.options.max_terms_per_list
+            ))
+            .or_offset(start));
+        }
+        Ok(())
+    }
+
     fn parse_binary_operator(&mut self, left: Expression) -> Result<Expression, ParseError> {
-        let (_, token) = self.advance().ok_or(ParseError::new())?;
-        Ok(Expression::BinaryOp(BinaryOp::new(
-            Box::new(left),
-            Operator::try_from(&token)?,
-            Box::new(self.parse_expression(Precedence::from(&token))?),
-        )))
+        let (_, token) = self
+            .advance()
+            .ok_or_else(|| ParseError::unexpected(vec!["operator".to_owned()], None))?;
+        let operator = Operator::try_from(&token)?;
+        let right = self.parse_expression(Precedence::from(&token))?;
+        // Cassandra does not support arithmetic on durations in terms
+        let is_duration = |e: &Expression| {
+            matches!(
+                e.kind,
+                ExpressionKind::Value(Literal::Constant(Constant::Duration(_)))
+            )
+        };
+        if operator.is_arithmetic() && (is_duration(&left) || is_duration(&right)) {
+            return Err(ParseError::with_message(format!(
+                "Operator '{}' cannot be applied to duration constants",
+                operator
+            ))
+            .or_offset(token.offset));
+        }
+        Ok(self.spanned(
+            left.span.start,
+            ExpressionKind::BinaryOp(BinaryOp::new(Box::new(left), operator, Box::new(right))),
+        ))
     }
 
     // collectionSubSelection [Selectable.Raw receiver] returns [Selectable.Raw s]
@@ -492,131 +1491,201 @@ impl<'a> Parser<'a> {
         left: Expression,
     ) -> Result<Expression, ParseError> {
         self.expect(TokenType::LBracket)?;
-        // parse term
+        let element = self.parse_expression(Precedence::Min)?;
+        let upto = if self.consume(TokenType::Range) {
+            Some(Box::new(self.parse_expression(Precedence::Min)?))
+        } else {
+            None
+        };
         self.expect(TokenType::RBracket)?;
-        Ok(Expression::CollectionSubSelection {
-            receiver: Box::new(left),
-            element: Box::new(self.parse_expression(Precedence::Min)?),
-            upto: None,
-        })
+        Ok(self.spanned(
+            left.span.start,
+            ExpressionKind::CollectionSubSelection {
+                receiver: Box::new(left),
+                element: Box::new(element),
+                upto,
+            },
+        ))
     }
 
     // Parse CQL data type
     fn parse_data_type(&mut self) -> Result<CqlType, ParseError> {
+        self.nested(Self::parse_data_type_inner)
+    }
+
+    // Parses the type of a column, or a field of a user defined type if `is_field`,
+    // and warns about the types that Cassandra rejects
+    fn parse_column_type(&mut self, is_field: bool) -> Result<CqlType, ParseError> {
+        let start = self.next_token_offset();
+        let data_type = self.parse_data_type()?;
+        for (kind, message) in check_type(&data_type, is_field) {
+            self.warn(start, kind, message);
+        }
+        Ok(data_type)
+    }
+
+    fn parse_data_type_inner(&mut self) -> Result<CqlType, ParseError> {
         // native data type?
-        let maybe_native_type = self.parse_native_data_type();
-        if maybe_native_type.is_ok() {
-            return maybe_native_type;
+        if let Some(native_type) = self.next_native_data_type() {
+            self.advance();
+            self.mark_last(SemanticTokenKind::Type);
+            return Ok(CqlType::Native(native_type));
         }
         // collection type?
-        let maybe_collection_type = self.parse_collection_type();
-        if maybe_collection_type.is_ok() {
-            return maybe_collection_type;
+        let is_collection = matches!(
+            self.peek().map(|(_, t)| &t.token_type),
+            Some(TokenType::Keyword(
+                Keyword::Map | Keyword::List | Keyword::Set
+            ))
+        );
+        if is_collection {
+            return self.parse_collection_type();
+        }
+        // tuple type?
+        if self.consume(TokenType::Keyword(Keyword::Tuple)) {
+            self.mark_last(SemanticTokenKind::Type);
+            return self.parse_tuple_type();
         }
         // frozen type?
-        let maybe_frozen = self.expect(TokenType::Keyword(Keyword::Frozen));
-        if maybe_frozen.is_ok() {
-            self.expect(TokenType::Lt)?;
+        if self.consume(TokenType::Keyword(Keyword::Frozen)) {
+            self.mark_last(SemanticTokenKind::Type);
+            self.expect_type_bracket(TokenType::Lt)?;
             let inner_type = self.parse_data_type()?;
-            self.expect(TokenType::Gt)?;
+            self.expect_type_bracket(TokenType::Gt)?;
             return Ok(CqlType::Frozen(Box::new(inner_type)));
         }
+        // custom type in string literal?
+        if matches!(
+            self.peek().map(|(_, t)| &t.token_type),
+            Some(TokenType::StringLiteral)
+        ) {
+            let class_name = self.parse_string()?;
+            self.mark_last(SemanticTokenKind::Type);
+            return Ok(CqlType::Custom(CustomType::parse(&class_name)));
+        }
         // User type name?
         let maybe_user_type_name = self.parse_user_type_name();
         if maybe_user_type_name.is_ok() {
             return Ok(CqlType::UserDefinedType(maybe_user_type_name?));
         }
 
-        Err(ParseError::new())
+        Err(self.unexpected(&["data type"]))
+    }
+
+    // Expect `<` or `>` around type parameters, which are not comparison operators
+    fn expect_type_bracket(&mut self, token_type: TokenType) -> Result<(), ParseError> {
+        self.expect(token_type)?;
+        self.mark_last(SemanticTokenKind::Punctuation);
+        Ok(())
     }
 
     // Parse CQL's native data type
     fn parse_native_data_type(&mut self) -> Result<CqlType, ParseError> {
-        if let Some((_, next_token)) = self.peek() {
-            let native_data_type = match &next_token.token_type {
-                TokenType::Keyword(k) => match k {
-                    Keyword::Ascii => Some(NativeDataType::Ascii),
-                    Keyword::Bigint => Some(NativeDataType::BigInt),
-                    Keyword::Blob => Some(NativeDataType::Blob),
-                    Keyword::Boolean => Some(NativeDataType::Boolean),
-                    Keyword::Counter => Some(NativeDataType::Counter),
-                    Keyword::Decimal => Some(NativeDataType::Decimal),
-                    Keyword::Double => Some(NativeDataType::Double),
-                    Keyword::Duration => Some(NativeDataType::Duration),
-                    Keyword::Float => Some(NativeDataType::Float),
-                    Keyword::Inet => Some(NativeDataType::Inet),
-                    Keyword::Int => Some(NativeDataType::Int),
-                    Keyword::SmallInt => Some(NativeDataType::SmallInt),
-                    Keyword::Text => Some(NativeDataType::Text),
-                    Keyword::Timestamp => Some(NativeDataType::Timestamp),
-                    Keyword::TinyInt => Some(NativeDataType::TinyInt),
-                    Keyword::UUID => Some(NativeDataType::UUID),
-                    Keyword::Varchar => Some(NativeDataType::Varchar),
-                    Keyword::VarInt => Some(NativeDataType::VarInt),
-                    Keyword::TimeUUID => Some(NativeDataType::TimeUUID),
-                    Keyword::Date => Some(NativeDataType::Date),
-                    Keyword::Time => Some(NativeDataType::Time),
-                    _ => None,
-                },
+        match self.next_native_data_type() {
+            Some(native_type) => {
+                self.advance();
+                self.mark_last(SemanticTokenKind::Type);
+                Ok(CqlType::Native(native_type))
+            }
+            None => Err(self.unexpected(&["native data type"])),
+        }
+    }
+
+    // Returns native data type if the next token is one
+    fn next_native_data_type(&mut self) -> Option<NativeDataType> {
+        match &self.peek()?.1.token_type {
+            TokenType::Keyword(k) => match k {
+                Keyword::Ascii => Some(NativeDataType::Ascii),
+                Keyword::Bigint => Some(NativeDataType::BigInt),
+                Keyword::Blob => Some(NativeDataType::Blob),
+                Keyword::Boolean => Some(NativeDataType::Boolean),
+                Keyword::Counter => Some(NativeDataType::Counter),
+                Keyword::Decimal => Some(NativeDataType::Decimal),
+                Keyword::Double => Some(NativeDataType::Double),
+                Keyword::Duration => Some(NativeDataType::Duration),
+                Keyword::Float => Some(NativeDataType::Float),
+                Keyword::Inet => Some(NativeDataType::Inet),
+                Keyword::Int => Some(NativeDataType::Int),
+                Keyword::SmallInt => Some(NativeDataType::SmallInt),
+                Keyword::Text => Some(NativeDataType::Text),
+                Keyword::Timestamp => Some(NativeDataType::Timestamp),
+                Keyword::TinyInt => Some(NativeDataType::TinyInt),
+                Keyword::UUID => Some(NativeDataType::UUID),
+                Keyword::Varchar => Some(NativeDataType::Varchar),
+                Keyword::VarInt => Some(NativeDataType::VarInt),
+                Keyword::TimeUUID => Some(NativeDataType::TimeUUID),
+                Keyword::Date => Some(NativeDataType::Date),
+                Keyword::Time => Some(NativeDataType::Time),
                 _ => None,
-            };
-            native_data_type
-                .map(|dt| {
-                    self.advance();
-                    CqlType::Native(dt)
-                })
-                .ok_or(ParseError::new())
-        } else {
-            Err(ParseError::new())
+            },
+            _ => None,
         }
     }
 
     fn parse_collection_type(&mut self) -> Result<CqlType, ParseError> {
-        if self.expect(TokenType::Keyword(Keyword::Map)).is_ok() {
-            self.expect(TokenType::Lt)?;
+        let start = self.next_token_offset();
+        self.mark(start, SemanticTokenKind::Type);
+        if self.consume(TokenType::Keyword(Keyword::Map)) {
+            self.expect_type_bracket(TokenType::Lt)?;
             let key_type = self.parse_data_type()?;
             self.expect(TokenType::Comma)?;
             let value_type = self.parse_data_type()?;
-            self.expect(TokenType::Gt)?;
+            self.expect_type_bracket(TokenType::Gt)?;
             Ok(CqlType::Collection(CollectionType::Map {
                 key_type: Box::new(key_type),
                 value_type: Box::new(value_type),
             }))
-        } else if self.expect(TokenType::Keyword(Keyword::List)).is_ok() {
-            self.expect(TokenType::Lt)?;
+        } else if self.consume(TokenType::Keyword(Keyword::List)) {
+            self.expect_type_bracket(TokenType::Lt)?;
             let inner_type = self.parse_data_type()?;
-            self.expect(TokenType::Gt)?;
+            self.expect_type_bracket(TokenType::Gt)?;
             Ok(CqlType::Collection(CollectionType::List(Box::new(
                 inner_type,
             ))))
-        } else if self.expect(TokenType::Keyword(Keyword::Set)).is_ok() {
-            self.expect(TokenType::Lt)?;
+        } else if self.consume(TokenType::Keyword(Keyword::Set)) {
+            self.expect_type_bracket(TokenType::Lt)?;
             let inner_type = self.parse_data_type()?;
-            self.expect(TokenType::Gt)?;
+            self.expect_type_bracket(TokenType::Gt)?;
             Ok(CqlType::Collection(CollectionType::Set(Box::new(
                 inner_type,
             ))))
-        } else if self.expect(TokenType::Keyword(Keyword::Tuple)).is_ok() {
-            self.expect(TokenType::Lt)?;
-            let mut inner_types = Vec::new();
-            inner_types.push(self.parse_data_type()?);
-            while self.expect(TokenType::Comma).is_ok() {
-                inner_types.push(self.parse_data_type()?);
-            }
-            self.expect(TokenType::Gt)?;
-            Ok(CqlType::Tuple(inner_types))
         } else {
-            Err(ParseError::new())
+            Err(self.unexpected(&["MAP", "LIST", "SET"]))
+        }
+    }
+
+    // Types of the elements of `tuple<...>`, after `tuple`
+    fn parse_tuple_type(&mut self) -> Result<CqlType, ParseError> {
+        self.expect_type_bracket(TokenType::Lt)?;
+        let mut inner_types = Vec::new();
+        inner_types.push(self.parse_data_type()?);
+        while self.consume(TokenType::Comma) {
+            inner_types.push(self.parse_data_type()?);
         }
+        self.expect_type_bracket(TokenType::Gt)?;
+        Ok(CqlType::Tuple(inner_types))
     }
 
     /// SELECT statement
     fn parse_select_statement(&mut self) -> CqlResult {
+        let start = self.next_token_offset();
         self.expect(TokenType::Keyword(Keyword::Select))?;
-        // TODO JSON
-        // json is a valid column name. By consequence, we need to resolve the ambiguity for "json - json"
-        // need to look ahead couples of tokens to determine...
-        // probabliy need mark()-rewind() solution?
+        // json is a valid column name, as in `SELECT json FROM t`,
+        // so JSON is the keyword only when a selector follows
+        let is_json = self
+            .peek()
+            .filter(|(_, t)| t.token_type == TokenType::Keyword(Keyword::Json))
+            .is_some()
+            && match self.peek_second() {
+                Some(TokenType::Asterisk | TokenType::Identifier | TokenType::QuotedName) => true,
+                Some(TokenType::Keyword(keyword)) => {
+                    !matches!(keyword, Keyword::From | Keyword::As)
+                }
+                _ => false,
+            };
+        if is_json {
+            self.advance();
+        }
 
         // TODO DISTINCT
         let projection = self.parse_projection()?;
@@ -625,41 +1694,51 @@ impl<'a> Parser<'a> {
         let table_name = self.parse_qualified_name()?;
 
         // WHERE clause
-        let selection = if self.expect(TokenType::Keyword(Keyword::Where)).is_ok() {
+        let selection = if self.consume(TokenType::Keyword(Keyword::Where)) {
             Some(self.parse_where_clause()?)
         } else {
             None
         };
 
         // GROUP BY clause
-        if self.expect(TokenType::Keyword(Keyword::Group)).is_ok() {
+        let group_by = if self.consume(TokenType::Keyword(Keyword::Group)) {
             self.expect(TokenType::Keyword(Keyword::By))?;
-            // TODO
-        }
+            self.parse_group_by()?
+        } else {
+            Vec::new()
+        };
         // ORDER BY clause
-        if self.expect(TokenType::Keyword(Keyword::Order)).is_ok() {
+        if self.consume(TokenType::Keyword(Keyword::Order)) {
             self.expect(TokenType::Keyword(Keyword::By))?;
             // TODO
         }
         // PER PARTITION LIMIT clause
-        let per_partition_limit = if self.expect(TokenType::Keyword(Keyword::Per)).is_ok() {
+        let clause_start = self.next_token_offset();
+        let per_partition_limit = if self.consume(TokenType::Keyword(Keyword::Per)) {
             self.expect(TokenType::Keyword(Keyword::Partition))?;
             self.expect(TokenType::Keyword(Keyword::Limit))?;
-            // TODO binding
-            Some(Literal::Constant(self.parse_integer()?))
+            self.check_version("PER PARTITION LIMIT", clause_start, |v| {
+                v >= CqlVersion::V3_6
+            })?;
+            Some(self.parse_integer_or_bind_marker()?)
         } else {
             None
         };
         // LIMIT
-        let limit = if self.expect(TokenType::Keyword(Keyword::Limit)).is_ok() {
-            // TODO binding
-            Some(Literal::Constant(self.parse_integer()?))
+        let limit = if self.consume(TokenType::Keyword(Keyword::Limit)) {
+            Some(self.parse_integer_or_bind_marker()?)
         } else {
             None
         };
         // ALLOW FILTERING
-        let allow_filtering = if self.expect(TokenType::Keyword(Keyword::Allow)).is_ok() {
+        let clause_start = self.next_token_offset();
+        let allow_filtering = if self.consume(TokenType::Keyword(Keyword::Allow)) {
             self.expect(TokenType::Keyword(Keyword::Filtering))?;
+            self.warn(
+                clause_start,
+                WarningKind::AllowFiltering,
+                "ALLOW FILTERING may scan all the partitions of the table".to_owned(),
+            );
             true
         } else {
             false
@@ -669,26 +1748,81 @@ impl<'a> Parser<'a> {
             table_name,
             projection,
             selection,
-            is_json: false,
+            group_by,
+            is_json,
             is_distinct: false,
             per_partition_limit,
             limit,
             allow_filtering,
+            span: Span::new(start, self.last_token_end),
         }))
     }
 
+    // groupByClause := groupByElement (',' groupByElement)*
+    //
+    // Elements are column names, and the last one can be a function call,
+    // such as `floor(ts, 1h)` to group rows by time buckets (Cassandra 4.0+)
+    fn parse_group_by(&mut self) -> Result<Vec<Expression>, ParseError> {
+        let mut elements = Vec::new();
+        loop {
+            let start = self.next_token_offset();
+            let element = self.parse_expression(Precedence::Min)?;
+            match &element.kind {
+                ExpressionKind::Identifier(_) => {}
+                ExpressionKind::Function { .. } => {
+                    self.check_version("Function in GROUP BY", start, |v| v >= CqlVersion::V4_0)?;
+                }
+                _ => {
+                    return Err(ParseError::with_message(
+                        "GROUP BY element must be a column or a function call".to_owned(),
+                    )
+                    .or_offset(start))
+                }
+            }
+            if let Some(Expression {
+                kind: ExpressionKind::Function { .. },
+                ..
+            }) = elements.last()
+            {
+                return Err(ParseError::with_message(
+                    "Functions are only supported on the last element of the GROUP BY clause"
+                        .to_owned(),
+                )
+                .or_offset(start));
+            }
+            elements.push(element);
+            if !self.consume(TokenType::Comma) {
+                return Ok(elements);
+            }
+        }
+    }
+
     fn parse_projection(&mut self) -> Result<Projection, ParseError> {
         // '*' - select all columns
-        if self.expect(TokenType::Asterisk).is_ok() {
+        if self.consume(TokenType::Asterisk) {
             return Ok(Projection::Wildcard);
         }
 
         let mut selectors = vec![];
+        let start = self.next_token_offset();
         loop {
-            let selector = self.parse_selector()?;
-            // TODO maybe limit the size of selectors for safety (to not panic)
+            let selector = self.parse_selector().map_err(|e| {
+                // nothing is parsed as a selector
+                if selectors.is_empty() && e.offset() == Some(start) {
+                    self.unexpected(&["selector", "'*'"])
+                } else {
+                    e
+                }
+            })?;
+            if selectors.len() >= self.options.max_selectors {
+                return Err(ParseError::with_message(format!(
+                    "Too many selectors (max: {})",
+                    self.options.max_selectors
+                ))
+                .or_offset(selector.span().start));
+            }
             selectors.push(selector);
-            if self.expect(TokenType::Comma).is_err() {
+            if !self.consume(TokenType::Comma) {
                 break;
             }
         }
@@ -696,25 +1830,79 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_selector(&mut self) -> Result<Selector, ParseError> {
+        let start = self.next_token_offset();
         let selector = self.parse_expression(Precedence::Min)?;
         // check if selector has alias
-        let alias = if self.expect(TokenType::Keyword(Keyword::As)).is_ok() {
-            self.parse_ident()
+        let alias = if self.consume(TokenType::Keyword(Keyword::As)) {
+            let alias = self.parse_ident();
+            if alias.is_some() {
+                self.mark_last(SemanticTokenKind::Alias);
+            }
+            alias
         } else {
             None
         };
-        Ok(Selector::new(selector, alias))
+        Ok(Selector::with_span(
+            selector,
+            alias,
+            Span::new(start, self.last_token_end),
+        ))
+    }
+
+    // Returns true if the next token is a constant
+    fn next_is_constant(&mut self) -> bool {
+        self.next_is_negative_number()
+            || matches!(
+                self.peek().map(|(_, t)| &t.token_type),
+                Some(
+                    TokenType::Keyword(Keyword::NaN)
+                        | TokenType::Keyword(Keyword::Infinity)
+                        | TokenType::StringLiteral
+                        | TokenType::Integer
+                        | TokenType::Float
+                        | TokenType::Boolean
+                        | TokenType::Duration
+                        | TokenType::UUID
+                        | TokenType::Hexnumber
+                )
+            )
+    }
+
+    // Returns true if the next token is `-` directly followed by an integer or a float,
+    // such as `-1` or `-1.5e-3`, which is a negative constant as in Cassandra.
+    // `- 1` with whitespace in between is a unary minus.
+    fn next_is_negative_number(&mut self) -> bool {
+        let offset = match self.peek() {
+            Some((_, t)) if t.token_type == TokenType::Minus => t.offset,
+            _ => return false,
+        };
+        let mut lexer = self.lexer.clone();
+        lexer.next();
+        matches!(lexer.next(), Some((_, t))
+            if matches!(t.token_type, TokenType::Integer | TokenType::Float) && t.offset == offset + 1)
     }
 
     // TODO Negative NaN and Negative Infinity need to be TokenTypes as well
     fn parse_constant(&mut self) -> Result<Constant, ParseError> {
+        if self.next_is_negative_number() {
+            return match self.peek_second() {
+                Some(TokenType::Float) => self.parse_float(),
+                _ => self.parse_integer(),
+            };
+        }
         if let Some((_, next)) = self.peek() {
             match &next.token_type {
                 TokenType::Keyword(keyword) => match keyword {
                     // Literal constants
-                    Keyword::NaN => Ok(Constant::NaN),
-                    Keyword::Infinity => Ok(Constant::Infinity),
-                    _ => Err(ParseError::new()),
+                    Keyword::NaN => {
+                        self.advance();
+                        Ok(Constant::NaN)
+                    }
+                    Keyword::Infinity => {
+                        self.advance();
+                        Ok(Constant::Infinity)
+                    }
+                    _ => Err(self.unexpected(&["constant"])),
                 },
                 // Literal constants
                 TokenType::StringLiteral => self.parse_string_literal(),
@@ -724,71 +1912,267 @@ impl<'a> Parser<'a> {
                 TokenType::Duration => self.parse_duration(),
                 TokenType::UUID => self.parse_uuid(),
                 TokenType::Hexnumber => self.parse_hexnumber(),
-                _ => Err(ParseError::new()),
+                _ => Err(self.unexpected(&["constant"])),
+            }
+        } else {
+            Err(self.unexpected(&["constant"]))
+        }
+    }
+
+    // whereClause := relation_or_expression (AND relation_or_expression)*
+    // relation_or_expression := relation
+    //                        |  custom_index_expression
+    //                        |  '(' whereClause ')'
+    //
+    // # Custom index expression (CASSANDRA-10217)
+    //
+    // WHERE expr(lucene, '{lucene query here}')
+    //
+    // Parentheses around relations are redundant, so the relations in them
+    // are flattened into the where clause.
+    fn parse_where_clause(&mut self) -> Result<WhereClause, ParseError> {
+        let mut relations = Vec::new();
+        self.parse_relations(&mut relations)?;
+        Ok(WhereClause::new(relations))
+    }
+
+    // Parse relations joined with AND into `relations`
+    fn parse_relations(&mut self, relations: &mut Vec<Relation>) -> Result<(), ParseError> {
+        loop {
+            if self.next_is_relation_group() {
+                self.nested(|p| {
+                    p.expect(TokenType::LParen)?;
+                    p.parse_relations(relations)?;
+                    p.expect(TokenType::RParen)?;
+                    Ok(())
+                })?;
+            } else {
+                relations.push(self.parse_relation()?);
+            }
+            if let Some((_, t)) = self.peek() {
+                if t.token_type == TokenType::Keyword(Keyword::Or) {
+                    return Err(self.or_not_supported());
+                }
+            }
+            if !self.consume(TokenType::Keyword(Keyword::And)) {
+                return Ok(());
+            }
+        }
+    }
+
+    // Returns true if the next `(` starts relations in parentheses, such as `(a = 1 AND b = 2)`,
+    // rather than the columns of multi-column relation, such as `(a, b) = (1, 2)`
+    fn next_is_relation_group(&mut self) -> bool {
+        if !matches!(self.peek(), Some((_, t)) if t.token_type == TokenType::LParen) {
+            return false;
+        }
+        let mut tokens = self
+            .lexer
+            .clone()
+            .map(|(_, t)| t.token_type)
+            .filter(|t| !matches!(t, TokenType::Whitespace | TokenType::Comment(_)))
+            .skip(1);
+        match tokens.next() {
+            Some(TokenType::LParen | TokenType::Keyword(Keyword::Token)) => true,
+            // columns are followed by `,` or `)`
+            Some(TokenType::Identifier | TokenType::QuotedName | TokenType::Keyword(_)) => {
+                !matches!(tokens.next(), Some(TokenType::Comma | TokenType::RParen))
+            }
+            _ => false,
+        }
+    }
+
+    // Error at the next token, which is OR
+    fn or_not_supported(&mut self) -> ParseError {
+        let offset = self.next_token_offset();
+        ParseError::with_message("OR is not supported in CQL WHERE clauses".to_owned())
+            .or_offset(offset)
+    }
+
+    // relation := column op term
+    //          |  column IS NOT NULL
+    //          |  TOKEN '(' columns ')' op term
+    //          |  '(' columns ')' op term
+    fn parse_relation(&mut self) -> Result<Relation, ParseError> {
+        let is_custom_index = matches!(self.peek(), Some((s, t))
+            if t.token_type == TokenType::Identifier && s.eq_ignore_ascii_case("expr"))
+            && matches!(self.peek_second(), Some(TokenType::LParen));
+        if is_custom_index {
+            self.advance();
+            self.mark_last(SemanticTokenKind::Function);
+            self.expect(TokenType::LParen)?;
+            let index = self
+                .parse_ident()
+                .ok_or_else(|| self.unexpected(&["index name"]))?;
+            self.mark_last(SemanticTokenKind::Index);
+            self.expect(TokenType::Comma)?;
+            let value = self.parse_expression(Precedence::Min)?;
+            self.expect(TokenType::RParen)?;
+            return Ok(Relation::CustomIndex { index, value });
+        }
+        if self.consume(TokenType::Keyword(Keyword::Token)) {
+            let columns = self.parse_relation_columns()?;
+            let (operator, value) = self.parse_relation_operator_and_value()?;
+            return Ok(Relation::Token {
+                columns,
+                operator,
+                value,
+            });
+        }
+        if matches!(self.peek(), Some((_, t)) if t.token_type == TokenType::LParen) {
+            let columns = self.parse_relation_columns()?;
+            let (operator, value) = self.parse_relation_operator_and_value()?;
+            return Ok(Relation::MultiColumn {
+                columns,
+                operator,
+                value,
+            });
+        }
+        let column = self
+            .parse_column_ident()
+            .ok_or_else(|| self.unexpected(&["column name", "TOKEN", "("]))?;
+        let (operator, value) = self.parse_relation_operator_and_value()?;
+        Ok(Relation::SingleColumn {
+            column,
+            operator,
+            value,
+        })
+    }
+
+    // Parse `'(' column (',' column)* ')'`
+    fn parse_relation_columns(&mut self) -> Result<Vec<Identifier>, ParseError> {
+        self.expect(TokenType::LParen)?;
+        let mut columns = Vec::new();
+        loop {
+            columns.push(self.expect_column_ident()?);
+            if !self.consume(TokenType::Comma) {
+                break;
+            }
+        }
+        self.expect(TokenType::RParen)?;
+        Ok(columns)
+    }
+
+    // Parse the operator of relation and the value to compare with
+    fn parse_relation_operator_and_value(&mut self) -> Result<(Operator, Expression), ParseError> {
+        let token = self
+            .advance_if(|(_, t)| {
+                matches!(
+                    t.token_type,
+                    TokenType::Equal
+                        | TokenType::NotEqual
+                        | TokenType::Lt
+                        | TokenType::Lte
+                        | TokenType::Gt
+                        | TokenType::Gte
+                        | TokenType::Keyword(Keyword::In)
+                        | TokenType::Keyword(Keyword::Contains)
+                        | TokenType::Keyword(Keyword::Like)
+                        | TokenType::Keyword(Keyword::Is)
+                )
+            })
+            .map(|(_, token)| token);
+        let token = match token {
+            Some(token) => token,
+            None => return Err(self.unexpected(&["relation operator"])),
+        };
+        match token.token_type {
+            TokenType::Keyword(Keyword::Is) => {
+                self.expect(TokenType::Keyword(Keyword::Not))?;
+                let start = self.next_token_offset();
+                self.expect(TokenType::Keyword(Keyword::Null))?;
+                Ok((
+                    Operator::IsNot,
+                    self.spanned(start, ExpressionKind::Value(Literal::Null)),
+                ))
             }
-        } else {
-            Err(ParseError::new())
+            TokenType::Keyword(Keyword::Contains) => {
+                let operator = if self.consume(TokenType::Keyword(Keyword::Key)) {
+                    Operator::ContainsKey
+                } else {
+                    Operator::Contains
+                };
+                Ok((operator, self.parse_expression(Precedence::And)?))
+            }
+            TokenType::Keyword(Keyword::In) => Ok((Operator::In, self.parse_in_values()?)),
+            _ => Ok((
+                Operator::try_from(&token)?,
+                self.parse_expression(Precedence::And)?,
+            )),
         }
     }
 
-    // Unlike Apache Cassnadra's CQL parser, where clause is parsed as expression
-    // relation_or_expression := relation
-    //                        |  custom_index_expression
-    //
-    // # Custom index expression (CASSANDRA-10217)
-    //
-    // WHERE expr(lucene, '{lucene query here}')
-    fn parse_where_clause(&mut self) -> Result<Expression, ParseError> {
-        self.parse_expression(Precedence::Min)
+    // Parse values of IN relation, `'(' (term (',' term)*)? ')'` or bind marker
+    fn parse_in_values(&mut self) -> Result<Expression, ParseError> {
+        let start = self.next_token_offset();
+        if !self.consume(TokenType::LParen) {
+            let marker = self
+                .parse_bind_marker()
+                .map_err(|_| self.unexpected(&["(", "bind marker"]))?;
+            return Ok(self.spanned(start, ExpressionKind::Value(marker)));
+        }
+        if self.consume(TokenType::RParen) {
+            self.warn(
+                start,
+                WarningKind::EmptyIn,
+                "IN without values matches nothing".to_owned(),
+            );
+            return Ok(self.spanned(start, ExpressionKind::Value(Literal::List(Vec::new()))));
+        }
+        let values = self.parse_list_elements()?;
+        self.expect(TokenType::RParen)?;
+        Ok(self.spanned(start, ExpressionKind::Value(values)))
     }
 
     /// INSERT
     fn parse_insert_statement(&mut self) -> CqlResult {
+        let start = self.next_token_offset();
         self.expect(TokenType::Keyword(Keyword::Insert))?;
         self.expect(TokenType::Keyword(Keyword::Into))?;
         let table = self.parse_qualified_name()?;
 
         // JSON insert
-        let values = if self.expect(TokenType::Keyword(Keyword::Json)).is_ok() {
-            let (json_literal, _) = self.expect(TokenType::StringLiteral)?;
-            let json_string = json_literal.to_owned();
+        let values = if self.consume(TokenType::Keyword(Keyword::Json)) {
+            // JSON document is string literal or bind marker
+            let json = match self.peek() {
+                Some((_, t)) if matches!(t.token_type, TokenType::Qmark | TokenType::Colon) => {
+                    self.parse_bind_marker()?
+                }
+                _ => Literal::Constant(self.parse_string_literal()?),
+            };
             // (DEFAULT (NULL | UNSET))?
-            let has_default = self.expect(TokenType::Keyword(Keyword::Default)).is_ok();
+            let has_default = self.consume(TokenType::Keyword(Keyword::Default));
             let behavior = if has_default {
-                self.advance_if(|(_, t)| match t.token_type {
-                    TokenType::Keyword(Keyword::Unset) | TokenType::Keyword(Keyword::Null) => true,
-                    _ => false,
+                self.advance_if(|(_, t)| {
+                    matches!(
+                        t.token_type,
+                        TokenType::Keyword(Keyword::Unset) | TokenType::Keyword(Keyword::Null)
+                    )
                 })
                 .map(|(_, t)| match t.token_type {
                     TokenType::Keyword(Keyword::Unset) => JsonBehavior::Unset,
                     TokenType::Keyword(Keyword::Null) => JsonBehavior::Null,
                     _ => unreachable!(),
                 })
-                .ok_or(ParseError::with_message(format!(
-                    "UNSET or NULL was expected"
-                )))?
+                .ok_or_else(|| self.unexpected(&["UNSET", "NULL"]))?
             } else {
                 JsonBehavior::Unset
             };
-            InsertMethod::json(json_string, behavior)
+            InsertMethod::json(json, behavior)
         } else {
             // column list
             self.expect(TokenType::LParen)?;
             let mut columns = Vec::new();
             columns.push(self.parse_identifier()?);
-            while self.expect(TokenType::Comma).is_ok() {
+            while self.consume(TokenType::Comma) {
                 columns.push(self.parse_identifier()?);
             }
             self.expect(TokenType::RParen)?;
             self.expect(TokenType::Keyword(Keyword::Values))?;
             // value list
-            self.expect(TokenType::LParen)?;
-            let mut values = Vec::new();
-            values.push(self.parse_expression(Precedence::Min)?);
-            while self.expect(TokenType::Comma).is_ok() {
-                values.push(self.parse_expression(Precedence::Min)?);
-            }
-            self.expect(TokenType::RParen)?;
+            let values = self
+                .parse_insert_values()
+                .map_err(|e| e.in_context("INSERT values".to_owned()))?;
             InsertMethod::normal(columns, values)
         };
         // IF NOT EXISTS
@@ -802,11 +2186,29 @@ impl<'a> Parser<'a> {
             if_not_exists,
             timestamp,
             time_to_live,
+
+            span: Span::new(start, self.last_token_end),
         }))
     }
 
+    // '(' expression (',' expression)* ')' after VALUES of INSERT
+    fn parse_insert_values(&mut self) -> Result<Vec<Expression>, ParseError> {
+        self.expect(TokenType::LParen)?;
+        let mut values = Vec::new();
+        values.push(self.parse_expression(Precedence::Min)?);
+        while self.consume(TokenType::Comma) {
+            let start = self.next_token_offset();
+            let value = self.parse_expression(Precedence::Min)?;
+            self.check_terms(values.len(), start)?;
+            values.push(value);
+        }
+        self.expect(TokenType::RParen)?;
+        Ok(values)
+    }
+
     // UPDATE statement
     fn parse_update_statement(&mut self) -> CqlResult {
+        let start = self.next_token_offset();
         self.expect(TokenType::Keyword(Keyword::Update))?;
         let table = self.parse_qualified_name()?;
         let (timestamp, time_to_live) = self.parse_using_clause()?;
@@ -814,35 +2216,166 @@ impl<'a> Parser<'a> {
         let mut assignments = Vec::new();
         loop {
             assignments.push(self.parse_expression(Precedence::Min)?);
-            if self.expect(TokenType::Comma).is_err() {
+            if !self.consume(TokenType::Comma) {
                 break;
             }
         }
         self.expect(TokenType::Keyword(Keyword::Where))?;
         let selection = self.parse_where_clause()?;
-        let mut if_exists = false;
-        // IF
-        if self.expect(TokenType::Keyword(Keyword::If)).is_ok() {
-            // EXISTS?
-            if self.expect(TokenType::Keyword(Keyword::Exists)).is_ok() {
-                if_exists = true;
-            } else {
-                // TODO IF condition
-            }
-        }
+        let (if_exists, conditions) = self.parse_if_exists_or_conditions()?;
         Ok(CqlStatement::Update(UpdateStatement {
             table,
             if_exists,
             assignments,
             selection,
+            conditions,
             timestamp,
             time_to_live,
+
+            span: Span::new(start, self.last_token_end),
+        }))
+    }
+
+    // deleteStatement := DELETE (deleteOp (',' deleteOp)*)? FROM table
+    //                    (USING TIMESTAMP value)? WHERE whereClause
+    //                    (IF (EXISTS | conditions))?
+    fn parse_delete_statement(&mut self) -> CqlResult {
+        let start = self.next_token_offset();
+        self.expect(TokenType::Keyword(Keyword::Delete))?;
+        let mut targets = Vec::new();
+        if !matches!(self.peek(), Some((_, t)) if t.token_type == TokenType::Keyword(Keyword::From))
+        {
+            loop {
+                targets.push(self.parse_delete_target()?);
+                if !self.consume(TokenType::Comma) {
+                    break;
+                }
+            }
+        }
+        self.expect(TokenType::Keyword(Keyword::From))?;
+        let table = self.parse_qualified_name()?;
+        let timestamp = if self.consume(TokenType::Keyword(Keyword::Using)) {
+            self.expect(TokenType::Keyword(Keyword::Timestamp))?;
+            Some(self.parse_integer_or_bind_marker()?)
+        } else {
+            None
+        };
+        self.expect(TokenType::Keyword(Keyword::Where))?;
+        let selection = self.parse_where_clause()?;
+        let (if_exists, conditions) = self.parse_if_exists_or_conditions()?;
+        Ok(CqlStatement::Delete(DeleteStatement {
+            table,
+            targets,
+            timestamp,
+            selection,
+            if_exists,
+            conditions,
+
+            span: Span::new(start, self.last_token_end),
+        }))
+    }
+
+    // batchStatement := BEGIN (UNLOGGED | COUNTER)? BATCH (USING TIMESTAMP value)?
+    //                   ((INSERT | UPDATE | DELETE) ';'?)* APPLY BATCH
+    fn parse_batch_statement(&mut self) -> CqlResult {
+        let start = self.next_token_offset();
+        let line = self.expect(TokenType::Keyword(Keyword::Begin))?.1.line;
+        let batch_type = if self.consume(TokenType::Keyword(Keyword::Unlogged)) {
+            BatchType::Unlogged
+        } else if self.consume(TokenType::Keyword(Keyword::Counter)) {
+            BatchType::Counter
+        } else {
+            BatchType::Logged
+        };
+        self.expect(TokenType::Keyword(Keyword::Batch))?;
+        let timestamp = if self.consume(TokenType::Keyword(Keyword::Using)) {
+            self.expect(TokenType::Keyword(Keyword::Timestamp))?;
+            Some(self.parse_integer_or_bind_marker()?)
+        } else {
+            None
+        };
+        let mut statements = Vec::new();
+        while !self.consume(TokenType::Keyword(Keyword::Apply)) {
+            let statement_start = self.next_token_offset();
+            let statement = match self.peek().map(|(_, t)| t.token_type.clone()) {
+                Some(TokenType::Keyword(Keyword::Insert)) => self.parse_insert_statement(),
+                Some(TokenType::Keyword(Keyword::Update)) => self.parse_update_statement(),
+                Some(TokenType::Keyword(Keyword::Delete)) => self.parse_delete_statement(),
+                _ => Err(self.unexpected(BATCH_STATEMENT_KEYWORDS)),
+            }
+            .map_err(|e| {
+                e.in_context(format!(
+                    "statement {} of BATCH starting at line {}",
+                    statements.len() + 1,
+                    line
+                ))
+            })?;
+            if statement.is_conditional() {
+                self.warn(
+                    statement_start,
+                    WarningKind::ConditionalBatch,
+                    "Conditions in BATCH are only allowed when all the statements \
+                     apply to the same partition"
+                        .to_owned(),
+                );
+            }
+            statements.push(statement);
+            self.consume(TokenType::SemiColon);
+        }
+        self.expect(TokenType::Keyword(Keyword::Batch))?;
+        Ok(CqlStatement::Batch(BatchStatement {
+            batch_type,
+            timestamp,
+            statements,
+
+            span: Span::new(start, self.last_token_end),
         }))
     }
 
+    // deleteOp := column
+    //          |  column '[' term ']'
+    //          |  column '.' field
+    fn parse_delete_target(&mut self) -> Result<DeleteTarget, ParseError> {
+        let column_start = self.next_token_offset();
+        let column = self.expect_column_ident()?;
+        match self.peek().map(|(_, t)| t.token_type.clone()) {
+            Some(TokenType::LBracket) => {
+                let start = self.next_token_offset();
+                let receiver =
+                    self.spanned(column_start, ExpressionKind::Identifier(column.clone()));
+                match self.parse_collection_subselection(receiver)?.kind {
+                    ExpressionKind::CollectionSubSelection {
+                        element,
+                        upto: None,
+                        ..
+                    } => Ok(DeleteTarget::Element {
+                        column,
+                        element: *element,
+                    }),
+                    _ => Err(ParseError::with_message(
+                        "Range of elements cannot be deleted".to_owned(),
+                    )
+                    .or_offset(start)),
+                }
+            }
+            Some(TokenType::Dot) => {
+                self.advance();
+                let field = self
+                    .parse_ident_and_keywords_as_written(|k| k.is_unreserved_keyword())
+                    .ok_or_else(|| self.unexpected(&["field name"]))?;
+                self.mark_last(SemanticTokenKind::Field);
+                Ok(DeleteTarget::Field {
+                    column,
+                    field: self.intern(&field),
+                })
+            }
+            _ => Ok(DeleteTarget::Column(column)),
+        }
+    }
+
     /// IF NOT EXISTS
     fn parse_if_not_exists(&mut self) -> Result<bool, ParseError> {
-        if self.expect(TokenType::Keyword(Keyword::If)).is_ok() {
+        if self.consume(TokenType::Keyword(Keyword::If)) {
             self.expect(TokenType::Keyword(Keyword::Not))?;
             self.expect(TokenType::Keyword(Keyword::Exists))?;
             Ok(true)
@@ -851,40 +2384,89 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// IF EXISTS
+    fn parse_if_exists(&mut self) -> Result<bool, ParseError> {
+        if self.consume(TokenType::Keyword(Keyword::If)) {
+            self.expect(TokenType::Keyword(Keyword::Exists))?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    // IF EXISTS or IF conditions of UPDATE and DELETE,
+    // where `exists` followed by an operator is a column name such as `IF exists = 1`
+    fn parse_if_exists_or_conditions(&mut self) -> Result<(bool, Option<Expression>), ParseError> {
+        if !self.consume(TokenType::Keyword(Keyword::If)) {
+            return Ok((false, None));
+        }
+        let is_column = matches!(
+            self.peek_second(),
+            Some(
+                TokenType::Equal
+                    | TokenType::NotEqual
+                    | TokenType::Gt
+                    | TokenType::Gte
+                    | TokenType::Lt
+                    | TokenType::Lte
+                    | TokenType::LBracket
+                    | TokenType::Dot
+            )
+        );
+        if !is_column && self.consume(TokenType::Keyword(Keyword::Exists)) {
+            Ok((true, None))
+        } else {
+            Ok((false, Some(self.parse_expression(Precedence::Min)?)))
+        }
+    }
+
     // Returns (timestamp, time_to_live) pair if USING clause is present
     fn parse_using_clause(&mut self) -> Result<(Option<Literal>, Option<Literal>), ParseError> {
-        let has_using_clause = self.expect(TokenType::Keyword(Keyword::Using)).is_ok();
+        let has_using_clause = self.consume(TokenType::Keyword(Keyword::Using));
         if has_using_clause {
             let mut timestamp = None;
             let mut ttl = None;
             loop {
-                if self.expect(TokenType::Keyword(Keyword::Timestamp)).is_ok() {
-                    match self.parse_integer() {
-                        Ok(v @ Constant::Integer(_)) => timestamp.replace(Literal::Constant(v)),
+                if self.consume(TokenType::Keyword(Keyword::Timestamp)) {
+                    match self.parse_integer_or_bind_marker() {
+                        Ok(v) => timestamp.replace(v),
                         _ => {
                             return Err(ParseError::with_message(
                                 "Integer value is expected in timestamp".to_owned(),
                             ))
                         }
                     };
-                    // TODO binding value
-                } else if self.expect(TokenType::Keyword(Keyword::Ttl)).is_ok() {
-                    match self.parse_integer() {
-                        Ok(v @ Constant::Integer(_)) => ttl.replace(Literal::Constant(v)),
+                } else if self.consume(TokenType::Keyword(Keyword::Ttl)) {
+                    let start = self.next_token_offset();
+                    match self.parse_integer_or_bind_marker() {
+                        Ok(Literal::Constant(Constant::Integer(seconds))) if seconds < 0 => {
+                            return Err(ParseError::with_message(format!(
+                                "A TTL must be greater or equal to 0, but was {}",
+                                seconds
+                            ))
+                            .or_offset(start));
+                        }
+                        Ok(Literal::Constant(Constant::Integer(seconds))) if seconds > MAX_TTL => {
+                            return Err(ParseError::with_message(format!(
+                                "ttl is too large. requested ({}) maximum ({})",
+                                seconds, MAX_TTL
+                            ))
+                            .or_offset(start));
+                        }
+                        Ok(v) => ttl.replace(v),
                         _ => {
                             return Err(ParseError::with_message(
                                 "Integer value is expected in ttl".to_owned(),
                             ))
                         }
                     };
-                    // TODO binding value
                 } else {
-                    return Err(ParseError::with_message(format!(
-                        "Only TIMESTAMP or TTL is expected in USING clause"
-                    )));
+                    return Err(ParseError::with_message(
+                        "Only TIMESTAMP or TTL is expected in USING clause".to_owned(),
+                    ));
                 }
 
-                if self.expect(TokenType::Keyword(Keyword::And)).is_err() {
+                if !self.consume(TokenType::Keyword(Keyword::And)) {
                     break;
                 }
             }
@@ -896,64 +2478,196 @@ impl<'a> Parser<'a> {
 
     // Entry point for all the CREATE statements
     fn create_statement(&mut self) -> CqlResult {
+        let start = self.next_token_offset();
         self.expect(TokenType::Keyword(Keyword::Create))?;
 
-        let (_, next_keyword_token) = self
-            .advance_if(|(_, t)| match t.token_type {
+        let create_targets = &[
+            "KEYSPACE",
+            "TABLE",
+            "CUSTOM INDEX",
+            "INDEX",
+            "MATERIALIZED VIEW",
+            "TYPE",
+        ];
+        let next_keyword_token = match self.advance_if(|(_, t)| {
+            matches!(
+                t.token_type,
                 TokenType::Keyword(Keyword::Keyspace)
-                | TokenType::Keyword(Keyword::Table)
-                | TokenType::Keyword(Keyword::Custom)
-                | TokenType::Keyword(Keyword::Index)
-                | TokenType::Keyword(Keyword::Materialized)
-                | TokenType::Keyword(Keyword::Type) => true,
-                _ => false,
-            })
-            .ok_or(ParseError::with_message(
-                "Unexpected token after CREATE".to_owned(),
-            ))?;
+                    | TokenType::Keyword(Keyword::Table)
+                    | TokenType::Keyword(Keyword::Custom)
+                    | TokenType::Keyword(Keyword::Index)
+                    | TokenType::Keyword(Keyword::Materialized)
+                    | TokenType::Keyword(Keyword::Type)
+            )
+        }) {
+            Some((_, token)) => token,
+            None => return Err(self.unexpected(create_targets)),
+        };
         match next_keyword_token.token_type {
-            TokenType::Keyword(Keyword::Keyspace) => self.parse_create_keyspace_statement(),
-            TokenType::Keyword(Keyword::Table) => self.parse_create_table_statement(),
-            TokenType::Keyword(Keyword::Index) => self.parse_create_index_statement(false),
+            TokenType::Keyword(Keyword::Keyspace) => self.parse_create_keyspace_statement(start),
+            TokenType::Keyword(Keyword::Table) => self.parse_create_table_statement(start),
+            TokenType::Keyword(Keyword::Index) => self.parse_create_index_statement(start, false),
             TokenType::Keyword(Keyword::Custom) => {
                 self.expect(TokenType::Keyword(Keyword::Index))?;
-                self.parse_create_index_statement(true)
+                self.parse_create_index_statement(start, true)
             }
             TokenType::Keyword(Keyword::Materialized) => {
                 self.expect(TokenType::Keyword(Keyword::View))?;
-                self.parse_create_materialized_view_statement()
+                self.parse_create_materialized_view_statement(start)
             }
-            TokenType::Keyword(Keyword::Type) => self.parse_create_type_statement(),
-            _ => Err(ParseError::new()),
+            TokenType::Keyword(Keyword::Type) => self.parse_create_type_statement(start),
+            _ => unreachable!(),
+        }
+    }
+
+    // Entry point for all the ALTER statements
+    fn alter_statement(&mut self) -> CqlResult {
+        let start = self.next_token_offset();
+        self.expect(TokenType::Keyword(Keyword::Alter))?;
+        if self.consume(TokenType::Keyword(Keyword::Keyspace)) {
+            self.parse_alter_keyspace_statement(start)
+        } else if self.consume(TokenType::Keyword(Keyword::Table)) {
+            self.parse_alter_table_statement(start)
+        } else if self.consume(TokenType::Keyword(Keyword::Type)) {
+            self.parse_alter_type_statement(start)
+        } else {
+            Err(self.unexpected(&["KEYSPACE", "TABLE", "TYPE"]))
+        }
+    }
+
+    // Entry point for all the DROP statements
+    fn drop_statement(&mut self) -> CqlResult {
+        self.expect(TokenType::Keyword(Keyword::Drop))?;
+        if self.consume(TokenType::Keyword(Keyword::Keyspace)) {
+            let if_exists = self.parse_if_exists()?;
+            let keyspace_name = self
+                .parse_ident()
+                .ok_or_else(|| self.unexpected(&["keyspace name"]))?;
+            self.mark_last(SemanticTokenKind::Keyspace);
+            Ok(CqlStatement::Drop(DropStatement::Keyspace {
+                name: keyspace_name,
+                if_exists,
+            }))
+        } else if self.consume(TokenType::Keyword(Keyword::Table)) {
+            let if_exists = self.parse_if_exists()?;
+            let name = self.parse_qualified_name()?;
+            Ok(CqlStatement::Drop(DropStatement::Table { name, if_exists }))
+        } else if self.consume(TokenType::Keyword(Keyword::Index)) {
+            let if_exists = self.parse_if_exists()?;
+            let name = self.parse_qualified_name_with(
+                |k| k.is_unreserved_keyword(),
+                SemanticTokenKind::Index,
+            )?;
+            Ok(CqlStatement::Drop(DropStatement::Index { name, if_exists }))
+        } else if self.consume(TokenType::Keyword(Keyword::Type)) {
+            let if_exists = self.parse_if_exists()?;
+            let name = self.parse_user_type_name()?;
+            Ok(CqlStatement::Drop(DropStatement::Type { name, if_exists }))
+        } else if self.consume(TokenType::Keyword(Keyword::Materialized)) {
+            self.expect(TokenType::Keyword(Keyword::View))?;
+            let if_exists = self.parse_if_exists()?;
+            let name = self.parse_qualified_name()?;
+            Ok(CqlStatement::Drop(DropStatement::MaterializedView {
+                name,
+                if_exists,
+            }))
+        } else {
+            Err(self.unexpected(&["KEYSPACE", "TABLE", "INDEX", "TYPE", "MATERIALIZED VIEW"]))
+        }
+    }
+
+    /// USE statement
+    fn parse_use_statement(&mut self) -> CqlResult {
+        self.expect(TokenType::Keyword(Keyword::Use))?;
+        let quoted = self.next_is_quoted_name();
+        let keyspace_name = self
+            .parse_ident()
+            .ok_or_else(|| self.unexpected(&["keyspace name"]))?;
+        self.mark_last(SemanticTokenKind::Keyspace);
+        if self.options.resolve_use_keyspace {
+            // kept without `"` as in `QualifiedName`
+            let keyspace = if quoted && self.options.preserve_identifier_case {
+                normalize_identifier(&keyspace_name)
+            } else {
+                keyspace_name.clone()
+            };
+            self.use_keyspace = Some((self.intern(&keyspace), quoted));
         }
+        Ok(CqlStatement::Use(keyspace_name))
+    }
+
+    /// TRUNCATE statement
+    fn parse_truncate_statement(&mut self) -> CqlResult {
+        self.expect(TokenType::Keyword(Keyword::Truncate))?;
+        self.consume(TokenType::Keyword(Keyword::Table));
+        let name = self.parse_qualified_name()?;
+        Ok(CqlStatement::Truncate(name))
     }
 
     /// CREATE KEYSPACE
-    fn parse_create_keyspace_statement(&mut self) -> CqlResult {
+    fn parse_create_keyspace_statement(&mut self, start: usize) -> CqlResult {
         let if_not_exists = self.parse_if_not_exists()?;
-        let keyspace_name = self.parse_ident().ok_or(ParseError::new())?;
+        let keyspace_name = self
+            .parse_ident()
+            .ok_or_else(|| self.unexpected(&["keyspace name"]))?;
+        self.mark_last(SemanticTokenKind::Keyspace);
 
         // parse properties
-        self.expect(TokenType::Keyword(Keyword::With))?;
+        if !self.consume(TokenType::Keyword(Keyword::With)) {
+            return Err(self.unexpected(&["WITH"]).with_reason(
+                "replication is required, such as WITH replication = {'class': 'SimpleStrategy', 'replication_factor': 1}",
+            ));
+        }
         let attributes = self.parse_properties()?;
 
         Ok(CqlStatement::CreateKeyspace(CreateKeyspaceStatement {
             keyspace_name,
             attributes,
             if_not_exists,
+
+            span: Span::new(start, self.last_token_end),
+        }))
+    }
+
+    /// ALTER KEYSPACE
+    fn parse_alter_keyspace_statement(&mut self, start: usize) -> CqlResult {
+        let if_exists = self.parse_if_exists()?;
+        let keyspace_name = self
+            .parse_ident()
+            .ok_or_else(|| self.unexpected(&["keyspace name"]))?;
+        self.mark_last(SemanticTokenKind::Keyspace);
+        self.expect(TokenType::Keyword(Keyword::With))?;
+        let attributes = self.parse_properties()?;
+        Ok(CqlStatement::AlterKeyspace(AlterKeyspaceStatement {
+            keyspace_name,
+            if_exists,
+            attributes,
+
+            span: Span::new(start, self.last_token_end),
         }))
     }
 
     /// CREATE TABLE
-    fn parse_create_table_statement(&mut self) -> CqlResult {
+    fn parse_create_table_statement(&mut self, start: usize) -> CqlResult {
         let if_not_exists = self.parse_if_not_exists()?;
         let table_name = self.parse_qualified_name()?;
+        self.parse_create_table_body(start, if_not_exists, table_name.clone())
+            .map_err(|e| e.in_context(format!("CREATE TABLE {}", table_name)))
+    }
+
+    // Column definitions and options of CREATE TABLE following the table name
+    fn parse_create_table_body(
+        &mut self,
+        start: usize,
+        if_not_exists: bool,
+        table_name: QualifiedName,
+    ) -> CqlResult {
         self.expect(TokenType::LParen)?;
         let mut column_definitions = Vec::new();
         let mut partition_keys = Vec::new();
         let mut clustering_columns = Vec::new();
-        let mut static_columns = Vec::new();
         loop {
+            let start = self.next_token_offset();
             if let Some((s, token)) = self.peek() {
                 match token.token_type {
                     // PRIMARY KEY (...) definition
@@ -963,15 +2677,11 @@ impl<'a> Parser<'a> {
                         clustering_columns.extend(clustering);
                     }
                     TokenType::Identifier | TokenType::QuotedName | TokenType::Keyword(_) => {
-                        let (column, data_type, is_static, is_pk) =
-                            self.parse_column_definition()?;
-                        column_definitions.push((column.clone(), data_type));
-                        if is_static {
-                            static_columns.push(column.clone());
-                        }
-                        if is_pk {
-                            partition_keys.push(vec![column]);
+                        let column = self.parse_column_definition()?;
+                        if column.is_primary_key {
+                            partition_keys.push(vec![column.name.clone()]);
                         }
+                        column_definitions.push(column);
                     }
                     _ => {
                         return Err(ParseError::with_message(format!(
@@ -981,37 +2691,29 @@ impl<'a> Parser<'a> {
                     }
                 }
             }
-            if self.expect(TokenType::Comma).is_err() {
+            if partition_keys.len() > 1 {
+                return Err(ParseError::with_message(format!(
+                    "PRIMARY KEY is defined more than once in table {}",
+                    table_name.name
+                ))
+                .or_offset(start));
+            }
+            if !self.consume(TokenType::Comma) {
                 break;
             }
         }
+        // reported at `)`, but after it so that unterminated definitions are incomplete input
+        let end = self.next_token_offset();
         self.expect(TokenType::RParen)?;
-        // Table properties
-        let mut table_properties = Vec::new();
-        let mut compact_storage = false;
-        let mut clustering_order = Vec::new();
-        if self.expect(TokenType::Keyword(Keyword::With)).is_ok() {
-            loop {
-                // Compact Storage
-                compact_storage = if !compact_storage
-                    && self.expect(TokenType::Keyword(Keyword::Compact)).is_ok()
-                {
-                    self.expect(TokenType::Keyword(Keyword::Storage))?;
-                    true
-                } else {
-                    false
-                };
-                // Clustering Order By
-                clustering_order.extend(self.parse_clustering_order_by()?);
-                // Table property
-                if let Ok(prop) = self.parse_property() {
-                    table_properties.push(prop);
-                }
-                if self.expect(TokenType::Keyword(Keyword::And)).is_err() {
-                    break;
-                }
-            }
+        if partition_keys.is_empty() {
+            return Err(ParseError::with_message(format!(
+                "No PRIMARY KEY specified for table {}",
+                table_name.name
+            ))
+            .or_offset(end));
         }
+        // Table properties
+        let (compact_storage, clustering_order, table_properties) = self.parse_table_options()?;
 
         Ok(CqlStatement::CreateTable(CreateTableStatement {
             if_not_exists,
@@ -1019,86 +2721,179 @@ impl<'a> Parser<'a> {
             column_definitions,
             partition_keys,
             clustering_columns,
-            static_columns,
             compact_storage,
             clustering_order,
             table_properties,
+
+            span: Span::new(start, self.last_token_end),
         }))
     }
 
     /// returns (partition keys, clustering columns) pair
-    fn parse_primary_key_clause(&mut self) -> Result<(Vec<String>, Vec<String>), ParseError> {
+    fn parse_primary_key_clause(
+        &mut self,
+    ) -> Result<(Vec<Identifier>, Vec<Identifier>), ParseError> {
         self.expect(TokenType::Keyword(Keyword::Primary))?;
         self.expect(TokenType::Keyword(Keyword::Key))?;
         self.expect(TokenType::LParen)?;
 
         let mut partition_keys = Vec::new();
-        if self.expect(TokenType::LParen).is_ok() {
+        if self.consume(TokenType::LParen) {
             // multiple partition keys
-            partition_keys.push(self.parse_ident().ok_or(ParseError::with_message(format!(
-                "Identifier is expected in partition key definition"
-            )))?);
-            while self.expect(TokenType::Comma).is_ok() {
-                partition_keys.push(self.parse_ident().ok_or(ParseError::with_message(
-                    format!("Identifier is expected in partition key definition"),
-                ))?);
+            partition_keys.push(self.expect_column_ident()?);
+            while self.consume(TokenType::Comma) {
+                partition_keys.push(self.expect_column_ident()?);
             }
             self.expect(TokenType::RParen)?;
         } else {
-            partition_keys.push(self.parse_ident().ok_or(ParseError::with_message(format!(
-                "Identifier is expected in partition key definition"
-            )))?);
+            partition_keys.push(self.expect_column_ident()?);
         }
         // Clustering columns
         let mut clustering_columns = Vec::new();
-        while self.expect(TokenType::Comma).is_ok() {
-            clustering_columns.push(self.parse_ident().ok_or(ParseError::with_message(
-                format!("Identifier is expected in clustring column definition"),
-            ))?);
+        while self.consume(TokenType::Comma) {
+            clustering_columns.push(self.expect_column_ident()?);
         }
         self.expect(TokenType::RParen)?;
 
         Ok((partition_keys, clustering_columns))
     }
 
-    // returns (column name, data type, static?, primary key?) pair
-    fn parse_column_definition(&mut self) -> Result<(String, CqlType, bool, bool), ParseError> {
-        let ident = self
-            .parse_ident()
-            .ok_or(ParseError::with_message(format!("identifier expected")))?;
-        let cql_type = self.parse_data_type()?;
+    // column_name data_type STATIC? (MASKED WITH ...)? (PRIMARY KEY)?
+    fn parse_column_definition(&mut self) -> Result<ColumnDefinition, ParseError> {
+        let ident = self.expect_column_ident()?;
+        self.parse_column_definition_body(ident.clone())
+            .map_err(|e| e.in_context(format!("column definition {}", DisplayIdentifier(&ident))))
+    }
+
+    // Data type and modifiers of the column definition following its name
+    fn parse_column_definition_body(
+        &mut self,
+        ident: Identifier,
+    ) -> Result<ColumnDefinition, ParseError> {
+        let cql_type = self.parse_column_type(false)?;
 
         // is STATIC column definition?
-        let is_static = self.expect(TokenType::Keyword(Keyword::Static)).is_ok();
+        let is_static = self.consume(TokenType::Keyword(Keyword::Static));
+        // MASKED WITH (DEFAULT | function(args...))
+        let start = self.next_token_offset();
+        let mask = if self.consume(TokenType::Keyword(Keyword::Masked)) {
+            self.check_version("MASKED WITH", start, |v| v >= CqlVersion::V5_0)?;
+            self.expect(TokenType::Keyword(Keyword::With))?;
+            Some(self.parse_column_mask()?)
+        } else {
+            None
+        };
         // is PRIMARY KEY?
-        let is_primary_key = if self.expect(TokenType::Keyword(Keyword::Primary)).is_ok() {
+        let is_primary_key = if self.consume(TokenType::Keyword(Keyword::Primary)) {
             self.expect(TokenType::Keyword(Keyword::Key))?;
             true
         } else {
             false
         };
 
-        Ok((ident, cql_type, is_static, is_primary_key))
+        Ok(ColumnDefinition {
+            name: ident,
+            data_type: cql_type,
+            is_static,
+            is_primary_key,
+            mask,
+        })
+    }
+
+    fn parse_column_mask(&mut self) -> Result<ColumnMask, ParseError> {
+        if self.consume(TokenType::Keyword(Keyword::Default)) {
+            return Ok(ColumnMask::Default);
+        }
+        let start = self.next_token_offset();
+        let found = self.peek().map(|(s, _)| String::from(*s));
+        // only parse function call, which has the highest precedence
+        match self.parse_expression(Precedence::Prefix) {
+            Ok(function) if matches!(function.kind, ExpressionKind::Function { .. }) => {
+                Ok(ColumnMask::Function(function))
+            }
+            _ => Err(ParseError::unexpected(
+                vec!["DEFAULT".to_owned(), "masking function".to_owned()],
+                found,
+            )
+            .or_offset(start)),
+        }
+    }
+
+    // Options of CREATE TABLE and CREATE MATERIALIZED VIEW:
+    //
+    // (WITH (COMPACT STORAGE | CLUSTERING ORDER BY (...) | property) (AND ...)*)?
+    //
+    // returns (compact storage, clustering order, properties)
+    fn parse_table_options(&mut self) -> Result<TableOptions, ParseError> {
+        let mut compact_storage = false;
+        let mut clustering_order = Vec::new();
+        let mut properties = Vec::new();
+        if self.consume(TokenType::Keyword(Keyword::With)) {
+            loop {
+                let start = self.next_token_offset();
+                let is_clustering = self
+                    .peek()
+                    .filter(|(_, t)| t.token_type == TokenType::Keyword(Keyword::Clustering))
+                    .is_some();
+                if self.consume(TokenType::Keyword(Keyword::Compact)) {
+                    // Compact Storage
+                    self.expect(TokenType::Keyword(Keyword::Storage))?;
+                    self.check_version("COMPACT STORAGE", start, |v| v < CqlVersion::V4_0)?;
+                    if compact_storage {
+                        return Err(ParseError::with_message(
+                            "COMPACT STORAGE is specified more than once".to_owned(),
+                        )
+                        .or_offset(start));
+                    }
+                    compact_storage = true;
+                    self.warn(
+                        start,
+                        WarningKind::CompactStorage,
+                        "COMPACT STORAGE is deprecated, and not supported since Cassandra 4.0"
+                            .to_owned(),
+                    );
+                } else if is_clustering {
+                    // Clustering Order By
+                    clustering_order.extend(self.parse_clustering_order_by()?);
+                } else {
+                    // Table property
+                    let prop = self.parse_property().map_err(|e| {
+                        // nothing is parsed as an option
+                        if e.offset() == Some(start) {
+                            self.unexpected(&["COMPACT STORAGE", "CLUSTERING ORDER BY", "property"])
+                        } else {
+                            e
+                        }
+                    })?;
+                    add_property(&mut properties, prop, start)?;
+                }
+                if !self.consume(TokenType::Keyword(Keyword::And)) {
+                    break;
+                }
+            }
+        }
+        Ok((compact_storage, clustering_order, properties))
     }
 
-    fn parse_clustering_order_by(&mut self) -> Result<Vec<(String, bool)>, ParseError> {
+    fn parse_clustering_order_by(&mut self) -> Result<Vec<(Identifier, bool)>, ParseError> {
         let mut clustering_orders = Vec::new();
-        if self.expect(TokenType::Keyword(Keyword::Clustering)).is_ok() {
+        if self.consume(TokenType::Keyword(Keyword::Clustering)) {
             self.expect(TokenType::Keyword(Keyword::Order))?;
             self.expect(TokenType::Keyword(Keyword::By))?;
             self.expect(TokenType::LParen)?;
             loop {
                 let ident = self
-                    .parse_ident()
-                    .ok_or(ParseError::with_message(format!("Identifier expected")))?;
-                let ascending = if self.expect(TokenType::Keyword(Keyword::Asc)).is_ok() {
+                    .parse_column_ident()
+                    .ok_or_else(|| self.unexpected(&["identifier"]))?;
+                let ascending = if self.consume(TokenType::Keyword(Keyword::Asc)) {
                     true
-                } else {
-                    self.expect(TokenType::Keyword(Keyword::Desc))?;
+                } else if self.consume(TokenType::Keyword(Keyword::Desc)) {
                     false
+                } else {
+                    return Err(self.unexpected(&["ASC", "DESC"]));
                 };
                 clustering_orders.push((ident, ascending));
-                if self.expect(TokenType::Comma).is_err() {
+                if !self.consume(TokenType::Comma) {
                     break;
                 }
             }
@@ -1109,138 +2904,201 @@ impl<'a> Parser<'a> {
 
     fn parse_properties(&mut self) -> Result<Vec<Property>, ParseError> {
         let mut properties = Vec::new();
-        properties.push(self.parse_property()?);
-        while self.expect(TokenType::Keyword(Keyword::And)).is_ok() {
-            properties.push(self.parse_property()?);
+        loop {
+            let start = self.next_token_offset();
+            let property = self.parse_property()?;
+            add_property(&mut properties, property, start)?;
+            if !self.consume(TokenType::Keyword(Keyword::And)) {
+                return Ok(properties);
+            }
         }
-        Ok(properties)
     }
 
     fn parse_property(&mut self) -> Result<Property, ParseError> {
-        let key = self.parse_ident().ok_or(ParseError::new())?;
+        let key = self
+            .parse_ident()
+            .ok_or_else(|| self.unexpected(&["property name"]))?;
+        self.mark_last(SemanticTokenKind::Property);
         self.expect(TokenType::Equal)?;
-        // Value for the property is either:
-        // - constant
-        // - unreserved keywords (though I'm not sure why unreserved keywords are allowed)
-        // - map literal
         let value = self
-            .parse_constant()
-            .map(Literal::Constant)
-            .or_else(|_| {
-                if let Some((s, _)) = self.advance_if(|(_, t)| match &t.token_type {
-                    TokenType::Keyword(k) => k.is_unreserved_keyword(),
-                    _ => false,
-                }) {
-                    Ok(Literal::Constant(Constant::StringLiteral(
-                        s.to_ascii_lowercase(),
-                    )))
-                } else {
-                    Err(ParseError::new())
-                }
-            })
-            .or_else(|_| self.parse_map_literal())?;
+            .parse_property_value()
+            .map_err(|e| e.in_context(format!("property {}", key)))?;
         Ok(Property::new(key, value))
     }
 
-    fn parse_qualified_name(&mut self) -> Result<QualifiedName, ParseError> {
-        self.parse_ident()
-            .map(|name| {
-                let second = if self.expect(TokenType::Dot).is_ok() {
-                    self.parse_ident()
-                } else {
-                    None
-                };
-                (name, second)
-            })
-            .and_then(|(first_name, second_name)| {
-                if second_name.is_some() {
-                    Some(QualifiedName::new(Some(first_name), second_name.unwrap()))
-                } else {
-                    Some(QualifiedName::new(None, first_name))
+    // Value for the property is either:
+    // - constant
+    // - identifier or unreserved keyword
+    // - number with unit, such as `99PERCENTILE`
+    // - map literal, whose keys and values are property values
+    fn parse_property_value(&mut self) -> Result<PropertyValue, ParseError> {
+        if self.consume(TokenType::LBrace) {
+            return self.nested(|p| {
+                let mut entries = Vec::new();
+                // can be empty
+                if p.consume(TokenType::RBrace) {
+                    return Ok(PropertyValue::Map(entries));
                 }
-            })
-            .ok_or(ParseError::with_message(
-                "Invalid qualified name".to_owned(),
-            ))
+                loop {
+                    let key = p.parse_property_value()?;
+                    p.expect(TokenType::Colon)?;
+                    let value = p.parse_property_value()?;
+                    entries.push((key, value));
+                    if !p.consume(TokenType::Comma) {
+                        break;
+                    }
+                }
+                p.expect(TokenType::RBrace)?;
+                Ok(PropertyValue::Map(entries))
+            });
+        }
+        if let Ok(constant) = self.parse_constant() {
+            return Ok(PropertyValue::Constant(constant));
+        }
+        if let Some((s, _)) = self.advance_if(|(_, t)| t.token_type == TokenType::MalformedNumber) {
+            self.mark_last(SemanticTokenKind::Number);
+            return Ok(PropertyValue::NumberWithUnit(s.to_owned()));
+        }
+        match self.advance_if(|(_, t)| match &t.token_type {
+            TokenType::Identifier => true,
+            TokenType::Keyword(k) => k.is_unreserved_keyword(),
+            _ => false,
+        }) {
+            Some((s, _)) => {
+                self.mark_last(SemanticTokenKind::Identifier);
+                Ok(PropertyValue::Identifier(s.to_owned()))
+            }
+            None => Err(self.unexpected(&["constant", "identifier", "map literal"])),
+        }
+    }
+
+    // Parse keyspace or table name, as `columnFamilyName` in Parser.g.
+    //
+    // Both keyspace and table name can be identifier or unreserved keyword.
+    fn parse_qualified_name(&mut self) -> Result<QualifiedName, ParseError> {
+        self.parse_qualified_name_with(|k| k.is_unreserved_keyword(), SemanticTokenKind::Table)
     }
 
     // Similar to `parse_qualified_name`, however,
-    // `TOKEN` and `COUNT` keywords are allowed for function name.
+    // function name can be `TOKEN`, `COUNT` or native type keyword,
+    // but not other unreserved keywords such as `TTL` or `KEY`.
+    //
+    // The name is recorded as a column, until it turns out to be a function name.
     fn parse_function_name(&mut self) -> Result<QualifiedName, ParseError> {
-        self.parse_ident()
-            .map(|name| {
-                let second = if self.expect(TokenType::Dot).is_ok() {
-                    // TODO TOKEN and COUNT are allowed
-                    self.parse_ident()
-                } else {
-                    None
-                };
-                (name, second)
-            })
-            .and_then(|(first_name, second_name)| {
-                if second_name.is_some() {
-                    Some(QualifiedName::new(Some(first_name), second_name.unwrap()))
-                } else {
-                    Some(QualifiedName::new(None, first_name))
-                }
-            })
-            .ok_or(ParseError::with_message(
-                "Invalid qualified name".to_owned(),
-            ))
+        self.parse_qualified_name_with(
+            |k| k.is_unreserved_for_function_name(),
+            SemanticTokenKind::Function,
+        )
     }
 
     // Similar to `parse_qualified_name`, however,
-    // only basic unreserved keyword + `KEY` keyword can be used.
+    // only basic unreserved keyword + `KEY` keyword can be used for type name.
     fn parse_user_type_name(&mut self) -> Result<QualifiedName, ParseError> {
-        // TODO first part (keyspace name can be just ident)
-        self.parse_non_type_ident()
-            .map(|name| {
-                let second = if self.expect(TokenType::Dot).is_ok() {
-                    self.parse_non_type_ident()
-                } else {
-                    None
-                };
-                (name, second)
-            })
-            .and_then(|(first_name, second_name)| {
-                if second_name.is_some() {
-                    Some(QualifiedName::new(Some(first_name), second_name.unwrap()))
-                } else {
-                    Some(QualifiedName::new(None, first_name))
+        self.parse_qualified_name_with(
+            |k| k.is_basic_unreserved_keyword() || *k == Keyword::Key,
+            SemanticTokenKind::Type,
+        )
+    }
+
+    // Parse `(keyspace_name '.')? name`, where keywords allowed for `name`
+    // are filtered by `keyword_filter`, and `name` is recorded as `kind`.
+    //
+    // Keyspace name follows the same rule as `parse_ident`.
+    // Keywords only allowed for keyspace name are not consumed
+    // unless they are followed by `.`.
+    fn parse_qualified_name_with<F>(
+        &mut self,
+        keyword_filter: F,
+        kind: SemanticTokenKind,
+    ) -> Result<QualifiedName, ParseError>
+    where
+        F: Fn(&Keyword) -> bool,
+    {
+        let is_keyspace = matches!(self.peek_second(), Some(TokenType::Dot));
+        let first_quoted = self.next_is_quoted_name();
+        let first = if is_keyspace {
+            self.parse_ident_and_keywords_as_written(|k| k.is_unreserved_keyword())
+        } else {
+            self.parse_ident_and_keywords_as_written(&keyword_filter)
+        };
+        let first = first.ok_or_else(|| self.unexpected(&["identifier"]))?;
+        let preserve = self.options.preserve_identifier_case;
+        // quoted names are kept without `"` even when the case is preserved,
+        // since they are marked as quoted in `QualifiedName`
+        let intern = |parser: &Self, name: Cow<str>, quoted: bool| {
+            if quoted && preserve {
+                parser.intern(&normalize_identifier(&name))
+            } else {
+                parser.intern(&name)
+            }
+        };
+        let first = intern(self, first, first_quoted);
+        self.mark_last(if is_keyspace {
+            SemanticTokenKind::Keyspace
+        } else {
+            kind
+        });
+        if !is_keyspace {
+            let (keyspace, keyspace_quoted, keyspace_source) = match &self.use_keyspace {
+                Some((keyspace, quoted)) => {
+                    (Some(keyspace.clone()), *quoted, KeyspaceSource::Inherited)
                 }
-            })
-            .ok_or(ParseError::with_message(
-                "Invalid qualified name".to_owned(),
-            ))
+                None => (None, false, KeyspaceSource::Explicit),
+            };
+            return Ok(QualifiedName {
+                keyspace,
+                name: first,
+                keyspace_quoted,
+                name_quoted: first_quoted,
+                keyspace_source,
+            });
+        }
+        self.expect(TokenType::Dot)?;
+        let name_quoted = self.next_is_quoted_name();
+        let name = self
+            .parse_ident_and_keywords_as_written(&keyword_filter)
+            .ok_or_else(|| self.unexpected(&["identifier"]))?;
+        self.mark_last(kind);
+        Ok(QualifiedName {
+            keyspace: Some(first),
+            name: intern(self, name, name_quoted),
+            keyspace_quoted: first_quoted,
+            name_quoted,
+            keyspace_source: KeyspaceSource::Explicit,
+        })
     }
 
-    fn parse_non_type_ident(&mut self) -> Option<String> {
-        self.parse_ident_and_keywords(|k| k.is_basic_unreserved_keyword() || *k == Keyword::Key)
+    fn next_is_quoted_name(&mut self) -> bool {
+        matches!(self.peek(), Some((_, t)) if t.token_type == TokenType::QuotedName)
     }
 
     fn parse_ident_and_keywords<F>(&mut self, keyword_filter: F) -> Option<String>
     where
         F: Fn(&Keyword) -> bool,
     {
-        if let Some((s, token)) = self.advance_if(|(_, t)| match &t.token_type {
+        self.parse_ident_and_keywords_as_written(keyword_filter)
+            .map(Cow::into_owned)
+    }
+
+    // Same as `parse_ident_and_keywords`, but borrows the identifier from the source
+    // if it does not need to be normalized
+    fn parse_ident_and_keywords_as_written<F>(&mut self, keyword_filter: F) -> Option<Cow<'a, str>>
+    where
+        F: Fn(&Keyword) -> bool,
+    {
+        let preserve_case = self.options.preserve_identifier_case;
+        let (s, token) = self.advance_if(|(_, t)| match &t.token_type {
             TokenType::Identifier | TokenType::QuotedName => true,
             TokenType::Keyword(k) => keyword_filter(k),
             _ => false,
-        }) {
-            match token.token_type {
-                // If IDENT, return lowercase version of the name
-                TokenType::Identifier => Some(String::from(s).to_ascii_lowercase()),
-                TokenType::QuotedName => {
-                    // remove surounding `"`
-                    let inner = s[1..s.len() - 1].to_owned();
-                    // replace `""` with single `"`
-                    Some(inner.replace("\"\"", "\""))
-                }
-                TokenType::Keyword(_) => Some(String::from(s).to_ascii_lowercase()),
-                _ => unreachable!(),
-            }
+        })?;
+        let is_normalized =
+            token.token_type != TokenType::QuotedName && !s.bytes().any(|b| b.is_ascii_uppercase());
+        if preserve_case || is_normalized {
+            Some(Cow::Borrowed(s))
         } else {
-            None
+            // If IDENT or keyword, return lowercase version of the name
+            Some(Cow::Owned(normalize_identifier(s)))
         }
     }
 
@@ -1259,18 +3117,22 @@ impl<'a> Parser<'a> {
     // ;
     fn parse_create_index_statement(
         &mut self,
+        start: usize,
         is_custom: bool,
     ) -> Result<CqlStatement, ParseError> {
         let if_not_exists = self.parse_if_not_exists()?;
         // index name is optional
         let index_name = self.parse_ident();
+        if index_name.is_some() {
+            self.mark_last(SemanticTokenKind::Index);
+        }
         self.expect(TokenType::Keyword(Keyword::On))?;
         let table_name = self.parse_qualified_name()?;
         self.expect(TokenType::LParen)?;
         let mut index_targets = Vec::new();
         loop {
             index_targets.push(self.parse_index_target()?);
-            if self.expect(TokenType::Comma).is_err() {
+            if !self.consume(TokenType::Comma) {
                 break;
             }
         }
@@ -1281,6 +3143,8 @@ impl<'a> Parser<'a> {
             if_not_exists,
             is_custom,
             index_targets,
+
+            span: Span::new(start, self.last_token_end),
         }))
     }
 
@@ -1291,117 +3155,259 @@ impl<'a> Parser<'a> {
     /// - KEYS(ident)
     /// - ENTRIES(ident)
     /// - FULL(ident)
-    fn parse_index_target(&mut self) -> Result<(String, IndexType), ParseError> {
-        if let Some((_, t)) = self.peek() {
-            match t.token_type {
-                TokenType::Keyword(Keyword::Values) => {
-                    if self.expect(TokenType::LParen).is_ok() {
-                        // VALUES(ident) pattern
-                        let ident = self
-                            .parse_ident()
-                            .ok_or(ParseError::with_message(format!("identifier expected")))?;
-                        self.expect(TokenType::RParen)?;
-                        Ok((ident, IndexType::Values))
-                    } else {
-                        // VALUES as simple index target
-                        Ok((String::from("values"), IndexType::Simple))
-                    }
-                }
-                TokenType::Keyword(Keyword::Keys) => {
-                    if self.expect(TokenType::LParen).is_ok() {
-                        // VALUES(ident) pattern
-                        let ident = self
-                            .parse_ident()
-                            .ok_or(ParseError::with_message(format!("identifier expected")))?;
-                        self.expect(TokenType::RParen)?;
-                        Ok((ident, IndexType::Keys))
-                    } else {
-                        // VALUES as simple index target
-                        Ok((String::from("keys"), IndexType::Simple))
-                    }
-                }
-                TokenType::Keyword(Keyword::Entries) => {
-                    if self.expect(TokenType::LParen).is_ok() {
-                        // VALUES(ident) pattern
-                        let ident = self
-                            .parse_ident()
-                            .ok_or(ParseError::with_message(format!("identifier expected")))?;
-                        self.expect(TokenType::RParen)?;
-                        Ok((ident, IndexType::KeysAndValues))
-                    } else {
-                        // VALUES as simple index target
-                        Ok((String::from("entries"), IndexType::Simple))
-                    }
+    fn parse_index_target(&mut self) -> Result<IndexTarget, ParseError> {
+        let index_type = match self.peek().map(|(_, t)| &t.token_type) {
+            Some(TokenType::Keyword(Keyword::Values)) => Some(IndexType::Values),
+            Some(TokenType::Keyword(Keyword::Keys)) => Some(IndexType::Keys),
+            Some(TokenType::Keyword(Keyword::Entries)) => Some(IndexType::KeysAndValues),
+            Some(TokenType::Keyword(Keyword::Full)) => Some(IndexType::Full),
+            _ => None,
+        };
+        match index_type {
+            // KEYS, VALUES, ENTRIES and FULL are also valid column names
+            Some(index_type) if self.peek_second() == Some(TokenType::LParen) => {
+                self.advance();
+                self.expect(TokenType::LParen)?;
+                let (column, quoted) = self.parse_index_target_column()?;
+                self.expect(TokenType::RParen)?;
+                Ok(IndexTarget {
+                    column,
+                    quoted,
+                    index_type,
+                })
+            }
+            _ => {
+                let (column, quoted) = self.parse_index_target_column()?;
+                Ok(IndexTarget {
+                    column,
+                    quoted,
+                    index_type: IndexType::Simple,
+                })
+            }
+        }
+    }
+
+    fn parse_index_target_column(&mut self) -> Result<(Identifier, bool), ParseError> {
+        let quoted = self.next_is_quoted_name();
+        match self.parse_column_ident() {
+            Some(column) => Ok((column, quoted)),
+            None => Err(self.unexpected(&["index target"])),
+        }
+    }
+
+    // ALTER TABLE statement
+    fn parse_alter_table_statement(&mut self, start: usize) -> CqlResult {
+        let if_exists = self.parse_if_exists()?;
+        let name = self.parse_qualified_name()?;
+        let operation_start = self.next_token_offset();
+        let operation = if self.consume(TokenType::Keyword(Keyword::Add)) {
+            let if_not_exists = self.parse_alter_if_not_exists()?;
+            let columns = self.parse_parenthesized_or_single(|parser| {
+                let start = parser.next_token_offset();
+                let column = parser.parse_column_definition()?;
+                if column.is_primary_key {
+                    return Err(ParseError::with_message(
+                        "PRIMARY KEY cannot be added by ALTER TABLE".to_owned(),
+                    )
+                    .or_offset(start));
                 }
-                TokenType::Keyword(Keyword::Full) => {
-                    if self.expect(TokenType::LParen).is_ok() {
-                        // VALUES(ident) pattern
-                        let ident = self
-                            .parse_ident()
-                            .ok_or(ParseError::with_message(format!("identifier expected")))?;
-                        self.expect(TokenType::RParen)?;
-                        Ok((ident, IndexType::Full))
-                    } else {
-                        // VALUES as simple index target
-                        Ok((String::from("full"), IndexType::Simple))
+                Ok(column)
+            })?;
+            AlterTableOperation::Add {
+                if_not_exists,
+                columns,
+            }
+        } else if self.consume(TokenType::Keyword(Keyword::Drop)) {
+            // `compact` not followed by STORAGE is a column name
+            if self.peek_second() == Some(TokenType::Keyword(Keyword::Storage)) {
+                self.expect(TokenType::Keyword(Keyword::Compact))?;
+                self.expect(TokenType::Keyword(Keyword::Storage))?;
+                AlterTableOperation::DropCompactStorage
+            } else {
+                let if_exists = self.parse_alter_if_exists()?;
+                let columns =
+                    self.parse_parenthesized_or_single(|parser| parser.expect_column_ident())?;
+                let timestamp = if self.consume(TokenType::Keyword(Keyword::Using)) {
+                    self.expect(TokenType::Keyword(Keyword::Timestamp))?;
+                    match self.parse_integer()? {
+                        Constant::Integer(timestamp) => Some(timestamp),
+                        _ => unreachable!(),
                     }
+                } else {
+                    None
+                };
+                AlterTableOperation::Drop {
+                    if_exists,
+                    columns,
+                    timestamp,
                 }
-                TokenType::Identifier | TokenType::QuotedName | TokenType::Keyword(_) => {
-                    let ident = self
-                        .parse_ident()
-                        .ok_or(ParseError::with_message(format!("identifier expected")))?;
-                    Ok((ident, IndexType::Simple))
-                }
-                _ => Err(ParseError::new()),
+            }
+        } else if self.consume(TokenType::Keyword(Keyword::Rename)) {
+            let if_exists = self.parse_alter_if_exists()?;
+            let columns = self
+                .parse_renames(SemanticTokenKind::Column)?
+                .into_iter()
+                .map(|(from, to)| (self.intern(&from), self.intern(&to)))
+                .collect();
+            AlterTableOperation::Rename { if_exists, columns }
+        } else if self.consume(TokenType::Keyword(Keyword::With)) {
+            AlterTableOperation::WithProperties(self.parse_properties()?)
+        } else if self.consume(TokenType::Keyword(Keyword::Alter)) {
+            self.check_version("ALTER ... TYPE", operation_start, |v| v < CqlVersion::V4_0)
+                .map_err(|e| e.with_reason("changing column types was removed in Cassandra 4.0"))?;
+            let column = self.expect_column_ident()?;
+            self.expect(TokenType::Keyword(Keyword::Type))?;
+            AlterTableOperation::AlterType {
+                column,
+                new_type: self.parse_column_type(false)?,
+            }
+        } else {
+            return Err(self.unexpected(&["ADD", "ALTER", "DROP", "RENAME", "WITH"]));
+        };
+        Ok(CqlStatement::AlterTable(AlterTableStatement {
+            name,
+            if_exists,
+            operation,
+
+            span: Span::new(start, self.last_token_end),
+        }))
+    }
+
+    // ALTER TYPE statement
+    fn parse_alter_type_statement(&mut self, start: usize) -> CqlResult {
+        let if_exists = self.parse_if_exists()?;
+        let name = self.parse_user_type_name()?;
+        let operation = if self.consume(TokenType::Keyword(Keyword::Add)) {
+            let if_not_exists = self.parse_alter_if_not_exists()?;
+            let field = self
+                .parse_ident()
+                .ok_or_else(|| self.unexpected(&["field name"]))?;
+            self.mark_last(SemanticTokenKind::Field);
+            let data_type = self.parse_column_type(true)?;
+            AlterTypeOperation::Add {
+                if_not_exists,
+                field,
+                data_type,
+            }
+        } else if self.consume(TokenType::Keyword(Keyword::Rename)) {
+            let if_exists = self.parse_alter_if_exists()?;
+            AlterTypeOperation::Rename {
+                if_exists,
+                fields: self.parse_renames(SemanticTokenKind::Field)?,
             }
         } else {
-            Err(ParseError::new())
+            return Err(self.unexpected(&["ADD", "RENAME"]));
+        };
+        Ok(CqlStatement::AlterType(AlterTypeStatement {
+            name,
+            if_exists,
+            operation,
+
+            span: Span::new(start, self.last_token_end),
+        }))
+    }
+
+    // IF EXISTS of ALTER operations, added in Cassandra 4.1
+    fn parse_alter_if_exists(&mut self) -> Result<bool, ParseError> {
+        let start = self.next_token_offset();
+        let if_exists = self.parse_if_exists()?;
+        if if_exists {
+            self.check_version("IF EXISTS", start, |v| v >= CqlVersion::V4_1)?;
+        }
+        Ok(if_exists)
+    }
+
+    // IF NOT EXISTS of ALTER operations, added in Cassandra 4.1
+    fn parse_alter_if_not_exists(&mut self) -> Result<bool, ParseError> {
+        let start = self.next_token_offset();
+        let if_not_exists = self.parse_if_not_exists()?;
+        if if_not_exists {
+            self.check_version("IF NOT EXISTS", start, |v| v >= CqlVersion::V4_1)?;
+        }
+        Ok(if_not_exists)
+    }
+
+    // `item` or `(item, item, ...)`
+    fn parse_parenthesized_or_single<T, F>(&mut self, mut f: F) -> Result<Vec<T>, ParseError>
+    where
+        F: FnMut(&mut Self) -> Result<T, ParseError>,
+    {
+        if !self.consume(TokenType::LParen) {
+            return Ok(vec![f(self)?]);
         }
+        let mut items = vec![f(self)?];
+        while self.consume(TokenType::Comma) {
+            items.push(f(self)?);
+        }
+        self.expect(TokenType::RParen)?;
+        Ok(items)
+    }
+
+    // `from TO to (AND from TO to)*`, where names are recorded as `kind`
+    fn parse_renames(
+        &mut self,
+        kind: SemanticTokenKind,
+    ) -> Result<Vec<(String, String)>, ParseError> {
+        let mut renames = Vec::new();
+        loop {
+            let from = self
+                .parse_ident()
+                .ok_or_else(|| self.unexpected(&["identifier"]))?;
+            self.mark_last(kind);
+            self.expect(TokenType::Keyword(Keyword::To))?;
+            let to = self
+                .parse_ident()
+                .ok_or_else(|| self.unexpected(&["identifier"]))?;
+            self.mark_last(kind);
+            renames.push((from, to));
+            if !self.consume(TokenType::Keyword(Keyword::And)) {
+                break;
+            }
+        }
+        Ok(renames)
     }
 
     // CREATE MATERIALIZED VIEW statement
-    fn parse_create_materialized_view_statement(&mut self) -> Result<CqlStatement, ParseError> {
+    fn parse_create_materialized_view_statement(
+        &mut self,
+        start: usize,
+    ) -> Result<CqlStatement, ParseError> {
         let if_not_exists = self.parse_if_not_exists()?;
         let name = self.parse_qualified_name()?;
         self.expect(TokenType::Keyword(Keyword::As))?;
         self.expect(TokenType::Keyword(Keyword::Select))?;
         let projection = self.parse_projection()?;
+        if let Projection::Selectors(selectors) = &projection {
+            for selector in selectors {
+                if let Some(message) = check_view_selector(selector) {
+                    self.warnings.push(ParseWarning::new(
+                        selector.span(),
+                        WarningKind::InvalidViewSelect,
+                        message,
+                    ));
+                }
+            }
+        }
         self.expect(TokenType::Keyword(Keyword::From))?;
         let base_table = self.parse_qualified_name()?;
         // WHERE clause
-        let selection = if self.expect(TokenType::Keyword(Keyword::Where)).is_ok() {
-            Some(self.parse_where_clause()?)
+        let selection = if self.consume(TokenType::Keyword(Keyword::Where)) {
+            let start = self.next_token_offset();
+            let selection = self.parse_where_clause()?;
+            // relations do not have their positions
+            for relation in selection.relations() {
+                if let Some(message) = check_view_relation(relation) {
+                    self.warn(start, WarningKind::InvalidViewSelect, message);
+                }
+            }
+            Some(selection)
         } else {
             None
         };
         // PRIMARY KEY (...) definition
         let (partition_keys, clustering_columns) = self.parse_primary_key_clause()?;
         // Table properties
-        let mut view_properties = Vec::new();
-        let mut compact_storage = false;
-        let mut clustering_order = Vec::new();
-        if self.expect(TokenType::Keyword(Keyword::With)).is_ok() {
-            loop {
-                // Compact Storage
-                compact_storage = if !compact_storage
-                    && self.expect(TokenType::Keyword(Keyword::Compact)).is_ok()
-                {
-                    self.expect(TokenType::Keyword(Keyword::Storage))?;
-                    true
-                } else {
-                    false
-                };
-                // Clustering Order By
-                clustering_order.extend(self.parse_clustering_order_by()?);
-                // Table property
-                if let Ok(prop) = self.parse_property() {
-                    view_properties.push(prop);
-                }
-                if self.expect(TokenType::Keyword(Keyword::And)).is_err() {
-                    break;
-                }
-            }
-        }
+        let (compact_storage, clustering_order, view_properties) = self.parse_table_options()?;
         Ok(CqlStatement::CreateMaterializedView(
             CreateMaterializedViewStatement {
                 name,
@@ -1414,43 +3420,44 @@ impl<'a> Parser<'a> {
                 compact_storage,
                 clustering_order,
                 view_properties,
+                span: Span::new(start, self.last_token_end),
             },
         ))
     }
 
     // CREATE TYPE statement
-    fn parse_create_type_statement(&mut self) -> Result<CqlStatement, ParseError> {
+    fn parse_create_type_statement(&mut self, start: usize) -> Result<CqlStatement, ParseError> {
         let if_not_exists = self.parse_if_not_exists()?;
         let name = self.parse_user_type_name()?;
         self.expect(TokenType::LParen)?;
         let mut field_definitions = Vec::new();
         loop {
-            if let Some((s, token)) = self.peek() {
-                match token.token_type {
-                    TokenType::Identifier | TokenType::QuotedName | TokenType::Keyword(_) => {
-                        let field = self
-                            .parse_ident()
-                            .ok_or(ParseError::with_message(format!("identifier expected")))?;
-                        let cql_type = self.parse_data_type()?;
-                        field_definitions.push((field, cql_type));
-                    }
-                    _ => {
-                        return Err(ParseError::with_message(format!(
-                            "unexpected token: {}",
-                            *s
-                        )));
-                    }
-                }
-            }
-            if self.expect(TokenType::Comma).is_err() {
+            let field = self
+                .parse_ident()
+                .ok_or_else(|| self.unexpected(&["field name"]))?;
+            self.mark_last(SemanticTokenKind::Field);
+            let cql_type = self.parse_column_type(true)?;
+            field_definitions.push((field, cql_type));
+
+            let comma = self.next_token_offset();
+            if !self.consume(TokenType::Comma) {
                 break;
             }
+            // unlike columns of CREATE TABLE, fields cannot end with `,`
+            if matches!(self.peek(), Some((_, t)) if t.token_type == TokenType::RParen) {
+                return Err(
+                    ParseError::with_message("trailing comma before ')'".to_owned())
+                        .or_offset(comma),
+                );
+            }
         }
         self.expect(TokenType::RParen)?;
         Ok(CqlStatement::CreateType(CreateTypeStatement {
             name,
             if_not_exists,
             field_definitions,
+
+            span: Span::new(start, self.last_token_end),
         }))
     }
 
@@ -1467,6 +3474,173 @@ impl<'a> Parser<'a> {
     fn parse_ident(&mut self) -> Option<String> {
         self.parse_ident_and_keywords(|k| k.is_unreserved_keyword())
     }
+
+    // Parse column identifier, which is interned in the identifier cache
+    fn parse_column_ident(&mut self) -> Option<Identifier> {
+        let ident = self.parse_ident_and_keywords_as_written(|k| k.is_unreserved_keyword())?;
+        self.mark_last(SemanticTokenKind::Column);
+        Some(self.intern(&ident))
+    }
+
+    // Parses a column name, or returns `ParseError` expecting one
+    fn expect_column_ident(&mut self) -> Result<Identifier, ParseError> {
+        self.parse_column_ident()
+            .ok_or_else(|| self.unexpected(&["column name"]))
+    }
+
+    fn intern(&self, name: &str) -> Identifier {
+        self.identifiers.intern(name)
+    }
+}
+
+impl<'a> Iterator for Parser<'a> {
+    type Item = Result<CqlStatement, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.next_spanned()?;
+        Some(result.map(Spanned::into_inner))
+    }
+}
+
+/// Parser that owns the CQL string
+///
+/// Unlike `Parser`, `OwnedParser` has no lifetime and is `Send`, so it can be stored
+/// in structs or moved to other threads. Parsing does not consume it, so the same
+/// CQL string can be parsed again, for example with `parse` and then `parse_spanned`.
+///
+/// ```
+/// use cqlparser::OwnedParser;
+/// let parser = OwnedParser::new(String::from("SELECT * FROM t1; SELECT * FROM"));
+/// assert_eq!(parser.parse_lenient().0.len(), 1);
+/// assert!(parser.parse().is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct OwnedParser {
+    cql: String,
+    options: ParserOptions,
+    source_name: Option<String>,
+    identifiers: IdentifierCache,
+}
+
+impl OwnedParser {
+    /// Create new `OwnedParser` of given CQL string
+    pub fn new(cql: String) -> Self {
+        OwnedParser::with_options(cql, ParserOptions::default())
+    }
+
+    /// Create new `OwnedParser` of given CQL string, with limits applied while parsing
+    pub fn with_options(cql: String, options: ParserOptions) -> Self {
+        OwnedParser {
+            cql,
+            options,
+            source_name: None,
+            identifiers: IdentifierCache::new(),
+        }
+    }
+
+    /// Make parsers set given name of the source to errors, as `Parser::with_source_name`
+    pub fn with_source_name(mut self, name: impl Into<String>) -> Self {
+        self.source_name = Some(name.into());
+        self
+    }
+
+    /// Make parsers intern identifiers in given cache, as `Parser::identifier_cache`
+    pub fn identifier_cache(mut self, cache: IdentifierCache) -> Self {
+        self.identifiers = cache;
+        self
+    }
+
+    /// Returns the CQL string
+    pub fn cql(&self) -> &str {
+        &self.cql
+    }
+
+    /// Returns new `Parser` of the CQL string, which can be iterated lazily
+    pub fn parser(&self) -> Parser<'_> {
+        let parser = Parser::with_options(&self.cql, self.options)
+            .identifier_cache(self.identifiers.clone());
+        match &self.source_name {
+            Some(name) => parser.with_source_name(name.clone()),
+            None => parser,
+        }
+    }
+
+    /// Parse CQL statements, as `Parser::parse`
+    pub fn parse(&self) -> Result<Vec<CqlStatement>, ParseError> {
+        self.parser().parse()
+    }
+
+    /// Parse CQL statements along with their positions, as `Parser::parse_spanned`
+    pub fn parse_spanned(&self) -> Result<Vec<Spanned<CqlStatement>>, ParseError> {
+        self.parser().parse_spanned()
+    }
+
+    /// Parse CQL statements continuing after errors, as `Parser::parse_lenient`
+    pub fn parse_lenient(&self) -> (Vec<CqlStatement>, Vec<ParseError>) {
+        self.parser().parse_lenient()
+    }
+}
+
+// Returns the keyword in `STATEMENT_KEYWORDS` whose first word is the closest to `word`,
+// if it is close enough to be a typo
+fn suggest_statement_keyword(word: &str) -> Option<&'static str> {
+    let word = word.to_ascii_uppercase();
+    STATEMENT_KEYWORDS
+        .iter()
+        .map(|keyword| {
+            let first = keyword.split(' ').next().unwrap_or(keyword);
+            (edit_distance(&word, first), first.len(), *keyword)
+        })
+        // one edit per three letters, or one for short keywords such as USE
+        .filter(|(distance, len, _)| *distance <= (len / 3).max(1))
+        .min_by_key(|(distance, _, _)| *distance)
+        .map(|(_, _, keyword)| keyword)
+}
+
+// Number of insertions, deletions, substitutions and transpositions of adjacent chars
+// to turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // distances[i][j] is the distance between a[..i] and b[..j]
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+// Adds the property defined at `offset`, rejecting the key defined more than once
+// as Cassandra does
+fn add_property(
+    properties: &mut Vec<Property>,
+    property: Property,
+    offset: usize,
+) -> Result<(), ParseError> {
+    if properties.iter().any(|p| p.key() == property.key()) {
+        return Err(ParseError::with_message(format!(
+            "Multiple definitions for property {}",
+            property.key()
+        ))
+        .or_offset(offset));
+    }
+    properties.push(property);
+    Ok(())
 }
 
 #[test]
@@ -1487,11 +3661,77 @@ fn test_parse_qualified_name() {
                 String::from("test1"),
             )),
         ),
+        ("ks.\"Order\"", Ok(qualified_name(Some("ks"), "Order"))),
+        // unreserved keywords
+        ("ks.key", Ok(qualified_name(Some("ks"), "key"))),
+        ("ttl.int", Ok(qualified_name(Some("ttl"), "int"))),
+        ("Keys.TYPE", Ok(qualified_name(Some("keys"), "type"))),
+        ("count", Ok(qualified_name(None, "count"))),
     ];
     for test in &test_cases {
         let mut p = Parser::new(test.0);
         assert_eq!(p.parse_qualified_name(), test.1);
     }
+
+    // reserved keywords
+    for cql in ["ks.set", "select", "ks.token", "from.tbl"] {
+        let mut p = Parser::new(cql);
+        assert!(p.parse_qualified_name().is_err(), "{}", cql);
+    }
+}
+
+#[test]
+fn test_parse_function_name() {
+    let test_cases = [
+        ("func", qualified_name(None, "func")),
+        ("system.token", qualified_name(Some("system"), "token")),
+        ("ks.COUNT", qualified_name(Some("ks"), "count")),
+        ("token", qualified_name(None, "token")),
+        ("ks.int", qualified_name(Some("ks"), "int")),
+        ("blob", qualified_name(None, "blob")),
+        ("ks.type", qualified_name(Some("ks"), "type")),
+        ("key.\"Func\"", qualified_name(Some("key"), "Func")),
+    ];
+    for (cql, expected) in test_cases {
+        let mut p = Parser::new(cql);
+        assert_eq!(p.parse_function_name(), Ok(expected), "{}", cql);
+    }
+
+    // unreserved keywords not allowed for function name
+    for cql in ["ks.ttl", "key", "ks.writetime", "cast", "ks.select"] {
+        let mut p = Parser::new(cql);
+        assert!(p.parse_function_name().is_err(), "{}", cql);
+    }
+}
+
+#[test]
+fn test_parse_user_type_name() {
+    let test_cases = [
+        ("address", qualified_name(None, "address")),
+        ("ks.address", qualified_name(Some("ks"), "address")),
+        ("key", qualified_name(None, "key")),
+        ("ks.type", qualified_name(Some("ks"), "type")),
+        ("ttl.key", qualified_name(Some("ttl"), "key")),
+        ("int.\"Address\"", qualified_name(Some("int"), "Address")),
+    ];
+    for (cql, expected) in test_cases {
+        let mut p = Parser::new(cql);
+        assert_eq!(p.parse_user_type_name(), Ok(expected), "{}", cql);
+    }
+
+    // keywords not allowed for type name are not consumed
+    for cql in ["ttl", "ks.int", "ks.count", "token"] {
+        let mut p = Parser::new(cql);
+        assert!(p.parse_user_type_name().is_err(), "{}", cql);
+    }
+    let mut p = Parser::new("ttl");
+    let _ = p.parse_user_type_name();
+    assert!(p.expect(TokenType::Keyword(Keyword::Ttl)).is_ok());
+}
+
+#[cfg(test)]
+fn qualified_name(keyspace: Option<&str>, name: &str) -> QualifiedName {
+    QualifiedName::new(keyspace.map(str::to_owned), name.to_owned())
 }
 
 #[test]
@@ -1501,31 +3741,81 @@ fn test_parse_property() {
             "prop = 'value'",
             Ok(Property::new(
                 "prop".to_owned(),
-                Literal::Constant(Constant::StringLiteral("value".to_owned())),
+                PropertyValue::Constant(Constant::StringLiteral("value".to_owned())),
             )),
         ),
         (
             "replication = {'class': 'NetworkTopologyStrategy', 'dc1': 3}",
             Ok(Property::new(
                 "replication".to_owned(),
-                Literal::Map(vec![
+                PropertyValue::Map(vec![
                     (
-                        Expression::Value(Literal::Constant(Constant::StringLiteral(
-                            "class".to_owned(),
-                        ))),
-                        Expression::Value(Literal::Constant(Constant::StringLiteral(
+                        PropertyValue::Constant(Constant::StringLiteral("class".to_owned())),
+                        PropertyValue::Constant(Constant::StringLiteral(
                             "NetworkTopologyStrategy".to_owned(),
-                        ))),
+                        )),
                     ),
                     (
-                        Expression::Value(Literal::Constant(Constant::StringLiteral(
-                            "dc1".to_owned(),
-                        ))),
-                        Expression::Value(Literal::Constant(Constant::Integer(3))),
+                        PropertyValue::Constant(Constant::StringLiteral("dc1".to_owned())),
+                        PropertyValue::Constant(Constant::Integer(3)),
                     ),
                 ]),
             )),
         ),
+        (
+            "id = 5a1c395e-b41f-11e5-9f22-ba0be0483c18",
+            Ok(Property::new(
+                "id".to_owned(),
+                PropertyValue::Constant(Constant::UUID(
+                    "5a1c395e-b41f-11e5-9f22-ba0be0483c18".to_owned(),
+                )),
+            )),
+        ),
+        (
+            "compaction = {'class': LeveledCompactionStrategy}",
+            Ok(Property::new(
+                "compaction".to_owned(),
+                PropertyValue::Map(vec![(
+                    PropertyValue::Constant(Constant::StringLiteral("class".to_owned())),
+                    PropertyValue::Identifier("LeveledCompactionStrategy".to_owned()),
+                )]),
+            )),
+        ),
+        (
+            "bloom_filter_fp_chance = 0.01",
+            Ok(Property::new(
+                "bloom_filter_fp_chance".to_owned(),
+                PropertyValue::Constant(Constant::Float("0.01".to_owned())),
+            )),
+        ),
+        (
+            "speculative_retry = ALWAYS",
+            Ok(Property::new(
+                "speculative_retry".to_owned(),
+                PropertyValue::Identifier("ALWAYS".to_owned()),
+            )),
+        ),
+        (
+            "speculative_retry = 99PERCENTILE",
+            Ok(Property::new(
+                "speculative_retry".to_owned(),
+                PropertyValue::NumberWithUnit("99PERCENTILE".to_owned()),
+            )),
+        ),
+        (
+            "speculative_retry = 99.9p",
+            Ok(Property::new(
+                "speculative_retry".to_owned(),
+                PropertyValue::NumberWithUnit("99.9p".to_owned()),
+            )),
+        ),
+        (
+            "speculative_retry = 50ms",
+            Ok(Property::new(
+                "speculative_retry".to_owned(),
+                PropertyValue::Constant(Constant::Duration("50ms".to_owned())),
+            )),
+        ),
     ];
     for test in &test_cases {
         let mut p = Parser::new(test.0);
@@ -1534,73 +3824,92 @@ fn test_parse_property() {
 }
 
 #[test]
-fn test_parse_map_literal() {
+fn test_parse_brace_literal() {
     let test_cases = [
         ("{}", Ok(Literal::Map(Vec::new()))),
         (
             "{'key': 1}",
             Ok(Literal::Map(vec![(
-                Expression::Value(Literal::Constant(Constant::StringLiteral(String::from(
+                ExpressionKind::Value(Literal::Constant(Constant::StringLiteral(String::from(
                     "key",
-                )))),
-                Expression::Value(Literal::Constant(Constant::Integer(1))),
+                ))))
+                .into(),
+                ExpressionKind::Value(Literal::Constant(Constant::Integer(1))).into(),
             )])),
         ),
+        (
+            "{1, 2}",
+            Ok(Literal::Set(vec![
+                ExpressionKind::Value(Literal::Constant(Constant::Integer(1))).into(),
+                ExpressionKind::Value(Literal::Constant(Constant::Integer(2))).into(),
+            ])),
+        ),
     ];
     for test in &test_cases {
         let mut p = Parser::new(test.0);
-        assert_eq!(p.parse_map_literal(), test.1);
+        assert_eq!(p.parse_brace_literal(), test.1);
     }
 }
 
 #[test]
 fn test_parse_tuple() {
     let test_cases = [
-        ("()", Ok(Expression::Value(Literal::Tuple(Vec::new())))),
+        (
+            "()",
+            Ok(ExpressionKind::Value(Literal::Tuple(Vec::new())).into()),
+        ),
         (
             "(1, 2, 3)",
-            Ok(Expression::Value(Literal::Tuple(vec![
-                Expression::Value(Literal::Constant(Constant::Integer(1))),
-                Expression::Value(Literal::Constant(Constant::Integer(2))),
-                Expression::Value(Literal::Constant(Constant::Integer(3))),
-            ]))),
+            Ok(ExpressionKind::Value(Literal::Tuple(vec![
+                ExpressionKind::Value(Literal::Constant(Constant::Integer(1))).into(),
+                ExpressionKind::Value(Literal::Constant(Constant::Integer(2))).into(),
+                ExpressionKind::Value(Literal::Constant(Constant::Integer(3))).into(),
+            ]))
+            .into()),
         ),
         // tuple of idents
         (
             "(a, B, \"C\")",
-            Ok(Expression::Value(Literal::Tuple(vec![
-                Expression::Identifier(String::from("a")),
-                Expression::Identifier(String::from("b")),
-                Expression::Identifier(String::from("C")),
-            ]))),
+            Ok(ExpressionKind::Value(Literal::Tuple(vec![
+                ExpressionKind::Identifier("a".into()).into(),
+                ExpressionKind::Identifier("b".into()).into(),
+                ExpressionKind::Identifier("C".into()).into(),
+            ]))
+            .into()),
         ),
         (
             "(1, (2, 3), 4)",
-            Ok(Expression::Value(Literal::Tuple(vec![
-                Expression::Value(Literal::Constant(Constant::Integer(1))),
-                Expression::Value(Literal::Tuple(vec![
-                    Expression::Value(Literal::Constant(Constant::Integer(2))),
-                    Expression::Value(Literal::Constant(Constant::Integer(3))),
-                ])),
-                Expression::Value(Literal::Constant(Constant::Integer(4))),
-            ]))),
+            Ok(ExpressionKind::Value(Literal::Tuple(vec![
+                ExpressionKind::Value(Literal::Constant(Constant::Integer(1))).into(),
+                ExpressionKind::Value(Literal::Tuple(vec![
+                    ExpressionKind::Value(Literal::Constant(Constant::Integer(2))).into(),
+                    ExpressionKind::Value(Literal::Constant(Constant::Integer(3))).into(),
+                ]))
+                .into(),
+                ExpressionKind::Value(Literal::Constant(Constant::Integer(4))).into(),
+            ]))
+            .into()),
         ),
         (
             "((1, 2), (3, 4), (5, 6))",
-            Ok(Expression::Value(Literal::Tuple(vec![
-                Expression::Value(Literal::Tuple(vec![
-                    Expression::Value(Literal::Constant(Constant::Integer(1))),
-                    Expression::Value(Literal::Constant(Constant::Integer(2))),
-                ])),
-                Expression::Value(Literal::Tuple(vec![
-                    Expression::Value(Literal::Constant(Constant::Integer(3))),
-                    Expression::Value(Literal::Constant(Constant::Integer(4))),
-                ])),
-                Expression::Value(Literal::Tuple(vec![
-                    Expression::Value(Literal::Constant(Constant::Integer(5))),
-                    Expression::Value(Literal::Constant(Constant::Integer(6))),
-                ])),
-            ]))),
+            Ok(ExpressionKind::Value(Literal::Tuple(vec![
+                ExpressionKind::Value(Literal::Tuple(vec![
+                    ExpressionKind::Value(Literal::Constant(Constant::Integer(1))).into(),
+                    ExpressionKind::Value(Literal::Constant(Constant::Integer(2))).into(),
+                ]))
+                .into(),
+                ExpressionKind::Value(Literal::Tuple(vec![
+                    ExpressionKind::Value(Literal::Constant(Constant::Integer(3))).into(),
+                    ExpressionKind::Value(Literal::Constant(Constant::Integer(4))).into(),
+                ]))
+                .into(),
+                ExpressionKind::Value(Literal::Tuple(vec![
+                    ExpressionKind::Value(Literal::Constant(Constant::Integer(5))).into(),
+                    ExpressionKind::Value(Literal::Constant(Constant::Integer(6))).into(),
+                ]))
+                .into(),
+            ]))
+            .into()),
         ),
     ];
     for test in &test_cases {
@@ -1613,10 +3922,11 @@ fn test_parse_tuple() {
 fn test_parse_cast() {
     let test_cases = [(
         "cast(col as int)",
-        Ok(Expression::TypeCast(
+        Ok(ExpressionKind::Cast(
             CqlType::Native(NativeDataType::Int),
-            Box::new(Expression::Identifier(String::from("col"))),
-        )),
+            Box::new(Expression::new(ExpressionKind::Identifier("col".into()))),
+        )
+        .into()),
     )];
     for test in &test_cases {
         let mut p = Parser::new(test.0);
@@ -1630,79 +3940,109 @@ fn test_parse_expression() {
         // unary operations
         (
             "-col",
-            Ok(Expression::UnaryOp(UnaryOp::new(
+            Ok(ExpressionKind::UnaryOp(UnaryOp::new(
                 Operator::Minus,
-                Box::new(Expression::Identifier("col".to_owned())),
-            ))),
+                Box::new(Expression::new(ExpressionKind::Identifier("col".into()))),
+            ))
+            .into()),
         ),
+        // negative constants
         (
             "-1000",
-            Ok(Expression::UnaryOp(UnaryOp::new(
+            Ok(ExpressionKind::Value(Literal::Constant(Constant::Integer(-1000))).into()),
+        ),
+        (
+            "-1.5e-3",
+            Ok(
+                ExpressionKind::Value(Literal::Constant(Constant::Float("-1.5e-3".to_owned())))
+                    .into(),
+            ),
+        ),
+        (
+            "- 1000",
+            Ok(ExpressionKind::UnaryOp(UnaryOp::new(
                 Operator::Minus,
-                Box::new(Expression::Value(Literal::Constant(Constant::Integer(
-                    1000,
+                Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
+                    Constant::Integer(1000),
                 )))),
-            ))),
+            ))
+            .into()),
         ),
         // binary operations
         (
             "col + 1",
-            Ok(Expression::BinaryOp(BinaryOp::new(
-                Box::new(Expression::Identifier("col".to_owned())),
+            Ok(ExpressionKind::BinaryOp(BinaryOp::new(
+                Box::new(Expression::new(ExpressionKind::Identifier("col".into()))),
                 Operator::Plus,
-                Box::new(Expression::Value(Literal::Constant(Constant::Integer(1)))),
-            ))),
+                Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
+                    Constant::Integer(1),
+                )))),
+            ))
+            .into()),
         ),
         (
             "col = 'test'",
-            Ok(Expression::BinaryOp(BinaryOp::new(
-                Box::new(Expression::Identifier("col".to_owned())),
+            Ok(ExpressionKind::BinaryOp(BinaryOp::new(
+                Box::new(Expression::new(ExpressionKind::Identifier("col".into()))),
                 Operator::Equal,
-                Box::new(Expression::Value(Literal::Constant(
+                Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
                     Constant::StringLiteral(String::from("test")),
-                ))),
-            ))),
+                )))),
+            ))
+            .into()),
         ),
         (
             "a = 1 AND b = 2",
-            Ok(Expression::BinaryOp(BinaryOp::new(
-                Box::new(Expression::BinaryOp(BinaryOp::new(
-                    Box::new(Expression::Identifier("a".to_owned())),
+            Ok(ExpressionKind::BinaryOp(BinaryOp::new(
+                Box::new(Expression::new(ExpressionKind::BinaryOp(BinaryOp::new(
+                    Box::new(Expression::new(ExpressionKind::Identifier("a".into()))),
                     Operator::Equal,
-                    Box::new(Expression::Value(Literal::Constant(Constant::Integer(1)))),
-                ))),
+                    Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
+                        Constant::Integer(1),
+                    )))),
+                )))),
                 Operator::And,
-                Box::new(Expression::BinaryOp(BinaryOp::new(
-                    Box::new(Expression::Identifier("b".to_owned())),
+                Box::new(Expression::new(ExpressionKind::BinaryOp(BinaryOp::new(
+                    Box::new(Expression::new(ExpressionKind::Identifier("b".into()))),
                     Operator::Equal,
-                    Box::new(Expression::Value(Literal::Constant(Constant::Integer(2)))),
-                ))),
-            ))),
+                    Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
+                        Constant::Integer(2),
+                    )))),
+                )))),
+            ))
+            .into()),
         ),
         // complex
         (
             "((cast(storage_port as int) + 1000) * 4) - cast(native_transport_port as int)",
-            Ok(Expression::BinaryOp(BinaryOp::new(
-                Box::new(Expression::BinaryOp(BinaryOp::new(
-                    Box::new(Expression::BinaryOp(BinaryOp::new(
-                        Box::new(Expression::TypeCast(
+            Ok(ExpressionKind::BinaryOp(BinaryOp::new(
+                Box::new(Expression::new(ExpressionKind::BinaryOp(BinaryOp::new(
+                    Box::new(Expression::new(ExpressionKind::BinaryOp(BinaryOp::new(
+                        Box::new(Expression::new(ExpressionKind::Cast(
                             CqlType::Native(NativeDataType::Int),
-                            Box::new(Expression::Identifier("storage_port".to_owned())),
-                        )),
+                            Box::new(Expression::new(ExpressionKind::Identifier(
+                                "storage_port".into(),
+                            ))),
+                        ))),
                         Operator::Plus,
-                        Box::new(Expression::Value(Literal::Constant(Constant::Integer(
-                            1000,
+                        Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
+                            Constant::Integer(1000),
                         )))),
-                    ))),
+                    )))),
                     Operator::Multiply,
-                    Box::new(Expression::Value(Literal::Constant(Constant::Integer(4)))),
-                ))),
+                    Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
+                        Constant::Integer(4),
+                    )))),
+                )))),
                 Operator::Minus,
-                Box::new(Expression::TypeCast(
+                Box::new(Expression::new(ExpressionKind::Cast(
                     CqlType::Native(NativeDataType::Int),
-                    Box::new(Expression::Identifier("native_transport_port".to_owned())),
-                )),
-            ))),
+                    Box::new(Expression::new(ExpressionKind::Identifier(
+                        "native_transport_port".into(),
+                    ))),
+                ))),
+            ))
+            .into()),
         ),
     ];
     for test in &test_cases {
@@ -1718,28 +4058,37 @@ fn test_parse_projection() {
         (
             "1 + 2 * (3 - 4) as calc",
             Ok(Projection::Selectors(vec![Selector::new(
-                Expression::BinaryOp(BinaryOp::new(
-                    Box::new(Expression::Value(Literal::Constant(Constant::Integer(1)))),
+                ExpressionKind::BinaryOp(BinaryOp::new(
+                    Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
+                        Constant::Integer(1),
+                    )))),
                     Operator::Plus,
-                    Box::new(Expression::BinaryOp(BinaryOp::new(
-                        Box::new(Expression::Value(Literal::Constant(Constant::Integer(2)))),
+                    Box::new(Expression::new(ExpressionKind::BinaryOp(BinaryOp::new(
+                        Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
+                            Constant::Integer(2),
+                        )))),
                         Operator::Multiply,
-                        Box::new(Expression::BinaryOp(BinaryOp::new(
-                            Box::new(Expression::Value(Literal::Constant(Constant::Integer(3)))),
+                        Box::new(Expression::new(ExpressionKind::BinaryOp(BinaryOp::new(
+                            Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
+                                Constant::Integer(3),
+                            )))),
                             Operator::Minus,
-                            Box::new(Expression::Value(Literal::Constant(Constant::Integer(4)))),
-                        ))),
-                    ))),
-                )),
+                            Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
+                                Constant::Integer(4),
+                            )))),
+                        )))),
+                    )))),
+                ))
+                .into(),
                 Some("calc".to_owned()),
             )])),
         ),
         (
             "col1, col2 as col_a",
             Ok(Projection::Selectors(vec![
-                Selector::new(Expression::Identifier("col1".to_owned()), None),
+                Selector::new(ExpressionKind::Identifier("col1".into()).into(), None),
                 Selector::new(
-                    Expression::Identifier("col2".to_owned()),
+                    ExpressionKind::Identifier("col2".into()).into(),
                     Some("col_a".to_owned()),
                 ),
             ])),
@@ -0,0 +1,980 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use super::{Column, ColumnKind, Index, Keyspace, Schema, Table, UserType};
+use crate::ast::{
+    AlterKeyspaceStatement, AlterTableOperation, AlterTableStatement, AlterTypeOperation,
+    AlterTypeStatement, CollectionType, ColumnDefinition, CqlStatement, CqlType,
+    CreateIndexStatement, CreateKeyspaceStatement, CreateMaterializedViewStatement,
+    CreateTableStatement, CreateTypeStatement, DisplayIdentifier, DropStatement, ExpressionKind,
+    Identifier, IndexTarget, IndexType, KeyspaceSource, Literal, Operator, Projection, Property,
+    PropertyValue, QualifiedName, Relation, Selector, Span, WhereClause,
+};
+
+/// Change from one schema to another, returned by [`diff`]
+///
+/// `Display` writes the change as a line of a migration report, such as
+/// `Column email text added to ks.users`.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum SchemaChange {
+    KeyspaceAdded {
+        keyspace: String,
+        properties: Vec<Property>,
+    },
+    KeyspaceRemoved {
+        keyspace: String,
+    },
+    /// Property of a keyspace is added (`old` is `None`), changed or removed (`new` is `None`)
+    KeyspacePropertyChanged {
+        keyspace: String,
+        property: String,
+        old: Option<PropertyValue>,
+        new: Option<PropertyValue>,
+    },
+    /// Table or materialized view is added
+    TableAdded(Table),
+    /// Table or materialized view is removed
+    TableRemoved(Table),
+    /// Property of a table or a materialized view is added (`old` is `None`),
+    /// changed or removed (`new` is `None`)
+    TablePropertyChanged {
+        keyspace: String,
+        table: String,
+        is_view: bool,
+        property: String,
+        old: Option<PropertyValue>,
+        new: Option<PropertyValue>,
+    },
+    ColumnAdded {
+        keyspace: String,
+        table: String,
+        column: Column,
+    },
+    ColumnRemoved {
+        keyspace: String,
+        table: String,
+        column: String,
+    },
+    /// Type of a column, or whether it is static, is changed. This is incompatible.
+    ColumnChanged {
+        keyspace: String,
+        table: String,
+        old: Column,
+        new: Column,
+    },
+    /// Partition keys, clustering columns or clustering order is changed.
+    /// This is incompatible, and the columns of the table are not compared.
+    PrimaryKeyChanged {
+        old: Box<Table>,
+        new: Box<Table>,
+    },
+    TypeAdded {
+        keyspace: String,
+        user_type: UserType,
+    },
+    TypeRemoved {
+        keyspace: String,
+        name: String,
+    },
+    FieldAdded {
+        keyspace: String,
+        name: String,
+        field: String,
+        data_type: CqlType,
+    },
+    /// Field of a user defined type is removed. This is incompatible.
+    FieldRemoved {
+        keyspace: String,
+        name: String,
+        field: String,
+    },
+    /// Type of a field is changed. This is incompatible.
+    FieldChanged {
+        keyspace: String,
+        name: String,
+        field: String,
+        old: CqlType,
+        new: CqlType,
+    },
+    IndexAdded {
+        keyspace: String,
+        table: String,
+        index: Index,
+    },
+    IndexRemoved {
+        keyspace: String,
+        table: String,
+        index: Index,
+    },
+}
+
+impl SchemaChange {
+    /// Returns true if the change cannot be made by altering the schema,
+    /// without dropping and recreating the table or the type
+    pub fn is_incompatible(&self) -> bool {
+        matches!(
+            self,
+            SchemaChange::ColumnChanged { .. }
+                | SchemaChange::PrimaryKeyChanged { .. }
+                | SchemaChange::FieldRemoved { .. }
+                | SchemaChange::FieldChanged { .. }
+        )
+    }
+
+    /// Returns the statement that makes this change
+    ///
+    /// Returns `None` for incompatible changes, removed properties, which cannot be
+    /// reset by a statement, and property changes of materialized views.
+    /// Materialized views are created with `IS NOT NULL` restrictions on their primary key
+    /// columns, since other restrictions are not kept in `Schema`.
+    pub fn to_statement(&self) -> Option<CqlStatement> {
+        let statement = match self {
+            SchemaChange::KeyspaceAdded {
+                keyspace,
+                properties,
+            } => CqlStatement::CreateKeyspace(CreateKeyspaceStatement {
+                keyspace_name: keyspace.clone(),
+                attributes: properties.clone(),
+                if_not_exists: false,
+                span: Span::default(),
+            }),
+            SchemaChange::KeyspaceRemoved { keyspace } => {
+                CqlStatement::Drop(DropStatement::Keyspace {
+                    name: keyspace.clone(),
+                    if_exists: false,
+                })
+            }
+            SchemaChange::KeyspacePropertyChanged {
+                keyspace,
+                property,
+                new: Some(value),
+                ..
+            } => CqlStatement::AlterKeyspace(AlterKeyspaceStatement {
+                keyspace_name: keyspace.clone(),
+                if_exists: false,
+                attributes: vec![Property::new(property.clone(), value.clone())],
+                span: Span::default(),
+            }),
+            SchemaChange::TableAdded(table) if table.base_table.is_some() => {
+                CqlStatement::CreateMaterializedView(create_view(table))
+            }
+            SchemaChange::TableAdded(table) => CqlStatement::CreateTable(create_table(table)),
+            SchemaChange::TableRemoved(table) => {
+                let name = qualified_name(&table.keyspace, &table.name);
+                let if_exists = false;
+                CqlStatement::Drop(if table.base_table.is_some() {
+                    DropStatement::MaterializedView { name, if_exists }
+                } else {
+                    DropStatement::Table { name, if_exists }
+                })
+            }
+            SchemaChange::TablePropertyChanged {
+                keyspace,
+                table,
+                is_view: false,
+                property,
+                new: Some(value),
+                ..
+            } => alter_table(
+                keyspace,
+                table,
+                AlterTableOperation::WithProperties(vec![Property::new(
+                    property.clone(),
+                    value.clone(),
+                )]),
+            ),
+            SchemaChange::ColumnAdded {
+                keyspace,
+                table,
+                column,
+            } => alter_table(
+                keyspace,
+                table,
+                AlterTableOperation::Add {
+                    if_not_exists: false,
+                    columns: vec![column_definition(column)],
+                },
+            ),
+            SchemaChange::ColumnRemoved {
+                keyspace,
+                table,
+                column,
+            } => alter_table(
+                keyspace,
+                table,
+                AlterTableOperation::Drop {
+                    if_exists: false,
+                    columns: vec![column.as_str().into()],
+                    timestamp: None,
+                },
+            ),
+            SchemaChange::TypeAdded {
+                keyspace,
+                user_type,
+            } => CqlStatement::CreateType(CreateTypeStatement {
+                name: qualified_name(keyspace, &user_type.name),
+                if_not_exists: false,
+                field_definitions: user_type.fields.clone(),
+                span: Span::default(),
+            }),
+            SchemaChange::TypeRemoved { keyspace, name } => {
+                CqlStatement::Drop(DropStatement::Type {
+                    name: qualified_name(keyspace, name),
+                    if_exists: false,
+                })
+            }
+            SchemaChange::FieldAdded {
+                keyspace,
+                name,
+                field,
+                data_type,
+            } => CqlStatement::AlterType(AlterTypeStatement {
+                name: qualified_name(keyspace, name),
+                if_exists: false,
+                operation: AlterTypeOperation::Add {
+                    if_not_exists: false,
+                    field: field.clone(),
+                    data_type: data_type.clone(),
+                },
+                span: Span::default(),
+            }),
+            SchemaChange::IndexAdded {
+                keyspace,
+                table,
+                index,
+            } => CqlStatement::CreateIndex(CreateIndexStatement {
+                index_name: Some(index.name.clone()),
+                table_name: qualified_name(keyspace, table),
+                if_not_exists: false,
+                is_custom: index.is_custom,
+                index_targets: index
+                    .targets
+                    .iter()
+                    .map(|(column, index_type)| IndexTarget {
+                        column: column.as_str().into(),
+                        quoted: false,
+                        index_type: *index_type,
+                    })
+                    .collect(),
+                span: Span::default(),
+            }),
+            SchemaChange::IndexRemoved {
+                keyspace, index, ..
+            } => CqlStatement::Drop(DropStatement::Index {
+                name: qualified_name(keyspace, &index.name),
+                if_exists: false,
+            }),
+            SchemaChange::KeyspacePropertyChanged { .. }
+            | SchemaChange::TablePropertyChanged { .. }
+            | SchemaChange::ColumnChanged { .. }
+            | SchemaChange::PrimaryKeyChanged { .. }
+            | SchemaChange::FieldRemoved { .. }
+            | SchemaChange::FieldChanged { .. } => return None,
+        };
+        Some(statement)
+    }
+}
+
+/// Formats the change as a line of a migration report,
+/// followed by `(incompatible)` if the change is incompatible
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaChange::KeyspaceAdded { keyspace, .. } => {
+                write!(f, "Keyspace {} added", DisplayIdentifier(keyspace))?
+            }
+            SchemaChange::KeyspaceRemoved { keyspace } => {
+                write!(f, "Keyspace {} removed", DisplayIdentifier(keyspace))?
+            }
+            SchemaChange::KeyspacePropertyChanged {
+                keyspace,
+                property,
+                old,
+                new,
+            } => {
+                let target = format!("keyspace {}", DisplayIdentifier(keyspace));
+                write_property_change(f, property, &target, old, new)?
+            }
+            SchemaChange::TableAdded(table) => write!(f, "{} added", DisplayTable(table))?,
+            SchemaChange::TableRemoved(table) => write!(f, "{} removed", DisplayTable(table))?,
+            SchemaChange::TablePropertyChanged {
+                keyspace,
+                table,
+                property,
+                old,
+                new,
+                ..
+            } => {
+                let target = qualified_name(keyspace, table).to_string();
+                write_property_change(f, property, &target, old, new)?
+            }
+            SchemaChange::ColumnAdded {
+                keyspace,
+                table,
+                column,
+            } => write!(
+                f,
+                "Column {} added to {}",
+                column_definition(column),
+                qualified_name(keyspace, table)
+            )?,
+            SchemaChange::ColumnRemoved {
+                keyspace,
+                table,
+                column,
+            } => write!(
+                f,
+                "Column {} removed from {}",
+                DisplayIdentifier(column),
+                qualified_name(keyspace, table)
+            )?,
+            SchemaChange::ColumnChanged {
+                keyspace,
+                table,
+                old,
+                new,
+            } => write!(
+                f,
+                "Column {} of {} changed from {} to {}",
+                DisplayIdentifier(&new.name),
+                qualified_name(keyspace, table),
+                DisplayColumnType(old),
+                DisplayColumnType(new)
+            )?,
+            SchemaChange::PrimaryKeyChanged { old, new } => write!(
+                f,
+                "Primary key of {} changed from {} to {}",
+                qualified_name(&new.keyspace, &new.name),
+                DisplayPrimaryKey(old),
+                DisplayPrimaryKey(new)
+            )?,
+            SchemaChange::TypeAdded {
+                keyspace,
+                user_type,
+            } => write!(
+                f,
+                "Type {} added",
+                qualified_name(keyspace, &user_type.name)
+            )?,
+            SchemaChange::TypeRemoved { keyspace, name } => {
+                write!(f, "Type {} removed", qualified_name(keyspace, name))?
+            }
+            SchemaChange::FieldAdded {
+                keyspace,
+                name,
+                field,
+                data_type,
+            } => write!(
+                f,
+                "Field {} {} added to type {}",
+                DisplayIdentifier(field),
+                data_type,
+                qualified_name(keyspace, name)
+            )?,
+            SchemaChange::FieldRemoved {
+                keyspace,
+                name,
+                field,
+            } => write!(
+                f,
+                "Field {} removed from type {}",
+                DisplayIdentifier(field),
+                qualified_name(keyspace, name)
+            )?,
+            SchemaChange::FieldChanged {
+                keyspace,
+                name,
+                field,
+                old,
+                new,
+            } => write!(
+                f,
+                "Field {} of type {} changed from {} to {}",
+                DisplayIdentifier(field),
+                qualified_name(keyspace, name),
+                old,
+                new
+            )?,
+            SchemaChange::IndexAdded {
+                keyspace,
+                table,
+                index,
+            } => write!(
+                f,
+                "Index {} added on {} ({})",
+                qualified_name(keyspace, &index.name),
+                qualified_name(keyspace, table),
+                index_targets(index)
+            )?,
+            SchemaChange::IndexRemoved {
+                keyspace,
+                table,
+                index,
+            } => write!(
+                f,
+                "Index {} removed from {}",
+                qualified_name(keyspace, &index.name),
+                qualified_name(keyspace, table)
+            )?,
+        }
+        if self.is_incompatible() {
+            f.write_str(" (incompatible)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns changes to make `old` schema into `new` schema
+///
+/// Keyspaces, tables, materialized views, columns, user defined types and their fields,
+/// secondary indexes and properties are compared by their names. A renamed column
+/// or field is reported as removed and added, and a changed index, or a changed definition
+/// of a materialized view, is reported as removed and added again.
+///
+/// Changes are ordered so that the statements returned by `SchemaChange::to_statement`
+/// can be executed in order: in each keyspace, indexes, materialized views and tables
+/// are removed first, and types are removed last. Tables, columns and indexes of added
+/// keyspaces and tables are reported as well, while the contents of removed ones are not.
+///
+/// ```
+/// use cqlparser::schema::{diff, Schema};
+/// use cqlparser::{format_statement, FormatOptions, Parser};
+///
+/// let schema = |cql| Schema::from_statements(&Parser::new(cql).parse().unwrap()).unwrap();
+/// let old = schema(
+///     "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy'};
+///      CREATE TABLE ks.users (id uuid PRIMARY KEY, name text)",
+/// );
+/// let new = schema(
+///     "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy'};
+///      CREATE TABLE ks.users (id uuid PRIMARY KEY, name text, email text)",
+/// );
+/// let changes = diff(&old, &new);
+/// assert_eq!(changes[0].to_string(), "Column email text added to ks.users");
+///
+/// let statement = changes[0].to_statement().unwrap();
+/// assert_eq!(
+///     format_statement(&statement, &FormatOptions::default()).unwrap(),
+///     "ALTER TABLE ks.users ADD email text"
+/// );
+/// ```
+pub fn diff(old: &Schema, new: &Schema) -> Vec<SchemaChange> {
+    let mut changes = Vec::new();
+    let names: BTreeSet<&String> = old.keyspaces.keys().chain(new.keyspaces.keys()).collect();
+    for name in names {
+        match (old.keyspace(name), new.keyspace(name)) {
+            (Some(_), None) => changes.push(SchemaChange::KeyspaceRemoved {
+                keyspace: name.clone(),
+            }),
+            (None, Some(new)) => {
+                changes.push(SchemaChange::KeyspaceAdded {
+                    keyspace: name.clone(),
+                    properties: new.properties.clone(),
+                });
+                diff_keyspace(&Keyspace::new(name.clone()), new, &mut changes);
+            }
+            (Some(old), Some(new)) => {
+                for (property, old, new) in diff_properties(&old.properties, &new.properties) {
+                    changes.push(SchemaChange::KeyspacePropertyChanged {
+                        keyspace: name.clone(),
+                        property,
+                        old,
+                        new,
+                    });
+                }
+                diff_keyspace(old, new, &mut changes);
+            }
+            (None, None) => {}
+        }
+    }
+    changes
+}
+
+// Appends changes between two versions of a keyspace
+fn diff_keyspace(old: &Keyspace, new: &Keyspace, changes: &mut Vec<SchemaChange>) {
+    let keyspace = &new.name;
+    // tables and views in both, which are compared by their columns and properties
+    let kept: Vec<(&Table, &Table)> = old
+        .tables()
+        .filter_map(|old_table| {
+            let new_table = new.table(&old_table.name)?;
+            let is_same_kind = old_table.base_table.is_none() && new_table.base_table.is_none()
+                || same_view_definition(old_table, new_table);
+            if is_same_kind {
+                Some((old_table, new_table))
+            } else {
+                None
+            }
+        })
+        .collect();
+    let is_kept = |name: &str| kept.iter().any(|(table, _)| table.name == name);
+
+    // removal of indexes, views and tables
+    for (old_table, new_table) in &kept {
+        for index in &old_table.indexes {
+            if !new_table.indexes.contains(index) {
+                changes.push(SchemaChange::IndexRemoved {
+                    keyspace: keyspace.clone(),
+                    table: old_table.name.clone(),
+                    index: index.clone(),
+                });
+            }
+        }
+    }
+    let (old_views, old_tables): (Vec<&Table>, Vec<&Table>) = old
+        .tables()
+        .filter(|table| !is_kept(&table.name))
+        .partition(|table| table.base_table.is_some());
+    for table in old_views.into_iter().chain(old_tables) {
+        changes.push(SchemaChange::TableRemoved(table.clone()));
+    }
+
+    // addition and changes of types
+    for user_type in dependency_order(keyspace, new.user_types()) {
+        match old.user_type(&user_type.name) {
+            Some(old_type) => diff_fields(keyspace, old_type, user_type, changes),
+            None => changes.push(SchemaChange::TypeAdded {
+                keyspace: keyspace.clone(),
+                user_type: user_type.clone(),
+            }),
+        }
+    }
+
+    // addition and changes of tables, then views that may select from them
+    let (new_views, new_tables): (Vec<&Table>, Vec<&Table>) =
+        new.tables().partition(|table| table.base_table.is_some());
+    for table in new_tables.into_iter().chain(new_views) {
+        match kept
+            .iter()
+            .find(|(old_table, _)| old_table.name == table.name)
+        {
+            Some((old_table, _)) => diff_table(old_table, table, changes),
+            None => changes.push(SchemaChange::TableAdded(table.clone())),
+        }
+    }
+
+    // addition of indexes
+    for table in new.tables() {
+        let old_table = kept
+            .iter()
+            .find(|(old_table, _)| old_table.name == table.name)
+            .map(|(old_table, _)| old_table);
+        for index in &table.indexes {
+            // `is_none_or` requires Rust 1.82
+            #[allow(clippy::unnecessary_map_or)]
+            let added = old_table.map_or(true, |old_table| !old_table.indexes.contains(index));
+            if added {
+                changes.push(SchemaChange::IndexAdded {
+                    keyspace: keyspace.clone(),
+                    table: table.name.clone(),
+                    index: index.clone(),
+                });
+            }
+        }
+    }
+
+    // removal of types, after the tables and the types using them
+    let mut removed: Vec<&UserType> = dependency_order(keyspace, old.user_types())
+        .into_iter()
+        .filter(|user_type| new.user_type(&user_type.name).is_none())
+        .collect();
+    removed.reverse();
+    for user_type in removed {
+        changes.push(SchemaChange::TypeRemoved {
+            keyspace: keyspace.clone(),
+            name: user_type.name.clone(),
+        });
+    }
+}
+
+// Returns true if both are materialized views that select the same columns
+// with the same primary key from the same base table
+fn same_view_definition(old: &Table, new: &Table) -> bool {
+    old.base_table.is_some()
+        && old.base_table == new.base_table
+        && old.columns == new.columns
+        && same_primary_key(old, new)
+}
+
+fn same_primary_key(old: &Table, new: &Table) -> bool {
+    old.partition_keys == new.partition_keys
+        && old.clustering_columns == new.clustering_columns
+        && old
+            .clustering_columns
+            .iter()
+            .all(|column| old.is_descending(column) == new.is_descending(column))
+}
+
+// Appends changes between two versions of a table or a materialized view
+fn diff_table(old: &Table, new: &Table, changes: &mut Vec<SchemaChange>) {
+    let keyspace = &new.keyspace;
+    let table = &new.name;
+    if !same_primary_key(old, new) {
+        changes.push(SchemaChange::PrimaryKeyChanged {
+            old: Box::new(old.clone()),
+            new: Box::new(new.clone()),
+        });
+    } else {
+        for column in &old.columns {
+            if new.column(&column.name).is_none() {
+                changes.push(SchemaChange::ColumnRemoved {
+                    keyspace: keyspace.clone(),
+                    table: table.clone(),
+                    column: column.name.clone(),
+                });
+            }
+        }
+        for column in &new.columns {
+            match old.columns.iter().find(|c| c.name == column.name) {
+                None => changes.push(SchemaChange::ColumnAdded {
+                    keyspace: keyspace.clone(),
+                    table: table.clone(),
+                    column: column.clone(),
+                }),
+                Some(old_column) if !same_column(old_column, column) => {
+                    changes.push(SchemaChange::ColumnChanged {
+                        keyspace: keyspace.clone(),
+                        table: table.clone(),
+                        old: old_column.clone(),
+                        new: column.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    let is_view = new.base_table.is_some();
+    for (property, old, new) in diff_properties(&old.properties, &new.properties) {
+        changes.push(SchemaChange::TablePropertyChanged {
+            keyspace: keyspace.clone(),
+            table: table.clone(),
+            is_view,
+            property,
+            old,
+            new,
+        });
+    }
+}
+
+// Returns true if the columns are the same, ignoring the `frozen<...>`
+// that Cassandra adds to their types implicitly
+fn same_column(old: &Column, new: &Column) -> bool {
+    let Column {
+        name,
+        data_type,
+        kind,
+    } = old;
+    *name == new.name
+        && data_type.canonicalize() == new.data_type.canonicalize()
+        && *kind == new.kind
+}
+
+fn diff_fields(keyspace: &str, old: &UserType, new: &UserType, changes: &mut Vec<SchemaChange>) {
+    for (field, _) in &old.fields {
+        if new.field(field).is_none() {
+            changes.push(SchemaChange::FieldRemoved {
+                keyspace: keyspace.to_owned(),
+                name: new.name.clone(),
+                field: field.clone(),
+            });
+        }
+    }
+    for (field, data_type) in &new.fields {
+        match old.field(field) {
+            None => changes.push(SchemaChange::FieldAdded {
+                keyspace: keyspace.to_owned(),
+                name: new.name.clone(),
+                field: field.clone(),
+                data_type: data_type.clone(),
+            }),
+            Some(old_type) if old_type.canonicalize() != data_type.canonicalize() => {
+                changes.push(SchemaChange::FieldChanged {
+                    keyspace: keyspace.to_owned(),
+                    name: new.name.clone(),
+                    field: field.clone(),
+                    old: old_type.clone(),
+                    new: data_type.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+// Returns (name, old value, new value) of the properties that differ.
+// Both properties are ordered by their names.
+fn diff_properties(
+    old: &[Property],
+    new: &[Property],
+) -> Vec<(String, Option<PropertyValue>, Option<PropertyValue>)> {
+    let find = |properties: &[Property], key: &str| {
+        properties
+            .iter()
+            .find(|property| property.key() == key)
+            .map(|property| property.value().clone())
+    };
+    let keys: BTreeSet<&str> = old.iter().chain(new).map(Property::key).collect();
+    keys.into_iter()
+        .map(|key| (key.to_owned(), find(old, key), find(new, key)))
+        .filter(|(_, old, new)| old != new)
+        .collect()
+}
+
+// Returns types ordered so that each type comes after the types of the same keyspace
+// used in its fields
+fn dependency_order<'a>(
+    keyspace: &str,
+    types: impl Iterator<Item = &'a UserType>,
+) -> Vec<&'a UserType> {
+    fn visit<'a>(
+        keyspace: &str,
+        user_type: &'a UserType,
+        types: &[&'a UserType],
+        ordered: &mut Vec<&'a UserType>,
+    ) {
+        if ordered.iter().any(|t| t.name == user_type.name) {
+            return;
+        }
+        let mut used = Vec::new();
+        for (_, data_type) in &user_type.fields {
+            used_types(keyspace, data_type, &mut used);
+        }
+        for name in used {
+            if let Some(used_type) = types.iter().find(|t| t.name == name) {
+                visit(keyspace, used_type, types, ordered);
+            }
+        }
+        ordered.push(user_type);
+    }
+
+    let types: Vec<&UserType> = types.collect();
+    let mut ordered = Vec::with_capacity(types.len());
+    for user_type in &types {
+        visit(keyspace, user_type, &types, &mut ordered);
+    }
+    ordered
+}
+
+// Collects names of user defined types of `keyspace` used in `cql_type`
+fn used_types(keyspace: &str, cql_type: &CqlType, names: &mut Vec<String>) {
+    match cql_type {
+        CqlType::UserDefinedType(name) => {
+            if name.normalized_keyspace().as_deref() == Some(keyspace) {
+                names.push(name.normalized_name());
+            }
+        }
+        CqlType::Collection(CollectionType::Map {
+            key_type,
+            value_type,
+        }) => {
+            used_types(keyspace, key_type, names);
+            used_types(keyspace, value_type, names);
+        }
+        CqlType::Collection(CollectionType::List(element_type))
+        | CqlType::Collection(CollectionType::Set(element_type))
+        | CqlType::Frozen(element_type) => used_types(keyspace, element_type, names),
+        CqlType::Tuple(types) => {
+            for element_type in types {
+                used_types(keyspace, element_type, names);
+            }
+        }
+        CqlType::Native(_) | CqlType::Custom(_) => {}
+    }
+}
+
+// Name that keeps the case of names in `Schema`
+fn qualified_name(keyspace: &str, name: &str) -> QualifiedName {
+    QualifiedName {
+        keyspace: Some(keyspace.into()),
+        name: name.into(),
+        keyspace_quoted: true,
+        name_quoted: true,
+        keyspace_source: KeyspaceSource::Explicit,
+    }
+}
+
+fn column_definition(column: &Column) -> ColumnDefinition {
+    let mut definition = ColumnDefinition::new(column.name.clone(), column.data_type.clone());
+    definition.is_static = column.kind == ColumnKind::Static;
+    definition
+}
+
+fn alter_table(keyspace: &str, table: &str, operation: AlterTableOperation) -> CqlStatement {
+    CqlStatement::AlterTable(AlterTableStatement {
+        name: qualified_name(keyspace, table),
+        if_exists: false,
+        operation,
+        span: Span::default(),
+    })
+}
+
+fn identifiers(names: &[String]) -> Vec<Identifier> {
+    names.iter().map(|name| name.as_str().into()).collect()
+}
+
+// `CLUSTERING ORDER BY` of all the clustering columns if any of them is in descending order
+fn clustering_order(table: &Table) -> Vec<(Identifier, bool)> {
+    if table.descending_columns.is_empty() {
+        return Vec::new();
+    }
+    table
+        .clustering_columns
+        .iter()
+        .map(|column| (column.as_str().into(), !table.is_descending(column)))
+        .collect()
+}
+
+fn create_table(table: &Table) -> CreateTableStatement {
+    CreateTableStatement {
+        name: qualified_name(&table.keyspace, &table.name),
+        if_not_exists: false,
+        column_definitions: table.columns.iter().map(column_definition).collect(),
+        partition_keys: vec![identifiers(&table.partition_keys)],
+        clustering_columns: identifiers(&table.clustering_columns),
+        compact_storage: false,
+        clustering_order: clustering_order(table),
+        table_properties: table.properties.clone(),
+        span: Span::default(),
+    }
+}
+
+fn create_view(view: &Table) -> CreateMaterializedViewStatement {
+    let selectors = view
+        .columns
+        .iter()
+        .map(|column| {
+            Selector::new(
+                ExpressionKind::Identifier(column.name.as_str().into()).into(),
+                None,
+            )
+        })
+        .collect();
+    let relations = view
+        .partition_keys
+        .iter()
+        .chain(&view.clustering_columns)
+        .map(|column| Relation::SingleColumn {
+            column: column.as_str().into(),
+            operator: Operator::IsNot,
+            value: ExpressionKind::Value(Literal::Null).into(),
+        })
+        .collect();
+    CreateMaterializedViewStatement {
+        name: qualified_name(&view.keyspace, &view.name),
+        base_table: qualified_name(&view.keyspace, view.base_table.as_deref().unwrap_or("")),
+        if_not_exists: false,
+        projection: Projection::Selectors(selectors),
+        selection: Some(WhereClause::new(relations)),
+        partition_keys: identifiers(&view.partition_keys),
+        clustering_columns: identifiers(&view.clustering_columns),
+        compact_storage: false,
+        clustering_order: clustering_order(view),
+        view_properties: view.properties.clone(),
+        span: Span::default(),
+    }
+}
+
+fn write_property_change(
+    f: &mut fmt::Formatter<'_>,
+    property: &str,
+    target: &str,
+    old: &Option<PropertyValue>,
+    new: &Option<PropertyValue>,
+) -> fmt::Result {
+    write!(f, "Property {} of {} ", DisplayIdentifier(property), target)?;
+    match (old, new) {
+        (Some(old), Some(new)) => write!(f, "changed from {} to {}", old, new),
+        (None, Some(new)) => write!(f, "set to {}", new),
+        _ => f.write_str("removed"),
+    }
+}
+
+fn index_targets(index: &Index) -> String {
+    index
+        .targets
+        .iter()
+        .map(|(column, index_type)| {
+            let column = DisplayIdentifier(column);
+            match index_type {
+                IndexType::Simple => column.to_string(),
+                IndexType::Values => format!("values({})", column),
+                IndexType::Keys => format!("keys({})", column),
+                IndexType::KeysAndValues => format!("entries({})", column),
+                IndexType::Full => format!("full({})", column),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Displays "Table ks.t" or "Materialized view ks.v"
+struct DisplayTable<'a>(&'a Table);
+
+impl fmt::Display for DisplayTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.0.base_table.is_some() {
+            "Materialized view"
+        } else {
+            "Table"
+        };
+        write!(
+            f,
+            "{} {}",
+            kind,
+            qualified_name(&self.0.keyspace, &self.0.name)
+        )
+    }
+}
+
+// Displays the type of a column, followed by `STATIC` if it is static
+struct DisplayColumnType<'a>(&'a Column);
+
+impl fmt::Display for DisplayColumnType<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.data_type)?;
+        if self.0.kind == ColumnKind::Static {
+            f.write_str(" STATIC")?;
+        }
+        Ok(())
+    }
+}
+
+// Displays primary key as `((k1, k2), c1 DESC, c2)`, with the clustering order
+struct DisplayPrimaryKey<'a>(&'a Table);
+
+impl fmt::Display for DisplayPrimaryKey<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let table = self.0;
+        let partition_keys: Vec<String> = table
+            .partition_keys
+            .iter()
+            .map(|key| DisplayIdentifier(key).to_string())
+            .collect();
+        f.write_str("(")?;
+        if partition_keys.len() == 1 {
+            f.write_str(&partition_keys[0])?;
+        } else {
+            write!(f, "({})", partition_keys.join(", "))?;
+        }
+        for column in &table.clustering_columns {
+            write!(f, ", {}", DisplayIdentifier(column))?;
+            if table.is_descending(column) {
+                f.write_str(" DESC")?;
+            }
+        }
+        f.write_str(")")
+    }
+}
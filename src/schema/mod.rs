@@ -0,0 +1,1075 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Model of keyspaces, tables and types defined by DDL statements
+//!
+//! ```
+//! use cqlparser::ast::{CqlType, NativeDataType};
+//! use cqlparser::schema::Schema;
+//! use cqlparser::Parser;
+//!
+//! let statements = Parser::new(
+//!     "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy'};
+//!      USE ks;
+//!      CREATE TABLE users (id uuid PRIMARY KEY, name text);
+//!      ALTER TABLE users ADD email text;",
+//! )
+//! .parse()
+//! .unwrap();
+//! let schema = Schema::from_statements(&statements).unwrap();
+//! let users = schema.table("ks", "users").unwrap();
+//! assert_eq!(users.column("email"), Some(&CqlType::Native(NativeDataType::Text)));
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::ast::{
+    AlterKeyspaceStatement, AlterTableOperation, AlterTableStatement, AlterTypeOperation,
+    AlterTypeStatement, CollectionType, CqlStatement, CqlType, CreateIndexStatement,
+    CreateKeyspaceStatement, CreateMaterializedViewStatement, CreateTableStatement,
+    CreateTypeStatement, DropStatement, ExpressionKind, Identifier, IndexType, KeyspaceSource,
+    Projection, Property, PropertyValue, QualifiedName,
+};
+
+mod diff;
+
+pub use diff::{diff, SchemaChange};
+
+/// Error in applying a statement to `Schema`
+///
+/// Names in errors are the names as interpreted by Cassandra.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum SchemaError {
+    /// Name without keyspace is used before `USE` statement
+    NoKeyspace {
+        name: String,
+    },
+    UnknownKeyspace {
+        keyspace: String,
+    },
+    DuplicateKeyspace {
+        keyspace: String,
+    },
+    /// Table or materialized view does not exist
+    UnknownTable {
+        keyspace: String,
+        table: String,
+    },
+    /// Table or materialized view of the same name already exists
+    DuplicateTable {
+        keyspace: String,
+        table: String,
+    },
+    UnknownType {
+        keyspace: String,
+        name: String,
+    },
+    DuplicateType {
+        keyspace: String,
+        name: String,
+    },
+    UnknownColumn {
+        keyspace: String,
+        table: String,
+        column: String,
+    },
+    DuplicateColumn {
+        keyspace: String,
+        table: String,
+        column: String,
+    },
+    /// Primary key column cannot be dropped
+    PrimaryKeyColumn {
+        keyspace: String,
+        table: String,
+        column: String,
+    },
+    UnknownField {
+        keyspace: String,
+        name: String,
+        field: String,
+    },
+    DuplicateField {
+        keyspace: String,
+        name: String,
+        field: String,
+    },
+    UnknownIndex {
+        keyspace: String,
+        index: String,
+    },
+    DuplicateIndex {
+        keyspace: String,
+        index: String,
+    },
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::NoKeyspace { name } => {
+                write!(f, "No keyspace has been specified for {}", name)
+            }
+            SchemaError::UnknownKeyspace { keyspace } => {
+                write!(f, "Keyspace {} does not exist", keyspace)
+            }
+            SchemaError::DuplicateKeyspace { keyspace } => {
+                write!(f, "Keyspace {} already exists", keyspace)
+            }
+            SchemaError::UnknownTable { keyspace, table } => {
+                write!(f, "Table {}.{} does not exist", keyspace, table)
+            }
+            SchemaError::DuplicateTable { keyspace, table } => {
+                write!(f, "Table {}.{} already exists", keyspace, table)
+            }
+            SchemaError::UnknownType { keyspace, name } => {
+                write!(f, "Type {}.{} does not exist", keyspace, name)
+            }
+            SchemaError::DuplicateType { keyspace, name } => {
+                write!(f, "Type {}.{} already exists", keyspace, name)
+            }
+            SchemaError::UnknownColumn {
+                keyspace,
+                table,
+                column,
+            } => write!(
+                f,
+                "Column {} does not exist in {}.{}",
+                column, keyspace, table
+            ),
+            SchemaError::DuplicateColumn {
+                keyspace,
+                table,
+                column,
+            } => write!(
+                f,
+                "Column {} already exists in {}.{}",
+                column, keyspace, table
+            ),
+            SchemaError::PrimaryKeyColumn {
+                keyspace,
+                table,
+                column,
+            } => write!(
+                f,
+                "Cannot drop primary key column {} of {}.{}",
+                column, keyspace, table
+            ),
+            SchemaError::UnknownField {
+                keyspace,
+                name,
+                field,
+            } => write!(f, "Field {} does not exist in {}.{}", field, keyspace, name),
+            SchemaError::DuplicateField {
+                keyspace,
+                name,
+                field,
+            } => write!(f, "Field {} already exists in {}.{}", field, keyspace, name),
+            SchemaError::UnknownIndex { keyspace, index } => {
+                write!(f, "Index {}.{} does not exist", keyspace, index)
+            }
+            SchemaError::DuplicateIndex { keyspace, index } => {
+                write!(f, "Index {}.{} already exists", keyspace, index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// Keyspaces defined by DDL statements
+///
+/// Statements are applied in order, the same way Cassandra executes them.
+/// Names are looked up by the names as interpreted by Cassandra, that is,
+/// lowercased unless they were quoted.
+#[derive(Clone, Debug, Default)]
+pub struct Schema {
+    keyspaces: BTreeMap<String, Keyspace>,
+    /// keyspace of the last `USE` statement
+    current_keyspace: Option<String>,
+}
+
+impl Schema {
+    /// Creates an empty schema
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds schema by applying statements in order,
+    /// stopping at the first statement that fails to apply
+    pub fn from_statements(statements: &[CqlStatement]) -> Result<Self, SchemaError> {
+        let mut schema = Schema::new();
+        for statement in statements {
+            schema.apply(statement)?;
+        }
+        Ok(schema)
+    }
+
+    /// Applies a statement to this schema
+    ///
+    /// `USE`, `CREATE`, `ALTER` and `DROP` statements change the schema, and the other
+    /// statements are ignored. If the statement fails to apply, the schema is left unchanged.
+    pub fn apply(&mut self, statement: &CqlStatement) -> Result<(), SchemaError> {
+        match statement {
+            CqlStatement::Use(keyspace) => {
+                self.keyspace_or_error(keyspace)?;
+                self.current_keyspace = Some(keyspace.clone());
+                Ok(())
+            }
+            CqlStatement::CreateKeyspace(create) => self.create_keyspace(create),
+            CqlStatement::CreateTable(create) => self.create_table(create),
+            CqlStatement::CreateType(create) => self.create_type(create),
+            CqlStatement::CreateIndex(create) => self.create_index(create),
+            CqlStatement::CreateMaterializedView(create) => self.create_materialized_view(create),
+            CqlStatement::AlterKeyspace(alter) => self.alter_keyspace(alter),
+            CqlStatement::AlterTable(alter) => self.alter_table(alter),
+            CqlStatement::AlterType(alter) => self.alter_type(alter),
+            CqlStatement::Drop(drop) => match drop {
+                DropStatement::Keyspace { name, if_exists } => self.drop_keyspace(name, *if_exists),
+                DropStatement::Table { name, if_exists }
+                | DropStatement::MaterializedView { name, if_exists } => {
+                    self.drop_table(name, *if_exists)
+                }
+                DropStatement::Type { name, if_exists } => self.drop_type(name, *if_exists),
+                DropStatement::Index { name, if_exists } => self.drop_index(name, *if_exists),
+                _ => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    pub fn keyspace(&self, name: &str) -> Option<&Keyspace> {
+        self.keyspaces.get(name)
+    }
+
+    /// Returns keyspaces ordered by their names
+    pub fn keyspaces(&self) -> impl Iterator<Item = &Keyspace> {
+        self.keyspaces.values()
+    }
+
+    /// Returns table or materialized view
+    pub fn table(&self, keyspace: &str, table: &str) -> Option<&Table> {
+        self.keyspace(keyspace)?.table(table)
+    }
+
+    /// Returns table or materialized view of given name, in the keyspace of the last
+    /// `USE` statement if the name has no keyspace
+    pub fn resolve_table(&self, name: &QualifiedName) -> Result<&Table, SchemaError> {
+        let (keyspace, table) = self.resolve_name(name)?;
+        self.table(&keyspace, &table)
+            .ok_or(SchemaError::UnknownTable { keyspace, table })
+    }
+
+    fn keyspace_or_error(&self, keyspace: &str) -> Result<&Keyspace, SchemaError> {
+        self.keyspaces
+            .get(keyspace)
+            .ok_or_else(|| SchemaError::UnknownKeyspace {
+                keyspace: keyspace.to_owned(),
+            })
+    }
+
+    // Returns (keyspace, name) of the existing keyspace that `name` belongs to
+    fn resolve_name(&self, name: &QualifiedName) -> Result<(String, String), SchemaError> {
+        let keyspace = name
+            .normalized_keyspace()
+            .or_else(|| self.current_keyspace.clone())
+            .ok_or_else(|| SchemaError::NoKeyspace {
+                name: name.normalized_name(),
+            })?;
+        self.keyspace_or_error(&keyspace)?;
+        Ok((keyspace, name.normalized_name()))
+    }
+
+    // Returns the table to change, which is committed with `put_table`
+    fn existing_table(&self, keyspace: &str, table: &str) -> Result<Table, SchemaError> {
+        self.table(keyspace, table)
+            .cloned()
+            .ok_or_else(|| SchemaError::UnknownTable {
+                keyspace: keyspace.to_owned(),
+                table: table.to_owned(),
+            })
+    }
+
+    fn put_table(&mut self, table: Table) {
+        if let Some(keyspace) = self.keyspaces.get_mut(&table.keyspace) {
+            keyspace.tables.insert(table.name.clone(), table);
+        }
+    }
+
+    // Qualifies user defined types in `cql_type` with their keyspaces,
+    // checking that they exist
+    fn resolve_type(&self, keyspace: &str, cql_type: &CqlType) -> Result<CqlType, SchemaError> {
+        let resolve = |cql_type: &CqlType| self.resolve_type(keyspace, cql_type).map(Box::new);
+        Ok(match cql_type {
+            CqlType::UserDefinedType(name) => {
+                let type_keyspace = name
+                    .normalized_keyspace()
+                    .unwrap_or_else(|| keyspace.to_owned());
+                let type_name = name.normalized_name();
+                self.keyspace_or_error(&type_keyspace)?
+                    .user_type(&type_name)
+                    .ok_or_else(|| SchemaError::UnknownType {
+                        keyspace: type_keyspace.clone(),
+                        name: type_name.clone(),
+                    })?;
+                CqlType::UserDefinedType(QualifiedName {
+                    keyspace: Some(type_keyspace.into()),
+                    name: type_name.into(),
+                    keyspace_quoted: true,
+                    name_quoted: true,
+                    keyspace_source: KeyspaceSource::Explicit,
+                })
+            }
+            CqlType::Collection(CollectionType::Map {
+                key_type,
+                value_type,
+            }) => CqlType::Collection(CollectionType::Map {
+                key_type: resolve(key_type)?,
+                value_type: resolve(value_type)?,
+            }),
+            CqlType::Collection(CollectionType::List(element_type)) => {
+                CqlType::Collection(CollectionType::List(resolve(element_type)?))
+            }
+            CqlType::Collection(CollectionType::Set(element_type)) => {
+                CqlType::Collection(CollectionType::Set(resolve(element_type)?))
+            }
+            CqlType::Tuple(types) => CqlType::Tuple(
+                types
+                    .iter()
+                    .map(|t| self.resolve_type(keyspace, t))
+                    .collect::<Result<_, _>>()?,
+            ),
+            CqlType::Frozen(inner) => CqlType::Frozen(resolve(inner)?),
+            CqlType::Native(_) | CqlType::Custom(_) => cql_type.clone(),
+        })
+    }
+
+    fn create_keyspace(&mut self, create: &CreateKeyspaceStatement) -> Result<(), SchemaError> {
+        let name = &create.keyspace_name;
+        if self.keyspaces.contains_key(name) {
+            if create.if_not_exists {
+                return Ok(());
+            }
+            return Err(SchemaError::DuplicateKeyspace {
+                keyspace: name.clone(),
+            });
+        }
+        let mut keyspace = Keyspace::new(name.clone());
+        merge_properties(&mut keyspace.properties, &create.attributes);
+        self.keyspaces.insert(name.clone(), keyspace);
+        Ok(())
+    }
+
+    fn alter_keyspace(&mut self, alter: &AlterKeyspaceStatement) -> Result<(), SchemaError> {
+        match self.keyspaces.get_mut(&alter.keyspace_name) {
+            Some(keyspace) => {
+                merge_properties(&mut keyspace.properties, &alter.attributes);
+                Ok(())
+            }
+            None if alter.if_exists => Ok(()),
+            None => Err(SchemaError::UnknownKeyspace {
+                keyspace: alter.keyspace_name.clone(),
+            }),
+        }
+    }
+
+    fn drop_keyspace(&mut self, keyspace: &str, if_exists: bool) -> Result<(), SchemaError> {
+        if self.keyspaces.remove(keyspace).is_none() && !if_exists {
+            return Err(SchemaError::UnknownKeyspace {
+                keyspace: keyspace.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn drop_table(&mut self, name: &QualifiedName, if_exists: bool) -> Result<(), SchemaError> {
+        let (keyspace, name) = match self.resolve_drop_name(name, if_exists)? {
+            Some(resolved) => resolved,
+            None => return Ok(()),
+        };
+        let removed = self
+            .keyspaces
+            .get_mut(&keyspace)
+            .and_then(|k| k.tables.remove(&name));
+        if removed.is_none() && !if_exists {
+            return Err(SchemaError::UnknownTable {
+                keyspace,
+                table: name,
+            });
+        }
+        Ok(())
+    }
+
+    fn drop_type(&mut self, name: &QualifiedName, if_exists: bool) -> Result<(), SchemaError> {
+        let (keyspace, name) = match self.resolve_drop_name(name, if_exists)? {
+            Some(resolved) => resolved,
+            None => return Ok(()),
+        };
+        let removed = self
+            .keyspaces
+            .get_mut(&keyspace)
+            .and_then(|k| k.types.remove(&name));
+        if removed.is_none() && !if_exists {
+            return Err(SchemaError::UnknownType { keyspace, name });
+        }
+        Ok(())
+    }
+
+    fn drop_index(&mut self, name: &QualifiedName, if_exists: bool) -> Result<(), SchemaError> {
+        let (keyspace, name) = match self.resolve_drop_name(name, if_exists)? {
+            Some(resolved) => resolved,
+            None => return Ok(()),
+        };
+        let table = self.keyspaces[&keyspace]
+            .tables()
+            .find(|table| table.indexes.iter().any(|index| index.name == name));
+        match table {
+            Some(table) => {
+                let mut table = table.clone();
+                table.indexes.retain(|index| index.name != name);
+                self.put_table(table);
+                Ok(())
+            }
+            None if if_exists => Ok(()),
+            None => Err(SchemaError::UnknownIndex {
+                keyspace,
+                index: name,
+            }),
+        }
+    }
+
+    // Resolves the name in DROP statement,
+    // or returns `None` if the keyspace does not exist and IF EXISTS is given
+    fn resolve_drop_name(
+        &self,
+        name: &QualifiedName,
+        if_exists: bool,
+    ) -> Result<Option<(String, String)>, SchemaError> {
+        match self.resolve_name(name) {
+            Ok(resolved) => Ok(Some(resolved)),
+            Err(SchemaError::UnknownKeyspace { .. }) if if_exists => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn create_table(&mut self, create: &CreateTableStatement) -> Result<(), SchemaError> {
+        let (keyspace, name) = self.resolve_name(&create.name)?;
+        if self.table(&keyspace, &name).is_some() {
+            if create.if_not_exists {
+                return Ok(());
+            }
+            return Err(SchemaError::DuplicateTable {
+                keyspace,
+                table: name,
+            });
+        }
+        let (partition_keys, clustering_columns) = create.primary_key().unwrap_or((&[], &[]));
+        let mut table = Table::new(keyspace, name);
+        table.partition_keys = partition_keys.iter().map(|k| k.to_string()).collect();
+        table.clustering_columns = clustering_columns.iter().map(|c| c.to_string()).collect();
+        table.set_clustering_order(&create.clustering_order);
+        merge_properties(&mut table.properties, &create.table_properties);
+        for definition in &create.column_definitions {
+            let column = Column {
+                name: definition.name.to_string(),
+                data_type: self.resolve_type(&table.keyspace, &definition.data_type)?,
+                kind: if definition.is_static {
+                    ColumnKind::Static
+                } else {
+                    ColumnKind::Regular
+                },
+            };
+            table.add_column(column)?;
+        }
+        table.assign_primary_key()?;
+        self.put_table(table);
+        Ok(())
+    }
+
+    fn create_materialized_view(
+        &mut self,
+        create: &CreateMaterializedViewStatement,
+    ) -> Result<(), SchemaError> {
+        let (keyspace, name) = self.resolve_name(&create.name)?;
+        if self.table(&keyspace, &name).is_some() {
+            if create.if_not_exists {
+                return Ok(());
+            }
+            return Err(SchemaError::DuplicateTable {
+                keyspace,
+                table: name,
+            });
+        }
+        let (base_keyspace, base_name) = self.resolve_name(&create.base_table)?;
+        let base = self.existing_table(&base_keyspace, &base_name)?;
+
+        let mut view = Table::new(keyspace, name);
+        view.partition_keys = create
+            .partition_keys
+            .iter()
+            .map(|k| k.to_string())
+            .collect();
+        view.clustering_columns = create
+            .clustering_columns
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        view.base_table = Some(base_name);
+        view.set_clustering_order(&create.clustering_order);
+        merge_properties(&mut view.properties, &create.view_properties);
+        // primary key columns first, then the selected columns in the order of the base table
+        let mut selected: Vec<String> = view
+            .partition_keys
+            .iter()
+            .chain(&view.clustering_columns)
+            .cloned()
+            .collect();
+        match &create.projection {
+            Projection::Wildcard => selected.extend(base.columns.iter().map(|c| c.name.clone())),
+            Projection::Selectors(selectors) => {
+                selected.extend(selectors.iter().filter_map(|s| match &s.selectable().kind {
+                    ExpressionKind::Identifier(column) => Some(column.to_string()),
+                    _ => None,
+                }))
+            }
+        }
+        for column in selected {
+            if view.column(&column).is_some() {
+                continue;
+            }
+            let data_type = base
+                .column(&column)
+                .ok_or_else(|| base.unknown_column(&column))?;
+            view.add_column(Column {
+                name: column,
+                data_type: data_type.clone(),
+                kind: ColumnKind::Regular,
+            })?;
+        }
+        view.assign_primary_key()?;
+        self.put_table(view);
+        Ok(())
+    }
+
+    fn create_type(&mut self, create: &CreateTypeStatement) -> Result<(), SchemaError> {
+        let (keyspace, name) = self.resolve_name(&create.name)?;
+        if self.keyspaces[&keyspace].types.contains_key(&name) {
+            if create.if_not_exists {
+                return Ok(());
+            }
+            return Err(SchemaError::DuplicateType { keyspace, name });
+        }
+        let mut user_type = UserType {
+            name: name.clone(),
+            fields: Vec::new(),
+        };
+        for (field, data_type) in &create.field_definitions {
+            if user_type.field(field).is_some() {
+                return Err(SchemaError::DuplicateField {
+                    keyspace,
+                    name,
+                    field: field.clone(),
+                });
+            }
+            let data_type = self.resolve_type(&keyspace, data_type)?;
+            user_type.fields.push((field.clone(), data_type));
+        }
+        if let Some(keyspace) = self.keyspaces.get_mut(&keyspace) {
+            keyspace.types.insert(name, user_type);
+        }
+        Ok(())
+    }
+
+    fn create_index(&mut self, create: &CreateIndexStatement) -> Result<(), SchemaError> {
+        let (keyspace, table_name) = self.resolve_name(&create.table_name)?;
+        let mut table = self.existing_table(&keyspace, &table_name)?;
+        for target in &create.index_targets {
+            if table.column(&target.column).is_none() {
+                return Err(table.unknown_column(&target.column));
+            }
+        }
+        let keyspace_model = &self.keyspaces[&keyspace];
+        let name = match &create.index_name {
+            Some(name) => {
+                if keyspace_model.index(name).is_some() {
+                    if create.if_not_exists {
+                        return Ok(());
+                    }
+                    return Err(SchemaError::DuplicateIndex {
+                        keyspace,
+                        index: name.clone(),
+                    });
+                }
+                name.clone()
+            }
+            None => {
+                let column = create
+                    .index_targets
+                    .first()
+                    .map_or("", |target| &target.column);
+                keyspace_model.default_index_name(&table_name, column)
+            }
+        };
+        table.indexes.push(Index {
+            name,
+            is_custom: create.is_custom,
+            targets: create
+                .index_targets
+                .iter()
+                .map(|target| (target.column.to_string(), target.index_type))
+                .collect(),
+        });
+        self.put_table(table);
+        Ok(())
+    }
+
+    fn alter_table(&mut self, alter: &AlterTableStatement) -> Result<(), SchemaError> {
+        let (keyspace, name) = self.resolve_name(&alter.name)?;
+        let mut table = match self.existing_table(&keyspace, &name) {
+            Ok(table) => table,
+            Err(_) if alter.if_exists => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        match &alter.operation {
+            AlterTableOperation::Add {
+                if_not_exists,
+                columns,
+            } => {
+                for definition in columns {
+                    if *if_not_exists && table.column(&definition.name).is_some() {
+                        continue;
+                    }
+                    table.add_column(Column {
+                        name: definition.name.to_string(),
+                        data_type: self.resolve_type(&keyspace, &definition.data_type)?,
+                        kind: if definition.is_static {
+                            ColumnKind::Static
+                        } else {
+                            ColumnKind::Regular
+                        },
+                    })?;
+                }
+            }
+            AlterTableOperation::Drop {
+                if_exists, columns, ..
+            } => {
+                for column in columns {
+                    match table.columns.iter().position(|c| c.name == **column) {
+                        Some(i) if table.columns[i].is_primary_key() => {
+                            return Err(SchemaError::PrimaryKeyColumn {
+                                keyspace,
+                                table: name,
+                                column: column.to_string(),
+                            });
+                        }
+                        Some(i) => {
+                            table.columns.remove(i);
+                        }
+                        None if *if_exists => {}
+                        None => return Err(table.unknown_column(column)),
+                    }
+                }
+            }
+            AlterTableOperation::Rename { if_exists, columns } => {
+                for (from, to) in columns {
+                    if table.column(from).is_none() {
+                        if *if_exists {
+                            continue;
+                        }
+                        return Err(table.unknown_column(from));
+                    }
+                    if table.column(to).is_some() {
+                        return Err(SchemaError::DuplicateColumn {
+                            keyspace,
+                            table: name,
+                            column: to.to_string(),
+                        });
+                    }
+                    table.rename_column(from, to);
+                }
+            }
+            AlterTableOperation::AlterType { column, new_type } => {
+                let data_type = self.resolve_type(&keyspace, new_type)?;
+                match table.columns.iter_mut().find(|c| c.name == **column) {
+                    Some(c) => c.data_type = data_type,
+                    None => return Err(table.unknown_column(column)),
+                }
+            }
+            AlterTableOperation::WithProperties(properties) => {
+                merge_properties(&mut table.properties, properties);
+            }
+            AlterTableOperation::DropCompactStorage => {}
+        }
+        self.put_table(table);
+        Ok(())
+    }
+
+    fn alter_type(&mut self, alter: &AlterTypeStatement) -> Result<(), SchemaError> {
+        let (keyspace, name) = self.resolve_name(&alter.name)?;
+        let mut user_type = match self.keyspaces[&keyspace].user_type(&name) {
+            Some(user_type) => user_type.clone(),
+            None if alter.if_exists => return Ok(()),
+            None => return Err(SchemaError::UnknownType { keyspace, name }),
+        };
+        match &alter.operation {
+            AlterTypeOperation::Add {
+                if_not_exists,
+                field,
+                data_type,
+            } => {
+                if user_type.field(field).is_some() {
+                    if *if_not_exists {
+                        return Ok(());
+                    }
+                    return Err(SchemaError::DuplicateField {
+                        keyspace,
+                        name,
+                        field: field.clone(),
+                    });
+                }
+                let data_type = self.resolve_type(&keyspace, data_type)?;
+                user_type.fields.push((field.clone(), data_type));
+            }
+            AlterTypeOperation::Rename { if_exists, fields } => {
+                for (from, to) in fields {
+                    if user_type.field(to).is_some() {
+                        return Err(SchemaError::DuplicateField {
+                            keyspace,
+                            name,
+                            field: to.clone(),
+                        });
+                    }
+                    match user_type.fields.iter_mut().find(|(field, _)| field == from) {
+                        Some((field, _)) => *field = to.clone(),
+                        None if *if_exists => {}
+                        None => {
+                            return Err(SchemaError::UnknownField {
+                                keyspace,
+                                name,
+                                field: from.clone(),
+                            })
+                        }
+                    }
+                }
+            }
+        }
+        if let Some(keyspace) = self.keyspaces.get_mut(&keyspace) {
+            keyspace.types.insert(name, user_type);
+        }
+        Ok(())
+    }
+}
+
+// Sets `properties` to the existing ones overridden by `new`, ordered by their names,
+// so that properties can be compared regardless of the order they are written
+fn merge_properties(properties: &mut Vec<Property>, new: &[Property]) {
+    for property in new {
+        let mut value = property.value().clone();
+        sort_map_entries(&mut value);
+        match properties.iter_mut().find(|p| p.key() == property.key()) {
+            Some(existing) => *existing.value_mut() = value,
+            None => properties.push(Property::new(property.key().to_owned(), value)),
+        }
+    }
+    properties.sort_by(|a, b| a.key().cmp(b.key()));
+}
+
+fn sort_map_entries(value: &mut PropertyValue) {
+    if let PropertyValue::Map(entries) = value {
+        for (_, value) in entries.iter_mut() {
+            sort_map_entries(value);
+        }
+        entries.sort_by_cached_key(|(key, _)| key.to_string());
+    }
+}
+
+/// Keyspace with its tables, materialized views and user defined types
+#[derive(Clone, Debug)]
+pub struct Keyspace {
+    name: String,
+    /// properties in WITH clause, such as `replication`
+    properties: Vec<Property>,
+    /// tables and materialized views, which share the same namespace
+    tables: BTreeMap<String, Table>,
+    types: BTreeMap<String, UserType>,
+}
+
+impl Keyspace {
+    fn new(name: String) -> Self {
+        Keyspace {
+            name,
+            properties: Vec::new(),
+            tables: BTreeMap::new(),
+            types: BTreeMap::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns properties ordered by their names, as merged by ALTER KEYSPACE
+    pub fn properties(&self) -> &[Property] {
+        &self.properties
+    }
+
+    /// Returns table or materialized view
+    pub fn table(&self, name: &str) -> Option<&Table> {
+        self.tables.get(name)
+    }
+
+    /// Returns tables and materialized views ordered by their names
+    pub fn tables(&self) -> impl Iterator<Item = &Table> {
+        self.tables.values()
+    }
+
+    pub fn user_type(&self, name: &str) -> Option<&UserType> {
+        self.types.get(name)
+    }
+
+    /// Returns user defined types ordered by their names
+    pub fn user_types(&self) -> impl Iterator<Item = &UserType> {
+        self.types.values()
+    }
+
+    /// Returns index of given name in any table of this keyspace
+    pub fn index(&self, name: &str) -> Option<&Index> {
+        self.tables()
+            .flat_map(|table| &table.indexes)
+            .find(|index| index.name == name)
+    }
+
+    // Index name Cassandra generates when it is not given: `table_column_idx`,
+    // followed by `_1`, `_2`, ... if the name is taken
+    fn default_index_name(&self, table: &str, column: &str) -> String {
+        let base: String = format!("{}_{}_idx", table, column)
+            .chars()
+            .filter(|c| c.is_alphanumeric() || *c == '_')
+            .collect();
+        let mut name = base.clone();
+        let mut i = 0;
+        while self.index(&name).is_some() {
+            i += 1;
+            name = format!("{}_{}", base, i);
+        }
+        name
+    }
+}
+
+/// Table or materialized view
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table {
+    keyspace: String,
+    name: String,
+    /// columns in the order of definition
+    columns: Vec<Column>,
+    partition_keys: Vec<String>,
+    clustering_columns: Vec<String>,
+    /// clustering columns in descending order
+    descending_columns: Vec<String>,
+    /// properties in WITH clause, ordered by their names
+    properties: Vec<Property>,
+    indexes: Vec<Index>,
+    /// base table if this is a materialized view
+    base_table: Option<String>,
+}
+
+impl Table {
+    fn new(keyspace: String, name: String) -> Self {
+        Table {
+            keyspace,
+            name,
+            columns: Vec::new(),
+            partition_keys: Vec::new(),
+            clustering_columns: Vec::new(),
+            descending_columns: Vec::new(),
+            properties: Vec::new(),
+            indexes: Vec::new(),
+            base_table: None,
+        }
+    }
+
+    pub fn keyspace(&self) -> &str {
+        &self.keyspace
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the type of given column, with user defined types qualified by their keyspaces
+    pub fn column(&self, name: &str) -> Option<&CqlType> {
+        self.columns
+            .iter()
+            .find(|column| column.name == name)
+            .map(|column| &column.data_type)
+    }
+
+    /// Returns columns in the order of definition,
+    /// and columns added by ALTER TABLE at the end
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    pub fn partition_keys(&self) -> &[String] {
+        &self.partition_keys
+    }
+
+    pub fn clustering_columns(&self) -> &[String] {
+        &self.clustering_columns
+    }
+
+    /// Returns true if given clustering column is in descending order
+    pub fn is_descending(&self, column: &str) -> bool {
+        self.descending_columns.iter().any(|c| c == column)
+    }
+
+    /// Returns properties ordered by their names, as merged by ALTER TABLE,
+    /// without `CLUSTERING ORDER BY` and `COMPACT STORAGE`
+    pub fn properties(&self) -> &[Property] {
+        &self.properties
+    }
+
+    pub fn indexes(&self) -> &[Index] {
+        &self.indexes
+    }
+
+    /// Returns the base table in the same keyspace if this is a materialized view
+    pub fn base_table(&self) -> Option<&str> {
+        self.base_table.as_deref()
+    }
+
+    fn set_clustering_order(&mut self, clustering_order: &[(Identifier, bool)]) {
+        self.descending_columns = clustering_order
+            .iter()
+            .filter(|(_, asc)| !asc)
+            .map(|(column, _)| column.to_string())
+            .collect();
+    }
+
+    fn unknown_column(&self, column: &str) -> SchemaError {
+        SchemaError::UnknownColumn {
+            keyspace: self.keyspace.clone(),
+            table: self.name.clone(),
+            column: column.to_owned(),
+        }
+    }
+
+    fn add_column(&mut self, column: Column) -> Result<(), SchemaError> {
+        if self.column(&column.name).is_some() {
+            return Err(SchemaError::DuplicateColumn {
+                keyspace: self.keyspace.clone(),
+                table: self.name.clone(),
+                column: column.name,
+            });
+        }
+        self.columns.push(column);
+        Ok(())
+    }
+
+    // Sets the kinds of primary key columns, checking that they are defined
+    fn assign_primary_key(&mut self) -> Result<(), SchemaError> {
+        let keys = self
+            .partition_keys
+            .iter()
+            .map(|k| (k, ColumnKind::PartitionKey))
+            .chain(
+                self.clustering_columns
+                    .iter()
+                    .map(|c| (c, ColumnKind::Clustering)),
+            );
+        for (key, kind) in keys {
+            match self.columns.iter_mut().find(|column| column.name == *key) {
+                Some(column) => column.kind = kind,
+                None => {
+                    return Err(SchemaError::UnknownColumn {
+                        keyspace: self.keyspace.clone(),
+                        table: self.name.clone(),
+                        column: key.clone(),
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn rename_column(&mut self, from: &str, to: &str) {
+        let names = self
+            .columns
+            .iter_mut()
+            .map(|column| &mut column.name)
+            .chain(&mut self.partition_keys)
+            .chain(&mut self.clustering_columns)
+            .chain(&mut self.descending_columns)
+            .chain(
+                self.indexes
+                    .iter_mut()
+                    .flat_map(|index| index.targets.iter_mut().map(|(column, _)| column)),
+            );
+        for name in names.filter(|name| *name == from) {
+            *name = to.to_owned();
+        }
+    }
+}
+
+/// Column of a table
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+    pub name: String,
+    /// Type with user defined types qualified by their keyspaces
+    pub data_type: CqlType,
+    pub kind: ColumnKind,
+}
+
+impl Column {
+    pub fn is_primary_key(&self) -> bool {
+        matches!(self.kind, ColumnKind::PartitionKey | ColumnKind::Clustering)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColumnKind {
+    PartitionKey,
+    Clustering,
+    Regular,
+    Static,
+}
+
+/// User defined type
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserType {
+    pub name: String,
+    /// Fields in the order of definition
+    pub fields: Vec<(String, CqlType)>,
+}
+
+impl UserType {
+    /// Returns the type of given field
+    pub fn field(&self, name: &str) -> Option<&CqlType> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, data_type)| data_type)
+    }
+}
+
+/// Secondary index of a table
+#[derive(Clone, Debug, PartialEq)]
+pub struct Index {
+    /// Index name, generated as Cassandra does if it is not given
+    pub name: String,
+    pub is_custom: bool,
+    /// Indexed columns with the types of the index
+    pub targets: Vec<(String, IndexType)>,
+}
@@ -0,0 +1,70 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Splitting CQL scripts into statements without parsing them
+
+use crate::lexer::{Keyword, Lexer, LexerOptions, TokenType};
+
+/// Splits CQL into statements at `;`, using the lexer only
+///
+/// Statements are returned as slices of `cql` from their first token to their last token
+/// before `;`, so that comments around statements and the `;` are not included.
+/// Empty statements such as the ones between `;;` are skipped. `;` in string literals,
+/// quoted names and comments does not end a statement, neither does `;` inside
+/// `BEGIN BATCH ... APPLY BATCH`.
+///
+/// Since statements are not parsed, this also splits statements that `Parser` does not
+/// support yet. Input that cannot be tokenized, such as an unterminated string literal,
+/// is kept in the statement it starts in.
+///
+/// ```
+/// use cqlparser::split_statements;
+/// let cql = "-- setup\nUSE ks; INSERT INTO t (k, v) VALUES (1, 'a;b');;\nDROP TABLE t";
+/// assert_eq!(
+///     split_statements(cql),
+///     ["USE ks", "INSERT INTO t (k, v) VALUES (1, 'a;b')", "DROP TABLE t"]
+/// );
+/// ```
+pub fn split_statements(cql: &str) -> Vec<&str> {
+    let options = LexerOptions {
+        skip_trivia: true,
+        ..LexerOptions::default()
+    };
+    let batch_end = [
+        Some(TokenType::Keyword(Keyword::Apply)),
+        Some(TokenType::Keyword(Keyword::Batch)),
+    ];
+    let mut statements = Vec::new();
+    // offset of the first token of the current statement
+    let mut start = None;
+    // end of the last token
+    let mut end = 0;
+    let mut in_batch = false;
+    let mut previous = [None, None];
+    for (_, token) in Lexer::with_options(cql, options) {
+        if token.token_type == TokenType::SemiColon && (!in_batch || previous == batch_end) {
+            if let Some(start) = start.take() {
+                statements.push(&cql[start..end]);
+            }
+        } else if start.is_none() {
+            start = Some(token.offset);
+            in_batch = token.token_type == TokenType::Keyword(Keyword::Begin);
+        }
+        end = token.offset + token.length;
+        previous = [previous[1].take(), Some(token.token_type)];
+    }
+    // the last statement without `;`
+    if let Some(start) = start {
+        statements.push(&cql[start..end]);
+    }
+    statements
+}
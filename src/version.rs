@@ -0,0 +1,73 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::ParseError;
+
+/// Version of Apache Cassandra whose CQL grammar the parser follows
+///
+/// Versions are ordered, so that a feature can be checked against
+/// the version it was introduced or removed.
+#[cfg_attr(target_arch = "wasm32", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CqlVersion {
+    /// Cassandra 3.0
+    V3_0,
+    /// Cassandra 3.6, which added PER PARTITION LIMIT
+    V3_6,
+    /// Cassandra 4.0, which dropped COMPACT STORAGE
+    V4_0,
+    /// Cassandra 4.1, which added IF (NOT) EXISTS to ALTER operations
+    V4_1,
+    /// Cassandra 5.0, which added dynamic data masking
+    V5_0,
+}
+
+impl fmt::Display for CqlVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let version = match self {
+            CqlVersion::V3_0 => "3.0",
+            CqlVersion::V3_6 => "3.6",
+            CqlVersion::V4_0 => "4.0",
+            CqlVersion::V4_1 => "4.1",
+            CqlVersion::V5_0 => "5.0",
+        };
+        write!(f, "{}", version)
+    }
+}
+
+/// Parses a version written as in `Display`, such as `4.0`
+///
+/// ```
+/// use cqlparser::CqlVersion;
+/// assert_eq!("4.1".parse(), Ok(CqlVersion::V4_1));
+/// assert!("4.2".parse::<CqlVersion>().is_err());
+/// ```
+impl FromStr for CqlVersion {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "3.0" => Ok(CqlVersion::V3_0),
+            "3.6" => Ok(CqlVersion::V3_6),
+            "4.0" => Ok(CqlVersion::V4_0),
+            "4.1" => Ok(CqlVersion::V4_1),
+            "5.0" => Ok(CqlVersion::V5_0),
+            _ => Err(ParseError::with_message(format!(
+                "Unknown CQL version: {}",
+                s
+            ))),
+        }
+    }
+}
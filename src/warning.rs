@@ -0,0 +1,155 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use crate::ast::{CollectionType, CqlType, ExpressionKind, Relation, Selector, Span};
+
+/// Warning about CQL that parses, but is likely to be rejected by Cassandra
+/// or to cause problems
+///
+/// Warnings are returned by `Parser::parse_with_warnings`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseWarning {
+    /// Position of the offending part in the original CQL string
+    pub span: Span,
+    pub kind: WarningKind,
+    pub message: String,
+}
+
+impl ParseWarning {
+    pub fn new(span: Span, kind: WarningKind, message: String) -> Self {
+        ParseWarning {
+            span,
+            kind,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Kind of `ParseWarning`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// `counter` inside collections or tuples, such as `list<counter>`
+    CounterInCollection,
+    /// User defined type or collection inside a collection without `frozen`,
+    /// such as `list<address>` or `map<text, list<int>>`,
+    /// or user defined type in a user defined type without `frozen`
+    NonFrozenNestedType,
+    /// `ALLOW FILTERING` in SELECT
+    AllowFiltering,
+    /// `COMPACT STORAGE` in table options
+    CompactStorage,
+    /// `IN ()` without values
+    EmptyIn,
+    /// Conditional INSERT, UPDATE or DELETE in BATCH
+    ConditionalBatch,
+    /// Selector or relation not allowed in SELECT of CREATE MATERIALIZED VIEW,
+    /// such as functions, aliases, or `token(...)` relations
+    InvalidViewSelect,
+}
+
+// Checks the type of a column or a field of a user defined type,
+// and returns warnings as (kind, message) pairs
+pub(crate) fn check_type(data_type: &CqlType, is_field: bool) -> Vec<(WarningKind, String)> {
+    let mut warnings = Vec::new();
+    if !matches!(data_type, CqlType::Native(_)) && data_type.contains_counter() {
+        warnings.push((
+            WarningKind::CounterInCollection,
+            format!(
+                "Counters are not allowed inside collections or tuples: {}",
+                data_type
+            ),
+        ));
+    }
+    if is_field && matches!(data_type, CqlType::UserDefinedType(_)) {
+        warnings.push((
+            WarningKind::NonFrozenNestedType,
+            format!(
+                "A user type cannot contain non-frozen user types: {}",
+                data_type
+            ),
+        ));
+    }
+    check_non_frozen(data_type, &mut warnings);
+    warnings
+}
+
+// Checks selector in SELECT of materialized view, which can only select columns by name
+pub(crate) fn check_view_selector(selector: &Selector) -> Option<String> {
+    if selector.alias().is_some() {
+        Some(format!(
+            "Cannot use aliases when defining a materialized view: {}",
+            selector
+        ))
+    } else if !matches!(selector.selectable().kind, ExpressionKind::Identifier(_)) {
+        Some(format!(
+            "Can only select columns by name when defining a materialized view: {}",
+            selector
+        ))
+    } else {
+        None
+    }
+}
+
+// Checks relation in WHERE clause of materialized view,
+// which can only have single column relations such as `c IS NOT NULL`
+pub(crate) fn check_view_relation(relation: &Relation) -> Option<String> {
+    let what = match relation {
+        Relation::SingleColumn { .. } => return None,
+        Relation::Token { .. } => "token relations",
+        Relation::MultiColumn { .. } => "multi-column relations",
+        Relation::CustomIndex { .. } => "custom index expressions",
+    };
+    Some(format!(
+        "Cannot use {} when defining a materialized view: {}",
+        what, relation
+    ))
+}
+
+// Warns about non-frozen types inside collections that are not frozen.
+// Types inside `frozen<...>` and tuples are frozen implicitly.
+fn check_non_frozen(data_type: &CqlType, warnings: &mut Vec<(WarningKind, String)>) {
+    let collection = match data_type {
+        CqlType::Collection(collection) => collection,
+        _ => return,
+    };
+    let elements = match collection {
+        CollectionType::Map {
+            key_type,
+            value_type,
+        } => vec![key_type, value_type],
+        CollectionType::List(element) | CollectionType::Set(element) => vec![element],
+    };
+    for element in elements {
+        let what = match **element {
+            CqlType::UserDefinedType(_) => "user types",
+            CqlType::Collection(_) => "collections",
+            _ => continue,
+        };
+        warnings.push((
+            WarningKind::NonFrozenNestedType,
+            format!(
+                "Non-frozen {} are not allowed inside collections: {}",
+                what, data_type
+            ),
+        ));
+        check_non_frozen(element, warnings);
+    }
+}
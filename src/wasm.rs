@@ -0,0 +1,180 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bindings for JavaScript
+//!
+//! Errors are thrown as objects of `message`, `expected`, `found`, `offset`,
+//! `line` and `column`. See the `serde::Serialize` implementation of `ParseError`.
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::{CqlVersion, Lexer, ParseError, Parser, ParserOptions, TokenType};
+
+/// Options accepted by `parse_with_options`
+///
+/// Fields are `Option`s since missing properties are read as `undefined`.
+#[derive(Debug, Default, Deserialize)]
+struct Options {
+    /// Continue parsing after statements that fail to parse
+    lenient: Option<bool>,
+    /// Cassandra version to follow, such as `"4.0"`
+    version: Option<String>,
+    /// Return statements with their spans and comments
+    preserve_comments: Option<bool>,
+}
+
+/// Result of lenient parsing
+#[derive(Serialize)]
+struct LenientResult<T> {
+    statements: Vec<T>,
+    errors: Vec<ParseError>,
+}
+
+/// Token returned by `tokenize`
+#[derive(Serialize)]
+struct JsToken {
+    #[serde(rename = "type")]
+    token_type: &'static str,
+    /// Lowercased keyword, only for keywords
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    /// Offset from the beginning, in UTF-16 code units as JavaScript strings are indexed
+    start: usize,
+    /// Length in UTF-16 code units
+    length: usize,
+}
+
+// Category of the token for syntax highlighting
+fn token_category(token_type: &TokenType) -> &'static str {
+    match token_type {
+        TokenType::Keyword(_) => "keyword",
+        TokenType::StringLiteral => "string",
+        TokenType::Identifier => "identifier",
+        TokenType::QuotedName => "quoted_name",
+        TokenType::Integer
+        | TokenType::Float
+        | TokenType::Boolean
+        | TokenType::Duration
+        | TokenType::Hexnumber
+        | TokenType::UUID => "constant",
+        TokenType::Whitespace => "whitespace",
+        TokenType::Comment(_) => "comment",
+        TokenType::Equal
+        | TokenType::NotEqual
+        | TokenType::Gt
+        | TokenType::Gte
+        | TokenType::Lt
+        | TokenType::Lte
+        | TokenType::Plus
+        | TokenType::Minus
+        | TokenType::Asterisk
+        | TokenType::Slash
+        | TokenType::Percent => "operator",
+        TokenType::Qmark => "bind_marker",
+        TokenType::MalformedNumber | TokenType::Error(_) => "error",
+        _ => "punctuation",
+    }
+}
+
+/// Tokenizes CQL for syntax highlighting
+///
+/// Returns an array of `{ type, start, length }` objects covering the whole input,
+/// including whitespaces and comments. Keywords have `value` of the lowercased keyword.
+/// Malformed input does not throw but produces tokens of type `error`.
+#[wasm_bindgen]
+pub fn tokenize(s: &str) -> Result<JsValue, JsValue> {
+    let mut byte_offset = 0;
+    let mut utf16_offset = 0;
+    let tokens: Vec<JsToken> = Lexer::new(s)
+        .map(|(text, token)| {
+            // tokens cover the whole input, but do not rely on it
+            utf16_offset += s[byte_offset..token.offset].encode_utf16().count();
+            let start = utf16_offset;
+            let length = text.encode_utf16().count();
+            utf16_offset += length;
+            byte_offset = token.offset + token.length;
+            let value = match token.token_type {
+                TokenType::Keyword(_) => Some(text.to_ascii_lowercase()),
+                _ => None,
+            };
+            JsToken {
+                token_type: token_category(&token.token_type),
+                value,
+                start,
+                length,
+            }
+        })
+        .collect();
+    Ok(serde_wasm_bindgen::to_value(&tokens)?)
+}
+
+/// Parses CQL statements, throwing the error if any statement fails to parse
+#[wasm_bindgen]
+pub fn parse(s: &str) -> Result<JsValue, JsValue> {
+    match Parser::new(s).parse() {
+        Ok(stmts) => Ok(serde_wasm_bindgen::to_value(&stmts)?),
+        Err(e) => Err(serde_wasm_bindgen::to_value(&e)?),
+    }
+}
+
+/// Parses CQL statements with options
+///
+/// `opts` is an object of `lenient`, `version` and `preserve_comments`,
+/// or `undefined` for the defaults.
+///
+/// With `preserve_comments`, each statement is returned as an object of `node`, `span`,
+/// `leadingComments` and `trailingComment`.
+///
+/// In lenient mode, returns an object of `statements` that parsed and `errors`
+/// of the ones that did not, instead of throwing the first error.
+#[wasm_bindgen]
+pub fn parse_with_options(s: &str, opts: JsValue) -> Result<JsValue, JsValue> {
+    let options: Options = if opts.is_undefined() || opts.is_null() {
+        Options::default()
+    } else {
+        serde_wasm_bindgen::from_value(opts)?
+    };
+    let version = match options.version.as_deref().map(str::parse::<CqlVersion>) {
+        Some(Err(e)) => return Err(serde_wasm_bindgen::to_value(&e)?),
+        Some(Ok(version)) => Some(version),
+        None => None,
+    };
+    let lenient = options.lenient.unwrap_or(false);
+    let preserve_comments = options.preserve_comments.unwrap_or(false);
+    let parser_options = ParserOptions {
+        version,
+        preserve_comments,
+        ..ParserOptions::default()
+    };
+    let parser = Parser::with_options(s, parser_options);
+
+    let value = match (lenient, preserve_comments) {
+        (true, true) => {
+            let (statements, errors) = parser.parse_spanned_lenient();
+            serde_wasm_bindgen::to_value(&LenientResult { statements, errors })
+        }
+        (true, false) => {
+            let (statements, errors) = parser.parse_lenient();
+            serde_wasm_bindgen::to_value(&LenientResult { statements, errors })
+        }
+        (false, true) => match parser.parse_spanned() {
+            Ok(statements) => serde_wasm_bindgen::to_value(&statements),
+            Err(e) => return Err(serde_wasm_bindgen::to_value(&e)?),
+        },
+        (false, false) => match parser.parse() {
+            Ok(statements) => serde_wasm_bindgen::to_value(&statements),
+            Err(e) => return Err(serde_wasm_bindgen::to_value(&e)?),
+        },
+    };
+    Ok(value?)
+}
@@ -0,0 +1,185 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use cqlparser::analysis::{classify_select, PartitionKeyRestriction, QueryShape};
+use cqlparser::ast::CqlStatement;
+use cqlparser::schema::{Schema, SchemaError};
+use cqlparser::Parser;
+
+fn schema() -> Schema {
+    let statements = Parser::new(
+        "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy'};
+         USE ks;
+         CREATE TABLE readings (
+             sensor text, day date, at timestamp, seq int, value double, tags set<text>,
+             PRIMARY KEY ((sensor, day), at, seq)
+         );
+         CREATE INDEX readings_value ON readings (value);
+         CREATE INDEX readings_tags ON readings (values(tags));
+         CREATE INDEX readings_seq ON readings (seq);",
+    )
+    .parse()
+    .unwrap();
+    Schema::from_statements(&statements).unwrap()
+}
+
+fn classify(cql: &str) -> Result<QueryShape, SchemaError> {
+    match &Parser::new(cql).parse().unwrap()[0] {
+        CqlStatement::Select(select) => classify_select(select, &schema()),
+        statement => panic!("not SELECT: {:?}", statement),
+    }
+}
+
+fn shape(
+    partition_key: PartitionKeyRestriction,
+    clustering_prefix: &[&str],
+    indexes: &[&str],
+    needs_filtering: bool,
+) -> QueryShape {
+    QueryShape {
+        partition_key,
+        clustering_prefix: clustering_prefix.iter().map(|c| c.to_string()).collect(),
+        indexes: indexes.iter().map(|i| i.to_string()).collect(),
+        needs_filtering,
+    }
+}
+
+#[test]
+fn test_classify_partition_key() {
+    use PartitionKeyRestriction::*;
+    let test_cases = [
+        ("SELECT * FROM readings", shape(None, &[], &[], false)),
+        (
+            "SELECT * FROM readings WHERE sensor = 's1' AND day = '2024-01-01'",
+            shape(Full, &[], &[], false),
+        ),
+        (
+            "SELECT * FROM readings WHERE sensor IN ('s1', 's2') AND day = ?",
+            shape(Full, &[], &[], false),
+        ),
+        (
+            "SELECT * FROM readings WHERE token(sensor, day) = ?",
+            shape(Full, &[], &[], false),
+        ),
+        (
+            "SELECT * FROM readings WHERE token(sensor, day) > ? AND token(sensor, day) <= ?",
+            shape(TokenRange, &[], &[], false),
+        ),
+        (
+            "SELECT * FROM readings WHERE sensor = 's1'",
+            shape(Partial, &[], &[], true),
+        ),
+    ];
+    for (cql, expected) in test_cases.iter() {
+        assert_eq!(&classify(cql).unwrap(), expected, "{}", cql);
+    }
+    assert!(classify("SELECT * FROM readings").unwrap().is_full_scan());
+    assert!(
+        !classify("SELECT * FROM readings WHERE token(sensor, day) = 1")
+            .unwrap()
+            .is_full_scan()
+    );
+}
+
+#[test]
+fn test_classify_clustering_columns() {
+    use PartitionKeyRestriction::*;
+    let partition = "SELECT * FROM readings WHERE sensor = 's1' AND day = '2024-01-01'";
+    let test_cases = [
+        (" AND at = 0", shape(Full, &["at"], &[], false)),
+        (
+            " AND at = 0 AND seq > 1",
+            shape(Full, &["at", "seq"], &[], false),
+        ),
+        (" AND at > 0 AND at < 100", shape(Full, &["at"], &[], false)),
+        (
+            " AND (at, seq) > (0, 1)",
+            shape(Full, &["at", "seq"], &[], false),
+        ),
+        // range on `at` ends the prefix, and `seq` is served by its index
+        (
+            " AND at > 0 AND seq = 1",
+            shape(Full, &["at"], &["readings_seq"], false),
+        ),
+        // `at` is not restricted, so `seq` is not in the prefix
+        (" AND seq > 1", shape(Full, &[], &[], true)),
+    ];
+    for (relations, expected) in test_cases.iter() {
+        let cql = format!("{}{}", partition, relations);
+        assert_eq!(&classify(&cql).unwrap(), expected, "{}", cql);
+    }
+
+    // clustering columns without partitions need filtering
+    assert_eq!(
+        classify("SELECT * FROM readings WHERE at = 0").unwrap(),
+        shape(None, &["at"], &[], true)
+    );
+}
+
+#[test]
+fn test_classify_secondary_indexes() {
+    use PartitionKeyRestriction::*;
+    let test_cases = [
+        (
+            "SELECT * FROM readings WHERE value = 1.5",
+            shape(None, &[], &["readings_value"], false),
+        ),
+        (
+            "SELECT * FROM readings WHERE tags CONTAINS 'x'",
+            shape(None, &[], &["readings_tags"], false),
+        ),
+        // index is used for the first restriction, and the other is filtered
+        (
+            "SELECT * FROM readings WHERE value = 1.5 AND tags CONTAINS 'x'",
+            shape(None, &[], &["readings_value"], true),
+        ),
+        // index of `value` does not serve range
+        (
+            "SELECT * FROM readings WHERE value > 1.5",
+            shape(None, &[], &[], true),
+        ),
+        (
+            "SELECT * FROM readings WHERE sensor = 's1' AND day = ? AND value = 1.5",
+            shape(Full, &[], &["readings_value"], false),
+        ),
+        // partially restricted partition key is filtered
+        (
+            "SELECT * FROM readings WHERE sensor = 's1' AND value = 1.5",
+            shape(Partial, &[], &["readings_value"], true),
+        ),
+    ];
+    for (cql, expected) in test_cases.iter() {
+        assert_eq!(&classify(cql).unwrap(), expected, "{}", cql);
+    }
+    assert!(!classify("SELECT * FROM readings WHERE value = 1.5")
+        .unwrap()
+        .is_full_scan());
+}
+
+#[test]
+fn test_classify_unknown_names() {
+    assert_eq!(
+        classify("SELECT * FROM events"),
+        Err(SchemaError::UnknownTable {
+            keyspace: "ks".to_owned(),
+            table: "events".to_owned(),
+        })
+    );
+    assert_eq!(
+        classify("SELECT * FROM readings WHERE sensor = 's1' AND missing = 1"),
+        Err(SchemaError::UnknownColumn {
+            keyspace: "ks".to_owned(),
+            table: "readings".to_owned(),
+            column: "missing".to_owned(),
+        })
+    );
+}
@@ -0,0 +1,159 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parses every statement in the `.cql` files under `tests/corpus`.
+//!
+//! Statements that are known not to parse yet are listed by `file:line`
+//! in `tests/corpus/expected_failures.txt`. Run with `--nocapture` to see
+//! how many statements of the corpus parse.
+//!
+//! Statements that parse are also parsed into `cst::SyntaxTree`,
+//! which must write them back byte for byte.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use cqlparser::Parser;
+
+const MANIFEST: &str = "expected_failures.txt";
+
+/// A statement in a corpus file
+struct Statement<'a> {
+    /// Line of the first token of the statement
+    line: usize,
+    /// Column of the first token of the statement
+    column: usize,
+    text: &'a str,
+}
+
+/// Splits CQL into statements with their positions
+fn split_statements(cql: &str) -> Vec<Statement<'_>> {
+    cqlparser::split_statements(cql)
+        .into_iter()
+        .map(|text| {
+            // statements are slices of `cql`
+            let offset = text.as_ptr() as usize - cql.as_ptr() as usize;
+            let line_start = cql[..offset].rfind('\n').map_or(0, |i| i + 1);
+            Statement {
+                line: cql[..offset].matches('\n').count() + 1,
+                column: cql[line_start..offset].chars().count() + 1,
+                text,
+            }
+        })
+        .collect()
+}
+
+/// Reads `file:line` entries of the manifest, ignoring `#` comments and blank lines
+fn read_manifest(path: &Path) -> BTreeSet<String> {
+    fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("cannot read {}: {}", path.display(), e))
+        .lines()
+        .map(|line| line.split('#').next().unwrap().trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Paths of the `.cql` files in the corpus, in order
+fn corpus_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "cql"))
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+#[test]
+fn test_corpus() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let mut expected_failures = read_manifest(&dir.join(MANIFEST));
+    let files = corpus_files(&dir);
+
+    let mut total = 0;
+    let mut parsed = 0;
+    let mut problems = Vec::new();
+    for path in &files {
+        let file_name = path.file_name().unwrap().to_string_lossy();
+        let cql = fs::read_to_string(path).unwrap();
+        for statement in split_statements(&cql) {
+            total += 1;
+            let key = format!("{}:{}", file_name, statement.line);
+            let expected_to_fail = expected_failures.remove(&key);
+            match Parser::new(statement.text).parse() {
+                Ok(_) => {
+                    parsed += 1;
+                    if expected_to_fail {
+                        problems.push(format!("{}: parses now, remove it from {}", key, MANIFEST));
+                    }
+                }
+                Err(_) if expected_to_fail => {}
+                Err(e) => {
+                    // Positions of the error are relative to the statement
+                    let line = statement.line + e.line().unwrap_or(1) - 1;
+                    let column = match e.line() {
+                        Some(1) | None => statement.column + e.column().unwrap_or(1) - 1,
+                        Some(_) => e.column().unwrap_or(1),
+                    };
+                    problems.push(format!(
+                        "{}:{}:{}: {}",
+                        file_name,
+                        line,
+                        column,
+                        e.message()
+                    ));
+                }
+            }
+        }
+    }
+    for key in expected_failures {
+        problems.push(format!(
+            "{}: no statement starts here, remove it from {}",
+            key, MANIFEST
+        ));
+    }
+
+    println!("{}/{} statements parse", parsed, total);
+    assert!(problems.is_empty(), "\n{}\n", problems.join("\n"));
+}
+
+#[test]
+fn test_corpus_round_trip() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    for path in corpus_files(&dir) {
+        let cql = fs::read_to_string(&path).unwrap();
+        let statements = split_statements(&cql);
+        // each statement up to the next one, with its `;`, comments and whitespaces,
+        // and the text before the first statement
+        let mut starts: Vec<usize> = statements
+            .iter()
+            .map(|statement| statement.text.as_ptr() as usize - cql.as_ptr() as usize)
+            .collect();
+        starts.insert(0, 0);
+        starts.push(cql.len());
+        let mut parsed = String::new();
+        for chunk in starts.windows(2).map(|range| &cql[range[0]..range[1]]) {
+            let tree = match Parser::new(chunk).parse_syntax_tree() {
+                Ok(tree) => tree,
+                // listed in the manifest
+                Err(_) => continue,
+            };
+            assert_eq!(tree.unparse(), chunk, "{}", path.display());
+            parsed.push_str(chunk);
+        }
+        // statements that parse, in a single source
+        let tree = Parser::new(&parsed).parse_syntax_tree().unwrap();
+        assert_eq!(tree.unparse(), parsed, "{}", path.display());
+    }
+}
@@ -0,0 +1,99 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use cqlparser::ast::{CqlStatement, Projection};
+use cqlparser::cst::SyntaxToken;
+use cqlparser::{Parser, ParserOptions, TokenType};
+
+#[test]
+fn test_unparse_reproduces_source() {
+    let test_cases = [
+        "",
+        "  \n",
+        "SELECT * FROM t",
+        "\u{FEFF}SELECT * FROM t;\r\n",
+        "-- header\n\nselect a,b FROM t;;  ; /* between */ INSERT INTO t (k) VALUES ('a;b');\n-- end",
+        "CREATE TABLE ks.\"Tàble\" (\n\tk int PRIMARY KEY, -- key\n\tv text\n) WITH comment = $$x$$ ;",
+        "BEGIN BATCH\n  INSERT INTO t (k) VALUES (1);\n  DELETE FROM t WHERE k = 2;\nAPPLY BATCH",
+    ];
+    for cql in test_cases.iter() {
+        let tree = Parser::new(cql).parse_syntax_tree().unwrap();
+        assert_eq!(tree.unparse(), *cql);
+        // same with comments attached to the statements
+        let options = ParserOptions {
+            preserve_comments: true,
+            ..ParserOptions::default()
+        };
+        let tree = Parser::with_options(cql, options)
+            .parse_syntax_tree()
+            .unwrap();
+        assert_eq!(tree.unparse(), *cql);
+    }
+    assert!(Parser::new("SELECT * FROM").parse_syntax_tree().is_err());
+}
+
+#[test]
+fn test_tokens_are_attached_to_statements() {
+    let cql = "-- first\nSELECT a FROM t; ;\nUPDATE t SET a = 1 WHERE k = 0 ; -- done\n";
+    let tree = Parser::new(cql).parse_syntax_tree().unwrap();
+    let texts =
+        |tokens: &[SyntaxToken]| -> String { tokens.iter().map(|token| &*token.text).collect() };
+    assert_eq!(tree.statements.len(), 2);
+    assert_eq!(texts(&tree.statements[0].leading_tokens), "-- first\n");
+    assert_eq!(tree.statements[0].unparse(), "SELECT a FROM t;");
+    assert_eq!(texts(&tree.statements[1].leading_tokens), " ;\n");
+    assert_eq!(
+        tree.statements[1].unparse(),
+        "UPDATE t SET a = 1 WHERE k = 0 ;"
+    );
+    assert_eq!(texts(&tree.trailing_tokens), " -- done\n");
+    assert!(tree.trailing_tokens.iter().all(|token| token.is_trivia()));
+    assert_eq!(
+        tree.tokens()
+            .map(|token| token.span().start)
+            .collect::<Vec<_>>(),
+        cqlparser::Lexer::new(cql)
+            .map(|(_, token)| token.offset)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_edit_tokens_of_nodes() {
+    let cql = "select  a AS x,b\nfrom t /* keep */ where k=1";
+    let mut tree = Parser::new(cql).parse_syntax_tree().unwrap();
+    let span = match &tree.statements[0].statement.node {
+        CqlStatement::Select(select) => match &select.projection {
+            Projection::Selectors(selectors) => selectors[0].span(),
+            projection => panic!("unexpected projection: {:?}", projection),
+        },
+        statement => panic!("unexpected statement: {:?}", statement),
+    };
+    let selector: Vec<_> = tree.statements[0]
+        .tokens_in(span)
+        .map(|token| token.text.to_string())
+        .collect();
+    assert_eq!(selector, ["a", " ", "AS", " ", "x"]);
+
+    // rename the alias, and lowercase the keywords
+    for token in &mut tree.statements[0].tokens {
+        if token.text == "x" {
+            token.text = "alias".into();
+        } else if token.token.token_type == TokenType::Keyword(cqlparser::Keyword::As) {
+            token.text = token.text.to_lowercase().into();
+        }
+    }
+    assert_eq!(
+        tree.unparse(),
+        "select  a as alias,b\nfrom t /* keep */ where k=1"
+    );
+}
@@ -0,0 +1,139 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Comparison of statements ignoring how they are written
+
+use cqlparser::ast::CqlStatement;
+use cqlparser::{Parser, ParserOptions};
+
+fn parse(cql: &str) -> CqlStatement {
+    let options = ParserOptions {
+        resolve_use_keyspace: true,
+        ..ParserOptions::default()
+    };
+    Parser::with_options(cql, options)
+        .parse()
+        .unwrap_or_else(|e| panic!("failed to parse {}: {}", cql, e))
+        .pop()
+        .unwrap()
+}
+
+#[test]
+fn test_normalized_eq() {
+    let test_cases = [
+        ("select * from T", "SELECT  *  FROM t;"),
+        (
+            "SELECT a, b FROM ks.t WHERE k = 1 -- comment",
+            "select A,B from \"ks\".\"t\" where k = ((1))",
+        ),
+        ("USE ks; SELECT * FROM t", "SELECT * FROM ks.t"),
+        (
+            "UPDATE t USING TTL 10 AND TIMESTAMP 1 SET v = 1 WHERE k = 1",
+            "UPDATE t USING TIMESTAMP 1 AND TTL 10 SET v = 1 WHERE k = 1",
+        ),
+        // properties in any order
+        (
+            "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy', 'replication_factor': 1}
+                 AND durable_writes = true",
+            "CREATE KEYSPACE ks WITH DURABLE_WRITES = true
+                 AND replication = {'replication_factor': 1, 'class': 'SimpleStrategy'}",
+        ),
+        (
+            "ALTER KEYSPACE ks WITH durable_writes = false AND replication = {'class': 'NetworkTopologyStrategy', 'dc1': 3, 'dc2': 2}",
+            "ALTER KEYSPACE ks WITH replication = {'class': 'NetworkTopologyStrategy', 'dc2': 2, 'dc1': 3} AND durable_writes = false",
+        ),
+        (
+            "CREATE TABLE t (k int PRIMARY KEY, v text) WITH comment = 'c' AND gc_grace_seconds = 10",
+            "CREATE TABLE t (k int, v text, PRIMARY KEY (k)) WITH gc_grace_seconds = 10 AND comment = 'c'",
+        ),
+        (
+            "ALTER TABLE t WITH comment = 'c' AND compaction = {'class': 'LeveledCompactionStrategy', 'sstable_size_in_mb': 160}",
+            "ALTER TABLE t WITH compaction = {'sstable_size_in_mb': 160, 'class': 'LeveledCompactionStrategy'} AND comment = 'c'",
+        ),
+        (
+            "CREATE MATERIALIZED VIEW v AS SELECT * FROM t WHERE k IS NOT NULL AND c IS NOT NULL
+                 PRIMARY KEY (c, k) WITH comment = 'c' AND CLUSTERING ORDER BY (k DESC)",
+            "CREATE MATERIALIZED VIEW v AS SELECT * FROM t WHERE k IS NOT NULL AND c IS NOT NULL
+                 PRIMARY KEY (c, k) WITH CLUSTERING ORDER BY (k DESC) AND comment = 'c'",
+        ),
+        // columns in any order with their values
+        (
+            "INSERT INTO t (k, a, b) VALUES (1, 'x', [1, 2])",
+            "INSERT INTO t (b, k, a) VALUES ([1, 2], 1, 'x')",
+        ),
+        (
+            "INSERT INTO t (k, v) VALUES (:k, :v) USING TTL 1",
+            "INSERT INTO t (v, k) VALUES (:v, :k) USING TTL 1",
+        ),
+        (
+            "INSERT INTO t (k, v) VALUES (?, ?)",
+            "insert into t (k, v) values (?, ?)",
+        ),
+        (
+            "CREATE INDEX i ON t (\"v\")",
+            "CREATE INDEX i ON t (v)",
+        ),
+    ];
+    for (a, b) in test_cases.iter() {
+        assert!(parse(a).normalized_eq(&parse(b)), "{}\n{}", a, b);
+        assert!(parse(b).normalized_eq(&parse(a)), "{}\n{}", b, a);
+    }
+}
+
+#[test]
+fn test_normalized_ne() {
+    let test_cases = [
+        ("SELECT * FROM t WHERE k = 1", "SELECT * FROM t WHERE k = 2"),
+        ("SELECT * FROM t WHERE k = 1", "SELECT * FROM t WHERE k = 1.0"),
+        ("SELECT * FROM t WHERE k = 'a'", "SELECT * FROM t WHERE k = 'A'"),
+        (
+            "SELECT * FROM t WHERE v = 1",
+            "SELECT * FROM t WHERE v = 1 ALLOW FILTERING",
+        ),
+        ("SELECT * FROM t", "SELECT * FROM \"T\""),
+        ("SELECT * FROM t", "SELECT * FROM ks.t"),
+        ("SELECT a, b FROM t", "SELECT b, a FROM t"),
+        (
+            "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy', 'replication_factor': 1}",
+            "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy', 'replication_factor': 2}",
+        ),
+        (
+            "CREATE TABLE t (k int PRIMARY KEY) WITH comment = 'c'",
+            "CREATE TABLE t (k int PRIMARY KEY) WITH comment = 'c' AND gc_grace_seconds = 10",
+        ),
+        (
+            "ALTER TABLE t WITH comment = 'a'",
+            "ALTER TABLE t WITH comment = 'b'",
+        ),
+        // values are not reordered with the columns
+        (
+            "INSERT INTO t (k, v) VALUES (1, 2)",
+            "INSERT INTO t (v, k) VALUES (1, 2)",
+        ),
+        // positional bind markers are bound in different order
+        (
+            "INSERT INTO t (k, v) VALUES (?, ?)",
+            "INSERT INTO t (v, k) VALUES (?, ?)",
+        ),
+        (
+            "INSERT INTO t (k, v) VALUES (1, 2)",
+            "INSERT INTO t (k, v) VALUES (1, 2) IF NOT EXISTS",
+        ),
+        ("CREATE INDEX i ON t (\"V\")", "CREATE INDEX i ON t (v)"),
+        ("CREATE INDEX i ON t (v)", "CREATE INDEX i ON t (keys(v))"),
+        ("SELECT * FROM t", "DELETE FROM t WHERE k = 1"),
+    ];
+    for (a, b) in test_cases.iter() {
+        assert!(!parse(a).normalized_eq(&parse(b)), "{}\n{}", a, b);
+        assert!(!parse(b).normalized_eq(&parse(a)), "{}\n{}", b, a);
+    }
+}
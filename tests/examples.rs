@@ -0,0 +1,149 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runs the examples with test input.
+//!
+//! `cargo test` builds the examples next to the test binaries.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+// Returns the command to run the example `name` from the root of the crate
+fn example(name: &str) -> Command {
+    // test binaries are in `target/<profile>/deps`, and examples in `target/<profile>/examples`
+    let mut path: PathBuf = std::env::current_exe().unwrap();
+    path.pop();
+    path.pop();
+    path.push("examples");
+    path.push(format!("{}{}", name, std::env::consts::EXE_SUFFIX));
+    let mut command = Command::new(path);
+    command.current_dir(env!("CARGO_MANIFEST_DIR"));
+    command
+}
+
+// Runs the CLI example with `input`, and returns its stdout without prompts
+fn run_cli(input: &str) -> String {
+    let mut child = example("cli")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .replace("cql> ", "")
+        .replace("...> ", "")
+}
+
+#[test]
+fn test_cli() {
+    let input = "\
+:format
+CREATE TABLE ks.t (
+    k int,
+    v text,
+    PRIMARY KEY (k)
+) WITH comment = 'x;y';
+-- comment only
+select k from ks.t where k = 1; select * from ks.t;
+begin batch insert into t (k) values (1);
+apply batch;
+:lint SELECT * FROM ks.t WHERE k = 1
+:tokens SELECT k FROM t
+:ast
+USE ks;
+SELECT * FROM t WHERE
+  k = ;
+:quit
+SELECT * FROM never_read;
+";
+    assert_eq!(
+        run_cli(input),
+        "\
+CREATE TABLE ks.t (
+    k int,
+    v text,
+    PRIMARY KEY (k)
+) WITH comment = 'x;y';
+SELECT k
+FROM ks.t
+WHERE k = 1;
+SELECT *
+FROM ks.t;
+BEGIN BATCH
+    INSERT INTO t (k)
+    VALUES (1);
+APPLY BATCH;
+SELECT * from ks.t, instead of listing the columns [select-star]
+1:1 0..6 Keyword(Select) \"SELECT\"
+1:8 7..8 Identifier \"k\"
+1:10 9..13 Keyword(From) \"FROM\"
+1:15 14..15 Identifier \"t\"
+Use(
+    \"ks\",
+)
+error: 2:7: expected expression, found ';'
+    k = ;
+        ^
+"
+    );
+}
+
+#[test]
+fn test_schema_directory() {
+    let output = example("schema")
+        .arg("tests/schema_files")
+        .output()
+        .unwrap();
+    // bad.cql has an error
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        r#"{
+  "files": [
+    {
+      "path": "tests/schema_files/bad.cql",
+      "statements": 1,
+      "errors": [{"line": 6, "column": 1, "message": "expected data type, found ')'"}],
+      "tables": ["music.users"]
+    },
+    {
+      "path": "tests/schema_files/good.cql",
+      "statements": 3,
+      "errors": [],
+      "tables": ["music.songs", "music.playlists"]
+    }
+  ],
+  "errors": 1
+}
+"#
+    );
+
+    let output = example("schema")
+        .arg("tests/schema_files/good.cql")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let output = example("schema")
+        .arg("tests/schema_files/bad.cql")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+}
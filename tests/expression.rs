@@ -0,0 +1,284 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Precedence and associativity of operators in expressions
+
+use cqlparser::ast::{Constant, CqlStatement, Expression, ExpressionKind, Operator};
+use cqlparser::Parser;
+
+// Writes `expression` with every operation parenthesized, to show the shape of the tree
+fn tree(expression: &Expression) -> String {
+    match &expression.kind {
+        ExpressionKind::UnaryOp(op) => format!("({}{})", op.operator(), tree(op.operand())),
+        ExpressionKind::BinaryOp(op) => format!(
+            "({} {} {})",
+            tree(op.left()),
+            op.operator(),
+            tree(op.right())
+        ),
+        ExpressionKind::Function { name, args } => format!(
+            "{}({})",
+            name,
+            args.iter().map(tree).collect::<Vec<_>>().join(", ")
+        ),
+        ExpressionKind::Cast(cql_type, expr) => format!("CAST({} AS {})", tree(expr), cql_type),
+        ExpressionKind::TypeCast(cql_type, expr) => format!("(({}) {})", cql_type, tree(expr)),
+        _ => expression.to_string(),
+    }
+}
+
+fn parse_tree(cql: &str) -> String {
+    let expression = Parser::parse_expression_only(cql)
+        .unwrap_or_else(|e| panic!("failed to parse {}: {}", cql, e));
+    tree(&expression)
+}
+
+#[test]
+fn test_same_precedence_is_left_associative() {
+    let test_cases = [
+        ("a - b - c", "((a - b) - c)"),
+        ("a + b - c + d", "(((a + b) - c) + d)"),
+        ("a / b * c", "((a / b) * c)"),
+        ("a % 2 * b / c", "(((a % 2) * b) / c)"),
+        (
+            "a = 1 AND b = 2 AND c = 3",
+            "(((a = 1) AND (b = 2)) AND (c = 3))",
+        ),
+    ];
+    for (cql, expected) in test_cases.iter() {
+        assert_eq!(parse_tree(cql), *expected, "{}", cql);
+    }
+}
+
+#[test]
+fn test_higher_precedence_binds_tighter() {
+    let test_cases = [
+        ("a + b * c", "(a + (b * c))"),
+        ("a * b + c", "((a * b) + c)"),
+        ("a - b % 2", "(a - (b % 2))"),
+        ("a + b * c - d / e", "((a + (b * c)) - (d / e))"),
+        ("a + 1 = b * 2", "((a + 1) = (b * 2))"),
+        ("a < b + 1 AND c >= d", "((a < (b + 1)) AND (c >= d))"),
+    ];
+    for (cql, expected) in test_cases.iter() {
+        assert_eq!(parse_tree(cql), *expected, "{}", cql);
+    }
+}
+
+#[test]
+fn test_unary_minus_binds_tighter_than_multiplication() {
+    let test_cases = [
+        ("-a * b", "((-a) * b)"),
+        ("a * -b", "(a * (-b))"),
+        ("-a + b", "((-a) + b)"),
+        ("- -a", "(-(-a))"),
+        // negative constants are not unary operations
+        ("-1 * a", "(-1 * a)"),
+        ("a - -1", "(a - -1)"),
+        // type cast binds as tight as unary minus
+        ("(int) a * b", "(((int) a) * b)"),
+    ];
+    for (cql, expected) in test_cases.iter() {
+        assert_eq!(parse_tree(cql), *expected, "{}", cql);
+    }
+}
+
+#[test]
+fn test_parentheses_override_precedence() {
+    let test_cases = [
+        ("(a + b) * c", "((a + b) * c)"),
+        ("a - (b - c)", "(a - (b - c))"),
+        ("a / (b * c)", "(a / (b * c))"),
+        ("-(a + b)", "(-(a + b))"),
+        ("((a))", "a"),
+        ("f(a + b) * c", "(f((a + b)) * c)"),
+    ];
+    for (cql, expected) in test_cases.iter() {
+        assert_eq!(parse_tree(cql), *expected, "{}", cql);
+    }
+}
+
+#[test]
+fn test_arithmetic_on_durations_is_rejected() {
+    // reported at the operator
+    let test_cases = [
+        ("a + 1h", "+", 2),
+        ("1h30m - a", "-", 6),
+        ("a / 2d", "/", 2),
+        ("P1D * 2", "*", 4),
+        ("now() - -3d", "-", 6),
+        ("(a + b) % 1mo", "%", 8),
+    ];
+    for (cql, operator, offset) in test_cases.iter() {
+        let error = Parser::parse_expression_only(cql).unwrap_err();
+        assert_eq!(
+            error.message(),
+            format!(
+                "Operator '{}' cannot be applied to duration constants",
+                operator
+            ),
+            "{}",
+            cql
+        );
+        assert_eq!(error.offset(), Some(*offset), "{}", cql);
+    }
+    let error = Parser::new("SELECT * FROM t WHERE d = 1h + 1m")
+        .parse()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "1:30: Operator '+' cannot be applied to duration constants"
+    );
+
+    // durations are fine as values and in relations
+    assert_eq!(parse_tree("a < 1h30m"), "(a < 1h30m)");
+    assert_eq!(parse_tree("-1d"), "-1d");
+    assert!(Parser::new("SELECT * FROM t WHERE d = 2h").parse().is_ok());
+    // `%` between a column and a number is modulus
+    assert_eq!(parse_tree("a%2"), "(a % 2)");
+}
+
+#[test]
+fn test_long_operator_chains_are_written() {
+    // left deep trees of 10000 operations or subscripts
+    let test_cases = [
+        vec!["a"; 10000].join(" + "),
+        vec!["a = 1"; 10000].join(" AND "),
+        format!("-{}", vec!["a * b"; 10000].join(" - ")),
+        format!("m{}", "[1]".repeat(10000)),
+        format!("-m{} + 1", "['k'][0..1]".repeat(4000)),
+    ];
+    for cql in test_cases.iter() {
+        let expression = Parser::parse_expression_only(cql).unwrap();
+        assert_eq!(expression.to_string(), *cql);
+        assert!(
+            expression.summary(3).len() < 50,
+            "{}",
+            expression.summary(3)
+        );
+        assert_eq!(expression.summary(usize::MAX), *cql);
+    }
+}
+
+#[test]
+fn test_summary_elides_deep_subexpressions() {
+    let test_cases = [
+        ("a + b * c", 0, "..."),
+        ("a + b * c", 1, "a + ..."),
+        ("a + b * c", 2, "a + b * c"),
+        ("a + b + c + d", 1, "... + d"),
+        ("a + b + c + d", 2, "... + c + d"),
+        ("(a + b) * c", 1, "... * c"),
+        ("-(a + b)", 1, "-..."),
+        ("f(g(x), 1)", 1, "f(..., 1)"),
+        ("[1, [2, 3]]", 1, "[1, ...]"),
+        ("{'k': (1, 2)}", 1, "{'k': ...}"),
+        ("m['k'][0]", 1, "...[0]"),
+        ("CAST(a + 1 AS text)", 1, "CAST(... AS text)"),
+        // identifiers and constants are never elided
+        ("a", 0, "a"),
+        ("'text'", 0, "'text'"),
+    ];
+    for (cql, max_depth, expected) in test_cases.iter() {
+        let expression = Parser::parse_expression_only(cql).unwrap();
+        assert_eq!(expression.summary(*max_depth), *expected, "{}", cql);
+    }
+}
+
+#[test]
+fn test_and_operands() {
+    let test_cases = [
+        ("a = 1", vec!["a = 1"]),
+        ("a = 1 AND b > 2", vec!["a = 1", "b > 2"]),
+        (
+            "a = 1 AND b > 2 AND c <= 3",
+            vec!["a = 1", "b > 2", "c <= 3"],
+        ),
+        (
+            "a = 1 AND (b = 2 AND c = 3)",
+            vec!["a = 1", "b = 2", "c = 3"],
+        ),
+        (
+            "(a = 1 AND b = 2) AND (c = 3 AND d = 4)",
+            vec!["a = 1", "b = 2", "c = 3", "d = 4"],
+        ),
+        // operands of other operators are not flattened
+        ("f(a AND b) AND c", vec!["f(a AND b)", "c"]),
+        ("a + 1", vec!["a + 1"]),
+    ];
+    for (cql, expected) in test_cases.iter() {
+        let expression = Parser::parse_expression_only(cql).unwrap();
+        let operands: Vec<String> = expression
+            .and_operands()
+            .iter()
+            .map(|operand| operand.to_string())
+            .collect();
+        assert_eq!(operands, *expected, "{}", cql);
+    }
+
+    let cql = vec!["a = 1"; 10000].join(" AND ");
+    let expression = Parser::parse_expression_only(&cql).unwrap();
+    assert_eq!(expression.and_operands().len(), 10000);
+}
+
+#[test]
+fn test_expression_kind_helpers() {
+    // WHERE clause as an expression
+    let cql = "SELECT * FROM t WHERE k = 1 AND v CONTAINS 'x' AND c IN (1, 2) AND (a, b) > (1, 2)";
+    let expression = match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::Select(select)) => Expression::from(select.selection.unwrap()),
+        other => panic!("unexpected result: {:?}", other),
+    };
+    let relations: Vec<_> = expression
+        .and_operands()
+        .into_iter()
+        .map(|operand| {
+            let (left, operator, right) = operand.as_relation().unwrap();
+            (left.as_column(), operator, right.as_constant())
+        })
+        .collect();
+    assert_eq!(
+        relations,
+        [
+            (Some("k"), &Operator::Equal, Some(&Constant::Integer(1))),
+            (
+                Some("v"),
+                &Operator::Contains,
+                Some(&Constant::StringLiteral("x".to_owned()))
+            ),
+            (Some("c"), &Operator::In, None),
+            (None, &Operator::GreaterThan, None),
+        ]
+    );
+
+    // non-matching variants
+    let test_cases = [
+        "a", "1", "a + 1", "-a", "f(a)", "a AND b", "(int) a", "m['k']",
+    ];
+    for cql in test_cases.iter() {
+        let expression = Parser::parse_expression_only(cql).unwrap();
+        assert!(expression.as_relation().is_none(), "{}", cql);
+    }
+    let column = Parser::parse_expression_only("\"Col\"").unwrap();
+    assert_eq!(column.as_column(), Some("Col"));
+    assert_eq!(column.as_constant(), None);
+    let constant = Parser::parse_expression_only("'a'").unwrap();
+    assert_eq!(constant.as_column(), None);
+    assert_eq!(
+        constant.as_constant(),
+        Some(&Constant::StringLiteral("a".to_owned()))
+    );
+    assert_eq!(
+        Parser::parse_expression_only("[1]").unwrap().as_constant(),
+        None
+    );
+}
@@ -0,0 +1,158 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use cqlparser::{fingerprint, fingerprint_hash};
+
+#[test]
+fn test_fingerprint_same_shape() {
+    let cases: &[(&[&str], &str)] = &[
+        (
+            &[
+                "SELECT a, b FROM ks.t WHERE k = 1 AND c IN (1, 2, 3) LIMIT 10",
+                "select a,b from KS.T where k=2 and c in (4) limit 1",
+                "SELECT a, b -- columns\n FROM ks.t\n WHERE k = ? AND c IN (?, ?) LIMIT :n",
+                "SELECT a, b FROM ks.t WHERE k = :k AND c IN (:a, :b) LIMIT 5",
+            ],
+            "SELECT a, b FROM ks.t WHERE k = ? AND c IN (?) LIMIT ?",
+        ),
+        // multi-column IN of any length
+        (
+            &[
+                "SELECT * FROM t WHERE k = 1 AND (a, b) IN ((?, ?))",
+                "SELECT * FROM t WHERE k = 1 AND (a, b) IN ((?, ?), (?, ?))",
+                "SELECT * FROM t WHERE k = 1 AND (a, b) IN ((1, 'x'), (2, :b), (3, 'z'))",
+            ],
+            "SELECT * FROM t WHERE k = ? AND (a, b) IN ((?, ?))",
+        ),
+        (
+            &[
+                "INSERT INTO t (k, attrs) VALUES (1, {'x': 1}) USING TTL 10",
+                "insert into t (k, attrs) values (2, {'x': 1, 'y': 2}) using ttl 5",
+            ],
+            "INSERT INTO t (k, attrs) VALUES (?, {?: ?}) USING TTL ?",
+        ),
+        (
+            &[
+                "UPDATE t SET v = 'x', n = n + 1 WHERE k = 1 IF v = 'y'",
+                "UPDATE t SET v = 'z', n = n + 10 WHERE k = 2 IF v = 'w'",
+            ],
+            "UPDATE t SET v = ?, n = n + ? WHERE k = ? IF v = ?",
+        ),
+        // quoted names keep their case
+        (
+            &[
+                "SELECT \"Name\" FROM \"Users\" WHERE id = 1",
+                "SELECT \"Name\" FROM \"Users\" WHERE id = 2",
+            ],
+            "SELECT \"Name\" FROM \"Users\" WHERE id = ?",
+        ),
+        // multiple statements
+        (
+            &[
+                "USE ks; SELECT * FROM t WHERE k = 1;",
+                "use ks;select * from t where k = 2",
+            ],
+            "USE ks; SELECT * FROM t WHERE k = ?",
+        ),
+    ];
+    for (queries, expected) in cases {
+        for query in *queries {
+            assert_eq!(
+                &fingerprint(query).unwrap(),
+                expected,
+                "fingerprint of {}",
+                query
+            );
+        }
+        let hashes: Vec<u64> = queries
+            .iter()
+            .map(|q| fingerprint_hash(q).unwrap())
+            .collect();
+        assert!(
+            hashes.windows(2).all(|w| w[0] == w[1]),
+            "hashes of {:?}",
+            queries
+        );
+    }
+}
+
+#[test]
+fn test_fingerprint_different_shape() {
+    let cases = [
+        // column order
+        ("SELECT a, b FROM t", "SELECT b, a FROM t"),
+        // relation order
+        (
+            "SELECT * FROM t WHERE k = 1 AND c = 2",
+            "SELECT * FROM t WHERE c = 2 AND k = 1",
+        ),
+        // operators
+        ("SELECT * FROM t WHERE k = 1", "SELECT * FROM t WHERE k > 1"),
+        // IN with a list and with a single bind marker
+        (
+            "SELECT * FROM t WHERE k IN (1)",
+            "SELECT * FROM t WHERE k IN ?",
+        ),
+        // NULL is not a constant to be replaced
+        (
+            "UPDATE t SET v = 1 WHERE k = 1",
+            "UPDATE t SET v = NULL WHERE k = 1",
+        ),
+        // maps mixing bind markers and other expressions are not collapsed
+        (
+            "INSERT INTO t (k, m) VALUES (1, {1: 2})",
+            "INSERT INTO t (k, m) VALUES (1, {1: now()})",
+        ),
+        ("SELECT * FROM t", "SELECT * FROM ks.t"),
+        ("SELECT * FROM t", "SELECT * FROM \"T\""),
+    ];
+    for (a, b) in cases {
+        assert_ne!(
+            fingerprint(a).unwrap(),
+            fingerprint(b).unwrap(),
+            "{} and {}",
+            a,
+            b
+        );
+        assert_ne!(fingerprint_hash(a).unwrap(), fingerprint_hash(b).unwrap());
+    }
+    assert_eq!(
+        fingerprint("INSERT INTO t (k, m) VALUES (1, {1: now(), 2: 3})").unwrap(),
+        "INSERT INTO t (k, m) VALUES (?, {?: now(), ?: ?})"
+    );
+}
+
+#[test]
+fn test_fingerprint_errors() {
+    assert_eq!(fingerprint("").unwrap(), "");
+    let error = fingerprint("SELECT * FROM t WHERE").unwrap_err();
+    assert_eq!(error.offset(), Some(21));
+    assert!(fingerprint_hash("SELECT FROM t").is_err());
+}
+
+#[test]
+fn test_fingerprint_long_chains() {
+    // left deep trees of 10000 operations or subscripts
+    let test_cases = [
+        (
+            format!("SELECT * FROM t WHERE k = {}", vec!["1"; 10000].join(" + ")),
+            format!("SELECT * FROM t WHERE k = {}", vec!["?"; 10000].join(" + ")),
+        ),
+        (
+            format!("SELECT m{} FROM t", "[1]".repeat(10000)),
+            format!("SELECT m{} FROM t", "[?]".repeat(10000)),
+        ),
+    ];
+    for (cql, expected) in test_cases.iter() {
+        assert_eq!(fingerprint(cql).unwrap(), *expected);
+    }
+}
@@ -0,0 +1,131 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::path::Path;
+
+use cqlparser::{format, FormatOptions, Parser};
+
+// Formats `cql` and checks that formatting the output again does not change it
+fn format_twice(cql: &str, options: FormatOptions) -> String {
+    let formatted = format(cql, options.clone()).unwrap();
+    assert_eq!(
+        format(&formatted, options).unwrap(),
+        formatted,
+        "formatting is not idempotent for:\n{}",
+        cql
+    );
+    formatted
+}
+
+#[test]
+fn test_format_snapshot() {
+    let cql = include_str!("format/messy.cql");
+    let expected = include_str!("format/messy.expected.cql");
+    let formatted = format_twice(cql, FormatOptions::default());
+    assert_eq!(formatted, expected);
+    assert_eq!(
+        Parser::new(&formatted).parse().unwrap(),
+        Parser::new(cql).parse().unwrap()
+    );
+}
+
+#[test]
+fn test_format_options() {
+    let cql = "create table t (k int, c text, primary key (k, c)) with comment = 'x' \
+               and gc_grace_seconds = 0; \
+               select * from t where k = 1 and c > 'a' and c < 'z' allow filtering";
+    let options = FormatOptions {
+        uppercase_keywords: false,
+        indent_width: 2,
+        column_per_line: false,
+        max_line_width: 30,
+    };
+    assert_eq!(
+        format_twice(cql, options),
+        "create table t (k int, c text, primary key (k, c)) with comment = 'x'\n  \
+         and gc_grace_seconds = 0;\n\
+         \n\
+         select *\n\
+         from t\n\
+         where k = 1\n  \
+         and c > 'a'\n  \
+         and c < 'z'\n\
+         allow filtering;\n"
+    );
+
+    // names that are keywords are not lowercased as keywords
+    let options = FormatOptions {
+        uppercase_keywords: false,
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        format_twice("SELECT \"Key\", TTL(v) FROM \"Ttl\" WHERE key = 1", options),
+        "select \"Key\", ttl(v)\nfrom \"Ttl\"\nwhere key = 1;\n"
+    );
+}
+
+#[test]
+fn test_format_comments() {
+    let cql = "/* header */\n\n\
+               -- first\n\
+               SELECT * FROM t -- inside\n\
+               WHERE k = 1; // trailing\n\
+               -- last";
+    assert_eq!(
+        format_twice(cql, FormatOptions::default()),
+        "/* header */\n\
+         -- first\n\
+         -- inside\n\
+         SELECT *\n\
+         FROM t\n\
+         WHERE k = 1; // trailing\n\
+         \n\
+         -- last\n"
+    );
+
+    assert_eq!(format_twice("", FormatOptions::default()), "");
+    assert_eq!(
+        format_twice(" -- only a comment\n", FormatOptions::default()),
+        "-- only a comment\n"
+    );
+
+    let error = format("SELECT * FROM t; SELECT FROM t", FormatOptions::default()).unwrap_err();
+    assert_eq!(error.offset(), Some(24));
+}
+
+#[test]
+fn test_format_corpus() {
+    // every statement in the corpus that parses formats into the same statement
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let mut paths: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "cql"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let source = fs::read_to_string(&path).unwrap();
+        let (statements, _) = Parser::new(&source).parse_spanned_lenient();
+        for statement in statements {
+            let cql = &source[statement.span.range()];
+            let formatted = format_twice(cql, FormatOptions::default());
+            assert_eq!(
+                Parser::new(&formatted).parse().unwrap(),
+                [statement.node],
+                "{}: formatting changed the statement:\n{}",
+                path.display(),
+                formatted
+            );
+        }
+    }
+}
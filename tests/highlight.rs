@@ -0,0 +1,150 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use cqlparser::{highlight, SemanticTokenKind};
+
+use SemanticTokenKind::*;
+
+// Returns (token text, kind) pairs
+fn classify(cql: &str) -> Vec<(&str, SemanticTokenKind)> {
+    highlight(cql)
+        .into_iter()
+        .map(|token| (&cql[token.span.range()], token.kind))
+        .collect()
+}
+
+#[test]
+fn test_highlight_select() {
+    let cql = "SELECT ks.avg(price) AS \"Average\", writetime(v), key FROM shop.items \
+               WHERE k = :id AND t > 1.5 LIMIT ?; -- done";
+    assert_eq!(
+        classify(cql),
+        vec![
+            ("SELECT", Keyword),
+            ("ks", Keyspace),
+            (".", Punctuation),
+            ("avg", Function),
+            ("(", Punctuation),
+            ("price", Column),
+            (")", Punctuation),
+            ("AS", Keyword),
+            ("\"Average\"", Alias),
+            (",", Punctuation),
+            ("writetime", Function),
+            ("(", Punctuation),
+            ("v", Column),
+            (")", Punctuation),
+            (",", Punctuation),
+            // unreserved keyword used as a column name
+            ("key", Column),
+            ("FROM", Keyword),
+            ("shop", Keyspace),
+            (".", Punctuation),
+            ("items", Table),
+            ("WHERE", Keyword),
+            ("k", Column),
+            ("=", Operator),
+            (":", BindMarker),
+            ("id", BindMarker),
+            ("AND", Keyword),
+            ("t", Column),
+            (">", Operator),
+            ("1.5", Number),
+            ("LIMIT", Keyword),
+            ("?", BindMarker),
+            (";", Punctuation),
+            ("-- done", Comment),
+        ]
+    );
+}
+
+#[test]
+fn test_highlight_ddl() {
+    let cql = "CREATE TABLE t (k int PRIMARY KEY, v frozen<map<text, address>>) \
+               WITH comment = 'c'; ALTER TYPE address RENAME zip TO zip_code;";
+    assert_eq!(
+        classify(cql),
+        vec![
+            ("CREATE", Keyword),
+            ("TABLE", Keyword),
+            ("t", Table),
+            ("(", Punctuation),
+            ("k", Column),
+            ("int", Type),
+            ("PRIMARY", Keyword),
+            ("KEY", Keyword),
+            (",", Punctuation),
+            ("v", Column),
+            ("frozen", Type),
+            ("<", Punctuation),
+            ("map", Type),
+            ("<", Punctuation),
+            ("text", Type),
+            (",", Punctuation),
+            ("address", Type),
+            (">", Punctuation),
+            (">", Punctuation),
+            (")", Punctuation),
+            ("WITH", Keyword),
+            ("comment", Property),
+            ("=", Operator),
+            ("'c'", String),
+            (";", Punctuation),
+            ("ALTER", Keyword),
+            ("TYPE", Keyword),
+            ("address", Type),
+            ("RENAME", Keyword),
+            ("zip", Field),
+            ("TO", Keyword),
+            ("zip_code", Field),
+            (";", Punctuation),
+        ]
+    );
+}
+
+#[test]
+fn test_highlight_unparsable() {
+    // tokens parsed before the error keep their roles,
+    // and the rest are classified by their token types
+    let cql = "SELECT a FROM t WHERE 'unterminated; UPDATE t SET v = 0x01 WHERE k = 1";
+    assert_eq!(
+        classify(cql),
+        vec![
+            ("SELECT", Keyword),
+            ("a", Column),
+            ("FROM", Keyword),
+            ("t", Table),
+            ("WHERE", Keyword),
+            ("'unterminated; UPDATE t SET v = 0x01 WHERE k = 1", Error),
+        ]
+    );
+
+    // the statement following the broken one is classified by the parser
+    let cql = "SELECT FROM t x; UPDATE t SET v = 0x01 WHERE k = 1";
+    let tokens = classify(cql);
+    assert_eq!(tokens[2..4], [("t", Identifier), ("x", Identifier)]);
+    assert_eq!(
+        tokens[5..],
+        [
+            ("UPDATE", Keyword),
+            ("t", Table),
+            ("SET", Keyword),
+            ("v", Column),
+            ("=", Operator),
+            ("0x01", Blob),
+            ("WHERE", Keyword),
+            ("k", Column),
+            ("=", Operator),
+            ("1", Number),
+        ]
+    );
+}
@@ -0,0 +1,148 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Unreserved keywords used as names
+//!
+//! Cassandra adds keywords as unreserved keywords, which stay valid as names.
+//! Every unreserved keyword is tried as a column name, a table name and an alias,
+//! so that adding a keyword to the lexer does not break the statements using it as a name.
+
+use cqlparser::{format, FormatOptions, Keyword, Parser};
+
+// `{}` is replaced with the keyword
+const COLUMN_NAMES: &[&str] = &[
+    "CREATE TABLE t ({} int PRIMARY KEY, v int)",
+    "CREATE TABLE t (a int, {} int, PRIMARY KEY ({}, a)) WITH CLUSTERING ORDER BY (a DESC)",
+    "CREATE TABLE t (a int, {} int, PRIMARY KEY (a, {})) WITH CLUSTERING ORDER BY ({} DESC)",
+    "ALTER TABLE t ADD {} int",
+    "ALTER TABLE t DROP {}",
+    "ALTER TABLE t RENAME {} TO a",
+    "CREATE INDEX ON t ({})",
+    "CREATE INDEX i ON t (keys({}))",
+    "SELECT {} FROM t",
+    "SELECT a, {} FROM t",
+    "SELECT {}, a FROM t",
+    "SELECT JSON {} FROM t",
+    "SELECT f({}) FROM t",
+    "SELECT writetime({}) FROM t",
+    "SELECT {}[0] FROM t",
+    "SELECT * FROM t WHERE {} = 1",
+    "SELECT * FROM t WHERE a = 1 AND {} > 1",
+    "SELECT * FROM t WHERE {} IN (1, 2)",
+    "SELECT * FROM t WHERE {} CONTAINS 1",
+    "SELECT * FROM t WHERE ({}, a) > (1, 2)",
+    "SELECT * FROM t WHERE (a, {}) > (1, 2)",
+    "SELECT * FROM t WHERE token({}) > 1",
+    "SELECT * FROM t WHERE ({} = 1 AND a = 2)",
+    "SELECT * FROM t GROUP BY {}",
+    "INSERT INTO t ({}) VALUES (1)",
+    "INSERT INTO t (a, {}) VALUES (1, 2)",
+    "UPDATE t SET {} = 1 WHERE a = 1",
+    "UPDATE t SET {} = {} + 1 WHERE a = 1",
+    "UPDATE t SET {}['k'] = 1 WHERE a = 1",
+    "UPDATE t SET a = 1 WHERE {} = 1",
+    "UPDATE t SET a = 1 WHERE a = 1 IF {} = 1",
+    "DELETE {} FROM t WHERE a = 1",
+    "DELETE a, {} FROM t WHERE a = 1",
+    "DELETE FROM t WHERE {} = 1",
+    "CREATE TYPE u ({} int)",
+    "ALTER TYPE u ADD {} int",
+];
+
+const TABLE_NAMES: &[&str] = &[
+    "CREATE TABLE {} (a int PRIMARY KEY)",
+    "CREATE TABLE ks.{} (a int PRIMARY KEY)",
+    "ALTER TABLE {} ADD a int",
+    "DROP TABLE {}",
+    "CREATE INDEX ON {} (a)",
+    "SELECT * FROM {}",
+    "SELECT * FROM ks.{} WHERE a = 1",
+    "INSERT INTO {} (a) VALUES (1)",
+    "UPDATE {} SET a = 1 WHERE a = 1",
+    "DELETE FROM {} WHERE a = 1",
+    "BEGIN BATCH INSERT INTO {} (a) VALUES (1); APPLY BATCH",
+    "CREATE MATERIALIZED VIEW v AS SELECT * FROM {} WHERE a IS NOT NULL PRIMARY KEY (a)",
+];
+
+const ALIASES: &[&str] = &[
+    "SELECT a AS {} FROM t",
+    "SELECT a AS {}, b FROM t",
+    "SELECT f(a) AS {} FROM t",
+];
+
+fn unreserved_keywords() -> Vec<String> {
+    Keyword::iter()
+        .filter(|k| k.is_unreserved_keyword())
+        .map(|k| k.as_str().to_ascii_lowercase())
+        .collect()
+}
+
+// Parses every template with every unreserved keyword, and returns the failures
+fn failures(templates: &[&str]) -> Vec<String> {
+    let mut failures = Vec::new();
+    for keyword in unreserved_keywords() {
+        for template in templates {
+            let cql = template.replace("{}", &keyword);
+            let statements = match Parser::new(&cql).parse() {
+                Ok(statements) => statements,
+                Err(e) => {
+                    failures.push(format!("{}: {}", cql, e));
+                    continue;
+                }
+            };
+            // names are written back as they can be parsed
+            let formatted = match format(&cql, FormatOptions::default()) {
+                Ok(formatted) => formatted,
+                Err(e) => {
+                    failures.push(format!("{}: failed to format: {}", cql, e));
+                    continue;
+                }
+            };
+            match Parser::new(&formatted).parse() {
+                Ok(reparsed) if reparsed == statements => {}
+                Ok(_) => failures.push(format!("{}: reparsed differently: {}", cql, formatted)),
+                Err(e) => failures.push(format!("{}: failed to reparse {}: {}", cql, formatted, e)),
+            }
+        }
+    }
+    failures
+}
+
+#[test]
+fn test_unreserved_keywords_as_column_names() {
+    let failures = failures(COLUMN_NAMES);
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn test_unreserved_keywords_as_table_names() {
+    let failures = failures(TABLE_NAMES);
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn test_unreserved_keywords_as_aliases() {
+    let failures = failures(ALIASES);
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn test_reserved_keywords_are_not_names() {
+    // reserved keywords must be quoted
+    for keyword in Keyword::iter().filter(|k| k.is_reserved()) {
+        let name = keyword.as_str().to_ascii_lowercase();
+        let cql = format!("SELECT * FROM {} WHERE a = 1", name);
+        assert!(Parser::new(&cql).parse().is_err(), "{}", cql);
+        let cql = format!("SELECT * FROM \"{}\" WHERE \"{}\" = 1", name, name);
+        assert!(Parser::new(&cql).parse().is_ok(), "{}", cql);
+    }
+}
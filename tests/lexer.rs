@@ -10,7 +10,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use cqlparser::{Lexer, Token, TokenType};
+use cqlparser::{Keyword, LexErrorKind, Lexer, LexerOptions, Token, TokenType};
 
 macro_rules! test_tokenize {
     ($input:literal, $expected_token:expr) => {
@@ -18,7 +18,7 @@ macro_rules! test_tokenize {
         let mut lexer = Lexer::new(input);
         let (s, token) = lexer.next().unwrap();
         assert_eq!(s, input);
-        assert_eq!(token, Token::new($expected_token, 0, input.len()));
+        assert_eq!(token, Token::new($expected_token, 0, input.len(), 1, 1));
     };
 }
 
@@ -27,12 +27,43 @@ fn tokenize_string_literal() {
     test_tokenize!("'test'", TokenType::StringLiteral);
     test_tokenize!("'te''st'", TokenType::StringLiteral);
     // Unclosed string literal
-    test_tokenize!("'test", TokenType::Error);
+    test_tokenize!("'test", TokenType::Error(LexErrorKind::UnterminatedString));
     // PG style string literal
     test_tokenize!("$$It's a test$$", TokenType::StringLiteral);
     // Unclosed PG style string literal
-    test_tokenize!("$$It's a test$", TokenType::Error);
-    test_tokenize!("$$It's a test", TokenType::Error);
+    test_tokenize!(
+        "$$It's a test$",
+        TokenType::Error(LexErrorKind::UnterminatedString)
+    );
+    test_tokenize!(
+        "$$It's a test",
+        TokenType::Error(LexErrorKind::UnterminatedString)
+    );
+    // Lone '$'
+    test_tokenize!("$", TokenType::Error(LexErrorKind::InvalidCharacter));
+
+    let tokens: Vec<_> = Lexer::new("$x").collect();
+    assert_eq!(
+        tokens,
+        vec![
+            (
+                "$",
+                Token::new(TokenType::Error(LexErrorKind::InvalidCharacter), 0, 1, 1, 1)
+            ),
+            ("x", Token::new(TokenType::Identifier, 1, 1, 1, 2)),
+        ]
+    );
+    let tokens: Vec<_> = Lexer::new("$$a$$$").collect();
+    assert_eq!(
+        tokens,
+        vec![
+            ("$$a$$", Token::new(TokenType::StringLiteral, 0, 5, 1, 1)),
+            (
+                "$",
+                Token::new(TokenType::Error(LexErrorKind::InvalidCharacter), 5, 1, 1, 6)
+            ),
+        ]
+    );
 }
 
 #[test]
@@ -45,16 +76,89 @@ fn tokenize_ident() {
     // Escaped double quote
     test_tokenize!("\"escaped \"\" quotes \"\"\"", TokenType::QuotedName);
     // Unclosed quoted identifier
-    test_tokenize!("\"Quoted ident", TokenType::Error);
+    test_tokenize!(
+        "\"Quoted ident",
+        TokenType::Error(LexErrorKind::UnterminatedQuotedName)
+    );
     // Quoted identifier with multi byte unicode
     test_tokenize!("\"�\"", TokenType::QuotedName);
 
-    test_tokenize!("2cab", TokenType::Error);
+    test_tokenize!("2cab", TokenType::MalformedNumber);
 }
 
 #[test]
 fn tokenize_numbers() {
     test_tokenize!("0xDeadBeef", TokenType::Hexnumber);
+    test_tokenize!("123", TokenType::Integer);
+    test_tokenize!("1.5e10", TokenType::Float);
+    test_tokenize!("1e10", TokenType::Float);
+
+    // digits followed by identifier characters
+    test_tokenize!("99percentile", TokenType::MalformedNumber);
+    test_tokenize!("99PERCENTILE", TokenType::MalformedNumber);
+    test_tokenize!("123abc", TokenType::MalformedNumber);
+    test_tokenize!("1_000", TokenType::MalformedNumber);
+    test_tokenize!("0xfg", TokenType::MalformedNumber);
+    test_tokenize!("99.9PERCENTILE", TokenType::MalformedNumber);
+    test_tokenize!("1.5ms", TokenType::MalformedNumber);
+    // hexnumber without digits is an empty blob, as in Cassandra
+    test_tokenize!("0x", TokenType::Hexnumber);
+}
+
+#[test]
+fn tokenize_numbers_and_ranges() {
+    let test_cases: [(&str, &[(&str, TokenType)]); 5] = [
+        (
+            "100..200",
+            &[
+                ("100", TokenType::Integer),
+                ("..", TokenType::Range),
+                ("200", TokenType::Integer),
+            ],
+        ),
+        (
+            "1..2",
+            &[
+                ("1", TokenType::Integer),
+                ("..", TokenType::Range),
+                ("2", TokenType::Integer),
+            ],
+        ),
+        (
+            "1.5..",
+            &[("1.5", TokenType::Float), ("..", TokenType::Range)],
+        ),
+        ("1.5", &[("1.5", TokenType::Float)]),
+        ("1.", &[("1.", TokenType::Float)]),
+    ];
+    for (input, expected) in test_cases.iter() {
+        let tokens: Vec<_> = Lexer::new(input).map(|(s, t)| (s, t.token_type)).collect();
+        assert_eq!(&tokens, expected, "{}", input);
+    }
+}
+
+#[test]
+fn tokenize_identifiers_starting_like_literals() {
+    // identifiers and malformed numbers are scanned to the end
+    // after UUID, duration and number parsers reject
+    let tokens: Vec<_> = Lexer::new("deadbeef_1 PT1H_x\n1x.5.6 email;1e5x..").collect();
+    assert_eq!(
+        tokens,
+        vec![
+            ("deadbeef_1", Token::new(TokenType::Identifier, 0, 10, 1, 1)),
+            (" ", Token::new(TokenType::Whitespace, 10, 1, 1, 11)),
+            ("PT1H_x", Token::new(TokenType::Identifier, 11, 6, 1, 12)),
+            ("\n", Token::new(TokenType::Whitespace, 17, 1, 1, 18)),
+            ("1x.5", Token::new(TokenType::MalformedNumber, 18, 4, 2, 1)),
+            (".", Token::new(TokenType::Dot, 22, 1, 2, 5)),
+            ("6", Token::new(TokenType::Integer, 23, 1, 2, 6)),
+            (" ", Token::new(TokenType::Whitespace, 24, 1, 2, 7)),
+            ("email", Token::new(TokenType::Identifier, 25, 5, 2, 8)),
+            (";", Token::new(TokenType::SemiColon, 30, 1, 2, 13)),
+            ("1e5x", Token::new(TokenType::MalformedNumber, 31, 4, 2, 14)),
+            ("..", Token::new(TokenType::Range, 35, 2, 2, 18)),
+        ]
+    );
 }
 
 #[test]
@@ -62,8 +166,11 @@ fn tokenize_uuid() {
     test_tokenize!("cbad2f6e-3fba-a2b1-bd0a-bd31bb0d0b40", TokenType::UUID);
     test_tokenize!("CBAD2F6E-3FBA-A2B1-BD0A-BD31BB0D0B40", TokenType::UUID);
     test_tokenize!("99b914b5-1382-4d84-a4b4-f244f40b833c", TokenType::UUID);
-    test_tokenize!("cbad2f6e-3fba", TokenType::Error);
-    test_tokenize!("cbad2f6e-", TokenType::Error);
+    test_tokenize!(
+        "cbad2f6e-3fba",
+        TokenType::Error(LexErrorKind::InvalidLiteral)
+    );
+    test_tokenize!("cbad2f6e-", TokenType::Error(LexErrorKind::InvalidLiteral));
 }
 
 #[test]
@@ -84,11 +191,94 @@ fn tokenize_duration() {
     // P\d{4} should be identified as `Identifier`
     test_tokenize!("P2020", TokenType::Identifier);
     // though P\d{4}- should be identified as `Error`
-    test_tokenize!("P2020-", TokenType::Error);
+    test_tokenize!("P2020-", TokenType::Error(LexErrorKind::InvalidLiteral));
     // Identifier chars after proper duration is identified as `Identifier`
     test_tokenize!("P1W1", TokenType::Identifier);
     test_tokenize!("P1Y_", TokenType::Identifier);
     test_tokenize!("PT_1", TokenType::Identifier);
+
+    // units must be in descending order without repetition
+    test_tokenize!("1h30m45s", TokenType::Duration);
+    test_tokenize!("1mo2d", TokenType::Duration);
+    test_tokenize!("30m1h", TokenType::MalformedNumber);
+    test_tokenize!("3mo2mo2mo", TokenType::MalformedNumber);
+
+    // negative durations
+    test_tokenize!("-3d", TokenType::Duration);
+    test_tokenize!("-P1Y", TokenType::Duration);
+    test_tokenize!("-P0001-02-03T04:05:06", TokenType::Duration);
+    let options = LexerOptions {
+        skip_trivia: true,
+        emit_eof: false,
+    };
+    let tokens: Vec<_> = Lexer::with_options("-1 -Px -3x", options)
+        .map(|(s, t)| (s, t.token_type))
+        .collect();
+    assert_eq!(
+        tokens,
+        vec![
+            ("-", TokenType::Minus),
+            ("1", TokenType::Integer),
+            ("-", TokenType::Minus),
+            ("Px", TokenType::Identifier),
+            ("-", TokenType::Minus),
+            ("3x", TokenType::MalformedNumber),
+        ]
+    );
+}
+
+#[test]
+fn tokenize_operators() {
+    test_tokenize!("!=", TokenType::NotEqual);
+    test_tokenize!("%", TokenType::Percent);
+    test_tokenize!("!", TokenType::Error(LexErrorKind::InvalidCharacter));
+
+    let tokens: Vec<_> = Lexer::new("a!=1").map(|(s, t)| (s, t.token_type)).collect();
+    assert_eq!(
+        tokens,
+        vec![
+            ("a", TokenType::Identifier),
+            ("!=", TokenType::NotEqual),
+            ("1", TokenType::Integer),
+        ]
+    );
+    let tokens: Vec<_> = Lexer::new("!a").map(|(s, t)| (s, t.token_type)).collect();
+    assert_eq!(
+        tokens,
+        vec![
+            ("!", TokenType::Error(LexErrorKind::InvalidCharacter)),
+            ("a", TokenType::Identifier)
+        ]
+    );
+}
+
+#[test]
+fn tokenize_bind_markers() {
+    test_tokenize!("?", TokenType::Qmark);
+    test_tokenize!("&", TokenType::Ampersand);
+
+    let tokens: Vec<_> = Lexer::new("a=?&?").collect();
+    assert_eq!(
+        tokens,
+        vec![
+            ("a", Token::new(TokenType::Identifier, 0, 1, 1, 1)),
+            ("=", Token::new(TokenType::Equal, 1, 1, 1, 2)),
+            ("?", Token::new(TokenType::Qmark, 2, 1, 1, 3)),
+            ("&", Token::new(TokenType::Ampersand, 3, 1, 1, 4)),
+            ("?", Token::new(TokenType::Qmark, 4, 1, 1, 5)),
+        ]
+    );
+}
+
+#[test]
+fn tokenize_errors() {
+    test_tokenize!(
+        "/* unterminated",
+        TokenType::Error(LexErrorKind::UnterminatedComment)
+    );
+    test_tokenize!("#", TokenType::Error(LexErrorKind::InvalidCharacter));
+    test_tokenize!("1.5e", TokenType::Error(LexErrorKind::InvalidNumber));
+    test_tokenize!("12345678-", TokenType::Error(LexErrorKind::InvalidNumber));
 }
 
 #[test]
@@ -112,9 +302,117 @@ fn multiline_comment_test() {
     );
 }
 
+#[test]
+fn token_position() {
+    let cql = "CREATE TABLE ks.\"Tâble\" (\r\n    k int PRIMARY KEY,\n    v text\n) WITH comment = 'ünïcode\nline';\nSELECT";
+    let tokens: Vec<_> = Lexer::new(cql)
+        .filter(|(_, t)| !t.is_type(TokenType::Whitespace))
+        .map(|(s, t)| (s, t.line, t.column))
+        .collect();
+    assert_eq!(tokens[0], ("CREATE", 1, 1));
+    assert_eq!(tokens[4], ("\"Tâble\"", 1, 17));
+    assert_eq!(tokens[5], ("(", 1, 25));
+    assert_eq!(tokens[6], ("k", 2, 5));
+    assert_eq!(tokens[7], ("int", 2, 7));
+    assert_eq!(tokens[11], ("v", 3, 5));
+    assert_eq!(tokens[13], (")", 4, 1));
+    assert_eq!(tokens[17], ("'ünïcode\nline'", 4, 18));
+    assert_eq!(tokens[18], (";", 5, 6));
+    assert_eq!(tokens[19], ("SELECT", 6, 1));
+}
+
+#[test]
+fn lexer_options() {
+    let cql = "SELECT a /* comment */ FROM t; -- end";
+    let tokens = |options: LexerOptions| -> Vec<_> {
+        Lexer::with_options(cql, options)
+            .map(|(s, t)| (s, t.token_type))
+            .collect()
+    };
+    // default
+    let all = tokens(LexerOptions::default());
+    assert_eq!(all.len(), 12);
+    assert_eq!(all[11], ("-- end", TokenType::Comment(false)));
+
+    let expected = vec![
+        ("SELECT", TokenType::Keyword(Keyword::Select)),
+        ("a", TokenType::Identifier),
+        ("FROM", TokenType::Keyword(Keyword::From)),
+        ("t", TokenType::Identifier),
+        (";", TokenType::SemiColon),
+    ];
+    assert_eq!(
+        tokens(LexerOptions {
+            skip_trivia: true,
+            emit_eof: false,
+        }),
+        expected
+    );
+
+    let mut with_eof = expected.clone();
+    with_eof.push(("", TokenType::EOF));
+    assert_eq!(
+        tokens(LexerOptions {
+            skip_trivia: true,
+            emit_eof: true,
+        }),
+        with_eof
+    );
+
+    // EOF after trivia
+    let mut lexer = Lexer::with_options(
+        cql,
+        LexerOptions {
+            skip_trivia: false,
+            emit_eof: true,
+        },
+    );
+    let (s, eof) = lexer.by_ref().last().unwrap();
+    assert_eq!(s, "");
+    assert_eq!(eof, Token::new(TokenType::EOF, cql.len(), 0, 1, 38));
+    // EOF is produced only once
+    assert_eq!(lexer.next(), None);
+
+    // empty input
+    let tokens: Vec<_> = Lexer::with_options(
+        "",
+        LexerOptions {
+            skip_trivia: true,
+            emit_eof: true,
+        },
+    )
+    .collect();
+    assert_eq!(tokens, vec![("", Token::new(TokenType::EOF, 0, 0, 1, 1))]);
+}
+
+#[test]
+fn tokenize_all() {
+    let cql = "SELECT a, 'b' -- c\nFROM t WHERE x = 'unterminated";
+    let tokens = Lexer::new(cql).tokenize_all();
+    // tokens cover the whole input without gaps
+    let mut end = 0;
+    for token in &tokens {
+        assert_eq!(token.offset, end);
+        end += token.length;
+    }
+    assert_eq!(end, cql.len());
+    assert_eq!(tokens[5].token_type, TokenType::StringLiteral);
+    assert_eq!(tokens[7].token_type, TokenType::Comment(false));
+    assert_eq!(
+        tokens.last().unwrap().token_type,
+        TokenType::Error(LexErrorKind::UnterminatedString)
+    );
+
+    let options = LexerOptions {
+        skip_trivia: true,
+        emit_eof: false,
+    };
+    assert_eq!(Lexer::with_options(cql, options).tokenize_all().len(), 10);
+}
+
 #[test]
 fn create_table_test() {
-    let mut lexer = Lexer::new(
+    let lexer = Lexer::new(
         "
 CREATE TABLE IF NOT EXISTS app.users (
 user_id UUID,
@@ -126,7 +424,200 @@ AND value = true
 AND CLUSTERING ORDER BY (updated_at DESC);
     ",
     );
-    while let Some(t) = lexer.next() {
+    for t in lexer {
         println!("{:?}", t);
     }
 }
+
+#[test]
+fn keyword_to_string() {
+    let test_cases = [
+        (Keyword::Select, "SELECT"),
+        (Keyword::Materialized, "MATERIALIZED"),
+        (Keyword::Table, "TABLE"),
+        (Keyword::Tables, "TABLES"),
+    ];
+    for (keyword, expected) in test_cases.iter() {
+        assert_eq!(keyword.as_str(), *expected);
+        assert_eq!(keyword.to_string(), *expected);
+        assert_eq!(Keyword::from_string(expected).as_ref(), Some(keyword));
+    }
+    // aliases are written in their canonical form
+    assert_eq!(
+        Keyword::from_string("columnfamily").unwrap().as_str(),
+        "TABLE"
+    );
+}
+
+#[test]
+fn keyword_from_string() {
+    for keyword in Keyword::iter() {
+        let s = keyword.as_str();
+        assert_eq!(Keyword::from_string(s).as_ref(), Some(keyword), "{}", s);
+        assert_eq!(
+            Keyword::from_string(&s.to_lowercase()).as_ref(),
+            Some(keyword),
+            "{}",
+            s
+        );
+        assert_eq!(
+            Keyword::ALL.iter().filter(|k| *k == keyword).count(),
+            1,
+            "{}",
+            s
+        );
+    }
+    assert_eq!(Keyword::from_string("SeLeCt"), Some(Keyword::Select));
+    assert_eq!(
+        Keyword::from_string("ColumnFamilies"),
+        Some(Keyword::Tables)
+    );
+    for s in [
+        "",
+        "SELECTS",
+        "SELEC",
+        "events",
+        "COLUMNFAMILIESX",
+        "séLECT",
+    ] {
+        assert_eq!(Keyword::from_string(s), None, "{}", s);
+    }
+}
+
+#[test]
+fn token_type_to_string() {
+    let test_cases = [
+        (TokenType::Keyword(Keyword::From), "FROM", "FROM"),
+        (TokenType::RParen, ")", "')'"),
+        (TokenType::Range, "..", "'..'"),
+        (TokenType::Gte, ">=", "'>='"),
+        (TokenType::StringLiteral, "string literal", "string literal"),
+        (TokenType::QuotedName, "quoted name", "quoted name"),
+        (TokenType::EOF, "end of input", "end of input"),
+    ];
+    for (token_type, display, description) in test_cases.iter() {
+        assert_eq!(&token_type.to_string(), display);
+        assert_eq!(&token_type.describe(), description);
+    }
+}
+
+#[test]
+fn token_text() {
+    let cql = "INSERT INTO \"tàble\" (k) VALUES ('日本語'); -- コメント\nSELECT ünïcode";
+    let tokens: Vec<_> = Lexer::new(cql).collect();
+    for (s, token) in tokens.iter() {
+        assert_eq!(token.text(cql), *s);
+    }
+    assert_eq!(tokens[4].1.text(cql), "\"tàble\"");
+    // out of range of the source
+    assert_eq!(tokens[4].1.text("INSERT"), "");
+
+    // owned tokens outlive the input
+    let owned = {
+        let input = cql.to_owned();
+        Lexer::tokenize(&input)
+    };
+    assert_eq!(owned.len(), tokens.len());
+    for ((owned_text, owned_token), (s, token)) in owned.iter().zip(tokens.iter()) {
+        assert_eq!(owned_text, s);
+        assert_eq!(owned_token, token);
+    }
+}
+
+#[test]
+fn tokenize_byte_order_mark() {
+    let cql = "\u{FEFF}SELECT";
+    let tokens: Vec<_> = Lexer::new(cql).collect();
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].0, "SELECT");
+    // offset is in the original input, but column does not count the byte order mark
+    assert_eq!(
+        tokens[0].1,
+        Token::new(TokenType::Keyword(Keyword::Select), 3, 6, 1, 1)
+    );
+    assert_eq!(tokens[0].1.text(cql), "SELECT");
+
+    // byte order mark only at the beginning
+    let tokens: Vec<_> = Lexer::new("a\u{FEFF}").collect();
+    assert_eq!(
+        tokens[1].1.token_type,
+        TokenType::Error(LexErrorKind::NonAsciiCharacter)
+    );
+}
+
+#[test]
+fn tokenize_non_ascii() {
+    let tokens: Vec<_> = Lexer::new("café ñame_1 'ñ' \"ñ\"")
+        .map(|(s, t)| (s, t.token_type))
+        .filter(|(_, t)| *t != TokenType::Whitespace)
+        .collect();
+    let error = TokenType::Error(LexErrorKind::NonAsciiCharacter);
+    assert_eq!(
+        tokens,
+        [
+            ("caf", TokenType::Identifier),
+            ("é", error.clone()),
+            ("ñame_1", error),
+            ("'ñ'", TokenType::StringLiteral),
+            ("\"ñ\"", TokenType::QuotedName),
+        ]
+    );
+}
+
+#[test]
+fn tokenize_multibyte_characters_in_quotes() {
+    let test_cases = [
+        ("'日本語''s'", TokenType::StringLiteral),
+        ("$$🎉'$$", TokenType::StringLiteral),
+        // combining acute accent and zero width joiner
+        ("'e\u{301}'", TokenType::StringLiteral),
+        ("'👍\u{200d}🎉'", TokenType::StringLiteral),
+        ("\"é\"\"x\"", TokenType::QuotedName),
+        ("\"e\u{301}\"", TokenType::QuotedName),
+        // fullwidth quotes do not close
+        ("'a＇b'", TokenType::StringLiteral),
+        ("\"q＂\"", TokenType::QuotedName),
+        ("'ü＇", TokenType::Error(LexErrorKind::UnterminatedString)),
+        (
+            "\"ü＂",
+            TokenType::Error(LexErrorKind::UnterminatedQuotedName),
+        ),
+    ];
+    for (input, token_type) in test_cases.iter() {
+        let tokens: Vec<_> = Lexer::new(input).collect();
+        assert_eq!(
+            tokens,
+            [(*input, Token::new(token_type.clone(), 0, input.len(), 1, 1))],
+            "{}",
+            input
+        );
+    }
+}
+
+#[test]
+fn token_offsets_are_in_bytes() {
+    let cql = "SELECT \"é\", ＂a＂ FROM t WHERE k = 'ñ＇' AND \"e\u{301}\" = $$日本$$ -- 注\n;";
+    let tokens: Vec<_> = Lexer::new(cql).collect();
+    let mut end = 0;
+    for (s, token) in tokens.iter() {
+        // tokens cover the source without gaps
+        assert_eq!(token.offset, end, "{}", s);
+        assert_eq!(&cql[token.offset..token.offset + token.length], *s);
+        end = token.offset + token.length;
+    }
+    assert_eq!(end, cql.len());
+
+    let positions: Vec<_> = tokens
+        .iter()
+        .filter(|(_, t)| !t.is_type(TokenType::Whitespace))
+        .map(|(s, t)| (*s, t.offset, t.column))
+        .collect();
+    assert_eq!(positions[1], ("\"é\"", 7, 8));
+    assert_eq!(positions[2], (",", 11, 11));
+    // fullwidth quote is an invalid character up to the end of the word
+    assert_eq!(positions[3], ("＂a", 13, 13));
+    assert_eq!(positions[4], ("＂", 17, 15));
+    assert_eq!(positions[10], ("'ñ＇'", 38, 34));
+    assert_eq!(positions[12], ("\"e\u{301}\"", 50, 43));
+    assert_eq!(positions[14], ("$$日本$$", 58, 50));
+}
@@ -0,0 +1,114 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use cqlparser::ast::*;
+use cqlparser::lints::{Diagnostic, Lint, Linter, UnboundedSelect};
+use cqlparser::Parser;
+
+#[test]
+fn test_default_lints() {
+    let cql = "CREATE TABLE t (k int PRIMARY KEY, v int);
+CREATE TABLE u (k int PRIMARY KEY) WITH comment = 'users';
+SELECT * FROM t WHERE k = 1;
+SELECT k, v FROM t;
+SELECT k, v FROM t LIMIT 10";
+    let statements = Parser::new(cql).parse_spanned().unwrap();
+    let diagnostics = Linter::with_default_lints().lint(&statements);
+    let found: Vec<_> = diagnostics
+        .iter()
+        .map(|d| (d.lint.as_str(), d.message.as_str(), &cql[d.span.range()]))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (
+                "missing-comment",
+                "Table t has no comment",
+                "CREATE TABLE t (k int PRIMARY KEY, v int);"
+            ),
+            (
+                "select-star",
+                "SELECT * from t, instead of listing the columns",
+                "SELECT * FROM t WHERE k = 1;"
+            ),
+            (
+                "unbounded-select",
+                "SELECT from t without WHERE clause nor LIMIT reads the whole table",
+                "SELECT k, v FROM t;"
+            ),
+        ]
+    );
+    assert_eq!(
+        diagnostics[0].to_string(),
+        "Table t has no comment [missing-comment]"
+    );
+
+    let linter = Linter::with_default_lints().without("missing-comment");
+    assert_eq!(
+        linter.lint_names().collect::<Vec<_>>(),
+        ["select-star", "unbounded-select"]
+    );
+    assert!(linter.lint(&statements[..2]).is_empty());
+}
+
+// Partition key must not be a single timestamp column
+struct TimestampPartitionKey;
+
+impl Lint for TimestampPartitionKey {
+    fn name(&self) -> &str {
+        "timestamp-partition-key"
+    }
+
+    fn check(&self, statement: &CqlStatement) -> Vec<Diagnostic> {
+        let table = match statement {
+            CqlStatement::CreateTable(table) => table,
+            _ => return Vec::new(),
+        };
+        match table.primary_key() {
+            Some(([key], _)) => table
+                .column_definitions
+                .iter()
+                .filter(|c| &c.name == key)
+                .filter(|c| c.data_type == CqlType::Native(NativeDataType::Timestamp))
+                .map(|c| Diagnostic::new(format!("Partition key {} is a timestamp", c.name)))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[test]
+fn test_custom_lint() {
+    let linter = Linter::new()
+        .with(TimestampPartitionKey)
+        .with(UnboundedSelect);
+    let statements = Parser::new(
+        "CREATE TABLE events (ts timestamp PRIMARY KEY, v text);
+         CREATE TABLE logs (day date, ts timestamp, PRIMARY KEY (day, ts));
+         SELECT ts FROM events",
+    )
+    .parse()
+    .unwrap();
+    let diagnostics: Vec<_> = statements
+        .iter()
+        .flat_map(|s| linter.lint_statement(s))
+        .map(|d| d.to_string())
+        .collect();
+    assert_eq!(
+        diagnostics,
+        [
+            "Partition key ts is a timestamp [timestamp-partition-key]",
+            "SELECT from events without WHERE clause nor LIMIT reads the whole table \
+             [unbounded-select]",
+        ]
+    );
+}
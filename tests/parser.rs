@@ -11,7 +11,11 @@
 // limitations under the License.
 
 use cqlparser::ast::*;
-use cqlparser::Parser;
+use cqlparser::{
+    format_statement, CqlVersion, FormatOptions, OwnedParser, Parser, ParserOptions, WarningKind,
+};
+use std::sync::Arc;
+use std::thread;
 
 #[test]
 fn test_create() {
@@ -24,8 +28,9 @@ fn test_create() {
                     if_not_exists: false,
                     attributes: vec![Property::new(
                         String::from("prop"),
-                        Literal::Constant(Constant::Integer(2)),
+                        PropertyValue::Constant(Constant::Integer(2)),
                     )],
+                    span: Span::default(),
                 },
             )]),
         ),
@@ -40,14 +45,17 @@ fn test_create() {
                 name: QualifiedName::new(Some(String::from("ks")), String::from("test")),
                 if_not_exists: false,
                 column_definitions: vec![
-                    (String::from("key"), CqlType::Native(NativeDataType::Int)),
-                    (
+                    ColumnDefinition::new(
+                        String::from("key"),
+                        CqlType::Native(NativeDataType::Int),
+                    ),
+                    ColumnDefinition::new(
                         String::from("values"),
                         CqlType::Collection(CollectionType::Set(Box::new(CqlType::Native(
                             NativeDataType::Text,
                         )))),
                     ),
-                    (
+                    ColumnDefinition::new(
                         String::from("col1"),
                         CqlType::Frozen(Box::new(CqlType::Tuple(vec![
                             CqlType::Native(NativeDataType::Text),
@@ -55,15 +63,15 @@ fn test_create() {
                         ]))),
                     ),
                 ],
-                static_columns: vec![],
-                partition_keys: vec![vec![String::from("key")]],
+                partition_keys: vec![vec!["key".into()]],
                 clustering_columns: vec![],
                 compact_storage: false,
                 clustering_order: vec![],
                 table_properties: vec![Property::new(
                     String::from("prop"),
-                    Literal::Constant(Constant::Integer(2)),
+                    PropertyValue::Constant(Constant::Integer(2)),
                 )],
+                span: Span::default(),
             })]),
         ),
         (
@@ -86,56 +94,56 @@ fn test_create() {
                     ),
                     if_not_exists: false,
                     projection: Projection::Selectors(vec![
-                        Selector::new(Expression::Identifier(String::from("age")), None),
-                        Selector::new(Expression::Identifier(String::from("name")), None),
-                        Selector::new(Expression::Identifier(String::from("country")), None),
+                        Selector::new(ExpressionKind::Identifier("age".into()).into(), None),
+                        Selector::new(ExpressionKind::Identifier("name".into()).into(), None),
+                        Selector::new(ExpressionKind::Identifier("country".into()).into(), None),
                     ]),
-                    selection: Some(Expression::BinaryOp(BinaryOp::new(
-                        Box::new(Expression::BinaryOp(BinaryOp::new(
-                            Box::new(Expression::Identifier(String::from("age"))),
-                            Operator::IsNot,
-                            Box::new(Expression::Value(Literal::Null)),
-                        ))),
-                        Operator::And,
-                        Box::new(Expression::BinaryOp(BinaryOp::new(
-                            Box::new(Expression::Identifier(String::from("cid"))),
-                            Operator::IsNot,
-                            Box::new(Expression::Value(Literal::Null)),
-                        ))),
-                    ))),
-                    partition_keys: vec![String::from("age")],
-                    clustering_columns: vec![String::from("cid")],
+                    selection: Some(WhereClause::new(vec![
+                        Relation::SingleColumn {
+                            column: "age".into(),
+                            operator: Operator::IsNot,
+                            value: ExpressionKind::Value(Literal::Null).into(),
+                        },
+                        Relation::SingleColumn {
+                            column: "cid".into(),
+                            operator: Operator::IsNot,
+                            value: ExpressionKind::Value(Literal::Null).into(),
+                        },
+                    ])),
+                    partition_keys: vec!["age".into()],
+                    clustering_columns: vec!["cid".into()],
                     compact_storage: false,
                     clustering_order: Vec::new(),
                     view_properties: vec![
                         Property::new(
                             String::from("caching"),
-                            Literal::Map(vec![
+                            PropertyValue::Map(vec![
                                 (
-                                    Expression::Value(Literal::Constant(Constant::StringLiteral(
-                                        String::from("keys"),
+                                    PropertyValue::Constant(Constant::StringLiteral(String::from(
+                                        "keys",
                                     ))),
-                                    Expression::Value(Literal::Constant(Constant::StringLiteral(
-                                        String::from("ALL"),
+                                    PropertyValue::Constant(Constant::StringLiteral(String::from(
+                                        "ALL",
                                     ))),
                                 ),
                                 (
-                                    Expression::Value(Literal::Constant(Constant::StringLiteral(
-                                        String::from("rows_per_partition"),
+                                    PropertyValue::Constant(Constant::StringLiteral(String::from(
+                                        "rows_per_partition",
                                     ))),
-                                    Expression::Value(Literal::Constant(Constant::StringLiteral(
-                                        String::from("100"),
+                                    PropertyValue::Constant(Constant::StringLiteral(String::from(
+                                        "100",
                                     ))),
                                 ),
                             ]),
                         ),
                         Property::new(
                             String::from("comment"),
-                            Literal::Constant(Constant::StringLiteral(String::from(
+                            PropertyValue::Constant(Constant::StringLiteral(String::from(
                                 "Based on table cyclist",
                             ))),
                         ),
                     ],
+                    span: Span::default(),
                 },
             )]),
         ),
@@ -146,6 +154,71 @@ fn test_create() {
     }
 }
 
+#[test]
+fn test_create_keyspace() {
+    let replication = Property::new(
+        String::from("replication"),
+        PropertyValue::Map(vec![(
+            PropertyValue::Constant(Constant::StringLiteral(String::from("class"))),
+            PropertyValue::Constant(Constant::StringLiteral(String::from("SimpleStrategy"))),
+        )]),
+    );
+    let durable_writes = Property::new(
+        String::from("durable_writes"),
+        PropertyValue::Constant(Constant::Boolean(false)),
+    );
+    // properties can be in any order, and are kept in the order written
+    let test_cases = [
+        (
+            "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy'} AND durable_writes = false",
+            "ks",
+            false,
+            vec![replication.clone(), durable_writes.clone()],
+        ),
+        (
+            "CREATE KEYSPACE ks WITH durable_writes = false AND replication = {'class': 'SimpleStrategy'}",
+            "ks",
+            false,
+            vec![durable_writes, replication.clone()],
+        ),
+        (
+            "CREATE KEYSPACE IF NOT EXISTS \"MyKeyspace\" WITH replication = {'class': 'SimpleStrategy'}",
+            "MyKeyspace",
+            true,
+            vec![replication.clone()],
+        ),
+        (
+            "create keyspace if not exists MyKeyspace with replication = {'class': 'SimpleStrategy'}",
+            "mykeyspace",
+            true,
+            vec![replication],
+        ),
+    ];
+    for (cql, keyspace_name, if_not_exists, attributes) in test_cases {
+        assert_eq!(
+            Parser::new(cql).parse(),
+            Ok(vec![CqlStatement::CreateKeyspace(
+                CreateKeyspaceStatement {
+                    keyspace_name: String::from(keyspace_name),
+                    if_not_exists,
+                    attributes,
+                    span: Span::default(),
+                }
+            )]),
+            "{}",
+            cql
+        );
+    }
+
+    let error = Parser::new("CREATE KEYSPACE ks;").parse().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "1:19: expected WITH, found ';' (replication is required, \
+         such as WITH replication = {'class': 'SimpleStrategy', 'replication_factor': 1})"
+    );
+    assert_eq!(error.expected(), ["WITH"]);
+}
+
 #[test]
 fn test_select_statements() {
     let test_cases = [
@@ -155,11 +228,13 @@ fn test_select_statements() {
                 table_name: QualifiedName::new(Some(String::from("ks")), String::from("tbl")),
                 projection: Projection::Wildcard,
                 selection: None,
+                group_by: vec![],
                 is_json: false,
                 is_distinct: false,
                 per_partition_limit: None,
                 limit: None,
                 allow_filtering: false,
+                span: Span::default(),
             })]),
         ),
         (
@@ -167,16 +242,18 @@ fn test_select_statements() {
             Ok(vec![CqlStatement::Select(SelectStatement {
                 table_name: QualifiedName::new(Some(String::from("ks")), String::from("tbl")),
                 projection: Projection::Wildcard,
-                selection: Some(Expression::BinaryOp(BinaryOp::new(
-                    Box::new(Expression::Identifier(String::from("key"))),
-                    Operator::Equal,
-                    Box::new(Expression::Value(Literal::Constant(Constant::Integer(1)))),
-                ))),
+                selection: Some(WhereClause::new(vec![Relation::SingleColumn {
+                    column: "key".into(),
+                    operator: Operator::Equal,
+                    value: ExpressionKind::Value(Literal::Constant(Constant::Integer(1))).into(),
+                }])),
+                group_by: vec![],
                 is_json: false,
                 is_distinct: false,
                 per_partition_limit: None,
                 limit: None,
                 allow_filtering: false,
+                span: Span::default(),
             })]),
         ),
         (
@@ -184,18 +261,20 @@ fn test_select_statements() {
             Ok(vec![CqlStatement::Select(SelectStatement {
                 table_name: QualifiedName::new(None, String::from("tbl")),
                 projection: Projection::Selectors(vec![
-                    Selector::new(Expression::Identifier(String::from("col1")), None),
+                    Selector::new(ExpressionKind::Identifier("col1".into()).into(), None),
                     Selector::new(
-                        Expression::Identifier(String::from("col2")),
+                        ExpressionKind::Identifier("col2".into()).into(),
                         Some(String::from("col_A")),
                     ),
                 ]),
                 selection: None,
+                group_by: vec![],
                 is_json: false,
                 is_distinct: false,
                 per_partition_limit: None,
                 limit: Some(Literal::Constant(Constant::Integer(10))),
                 allow_filtering: true,
+                span: Span::default(),
             })]),
         ),
     ];
@@ -206,37 +285,3826 @@ fn test_select_statements() {
 }
 
 #[test]
-fn test_update_statements() {
-    let test_cases = [(
-        "UPDATE tbl SET col1 = 'text', col2 = 1 WHERE k = 1",
-        Ok(vec![CqlStatement::Update(UpdateStatement {
-            table: QualifiedName::new(None, String::from("tbl")),
-            assignments: vec![
-                Expression::BinaryOp(BinaryOp::new(
-                    Box::new(Expression::Identifier(String::from("col1"))),
-                    Operator::Equal,
-                    Box::new(Expression::Value(Literal::Constant(
-                        Constant::StringLiteral(String::from("text")),
-                    ))),
-                )),
-                Expression::BinaryOp(BinaryOp::new(
-                    Box::new(Expression::Identifier(String::from("col2"))),
-                    Operator::Equal,
-                    Box::new(Expression::Value(Literal::Constant(Constant::Integer(1)))),
-                )),
+fn test_select_json() {
+    let select = |cql: &str| match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::Select(select)) => select,
+        other => panic!("unexpected result: {:?}", other),
+    };
+    for cql in [
+        "SELECT JSON * FROM t",
+        "SELECT JSON a, b FROM t",
+        "SELECT JSON \"A\" FROM t",
+        "SELECT JSON max(a) AS m FROM t",
+    ] {
+        let select = select(cql);
+        assert!(select.is_json, "{}", cql);
+        assert_eq!(select.to_string(), cql);
+    }
+    // `json` is a column name unless a selector follows
+    let test_cases = [
+        ("SELECT JSON json FROM t", true),
+        ("SELECT json FROM t", false),
+        ("SELECT json AS j FROM t", false),
+        ("SELECT json, a FROM t", false),
+    ];
+    for (cql, is_json) in test_cases {
+        assert_eq!(select(cql).is_json, is_json, "{}", cql);
+    }
+}
+
+#[test]
+fn test_nested_selector_functions() {
+    fn function(name: &str, args: Vec<Expression>) -> Expression {
+        ExpressionKind::Function {
+            name: QualifiedName::new(None, name.to_owned()),
+            args,
+        }
+        .into()
+    }
+    fn column(name: &str) -> Expression {
+        ExpressionKind::Identifier(name.into()).into()
+    }
+
+    let test_cases = [
+        (
+            "SELECT toJson(writetime(v)) AS wt_json FROM t",
+            vec![Selector::new(
+                function("tojson", vec![function("writetime", vec![column("v")])]),
+                Some("wt_json".to_owned()),
+            )],
+            "SELECT tojson(writetime(v)) AS wt_json FROM t",
+        ),
+        (
+            "SELECT blobAsBigint(timeuuidAsBlob(id)) FROM t",
+            vec![Selector::new(
+                function(
+                    "blobasbigint",
+                    vec![function("timeuuidasblob", vec![column("id")])],
+                ),
+                None,
+            )],
+            "SELECT blobasbigint(timeuuidasblob(id)) FROM t",
+        ),
+        (
+            "SELECT toJson(ttl(v)), cast(writetime(v) AS text) FROM t",
+            vec![
+                Selector::new(
+                    function("tojson", vec![function("ttl", vec![column("v")])]),
+                    None,
+                ),
+                Selector::new(
+                    ExpressionKind::Cast(
+                        CqlType::Native(NativeDataType::Text),
+                        Box::new(function("writetime", vec![column("v")])),
+                    )
+                    .into(),
+                    None,
+                ),
             ],
-            selection: Expression::BinaryOp(BinaryOp::new(
-                Box::new(Expression::Identifier(String::from("k"))),
-                Operator::Equal,
-                Box::new(Expression::Value(Literal::Constant(Constant::Integer(1)))),
+            "SELECT tojson(ttl(v)), CAST(writetime(v) AS text) FROM t",
+        ),
+        (
+            "SELECT f(g(h(i(j(k))))) FROM t",
+            vec![Selector::new(
+                function(
+                    "f",
+                    vec![function(
+                        "g",
+                        vec![function(
+                            "h",
+                            vec![function("i", vec![function("j", vec![column("k")])])],
+                        )],
+                    )],
+                ),
+                None,
+            )],
+            "SELECT f(g(h(i(j(k))))) FROM t",
+        ),
+        (
+            "SELECT system.toJson(v), \"Ks\".f(x) FROM t",
+            vec![
+                Selector::new(
+                    ExpressionKind::Function {
+                        name: QualifiedName::new(Some("system".to_owned()), "tojson".to_owned()),
+                        args: vec![column("v")],
+                    }
+                    .into(),
+                    None,
+                ),
+                Selector::new(
+                    ExpressionKind::Function {
+                        name: QualifiedName::new(Some("Ks".to_owned()), "f".to_owned()),
+                        args: vec![column("x")],
+                    }
+                    .into(),
+                    None,
+                ),
+            ],
+            "SELECT system.tojson(v), \"Ks\".f(x) FROM t",
+        ),
+    ];
+    for (cql, selectors, formatted) in test_cases.iter() {
+        let select = match Parser::new(cql).parse().unwrap().pop() {
+            Some(CqlStatement::Select(select)) => select,
+            other => panic!("unexpected result: {:?}", other),
+        };
+        match &select.projection {
+            Projection::Selectors(actual) => assert_eq!(actual, selectors, "{}", cql),
+            other => panic!("unexpected projection: {:?}", other),
+        }
+        assert_eq!(&select.to_string(), formatted);
+    }
+}
+
+#[test]
+fn test_function_names() {
+    fn function(keyspace: Option<&str>, name: &str) -> Expression {
+        ExpressionKind::Function {
+            name: QualifiedName::new(keyspace.map(str::to_owned), name.to_owned()),
+            args: vec![ExpressionKind::Value(Literal::Constant(Constant::Integer(1))).into()],
+        }
+        .into()
+    }
+
+    // names that are type keywords are parsed the same as other names
+    let test_cases = [
+        ("myfn(1)", function(None, "myfn"), "myfn(1)"),
+        ("ks.myfn(1)", function(Some("ks"), "myfn"), "ks.myfn(1)"),
+        ("int(1)", function(None, "int"), "int(1)"),
+        ("ks.int(1)", function(Some("ks"), "int"), "ks.int(1)"),
+        ("Ks.MyFn(1)", function(Some("ks"), "myfn"), "ks.myfn(1)"),
+        ("\"MyFn\"(1)", function(None, "MyFn"), "\"MyFn\"(1)"),
+        ("writetime(1)", function(None, "writetime"), "writetime(1)"),
+        ("token(1)", function(None, "token"), "token(1)"),
+        ("(int(1))", function(None, "int"), "int(1)"),
+    ];
+    for (cql, expected, formatted) in test_cases.iter() {
+        let expression = Parser::parse_expression_only(cql)
+            .unwrap_or_else(|e| panic!("failed to parse {}: {}", cql, e));
+        assert_eq!(expression, *expected, "{}", cql);
+        assert_eq!(expression.to_string(), *formatted, "{}", cql);
+        assert_eq!(
+            Parser::parse_expression_only(formatted).unwrap(),
+            expression,
+            "{}",
+            formatted
+        );
+    }
+
+    // keyspace from USE is not applied to function names
+    let cql = "USE ks; SELECT myfn(1) FROM t";
+    let options = ParserOptions {
+        resolve_use_keyspace: true,
+        ..ParserOptions::default()
+    };
+    let select = match Parser::with_options(cql, options).parse().unwrap().pop() {
+        Some(CqlStatement::Select(select)) => select,
+        other => panic!("unexpected result: {:?}", other),
+    };
+    match &select.projection {
+        Projection::Selectors(selectors) => {
+            assert_eq!(selectors[0], Selector::new(function(None, "myfn"), None))
+        }
+        other => panic!("unexpected projection: {:?}", other),
+    }
+
+    // type cast and field selection are not function calls
+    let cast = Parser::parse_expression_only("(int) (1)").unwrap();
+    assert!(
+        matches!(cast.kind, ExpressionKind::TypeCast(..)),
+        "{:?}",
+        cast
+    );
+    let field = Parser::parse_expression_only("name.firstname").unwrap();
+    assert_eq!(field.to_string(), "name.firstname");
+    match &field.kind {
+        ExpressionKind::BinaryOp(op) => assert_eq!(*op.operator(), Operator::Dot),
+        other => panic!("unexpected expression: {:?}", other),
+    }
+}
+
+#[test]
+fn test_insert_json() {
+    fn insert(cql: &str) -> InsertStatement {
+        match Parser::new(cql).parse().unwrap().pop() {
+            Some(CqlStatement::Insert(insert)) => insert,
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    let statement = insert(r#"INSERT INTO t JSON '{"name": "O''Brien", "k": 1}'"#);
+    assert_eq!(
+        statement.values,
+        InsertMethod::json(
+            Literal::Constant(Constant::StringLiteral(
+                r#"{"name": "O'Brien", "k": 1}"#.to_owned()
             )),
-            if_exists: false,
-            timestamp: None,
-            time_to_live: None,
-        })]),
-    )];
+            JsonBehavior::Unset,
+        )
+    );
+
+    let statement = insert(r#"INSERT INTO t JSON $${"name": "O'Brien"}$$ DEFAULT UNSET"#);
+    assert_eq!(
+        statement.values,
+        InsertMethod::json(
+            Literal::Constant(Constant::StringLiteral(r#"{"name": "O'Brien"}"#.to_owned())),
+            JsonBehavior::Unset,
+        )
+    );
+
+    let statement = insert("INSERT INTO t JSON ? DEFAULT NULL USING TTL ?");
+    assert_eq!(
+        statement.values,
+        InsertMethod::json(Literal::Binding(None), JsonBehavior::Null)
+    );
+    let markers = CqlStatement::Insert(statement).bind_markers();
+    assert_eq!(markers.len(), 2);
+    assert_eq!(markers[0].clause, BindMarkerClause::Values);
+    assert_eq!(markers[1].clause, BindMarkerClause::Using);
+
+    let statement = insert("INSERT INTO t JSON :doc DEFAULT NULL");
+    assert_eq!(
+        statement.values,
+        InsertMethod::json(Literal::Binding(Some("doc".to_owned())), JsonBehavior::Null)
+    );
+
+    let error = Parser::new("INSERT INTO t JSON 1").parse().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "1:20: expected string literal, found '1'"
+    );
+}
+
+#[test]
+fn test_update_statements() {
+    let test_cases = [
+        (
+            "UPDATE tbl SET col1 = 'text', col2 = 1 WHERE k = 1",
+            Ok(vec![CqlStatement::Update(UpdateStatement {
+                table: QualifiedName::new(None, String::from("tbl")),
+                assignments: vec![
+                    ExpressionKind::BinaryOp(BinaryOp::new(
+                        Box::new(Expression::new(ExpressionKind::Identifier("col1".into()))),
+                        Operator::Equal,
+                        Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
+                            Constant::StringLiteral(String::from("text")),
+                        )))),
+                    ))
+                    .into(),
+                    ExpressionKind::BinaryOp(BinaryOp::new(
+                        Box::new(Expression::new(ExpressionKind::Identifier("col2".into()))),
+                        Operator::Equal,
+                        Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
+                            Constant::Integer(1),
+                        )))),
+                    ))
+                    .into(),
+                ],
+                selection: WhereClause::new(vec![Relation::SingleColumn {
+                    column: "k".into(),
+                    operator: Operator::Equal,
+                    value: ExpressionKind::Value(Literal::Constant(Constant::Integer(1))).into(),
+                }]),
+                if_exists: false,
+                conditions: None,
+                timestamp: None,
+                time_to_live: None,
+                span: Span::default(),
+            })]),
+        ),
+        (
+            "UPDATE tbl SET v = 2 WHERE k = 1 IF v != 1",
+            Ok(vec![CqlStatement::Update(UpdateStatement {
+                table: QualifiedName::new(None, String::from("tbl")),
+                assignments: vec![ExpressionKind::BinaryOp(BinaryOp::new(
+                    Box::new(Expression::new(ExpressionKind::Identifier("v".into()))),
+                    Operator::Equal,
+                    Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
+                        Constant::Integer(2),
+                    )))),
+                ))
+                .into()],
+                selection: WhereClause::new(vec![Relation::SingleColumn {
+                    column: "k".into(),
+                    operator: Operator::Equal,
+                    value: ExpressionKind::Value(Literal::Constant(Constant::Integer(1))).into(),
+                }]),
+                if_exists: false,
+                conditions: Some(
+                    ExpressionKind::BinaryOp(BinaryOp::new(
+                        Box::new(Expression::new(ExpressionKind::Identifier("v".into()))),
+                        Operator::NotEqual,
+                        Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
+                            Constant::Integer(1),
+                        )))),
+                    ))
+                    .into(),
+                ),
+                timestamp: None,
+                time_to_live: None,
+                span: Span::default(),
+            })]),
+        ),
+    ];
     for test in &test_cases {
         let p = Parser::new(test.0);
         assert_eq!(p.parse(), test.1);
     }
 }
+
+#[test]
+fn test_delete_statement() {
+    let cql = "DELETE emails, prefs['theme'], scores[0], addr.city, tags[?] \
+        FROM ks.users USING TIMESTAMP 1709200000000000 WHERE id = ? IF EXISTS";
+    let statement = Parser::new(cql).parse().unwrap().remove(0);
+    assert!(statement.is_dml());
+    assert!(statement.is_conditional());
+    let markers = statement.bind_markers();
+    assert_eq!(markers.len(), 2);
+    assert_eq!(markers[0].clause, BindMarkerClause::Deletion);
+    assert_eq!(markers[1].clause, BindMarkerClause::Where);
+    let delete = match statement {
+        CqlStatement::Delete(delete) => delete,
+        other => panic!("unexpected result: {:?}", other),
+    };
+    assert_eq!(
+        delete.targets,
+        vec![
+            DeleteTarget::Column("emails".into()),
+            DeleteTarget::Element {
+                column: "prefs".into(),
+                element: ExpressionKind::Value(Literal::Constant(Constant::StringLiteral(
+                    "theme".to_owned()
+                )))
+                .into(),
+            },
+            DeleteTarget::Element {
+                column: "scores".into(),
+                element: ExpressionKind::Value(Literal::Constant(Constant::Integer(0))).into(),
+            },
+            DeleteTarget::Field {
+                column: "addr".into(),
+                field: "city".into(),
+            },
+            DeleteTarget::Element {
+                column: "tags".into(),
+                element: ExpressionKind::Value(Literal::Binding(None)).into(),
+            },
+        ]
+    );
+    assert_eq!(
+        delete
+            .targets
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        [
+            "emails",
+            "prefs['theme']",
+            "scores[0]",
+            "addr.city",
+            "tags[?]"
+        ]
+    );
+    assert_eq!(delete.targets[3].column().as_ref(), "addr");
+    assert_eq!(delete.table.to_string(), "ks.users");
+    assert_eq!(
+        delete.timestamp,
+        Some(Literal::Constant(Constant::Integer(1_709_200_000_000_000)))
+    );
+    assert!(delete.if_exists);
+    assert_eq!(delete.conditions, None);
+
+    // whole rows, with conditions
+    let delete = match Parser::new("DELETE FROM t WHERE k = 1 IF v = 2")
+        .parse()
+        .unwrap()
+        .remove(0)
+    {
+        CqlStatement::Delete(delete) => delete,
+        other => panic!("unexpected result: {:?}", other),
+    };
+    assert!(delete.targets.is_empty());
+    assert!(!delete.if_exists);
+    assert_eq!(
+        delete.conditions.map(|c| c.to_string()),
+        Some("v = 2".to_owned())
+    );
+
+    let test_cases = [
+        (
+            "DELETE l[1..2] FROM t WHERE k = 1",
+            "1:9: Range of elements cannot be deleted",
+        ),
+        (
+            "DELETE a FROM t USING TTL 1 WHERE k = 1",
+            "1:23: expected TIMESTAMP, found 'TTL'",
+        ),
+        (
+            "DELETE a FROM t",
+            "1:16: expected WHERE, found end of input",
+        ),
+        (
+            "DELETE a. FROM t WHERE k = 1",
+            "1:11: expected field name, found 'FROM'",
+        ),
+    ];
+    for (cql, message) in test_cases.iter() {
+        let error = Parser::new(cql).parse().unwrap_err();
+        assert_eq!(&error.to_string(), message, "{}", cql);
+    }
+}
+
+#[test]
+fn test_batch_statement() {
+    let cql = "BEGIN UNLOGGED BATCH USING TIMESTAMP ?
+    INSERT INTO ks.t1 (k, v) VALUES (?, ?);
+    UPDATE ks.t2 SET v = 1 WHERE k = ? IF v = 0
+    DELETE FROM t1 WHERE k = 1;
+APPLY BATCH";
+    let statement = Parser::new(cql).parse().unwrap().remove(0);
+    assert!(statement.is_dml());
+    assert!(statement.is_conditional());
+    assert_eq!(
+        statement
+            .table_references()
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<_>>(),
+        ["ks.t1", "ks.t2", "t1"]
+    );
+    let markers = statement.bind_markers();
+    assert_eq!(markers.len(), 4);
+    assert_eq!(markers[0].clause, BindMarkerClause::Using);
+    let batch = match statement {
+        CqlStatement::Batch(batch) => batch,
+        other => panic!("unexpected result: {:?}", other),
+    };
+    assert_eq!(batch.batch_type, BatchType::Unlogged);
+    assert_eq!(batch.timestamp, Some(Literal::Binding(None)));
+    assert_eq!(batch.statements.len(), 3);
+    assert!(matches!(batch.statements[2], CqlStatement::Delete(_)));
+
+    let batch = match Parser::new("BEGIN COUNTER BATCH APPLY BATCH")
+        .parse()
+        .unwrap()
+        .remove(0)
+    {
+        CqlStatement::Batch(batch) => batch,
+        other => panic!("unexpected result: {:?}", other),
+    };
+    assert_eq!(batch.batch_type, BatchType::Counter);
+    assert!(batch.statements.is_empty());
+
+    let test_cases = [
+        (
+            "BEGIN BATCH SELECT * FROM t; APPLY BATCH",
+            "1:13: expected INSERT, UPDATE, DELETE or APPLY BATCH, found 'SELECT'\n  \
+             in statement 1 of BATCH starting at line 1",
+        ),
+        (
+            "BEGIN BATCH INSERT INTO t (k) VALUES (1)",
+            "1:41: expected INSERT, UPDATE, DELETE or APPLY BATCH, found end of input\n  \
+             in statement 2 of BATCH starting at line 1",
+        ),
+        (
+            "BEGIN BATCH USING TTL 1 APPLY BATCH",
+            "1:19: expected TIMESTAMP, found 'TTL'",
+        ),
+    ];
+    for (cql, message) in test_cases.iter() {
+        let error = Parser::new(cql).parse().unwrap_err();
+        assert_eq!(&error.to_string(), message, "{}", cql);
+    }
+
+    // errors inside the batch tell which statement is broken
+    let cql = "SELECT * FROM t;
+
+BEGIN BATCH
+    INSERT INTO t (k, v) VALUES (1, 1);
+    UPDATE t SET v = 2 WHERE k = 1;
+    INSERT INTO t (k, v) VALUES (3, );
+APPLY BATCH";
+    let error = Parser::new(cql).parse().unwrap_err();
+    assert_eq!(error.line(), Some(6));
+    assert_eq!(
+        error.context(),
+        ["INSERT values", "statement 3 of BATCH starting at line 3"]
+    );
+    assert_eq!(
+        error.to_string(),
+        "6:37: expected expression, found ')'\n  \
+         in INSERT values\n  \
+         in statement 3 of BATCH starting at line 3"
+    );
+}
+
+#[test]
+fn test_statement_spans() {
+    let cql = "SELECT a, b AS c FROM tbl;
+-- comment between statements
+/* another comment */
+UPDATE tbl SET a = 1 WHERE k = 1";
+    let statements = Parser::new(cql).parse_spanned().unwrap();
+    assert_eq!(statements.len(), 2);
+
+    // first statement includes trailing `;`
+    assert_eq!(statements[0].span, Span::new(0, 26));
+    assert_eq!(
+        &cql[statements[0].span.start..statements[0].span.end],
+        "SELECT a, b AS c FROM tbl;"
+    );
+    // comments are not part of the statement
+    let second = statements[1].span;
+    assert_eq!(
+        &cql[second.start..second.end],
+        "UPDATE tbl SET a = 1 WHERE k = 1"
+    );
+
+    // selectors
+    match &statements[0].node {
+        CqlStatement::Select(SelectStatement {
+            projection: Projection::Selectors(selectors),
+            ..
+        }) => {
+            assert_eq!(selectors[0].span(), Span::new(7, 8));
+            assert_eq!(selectors[1].span(), Span::new(10, 16));
+        }
+        _ => panic!("SELECT statement expected"),
+    }
+}
+
+#[test]
+fn test_expression_spans() {
+    let cql = "SELECT * FROM t WHERE a = 1 AND b IN (2, ?) AND c > now() AND d < (1 + 2) * 3 \
+               AND (e, f) = ('x', -4) AND g IS NOT NULL";
+    let select = match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::Select(select)) => select,
+        other => panic!("unexpected result: {:?}", other),
+    };
+    let values: Vec<_> = select
+        .selection
+        .unwrap()
+        .relations()
+        .iter()
+        .map(|relation| &cql[relation.value().span.range()])
+        .collect();
+    assert_eq!(
+        values,
+        ["1", "(2, ?)", "now()", "(1 + 2) * 3", "('x', -4)", "NULL"]
+    );
+
+    let cql = "INSERT INTO t (k, v, m) VALUES (1, [ 'a', 'b' ], {'k': now()}) USING TTL 10";
+    match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::Insert(InsertStatement {
+            values: InsertMethod::Normal { values, .. },
+            ..
+        })) => {
+            let values: Vec<_> = values.iter().map(|v| &cql[v.span.range()]).collect();
+            assert_eq!(values, ["1", "[ 'a', 'b' ]", "{'k': now()}"]);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    // operands of the operators
+    let cql = "m['a'] + CAST(x AS text)";
+    let expression = Parser::parse_expression_only(cql).unwrap();
+    assert_eq!(expression.span, Span::new(0, cql.len()));
+    match &expression.kind {
+        ExpressionKind::BinaryOp(op) => {
+            assert_eq!(&cql[op.left().span.range()], "m['a']");
+            assert_eq!(&cql[op.right().span.range()], "CAST(x AS text)");
+        }
+        other => panic!("unexpected expression: {:?}", other),
+    }
+
+    // spans are not compared
+    assert_eq!(
+        expression,
+        Parser::parse_expression_only(&format!("  {}", cql)).unwrap()
+    );
+}
+
+#[test]
+fn test_statement_struct_spans() {
+    let cql = "BEGIN BATCH
+  INSERT INTO t (k, v) VALUES (1, 2);
+  DELETE FROM t WHERE k = 3
+APPLY BATCH;";
+    let statements = Parser::new(cql).parse_spanned().unwrap();
+    let batch = match &statements[0].node {
+        CqlStatement::Batch(batch) => batch,
+        other => panic!("unexpected statement: {:?}", other),
+    };
+    // without the trailing `;`, unlike the span of the statement
+    assert_eq!(&cql[batch.span.range()], &cql[..cql.len() - 1]);
+    let spans: Vec<_> = batch
+        .statements
+        .iter()
+        .map(|statement| match statement {
+            CqlStatement::Insert(insert) => &cql[insert.span.range()],
+            CqlStatement::Delete(delete) => &cql[delete.span.range()],
+            other => panic!("unexpected statement: {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        spans,
+        [
+            "INSERT INTO t (k, v) VALUES (1, 2)",
+            "DELETE FROM t WHERE k = 3"
+        ]
+    );
+
+    let cql = "CREATE TABLE t (k int PRIMARY KEY) WITH comment = 'c'; ALTER TABLE t ADD v text";
+    let spans: Vec<_> = Parser::new(cql)
+        .parse()
+        .unwrap()
+        .iter()
+        .map(|statement| match statement {
+            CqlStatement::CreateTable(create) => &cql[create.span.range()],
+            CqlStatement::AlterTable(alter) => &cql[alter.span.range()],
+            other => panic!("unexpected statement: {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        spans,
+        [
+            "CREATE TABLE t (k int PRIMARY KEY) WITH comment = 'c'",
+            "ALTER TABLE t ADD v text"
+        ]
+    );
+
+    // spans are not compared
+    assert_eq!(
+        Parser::new("SELECT * FROM t").parse().unwrap(),
+        Parser::new("\n  SELECT  *  FROM  t").parse().unwrap()
+    );
+}
+
+#[test]
+fn test_error_position() {
+    let cql = "CREATE TABLE ks.test (
+    key int,
+    value text,
+    col1 map<text, int PRIMARY KEY
+)";
+    let error = Parser::new(cql).parse().unwrap_err();
+    assert_eq!(error.line(), Some(4));
+    assert_eq!(error.column(), Some(24));
+    assert_eq!(error.offset(), cql.find("PRIMARY"));
+    assert!(error.to_string().starts_with("4:24: "));
+}
+
+#[test]
+fn test_error_context() {
+    let cql = "CREATE TABLE ks.users (
+    id uuid PRIMARY KEY,
+    emails set<text,
+    name text
+)";
+    let error = Parser::new(cql).parse().unwrap_err();
+    assert_eq!(
+        error.context(),
+        ["column definition emails", "CREATE TABLE ks.users"]
+    );
+    assert_eq!(
+        error.to_string(),
+        "3:20: expected '>', found ','\n  \
+         in column definition emails\n  \
+         in CREATE TABLE ks.users"
+    );
+
+    let cql = "CREATE TABLE t (k int PRIMARY KEY) WITH compaction = {'class': }";
+    let error = Parser::new(cql).parse().unwrap_err();
+    assert_eq!(error.context(), ["property compaction", "CREATE TABLE t"]);
+
+    // no context outside of nested constructs
+    let error = Parser::new("SELECT FROM t").parse().unwrap_err();
+    assert!(error.context().is_empty());
+}
+
+#[test]
+fn test_byte_order_mark() {
+    let cql = "\u{FEFF}CREATE TABLE t (k int PRIMARY KEY);\nSELECT * FROM t";
+    let statements = Parser::new(cql).parse_spanned().unwrap();
+    assert_eq!(statements.len(), 2);
+    assert_eq!(statements[0].span.start, 3);
+
+    // columns do not count the byte order mark
+    let error = Parser::new("\u{FEFF}SELECT FROM t").parse().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "1:8: expected selector or '*', found 'FROM'"
+    );
+}
+
+#[test]
+fn test_incomplete_input() {
+    let incomplete = [
+        "SELECT * FROM",
+        "SELECT * FROM t WHERE a = 1 AND",
+        "SELECT * FROM t WHERE a IN (1,",
+        "SELECT * FROM t WHERE a = 'abc",
+        "SELECT \"a",
+        "SELECT * FROM t /* comment",
+        "CREATE TABLE t (k int PRIMARY KEY",
+        "CREATE TABLE t (k int",
+        "INSERT INTO t JSON '{}' DEFAULT",
+        "BEGIN BATCH INSERT INTO t (a) VALUES (1);",
+        "SELECT * FROM t; UPDATE t SET",
+    ];
+    for cql in incomplete.iter() {
+        let error = Parser::new(cql).parse().unwrap_err();
+        assert!(error.is_incomplete_input(), "{}: {}", cql, error);
+    }
+    let errors = [
+        "SELECT * FROM ;",
+        "SELECT FROM t",
+        "SELECT * FROM t WHERE a = 123abc",
+        "SELECT * FROM t WHERE a = 1 garbage",
+        "CREATE TABLE t (a int, b int)",
+        "INSERT INTO t JSON '{}' DEFAULT ZERO",
+        "SELECT * FROM t WHERE a = 1 AND; SELECT",
+    ];
+    for cql in errors.iter() {
+        let error = Parser::new(cql).parse().unwrap_err();
+        assert!(!error.is_incomplete_input(), "{}: {}", cql, error);
+    }
+}
+
+#[test]
+fn test_error_source_name() {
+    let cql = "CREATE TABLE ks.users (id uuid PRIMARY KEY);
+SELECT * FROM ks.users WHERE;
+SELECT FROM ks.users;";
+    let errors: Vec<String> = Parser::new(cql)
+        .with_source_name("schema/users.cql")
+        .lenient()
+        .filter_map(Result::err)
+        .map(|e| e.to_string())
+        .collect();
+    assert_eq!(
+        errors,
+        [
+            "schema/users.cql:2:29: expected column name, TOKEN or (, found ';'",
+            "schema/users.cql:3:8: expected selector or '*', found 'FROM'",
+        ]
+    );
+
+    let error = Parser::new("SELECT * FROM").parse().unwrap_err();
+    assert_eq!(error.source_name(), None);
+}
+
+#[test]
+fn test_error_messages() {
+    let test_cases = [
+        (
+            "SELECT FROM t",
+            "1:8: expected selector or '*', found 'FROM'",
+        ),
+        (
+            "GRANT ALL ON t TO r",
+            "1:1: expected SELECT, INSERT, UPDATE, DELETE, BEGIN BATCH, CREATE, ALTER, DROP, USE or TRUNCATE, found 'GRANT'",
+        ),
+        ("TRUNCATE TABLE", "1:15: expected identifier, found end of input"),
+        (
+            "SELECT count(a FROM t",
+            "1:16: expected ')', found 'FROM'",
+        ),
+        ("DROP MATERIALIZED t", "1:19: expected VIEW, found 't'"),
+        (
+            "DROP FUNCTION f",
+            "1:6: expected KEYSPACE, TABLE, INDEX, TYPE or MATERIALIZED VIEW, found 'FUNCTION'",
+        ),
+        (
+            "CREATE FOO x",
+            "1:8: expected KEYSPACE, TABLE, CUSTOM INDEX, INDEX, MATERIALIZED VIEW or TYPE, found 'FOO'",
+        ),
+        (
+            "SELECT * FROM t WHERE a = ",
+            "1:26: expected expression, found end of input",
+        ),
+        (
+            "CREATE KEYSPACE ks WITH a = ;",
+            "1:29: expected constant, identifier or map literal, found ';'\n  in property a",
+        ),
+        (
+            "SELECT * FROM t WHERE a = 123abc",
+            "1:27: expected expression, found '123abc'",
+        ),
+        (
+            "SELECT * FROM t WHERE d = 1m2h",
+            "1:27: expected expression, found '1m2h' (duration unit 'h' is out of order, \
+             units must be in the order y, mo, w, d, h, m, s, ms, us, ns)",
+        ),
+        (
+            "INSERT INTO t (k, d) VALUES (1, 1h30m1h)",
+            "1:33: expected expression, found '1h30m1h' (duration unit 'h' is out of order, \
+             units must be in the order y, mo, w, d, h, m, s, ms, us, ns)\n  in INSERT values",
+        ),
+        // string literals and quoted names are not quoted again
+        (
+            "CREATE KEYSPACE 'ks' WITH a = 1",
+            "1:17: expected keyspace name, found 'ks'",
+        ),
+        (
+            "SELECT * FROM t WHERE a = 1 $$x$$",
+            "1:29: expected a CQL statement, found $$x$$",
+        ),
+        (
+            "SELECT * FROM t \"T\"",
+            "1:17: expected a CQL statement, found \"T\"",
+        ),
+        (
+            "SELECT * FROM t WHERE a = 'abc",
+            "1:27: expected expression, found 'abc (unterminated string literal)",
+        ),
+        (
+            "SELECT * FROM \"tbl",
+            "1:15: expected identifier, found \"tbl (unterminated quoted name)",
+        ),
+        (
+            "SELECT * FROM éclairs",
+            "1:15: expected identifier, found 'éclairs' (non-ASCII character 'é' is only allowed inside quoted identifiers or string literals)",
+        ),
+        (
+            "SELECT * FROM café",
+            "1:18: expected a CQL statement, found 'é' (non-ASCII character 'é' is only allowed inside quoted identifiers or string literals)",
+        ),
+        (
+            "CREATE TYPE t (a int, b text,)",
+            "1:29: trailing comma before ')'",
+        ),
+        ("CREATE TYPE t ()", "1:16: expected field name, found ')'"),
+        (
+            "CREATE TYPE t (a int, FROM text)",
+            "1:23: expected field name, found 'FROM'",
+        ),
+    ];
+    for test in &test_cases {
+        let error = Parser::new(test.0).parse().unwrap_err();
+        assert_eq!(error.to_string(), test.1);
+    }
+
+    // misspelled statement keywords
+    let test_cases = [
+        ("SELCT * FROM t", Some("SELECT")),
+        ("selectt * FROM t", Some("SELECT")),
+        ("UPDTAE t SET a = 1", Some("UPDATE")),
+        ("DORP TABLE t", Some("DROP")),
+        ("US ks", Some("USE")),
+        ("BEGN BATCH APPLY BATCH", Some("BEGIN BATCH")),
+        ("ALTRE TABLE t DROP a", Some("ALTER")),
+        ("describe t", None),
+        ("AS ks", None),
+    ];
+    for (cql, suggestion) in test_cases.iter() {
+        let error = Parser::new(cql).parse().unwrap_err();
+        let found = cql.split(' ').next().unwrap();
+        let mut expected = format!(
+            "1:1: expected SELECT, INSERT, UPDATE, DELETE, BEGIN BATCH, CREATE, ALTER, DROP, USE or TRUNCATE, found '{}'",
+            found
+        );
+        if let Some(suggestion) = suggestion {
+            expected.push_str(&format!(" (did you mean {}?)", suggestion));
+        }
+        assert_eq!(error.to_string(), expected, "{}", cql);
+    }
+    // tokens that cannot start any statement
+    let error = Parser::new("1 + 1").parse().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "1:1: expected a CQL statement, found '1'"
+    );
+    assert_eq!(error.expected(), ["a CQL statement"]);
+    let error = Parser::new("SELECT * FROM t; (a)").parse().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "1:18: expected a CQL statement, found '('"
+    );
+
+    let error = Parser::new("SELECT FROM t").parse().unwrap_err();
+    assert_eq!(error.expected(), &["selector", "'*'"]);
+    assert_eq!(error.found(), Some("FROM"));
+    assert_eq!(error.offset(), Some(7));
+}
+
+#[test]
+fn test_parse_lenient() {
+    let cql = "SELECT * FROM tbl1;
+UPDATE tbl SET (a = 1 WHERE k = (1, 2);
+SELECT * FROM tbl2;";
+    let (statements, errors) = Parser::new(cql).parse_lenient();
+    assert_eq!(statements.len(), 2);
+    match (&statements[0], &statements[1]) {
+        (CqlStatement::Select(first), CqlStatement::Select(second)) => {
+            assert_eq!(&*first.table_name.name, "tbl1");
+            assert_eq!(&*second.table_name.name, "tbl2");
+        }
+        _ => panic!("SELECT statements expected"),
+    }
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line(), Some(2));
+
+    // strict parsing stops at the first error
+    assert_eq!(Parser::new(cql).parse().unwrap_err(), errors[0]);
+
+    let (spanned, spanned_errors) = Parser::new(cql).parse_spanned_lenient();
+    assert_eq!(spanned_errors, errors);
+    let ranges: Vec<_> = spanned.iter().map(|s| &cql[s.span.range()]).collect();
+    assert_eq!(ranges, ["SELECT * FROM tbl1;", "SELECT * FROM tbl2;"]);
+}
+
+#[test]
+fn test_parse_iterator() {
+    // the second statement is broken
+    let cql = "SELECT * FROM tbl1; SELECT FROM; SELECT * FROM tbl2";
+    let first: Result<Vec<CqlStatement>, _> = Parser::new(cql).take(1).collect();
+    assert_eq!(first.unwrap().len(), 1);
+
+    // iteration stops after the first error
+    let mut parser = Parser::new(cql);
+    assert!(parser.next().unwrap().is_ok());
+    assert!(parser.next().unwrap().is_err());
+    assert!(parser.next().is_none());
+
+    // lenient iteration continues after the error
+    let results: Vec<_> = Parser::new(cql).lenient().collect();
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+    assert!(results[2].is_ok());
+}
+
+#[test]
+fn test_ttl() {
+    fn ttl(cql: &str) -> Option<TtlValue> {
+        match &Parser::new(cql).parse().unwrap()[0] {
+            CqlStatement::Insert(insert) => insert.ttl_seconds(),
+            CqlStatement::Update(update) => update.ttl_seconds(),
+            statement => panic!("unexpected statement: {:?}", statement),
+        }
+    }
+    let test_cases = [
+        ("INSERT INTO t (k) VALUES (1)", None),
+        (
+            "INSERT INTO t (k) VALUES (1) USING TTL 0",
+            Some(TtlValue::Constant(0)),
+        ),
+        (
+            "INSERT INTO t (k) VALUES (1) USING TIMESTAMP 1 AND TTL 86400",
+            Some(TtlValue::Constant(86400)),
+        ),
+        (
+            "INSERT INTO t (k) VALUES (1) USING TTL ?",
+            Some(TtlValue::Binding(None)),
+        ),
+        ("UPDATE t USING TIMESTAMP 1 SET v = 1 WHERE k = 1", None),
+        (
+            "UPDATE t USING TTL 630720000 SET v = 1 WHERE k = 1",
+            Some(TtlValue::Constant(630_720_000)),
+        ),
+        (
+            "UPDATE t USING TTL :ttl SET v = 1 WHERE k = 1",
+            Some(TtlValue::Binding(Some("ttl".to_owned()))),
+        ),
+    ];
+    for (cql, expected) in test_cases.iter() {
+        assert_eq!(&ttl(cql), expected, "{}", cql);
+    }
+
+    let test_cases = [
+        (
+            "INSERT INTO t (k) VALUES (1) USING TTL -1",
+            "1:40: A TTL must be greater or equal to 0, but was -1",
+        ),
+        (
+            "UPDATE t USING TTL -86400 SET v = 1 WHERE k = 1",
+            "1:20: A TTL must be greater or equal to 0, but was -86400",
+        ),
+        (
+            "UPDATE t USING TTL 630720001 SET v = 1 WHERE k = 1",
+            "1:20: ttl is too large. requested (630720001) maximum (630720000)",
+        ),
+    ];
+    for (cql, message) in test_cases.iter() {
+        let error = Parser::new(cql).parse().unwrap_err();
+        assert_eq!(&error.to_string(), message, "{}", cql);
+    }
+}
+
+#[test]
+fn test_owned_parser() {
+    fn assert_send<T: Send>() {}
+    assert_send::<OwnedParser>();
+    assert_send::<Parser<'static>>();
+
+    let parser = {
+        let cql = String::from("SELECT * FROM t1;\nSELECT * FROM");
+        Parser::from_string(cql).with_source_name("queries.cql")
+    };
+    let parser = std::thread::spawn(move || {
+        assert_eq!(parser.parse_lenient().0.len(), 1);
+        parser
+    })
+    .join()
+    .unwrap();
+    // parsed again from the start
+    let error = parser.parse().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "queries.cql:2:14: expected identifier, found end of input"
+    );
+    let mut statements = parser.parser();
+    assert!(statements.next().unwrap().is_ok());
+    assert!(statements.next().unwrap().is_err());
+    assert_eq!(parser.cql(), "SELECT * FROM t1;\nSELECT * FROM");
+
+    assert!(format!("{:?}", Parser::new("SELECT")).starts_with("Parser {"));
+}
+
+#[test]
+fn test_parse_single() {
+    let test_cases = [
+        ("SELECT * FROM t", ""),
+        ("SELECT * FROM t;", ""),
+        ("  SELECT * FROM t  ", "  "),
+        ("SELECT * FROM t ;  \n", "  \n"),
+        ("SELECT * FROM t; -- comment\n", " -- comment\n"),
+        ("SELECT * FROM t; /* first */", " /* first */"),
+        ("SELECT * FROM t;\nSELECT * FROM u;", "\nSELECT * FROM u;"),
+        ("SELECT * FROM t; garbage", " garbage"),
+        (";; SELECT * FROM t;;", ";"),
+    ];
+    for (cql, rest) in test_cases.iter() {
+        let (statement, remaining) = Parser::parse_single(cql).unwrap();
+        assert!(matches!(statement, CqlStatement::Select(_)), "{}", cql);
+        assert_eq!(remaining, *rest, "{}", cql);
+    }
+
+    // the rest can be parsed again
+    let mut cql = "USE ks; SELECT * FROM t; -- done";
+    let mut statements = Vec::new();
+    while !cql.trim().starts_with("--") {
+        let (statement, rest) = Parser::parse_single(cql).unwrap();
+        statements.push(statement);
+        cql = rest;
+    }
+    assert_eq!(statements.len(), 2);
+
+    let test_cases = [
+        (
+            "SELECT * FROM t garbage",
+            "1:17: expected ';' or end of input, found 'garbage'",
+        ),
+        (
+            "SELECT * FROM t SELECT * FROM u",
+            "1:17: expected ';' or end of input, found 'SELECT'",
+        ),
+        (
+            "",
+            "1:1: expected SELECT, INSERT, UPDATE, DELETE, BEGIN BATCH, CREATE, ALTER, DROP, \
+             USE or TRUNCATE, found end of input",
+        ),
+        (
+            "-- nothing",
+            "1:1: expected SELECT, INSERT, UPDATE, DELETE, BEGIN BATCH, CREATE, ALTER, DROP, \
+             USE or TRUNCATE, found end of input",
+        ),
+        (
+            "SELECT FROM t; SELECT * FROM u",
+            "1:8: expected selector or '*', found 'FROM'",
+        ),
+    ];
+    for (cql, message) in test_cases.iter() {
+        let error = Parser::parse_single(cql).unwrap_err();
+        assert_eq!(&error.to_string(), message, "{}", cql);
+    }
+}
+
+#[test]
+fn test_parse_expression_only() {
+    assert_eq!(
+        Parser::parse_expression_only("a = 1 AND b >= 'x' AND c IS NOT NULL"),
+        Ok(ExpressionKind::BinaryOp(BinaryOp::new(
+            Box::new(Expression::new(ExpressionKind::BinaryOp(BinaryOp::new(
+                Box::new(Expression::new(ExpressionKind::BinaryOp(BinaryOp::new(
+                    Box::new(Expression::new(ExpressionKind::Identifier("a".into()))),
+                    Operator::Equal,
+                    Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
+                        Constant::Integer(1)
+                    )))),
+                )))),
+                Operator::And,
+                Box::new(Expression::new(ExpressionKind::BinaryOp(BinaryOp::new(
+                    Box::new(Expression::new(ExpressionKind::Identifier("b".into()))),
+                    Operator::GreaterThanOrEqual,
+                    Box::new(Expression::new(ExpressionKind::Value(Literal::Constant(
+                        Constant::StringLiteral(String::from("x")),
+                    )))),
+                )))),
+            )))),
+            Operator::And,
+            Box::new(Expression::new(ExpressionKind::BinaryOp(BinaryOp::new(
+                Box::new(Expression::new(ExpressionKind::Identifier("c".into()))),
+                Operator::IsNot,
+                Box::new(Expression::new(ExpressionKind::Value(Literal::Null))),
+            )))),
+        ))
+        .into())
+    );
+
+    // dangling operator
+    let error = Parser::parse_expression_only("a = 1 AND").unwrap_err();
+    assert_eq!(error.found(), None);
+    assert_eq!(error.offset(), Some(9));
+
+    // trailing tokens
+    let error = Parser::parse_expression_only("a = 1 b").unwrap_err();
+    assert_eq!(error.expected(), &["end of input"]);
+    assert_eq!(error.found(), Some("b"));
+    assert_eq!(error.column(), Some(7));
+}
+
+#[test]
+fn test_parse_cql_type_only() {
+    assert_eq!(
+        Parser::parse_cql_type_only("map<text, frozen<list<int>>>"),
+        Ok(CqlType::Collection(CollectionType::Map {
+            key_type: Box::new(CqlType::Native(NativeDataType::Text)),
+            value_type: Box::new(CqlType::Frozen(Box::new(CqlType::Collection(
+                CollectionType::List(Box::new(CqlType::Native(NativeDataType::Int)))
+            )))),
+        }))
+    );
+    assert!(Parser::parse_cql_type_only("int int").is_err());
+}
+
+#[test]
+fn test_nested_types() {
+    let int = || Box::new(CqlType::Native(NativeDataType::Int));
+    let text = || Box::new(CqlType::Native(NativeDataType::Text));
+    let frozen = |t: CqlType| CqlType::Frozen(Box::new(t));
+    let list = |t: Box<CqlType>| CqlType::Collection(CollectionType::List(t));
+    let udt = |keyspace: Option<&str>, name: &str| {
+        CqlType::UserDefinedType(QualifiedName::new(
+            keyspace.map(str::to_owned),
+            name.to_owned(),
+        ))
+    };
+    let test_cases = [
+        (
+            "frozen<map<text, frozen<list<int>>>>",
+            frozen(CqlType::Collection(CollectionType::Map {
+                key_type: text(),
+                value_type: Box::new(frozen(list(int()))),
+            })),
+        ),
+        (
+            "list<frozen<tuple<int, text>>>",
+            list(Box::new(frozen(CqlType::Tuple(vec![*int(), *text()])))),
+        ),
+        (
+            "map<frozen<ks.address>, int>",
+            CqlType::Collection(CollectionType::Map {
+                key_type: Box::new(frozen(udt(Some("ks"), "address"))),
+                value_type: int(),
+            }),
+        ),
+        (
+            "tuple<int, tuple<text, list<int>>, frozen<address>>",
+            CqlType::Tuple(vec![
+                *int(),
+                CqlType::Tuple(vec![*text(), list(int())]),
+                frozen(udt(None, "address")),
+            ]),
+        ),
+        (
+            "frozen<tuple<frozen<set<int>>>>",
+            frozen(CqlType::Tuple(vec![frozen(CqlType::Collection(
+                CollectionType::Set(int()),
+            ))])),
+        ),
+        (
+            "list<frozen<list<frozen<list<int>>>>>",
+            list(Box::new(frozen(list(Box::new(frozen(list(int()))))))),
+        ),
+        (
+            "map<text, frozen<tuple<int, text>>>",
+            CqlType::Collection(CollectionType::Map {
+                key_type: text(),
+                value_type: Box::new(frozen(CqlType::Tuple(vec![*int(), *text()]))),
+            }),
+        ),
+        (
+            "tuple<frozen<ks.point>, frozen<point>>",
+            CqlType::Tuple(vec![
+                frozen(udt(Some("ks"), "point")),
+                frozen(udt(None, "point")),
+            ]),
+        ),
+        (
+            "list<tuple<int>>",
+            list(Box::new(CqlType::Tuple(vec![*int()]))),
+        ),
+    ];
+    for (cql, expected) in test_cases.iter() {
+        let parsed = Parser::parse_cql_type_only(cql).unwrap();
+        assert_eq!(&parsed, expected, "{}", cql);
+        assert_eq!(parsed.to_string(), *cql);
+    }
+
+    let test_cases = [
+        ("frozen<list<int>>", true, false),
+        ("tuple<int, text>", true, false),
+        ("list<frozen<address>>", false, false),
+        ("counter", false, true),
+        ("map<text, frozen<tuple<int, counter>>>", false, true),
+        ("frozen<address>", true, false),
+    ];
+    for (cql, is_frozen, contains_counter) in test_cases.iter() {
+        let parsed = Parser::parse_cql_type_only(cql).unwrap();
+        assert_eq!(parsed.is_frozen(), *is_frozen, "{}", cql);
+        assert_eq!(parsed.contains_counter(), *contains_counter, "{}", cql);
+    }
+
+    for cql in ["tuple<>", "frozen<list<int>", "map<int>", "list<int, int>"].iter() {
+        assert!(Parser::parse_cql_type_only(cql).is_err(), "{}", cql);
+    }
+
+    // declared types, and the types reported by the server
+    let test_cases = [
+        ("int", "int"),
+        ("tuple<int, text>", "frozen<tuple<int, text>>"),
+        ("frozen<tuple<int, text>>", "frozen<tuple<int, text>>"),
+        ("list<int>", "list<int>"),
+        ("address", "address"),
+        ("frozen<address>", "frozen<address>"),
+        ("list<tuple<int>>", "list<frozen<tuple<int>>>"),
+        ("set<address>", "set<frozen<address>>"),
+        (
+            "map<frozen<address>, list<int>>",
+            "map<frozen<address>, frozen<list<int>>>",
+        ),
+        (
+            "tuple<int, list<int>, ks.address>",
+            "frozen<tuple<int, frozen<list<int>>, frozen<ks.address>>>",
+        ),
+        (
+            "frozen<map<text, list<tuple<int>>>>",
+            "frozen<map<text, frozen<list<frozen<tuple<int>>>>>>",
+        ),
+        ("frozen<frozen<list<int>>>", "frozen<list<int>>"),
+    ];
+    for (declared, reported) in test_cases.iter() {
+        let declared_type = Parser::parse_cql_type_only(declared).unwrap();
+        let reported_type = Parser::parse_cql_type_only(reported).unwrap();
+        assert_eq!(declared_type.canonicalize(), reported_type, "{}", declared);
+        assert_eq!(reported_type.canonicalize(), reported_type, "{}", reported);
+    }
+}
+
+#[test]
+fn test_custom_types() {
+    let cql = "CREATE TABLE t (
+    k int PRIMARY KEY,
+    v 'org.apache.cassandra.db.marshal.UTF8Type',
+    c 'org.apache.cassandra.db.marshal.DynamicCompositeType(s=>UTF8Type,i=>ReversedType(Int32Type))'
+)";
+    let data_types: Vec<_> = match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::CreateTable(create_table)) => create_table
+            .column_definitions
+            .into_iter()
+            .map(|definition| definition.data_type)
+            .collect(),
+        other => panic!("unexpected result: {:?}", other),
+    };
+    assert_eq!(
+        data_types[1],
+        CqlType::Custom(CustomType {
+            class_name: "org.apache.cassandra.db.marshal.UTF8Type".to_owned(),
+            parameters: None,
+        })
+    );
+    assert_eq!(
+        data_types[2],
+        CqlType::Custom(CustomType {
+            class_name: "org.apache.cassandra.db.marshal.DynamicCompositeType".to_owned(),
+            parameters: Some("s=>UTF8Type,i=>ReversedType(Int32Type)".to_owned()),
+        })
+    );
+    if let CqlType::Custom(custom_type) = &data_types[2] {
+        assert_eq!(custom_type.simple_class_name(), "DynamicCompositeType");
+    }
+
+    let test_cases = [
+        "'org.apache.cassandra.db.marshal.UTF8Type'",
+        "'org.apache.cassandra.db.marshal.DynamicCompositeType(s=>UTF8Type)'",
+        "'MyType'",
+        "'com.example.Type''s(a)'",
+        // not parameters unless closed at the end
+        "'com.example.Type(a'",
+        "'com.example.Type(a) b'",
+        "list<'com.example.Type'>",
+    ];
+    for cql in test_cases.iter() {
+        let cql_type = Parser::parse_cql_type_only(cql).unwrap();
+        assert_eq!(cql_type.to_string(), *cql);
+    }
+    let custom_type = CustomType::parse("com.example.Type(a) b");
+    assert_eq!(custom_type.class_name, "com.example.Type(a) b");
+    assert_eq!(custom_type.parameters, None);
+}
+
+#[test]
+fn test_type_warnings() {
+    let cql = "CREATE TABLE t (
+    k int PRIMARY KEY,
+    c counter,
+    l list<counter>,
+    a list<address>,
+    m map<text, list<int>>,
+    f list<frozen<address>>,
+    t tuple<int, list<int>>
+);
+CREATE TYPE address (street text, location point, zip frozen<zip_code>);
+ALTER TYPE address ADD tags set<frozen<tag>>";
+    let (statements, warnings) = Parser::new(cql).parse_with_warnings().unwrap();
+    assert_eq!(statements.len(), 3);
+    let warnings: Vec<_> = warnings
+        .iter()
+        .map(|w| (w.kind, &cql[w.span.range()], w.to_string()))
+        .collect();
+    assert_eq!(
+        warnings,
+        [
+            (
+                WarningKind::CounterInCollection,
+                "list<counter>",
+                "Counters are not allowed inside collections or tuples: list<counter>".to_owned()
+            ),
+            (
+                WarningKind::NonFrozenNestedType,
+                "list<address>",
+                "Non-frozen user types are not allowed inside collections: list<address>"
+                    .to_owned()
+            ),
+            (
+                WarningKind::NonFrozenNestedType,
+                "map<text, list<int>>",
+                "Non-frozen collections are not allowed inside collections: map<text, list<int>>"
+                    .to_owned()
+            ),
+            (
+                WarningKind::NonFrozenNestedType,
+                "point",
+                "A user type cannot contain non-frozen user types: point".to_owned()
+            ),
+        ]
+    );
+
+    // no warnings are returned when parsing fails
+    let cql = "CREATE TABLE t (k int PRIMARY KEY, l list<counter>, m map<int>)";
+    assert!(Parser::new(cql).parse_with_warnings().is_err());
+}
+
+#[test]
+fn test_parse_warnings() {
+    let cql = "SELECT * FROM t WHERE v = 1 ALLOW FILTERING;
+CREATE TABLE t (k int PRIMARY KEY, v int) WITH COMPACT STORAGE AND comment = 'x';
+SELECT * FROM t WHERE k IN ( );
+BEGIN BATCH
+    INSERT INTO t (k, v) VALUES (1, 1) IF NOT EXISTS;
+    UPDATE t SET v = 2 WHERE k = 1;
+APPLY BATCH;
+SELECT * FROM t WHERE k IN (1, 2) LIMIT 10";
+    let (statements, warnings) = Parser::new(cql).parse_with_warnings().unwrap();
+    assert_eq!(statements.len(), 5);
+    let warnings: Vec<_> = warnings
+        .iter()
+        .map(|w| (w.kind, &cql[w.span.range()]))
+        .collect();
+    assert_eq!(
+        warnings,
+        [
+            (WarningKind::AllowFiltering, "ALLOW FILTERING"),
+            (WarningKind::CompactStorage, "COMPACT STORAGE"),
+            (WarningKind::EmptyIn, "( )"),
+            (
+                WarningKind::ConditionalBatch,
+                "INSERT INTO t (k, v) VALUES (1, 1) IF NOT EXISTS"
+            ),
+        ]
+    );
+
+    // `parse` accepts the same statements without warnings
+    assert_eq!(Parser::new(cql).parse().unwrap(), statements);
+}
+
+#[test]
+fn test_materialized_view_select() {
+    let view = |cql: &str| match Parser::new(cql).parse_with_warnings() {
+        Ok((mut statements, warnings)) => match statements.remove(0) {
+            CqlStatement::CreateMaterializedView(view) => (view, warnings),
+            statement => panic!("unexpected statement: {:?}", statement),
+        },
+        Err(e) => panic!("failed to parse {}: {}", cql, e),
+    };
+
+    let (valid, warnings) = view(
+        "CREATE MATERIALIZED VIEW ks.by_day AS
+         SELECT day, sensor, \"Value\" FROM ks.readings
+         WHERE day IS NOT NULL AND sensor IS NOT NULL AND \"Value\" > 0
+         PRIMARY KEY (day, sensor)",
+    );
+    assert!(warnings.is_empty());
+    assert_eq!(
+        valid.selected_columns(),
+        Some(vec!["day", "sensor", "Value"])
+    );
+    assert_eq!(valid.not_null_columns(), ["day", "sensor"]);
+
+    let (wildcard, _) = view("CREATE MATERIALIZED VIEW v AS SELECT * FROM t PRIMARY KEY (k)");
+    assert_eq!(wildcard.selected_columns(), None);
+    assert!(wildcard.not_null_columns().is_empty());
+
+    let cql = "CREATE MATERIALIZED VIEW v AS SELECT k, writetime(v), c AS d FROM t
+               WHERE k IS NOT NULL PRIMARY KEY (k)";
+    let (functions, warnings) = view(cql);
+    assert_eq!(functions.selected_columns(), Some(vec!["k", "c"]));
+    let warnings: Vec<_> = warnings
+        .iter()
+        .map(|w| (w.kind, &cql[w.span.range()], w.to_string()))
+        .collect();
+    assert_eq!(
+        warnings,
+        [
+            (
+                WarningKind::InvalidViewSelect,
+                "writetime(v)",
+                "Can only select columns by name when defining a materialized view: writetime(v)"
+                    .to_owned()
+            ),
+            (
+                WarningKind::InvalidViewSelect,
+                "c AS d",
+                "Cannot use aliases when defining a materialized view: c AS d".to_owned()
+            ),
+        ]
+    );
+
+    // without OR, but with relations other than single column ones
+    let cql = "CREATE MATERIALIZED VIEW v AS SELECT * FROM t
+               WHERE k IS NOT NULL AND c IN (1, 2) AND token(k) > 0 AND (c, d) > (1, 2)
+               AND expr(idx, 'x') PRIMARY KEY (c, k)";
+    let (complex, warnings) = view(cql);
+    assert_eq!(complex.not_null_columns(), ["k"]);
+    let where_clause = "k IS NOT NULL AND c IN (1, 2) AND token(k) > 0 AND (c, d) > (1, 2)
+               AND expr(idx, 'x')";
+    let warnings: Vec<_> = warnings
+        .iter()
+        .map(|w| (w.kind, &cql[w.span.range()], w.to_string()))
+        .collect();
+    assert_eq!(
+        warnings,
+        [
+            (
+                WarningKind::InvalidViewSelect,
+                where_clause,
+                "Cannot use token relations when defining a materialized view: token(k) > 0"
+                    .to_owned()
+            ),
+            (
+                WarningKind::InvalidViewSelect,
+                where_clause,
+                "Cannot use multi-column relations when defining a materialized view: (c, d) > (1, 2)"
+                    .to_owned()
+            ),
+            (
+                WarningKind::InvalidViewSelect,
+                where_clause,
+                "Cannot use custom index expressions when defining a materialized view: expr(idx, 'x')"
+                    .to_owned()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_parser_options() {
+    // deeply nested parentheses should not overflow the stack
+    let cql = format!(
+        "SELECT * FROM test WHERE a = {}1{}",
+        "(".repeat(100_000),
+        ")".repeat(100_000)
+    );
+    let error = Parser::new(&cql).parse().unwrap_err();
+    assert_eq!(error.message(), "Nested too deeply (max depth: 128)");
+
+    let nested_type = format!("{}int{}", "list<".repeat(100_000), ">".repeat(100_000));
+    assert!(Parser::parse_cql_type_only(&nested_type).is_err());
+
+    // within the limit
+    let cql = format!(
+        "SELECT * FROM test WHERE a = {}1{}",
+        "(".repeat(100),
+        ")".repeat(100)
+    );
+    assert!(Parser::new(&cql).parse().is_ok());
+
+    // long chains of operators are parsed in a loop, but the tree is as deep as the chain,
+    // which should not overflow the stack when dropped
+    let test_cases = [
+        format!("SELECT {} FROM t", vec!["a"; 100_000].join(" + ")),
+        format!("SELECT a{} FROM t", "[1]".repeat(100_000)),
+        // chains in operands add up
+        format!(
+            "SELECT a + {} + {} FROM t",
+            vec!["b"; 6_000].join(" * "),
+            vec!["c"; 6_000].join(" + ")
+        ),
+    ];
+    for cql in test_cases {
+        let error = thread::Builder::new()
+            .stack_size(2 * 1024 * 1024)
+            .spawn(move || Parser::new(&cql).parse().unwrap_err())
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(
+            error.message(),
+            "Operators chained too deeply (max depth: 10000)"
+        );
+    }
+    let options = ParserOptions {
+        max_operator_depth: 2,
+        ..ParserOptions::default()
+    };
+    let parse = |cql| Parser::with_options(cql, options).parse();
+    assert!(parse("SELECT a + b + c, f(a + b, c * d) FROM t").is_ok());
+    assert!(parse("SELECT * FROM t WHERE a = b + c AND d = e * f").is_ok());
+    let error = parse("SELECT a + b + c + d FROM t").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "1:18: Operators chained too deeply (max depth: 2)"
+    );
+    assert!(parse("SELECT a + b * c * d FROM t").is_err());
+    assert!(parse("SELECT m[0][1][2] FROM t").is_err());
+
+    let options = ParserOptions {
+        max_selectors: 2,
+        max_statements: 2,
+        ..ParserOptions::default()
+    };
+    assert!(Parser::with_options("SELECT a, b FROM test", options)
+        .parse()
+        .is_ok());
+    let error = Parser::with_options("SELECT a, b, c FROM test", options)
+        .parse()
+        .unwrap_err();
+    assert_eq!(error.to_string(), "1:14: Too many selectors (max: 2)");
+
+    let cql = "SELECT * FROM t1; SELECT * FROM t2; SELECT * FROM t3; SELECT * FROM t4;";
+    let error = Parser::with_options(cql, options).parse().unwrap_err();
+    assert_eq!(error.to_string(), "1:37: Too many statements (max: 2)");
+    let (statements, errors) = Parser::with_options(cql, options).parse_lenient();
+    assert_eq!(statements.len(), 2);
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn test_parse_with_version() {
+    let cql = "SELECT * FROM test PER PARTITION LIMIT 1";
+    assert!(Parser::new(cql).parse().is_ok());
+    assert!(Parser::with_version(cql, CqlVersion::V3_6).parse().is_ok());
+    let error = Parser::with_version(cql, CqlVersion::V3_0)
+        .parse()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "1:20: PER PARTITION LIMIT is not supported in Cassandra 3.0"
+    );
+
+    let cql = "CREATE TABLE test (id int PRIMARY KEY, value text) WITH COMPACT STORAGE";
+    assert!(Parser::new(cql).parse().is_ok());
+    assert!(Parser::with_version(cql, CqlVersion::V3_0).parse().is_ok());
+    let error = Parser::with_version(cql, CqlVersion::V4_0)
+        .parse()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "1:57: COMPACT STORAGE is not supported in Cassandra 4.0\n  in CREATE TABLE test"
+    );
+}
+
+#[test]
+fn test_compact_storage() {
+    let test_cases = [
+        "CREATE TABLE t (k int PRIMARY KEY, v int) WITH COMPACT STORAGE AND comment = 'x'",
+        "CREATE TABLE t (k int PRIMARY KEY, v int) WITH comment = 'x' AND COMPACT STORAGE",
+        "CREATE TABLE t (k int PRIMARY KEY, v int) WITH comment = 'x' AND COMPACT STORAGE AND gc_grace_seconds = 0",
+    ];
+    for cql in test_cases.iter() {
+        match Parser::new(cql).parse().unwrap().pop() {
+            Some(CqlStatement::CreateTable(table)) => {
+                assert!(table.compact_storage, "{}", cql);
+                assert_eq!(table.table_properties[0].key(), "comment");
+            }
+            other => panic!("unexpected result for {}: {:?}", cql, other),
+        }
+    }
+
+    let cql = "CREATE MATERIALIZED VIEW v AS SELECT * FROM t
+        WHERE k IS NOT NULL PRIMARY KEY (k)
+        WITH COMPACT STORAGE AND comment = 'x'";
+    match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::CreateMaterializedView(view)) => {
+            assert!(view.compact_storage);
+            assert_eq!(view.view_properties.len(), 1);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_malformed_table_options() {
+    let test_cases = [
+        (
+            "CREATE TABLE t (k int PRIMARY KEY) WITH bogus !! garbage",
+            "1:47: expected '=', found '!' (invalid character)\n  in CREATE TABLE t",
+        ),
+        (
+            "CREATE TABLE t (k int PRIMARY KEY) WITH comment 'x'",
+            "1:49: expected '=', found 'x'\n  in CREATE TABLE t",
+        ),
+        (
+            "CREATE TABLE t (k int PRIMARY KEY) WITH comment = 'x' AND",
+            "1:58: expected COMPACT STORAGE, CLUSTERING ORDER BY or property, found end of input\n  in CREATE TABLE t",
+        ),
+        (
+            "CREATE TABLE t (k int PRIMARY KEY) WITH comment = 'a' AND comment = 'b'",
+            "1:59: Multiple definitions for property comment\n  in CREATE TABLE t",
+        ),
+        (
+            "CREATE TABLE t (k int PRIMARY KEY) WITH COMPACT STORAGE AND COMPACT STORAGE",
+            "1:61: COMPACT STORAGE is specified more than once\n  in CREATE TABLE t",
+        ),
+        (
+            "CREATE TABLE t (k int, c int, PRIMARY KEY (k, c)) WITH CLUSTERING ORDER BY (c UP)",
+            "1:79: expected ASC or DESC, found 'UP'\n  in CREATE TABLE t",
+        ),
+    ];
+    for (cql, message) in test_cases.iter() {
+        let error = Parser::new(cql).parse().unwrap_err();
+        assert_eq!(&error.to_string(), message, "{}", cql);
+    }
+}
+
+#[test]
+fn test_primary_key() {
+    let cql = "CREATE TABLE t (a int, b int, c int, d int, e text, PRIMARY KEY ((a, b), c, d))";
+    match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::CreateTable(table)) => {
+            let a_b: [Identifier; 2] = ["a".into(), "b".into()];
+            let c_d: [Identifier; 2] = ["c".into(), "d".into()];
+            assert_eq!(table.primary_key(), Some((&a_b[..], &c_d[..])));
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let error = Parser::new("CREATE TABLE t (a int PRIMARY KEY, b int, PRIMARY KEY (b))")
+        .parse()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "1:43: PRIMARY KEY is defined more than once in table t\n  in CREATE TABLE t"
+    );
+
+    let error = Parser::new("CREATE TABLE t (a int, b int)")
+        .parse()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "1:29: No PRIMARY KEY specified for table t\n  in CREATE TABLE t"
+    );
+}
+
+#[test]
+fn test_column_mask() {
+    let cql = "CREATE TABLE t (
+        id int PRIMARY KEY,
+        name text MASKED WITH DEFAULT,
+        email text MASKED WITH mask_inner(2, 2),
+        ssn text)";
+    match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::CreateTable(table)) => {
+            let masks: Vec<_> = table
+                .column_definitions
+                .iter()
+                .map(|column| column.mask.as_ref())
+                .collect();
+            assert_eq!(
+                masks,
+                vec![
+                    None,
+                    Some(&ColumnMask::Default),
+                    Some(&ColumnMask::Function(
+                        ExpressionKind::Function {
+                            name: QualifiedName::new(None, "mask_inner".to_owned()),
+                            args: vec![
+                                ExpressionKind::Value(Literal::Constant(Constant::Integer(2)))
+                                    .into(),
+                                ExpressionKind::Value(Literal::Constant(Constant::Integer(2)))
+                                    .into(),
+                            ],
+                        }
+                        .into()
+                    )),
+                    None,
+                ]
+            );
+            assert!(table.column_definitions[0].is_primary_key);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let cql = "CREATE TABLE t (id int PRIMARY KEY, v text MASKED WITH 'x')";
+    let error = Parser::new(cql).parse().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "1:56: expected DEFAULT or masking function, found 'x'\n  in column definition v\n  in CREATE TABLE t"
+    );
+    let cql = "CREATE TABLE t (id int PRIMARY KEY, v text MASKED WITH DEFAULT)";
+    assert!(Parser::with_version(cql, CqlVersion::V5_0).parse().is_ok());
+    assert!(Parser::with_version(cql, CqlVersion::V4_0).parse().is_err());
+}
+
+#[test]
+fn test_column_definitions() {
+    let cql =
+        "CREATE TABLE t (k int, c int, s1 text STATIC, s2 int static, v int, PRIMARY KEY (k, c))";
+    match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::CreateTable(table)) => {
+            assert_eq!(table.static_columns().collect::<Vec<_>>(), ["s1", "s2"]);
+            assert_eq!(
+                table.column_definitions[2],
+                ColumnDefinition {
+                    name: "s1".into(),
+                    data_type: CqlType::Native(NativeDataType::Text),
+                    is_static: true,
+                    is_primary_key: false,
+                    mask: None,
+                }
+            );
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_keyword_and_quoted_column_names() {
+    let test_cases = [
+        (
+            "CREATE TABLE t (\"text\" text, \"int\" int, PRIMARY KEY (\"text\"))",
+            &["text", "int"][..],
+            &[&["text"][..]][..],
+            &[][..],
+            &[][..],
+        ),
+        (
+            "CREATE TABLE t (text text, frozen int, list int, PRIMARY KEY ((text, frozen), list)) \
+             WITH CLUSTERING ORDER BY (list DESC)",
+            &["text", "frozen", "list"],
+            &[&["text", "frozen"]],
+            &["list"],
+            &[("list", false)],
+        ),
+        (
+            "CREATE TABLE t (\"Key\" int, \"Frozen\" frozen<list<int>>, \"select\" int, \
+             PRIMARY KEY (\"Key\", \"select\")) WITH CLUSTERING ORDER BY (\"select\" ASC)",
+            &["Key", "Frozen", "select"],
+            &[&["Key"]],
+            &["select"],
+            &[("select", true)],
+        ),
+        (
+            "CREATE TABLE t (Key int, Clu int, PRIMARY KEY (\"key\", CLU)) \
+             WITH CLUSTERING ORDER BY (\"clu\" DESC)",
+            &["key", "clu"],
+            &[&["key"]],
+            &["clu"],
+            &[("clu", false)],
+        ),
+        (
+            "CREATE TABLE t (frozen int PRIMARY KEY, static int STATIC)",
+            &["frozen", "static"],
+            &[&["frozen"]],
+            &[],
+            &[],
+        ),
+    ];
+    for (cql, columns, partition_keys, clustering_columns, clustering_order) in test_cases.iter() {
+        let table = match Parser::new(cql).parse().unwrap().pop() {
+            Some(CqlStatement::CreateTable(table)) => table,
+            other => panic!("unexpected result for {}: {:?}", cql, other),
+        };
+        let names: Vec<_> = table
+            .column_definitions
+            .iter()
+            .map(|c| c.name.to_string())
+            .collect();
+        assert_eq!(&names, columns, "{}", cql);
+        let keys: Vec<Vec<&str>> = table
+            .partition_keys
+            .iter()
+            .map(|keys| keys.iter().map(AsRef::as_ref).collect())
+            .collect();
+        assert_eq!(keys, *partition_keys, "{}", cql);
+        let clustering: Vec<&str> = table.clustering_columns.iter().map(AsRef::as_ref).collect();
+        assert_eq!(clustering, *clustering_columns, "{}", cql);
+        let order: Vec<_> = table
+            .clustering_order
+            .iter()
+            .map(|(column, asc)| (column.as_ref(), *asc))
+            .collect();
+        assert_eq!(&order, clustering_order, "{}", cql);
+
+        // names are quoted as needed when written back
+        let reparsed = Parser::new(&table.to_string()).parse().unwrap().pop();
+        assert_eq!(reparsed, Some(CqlStatement::CreateTable(table)), "{}", cql);
+    }
+
+    let test_cases = [
+        (
+            "CREATE TABLE t (k int PRIMARY KEY, set int)",
+            "1:36: expected column name, found 'set'\n  in CREATE TABLE t",
+        ),
+        (
+            "CREATE TABLE t (\"select\" int, PRIMARY KEY (select))",
+            "1:44: expected column name, found 'select'\n  in CREATE TABLE t",
+        ),
+        (
+            "CREATE TABLE t (k int, c int, PRIMARY KEY (k, ))",
+            "1:47: expected column name, found ')'\n  in CREATE TABLE t",
+        ),
+    ];
+    for (cql, message) in test_cases.iter() {
+        let error = Parser::new(cql).parse().unwrap_err();
+        assert_eq!(&error.to_string(), message, "{}", cql);
+    }
+}
+
+#[test]
+fn test_preserve_comments() {
+    let cql = "-- users of the service
+// keyed by id
+CREATE TABLE users (id uuid PRIMARY KEY); -- partition by id
+/* multi-line
+   comment */
+SELECT * FROM users /* inside */ WHERE id = 1;
+INSERT INTO users (id) VALUES (1); // last";
+    let options = ParserOptions {
+        preserve_comments: true,
+        ..ParserOptions::default()
+    };
+    let statements = Parser::with_options(cql, options).parse_spanned().unwrap();
+    assert_eq!(statements.len(), 3);
+    assert_eq!(
+        statements[0].leading_comments,
+        ["-- users of the service", "// keyed by id"]
+    );
+    assert_eq!(
+        statements[0].trailing_comment.as_deref(),
+        Some("-- partition by id")
+    );
+    assert_eq!(
+        statements[1].leading_comments,
+        ["/* multi-line\n   comment */"]
+    );
+    assert_eq!(statements[1].trailing_comment, None);
+    assert!(statements[2].leading_comments.is_empty());
+    assert_eq!(statements[2].trailing_comment.as_deref(), Some("// last"));
+
+    // comments are dropped by default, and do not change the parsed statements
+    let default = Parser::new(cql).parse_spanned().unwrap();
+    assert!(default[0].leading_comments.is_empty());
+    assert_eq!(default[0].trailing_comment, None);
+    assert_eq!(default, statements);
+}
+
+#[test]
+fn test_parse_with_spans() {
+    let cql = "  select A ,b FROM Ks.Tbl   WHERE k = 'x;y' ;
+-- comment
+INSERT INTO tbl (a, b)
+  VALUES (1, {'k': 1, 'l': 2});UPDATE tbl
+  SET a = 1 WHERE k = 1;
+  CREATE TABLE t (k int PRIMARY KEY) ";
+    let statements = Parser::new(cql).parse_with_spans().unwrap();
+    let slices = statements
+        .iter()
+        .map(|(range, _)| &cql[range.clone()])
+        .collect::<Vec<_>>();
+    assert_eq!(
+        slices,
+        [
+            "select A ,b FROM Ks.Tbl   WHERE k = 'x;y' ;",
+            "INSERT INTO tbl (a, b)\n  VALUES (1, {'k': 1, 'l': 2});",
+            "UPDATE tbl\n  SET a = 1 WHERE k = 1;",
+            "CREATE TABLE t (k int PRIMARY KEY)",
+        ]
+    );
+    for (range, statement) in statements {
+        let mut reparsed = Parser::new(&cql[range]).parse().unwrap();
+        assert_eq!(reparsed.len(), 1);
+        assert_eq!(reparsed.pop().unwrap(), statement);
+    }
+}
+
+#[test]
+fn test_qualified_name_round_trip() {
+    let test_cases = [
+        // (input, keyspace, name, keyspace_quoted, name_quoted, displayed)
+        ("tbl", None, "tbl", false, false, "tbl"),
+        ("Ks.Tbl", Some("ks"), "tbl", false, false, "ks.tbl"),
+        (
+            "\"Ks\".\"Tbl\"",
+            Some("Ks"),
+            "Tbl",
+            true,
+            true,
+            "\"Ks\".\"Tbl\"",
+        ),
+        ("ks.\"Tbl\"", Some("ks"), "Tbl", false, true, "ks.\"Tbl\""),
+        ("\"ks\".tbl", Some("ks"), "tbl", true, false, "ks.tbl"),
+        ("\"a\"\"b\"", None, "a\"b", false, true, "\"a\"\"b\""),
+        (
+            "\"my table\"",
+            None,
+            "my table",
+            false,
+            true,
+            "\"my table\"",
+        ),
+        ("\"select\"", None, "select", false, true, "\"select\""),
+        ("ks.key", Some("ks"), "key", false, false, "ks.key"),
+        ("\"1st\"", None, "1st", false, true, "\"1st\""),
+    ];
+    for (input, keyspace, name, keyspace_quoted, name_quoted, displayed) in test_cases {
+        let parsed: QualifiedName = input.parse().unwrap();
+        assert_eq!(parsed.keyspace.as_deref(), keyspace, "{}", input);
+        assert_eq!(&*parsed.name, name, "{}", input);
+        assert_eq!(parsed.keyspace_quoted, keyspace_quoted, "{}", input);
+        assert_eq!(parsed.name_quoted, name_quoted, "{}", input);
+        assert_eq!(parsed.to_string(), displayed);
+        assert_eq!(displayed.parse::<QualifiedName>().unwrap(), parsed);
+    }
+
+    for input in ["", "ks.", "ks.tbl.col", "select", "ks tbl"] {
+        assert!(input.parse::<QualifiedName>().is_err(), "{}", input);
+    }
+}
+
+#[test]
+fn test_preserve_identifier_case() {
+    let cql = "SELECT ColA, \"ColB\" FROM MyKs.\"MyTable\" WHERE ColA = 1";
+    let options = ParserOptions {
+        preserve_identifier_case: true,
+        ..ParserOptions::default()
+    };
+    let (default, preserved) = match (
+        Parser::new(cql).parse().unwrap().pop(),
+        Parser::with_options(cql, options).parse().unwrap().pop(),
+    ) {
+        (Some(CqlStatement::Select(default)), Some(CqlStatement::Select(preserved))) => {
+            (default, preserved)
+        }
+        _ => panic!("SELECT statement expected"),
+    };
+
+    let selectors = |select: &SelectStatement| match &select.projection {
+        Projection::Selectors(selectors) => selectors
+            .iter()
+            .map(|s| match &s.selectable().kind {
+                ExpressionKind::Identifier(name) => name.to_string(),
+                _ => panic!("identifier expected"),
+            })
+            .collect::<Vec<_>>(),
+        _ => panic!("selectors expected"),
+    };
+    assert_eq!(selectors(&default), ["cola", "ColB"]);
+    assert_eq!(selectors(&preserved), ["ColA", "\"ColB\""]);
+    assert_eq!(
+        selectors(&preserved)
+            .iter()
+            .map(|s| normalize_identifier(s))
+            .collect::<Vec<_>>(),
+        selectors(&default)
+    );
+
+    assert_eq!(default.table_name.keyspace.as_deref(), Some("myks"));
+    assert_eq!(&*default.table_name.name, "MyTable");
+    assert_eq!(preserved.table_name.keyspace.as_deref(), Some("MyKs"));
+    assert_eq!(&*preserved.table_name.name, "MyTable");
+    assert_eq!(preserved.table_name.to_string(), "MyKs.\"MyTable\"");
+    assert_eq!(
+        preserved.table_name.normalized_keyspace(),
+        default.table_name.normalized_keyspace()
+    );
+    assert_eq!(
+        preserved.table_name.normalized_name(),
+        default.table_name.normalized_name()
+    );
+
+    let where_clause = preserved.selection.expect("WHERE clause expected");
+    assert_eq!(
+        where_clause.relations()[0].columns().collect::<Vec<_>>(),
+        ["ColA"]
+    );
+}
+
+#[test]
+fn test_resolve_use_keyspace() {
+    let cql = "CREATE TABLE before (k int PRIMARY KEY);
+USE ks1;
+CREATE TYPE address (street text);
+CREATE TABLE users (k int PRIMARY KEY, a frozen<address>, b frozen<other.t>);
+SELECT now(), k FROM users WHERE k = 1;
+USE \"Ks2\";
+INSERT INTO ks1.users (k) VALUES (1);
+DROP TABLE users";
+    let options = ParserOptions {
+        resolve_use_keyspace: true,
+        ..ParserOptions::default()
+    };
+    let statements = Parser::with_options(cql, options).parse().unwrap();
+    let names: Vec<_> = statements
+        .iter()
+        .filter_map(|statement| match statement {
+            CqlStatement::CreateTable(table) => Some(&table.name),
+            CqlStatement::CreateType(user_type) => Some(&user_type.name),
+            CqlStatement::Select(select) => Some(&select.table_name),
+            CqlStatement::Insert(insert) => Some(&insert.table),
+            CqlStatement::Drop(DropStatement::Table { name, .. }) => Some(name),
+            _ => None,
+        })
+        .map(|name| {
+            (
+                name.keyspace.as_deref(),
+                &*name.name,
+                name.keyspace_source,
+                name.to_string(),
+            )
+        })
+        .collect();
+    use KeyspaceSource::*;
+    assert_eq!(
+        names,
+        [
+            (None, "before", Explicit, "before".to_owned()),
+            (Some("ks1"), "address", Inherited, "address".to_owned()),
+            (Some("ks1"), "users", Inherited, "users".to_owned()),
+            (Some("ks1"), "users", Inherited, "users".to_owned()),
+            (Some("ks1"), "users", Explicit, "ks1.users".to_owned()),
+            (Some("Ks2"), "users", Inherited, "users".to_owned()),
+        ]
+    );
+    // inherited keyspace is not written
+    assert_eq!(
+        format_statement(&statements[7], &FormatOptions::default()).as_deref(),
+        Some("DROP TABLE users")
+    );
+
+    // types of columns, but not functions
+    let columns = match &statements[3] {
+        CqlStatement::CreateTable(table) => &table.column_definitions,
+        statement => panic!("unexpected statement: {:?}", statement),
+    };
+    assert_eq!(
+        columns[1].data_type,
+        CqlType::Frozen(Box::new(CqlType::UserDefinedType(QualifiedName::new(
+            Some("ks1".to_owned()),
+            "address".to_owned()
+        ))))
+    );
+    assert_eq!(columns[2].data_type.to_string(), "frozen<other.t>");
+    match &statements[4] {
+        CqlStatement::Select(select) => {
+            assert_eq!(select.projection.to_string(), "now(), k");
+        }
+        statement => panic!("unexpected statement: {:?}", statement),
+    }
+
+    // keyspace is not filled by default
+    let statements = Parser::new(cql).parse().unwrap();
+    match &statements[2] {
+        CqlStatement::CreateType(user_type) => assert_eq!(user_type.name.keyspace, None),
+        statement => panic!("unexpected statement: {:?}", statement),
+    }
+}
+
+#[test]
+fn test_table_references() {
+    let test_cases = [
+        (
+            "SELECT * FROM ks.tbl WHERE k = 1",
+            vec!["ks.tbl"],
+            vec!["ks"],
+        ),
+        ("INSERT INTO tbl (k, v) VALUES (1, 2)", vec!["tbl"], vec![]),
+        (
+            "UPDATE ks.tbl SET v = 1 WHERE k = 1",
+            vec!["ks.tbl"],
+            vec!["ks"],
+        ),
+        (
+            "CREATE TABLE \"Ks\".tbl (k int PRIMARY KEY)",
+            vec!["\"Ks\".tbl"],
+            vec!["Ks"],
+        ),
+        ("CREATE INDEX idx ON ks.tbl (v)", vec!["ks.tbl"], vec!["ks"]),
+        (
+            "CREATE MATERIALIZED VIEW ks.mv AS SELECT * FROM ks2.tbl
+             WHERE k IS NOT NULL AND v IS NOT NULL PRIMARY KEY (v, k)",
+            vec!["ks.mv", "ks2.tbl"],
+            vec!["ks", "ks2"],
+        ),
+        (
+            "CREATE MATERIALIZED VIEW ks.mv AS SELECT * FROM ks.tbl
+             WHERE k IS NOT NULL AND v IS NOT NULL PRIMARY KEY (v, k)",
+            vec!["ks.mv", "ks.tbl"],
+            vec!["ks"],
+        ),
+        (
+            "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy'}",
+            vec![],
+            vec!["ks"],
+        ),
+        ("CREATE TYPE ks.address (street text)", vec![], vec![]),
+        ("DROP TYPE ks.address", vec![], vec![]),
+        ("TRUNCATE ks.tbl", vec!["ks.tbl"], vec!["ks"]),
+        ("TRUNCATE TABLE tbl", vec!["tbl"], vec![]),
+        ("DROP TABLE IF EXISTS ks.tbl", vec!["ks.tbl"], vec!["ks"]),
+        ("DROP TABLE tbl", vec!["tbl"], vec![]),
+        ("DROP MATERIALIZED VIEW ks.mv", vec!["ks.mv"], vec!["ks"]),
+        ("DROP INDEX ks.idx", vec![], vec!["ks"]),
+        ("DROP INDEX idx", vec![], vec![]),
+        ("DROP KEYSPACE IF EXISTS ks", vec![], vec!["ks"]),
+        (
+            "ALTER KEYSPACE ks WITH durable_writes = false",
+            vec![],
+            vec!["ks"],
+        ),
+        ("ALTER TABLE ks.tbl ADD v int", vec!["ks.tbl"], vec!["ks"]),
+        ("USE ks", vec![], vec!["ks"]),
+        (
+            "DELETE FROM ks.tbl WHERE k = 1",
+            vec!["ks.tbl"],
+            vec!["ks"],
+        ),
+        (
+            "BEGIN BATCH INSERT INTO ks.a (k) VALUES (1); DELETE FROM ks.b WHERE k = 1; APPLY BATCH",
+            vec!["ks.a", "ks.b"],
+            vec!["ks"],
+        ),
+    ];
+    for (cql, tables, keyspaces) in test_cases {
+        let statement = Parser::new(cql).parse().unwrap().remove(0);
+        assert_eq!(
+            statement
+                .table_references()
+                .iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>(),
+            tables,
+            "{}",
+            cql
+        );
+        assert_eq!(statement.keyspace_references(), keyspaces, "{}", cql);
+    }
+}
+
+#[test]
+fn test_bind_markers() {
+    let markers = |cql: &str| {
+        Parser::new(cql)
+            .parse()
+            .unwrap()
+            .remove(0)
+            .bind_markers()
+            .into_iter()
+            .map(|m| (m.name, m.clause))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        markers("INSERT INTO t (a,b) VALUES (?, :b) USING TTL ?"),
+        [
+            (None, BindMarkerClause::Values),
+            (Some("b".to_owned()), BindMarkerClause::Values),
+            (None, BindMarkerClause::Using),
+        ]
+    );
+    assert_eq!(
+        markers("SELECT a, f(?) FROM t WHERE k = :k AND c > ? PER PARTITION LIMIT ? LIMIT :l"),
+        [
+            (None, BindMarkerClause::Projection),
+            (Some("k".to_owned()), BindMarkerClause::Where),
+            (None, BindMarkerClause::Where),
+            (None, BindMarkerClause::Limit),
+            (Some("l".to_owned()), BindMarkerClause::Limit),
+        ]
+    );
+    assert_eq!(
+        markers("UPDATE t USING TIMESTAMP :ts AND TTL ? SET a = ?, b = :b WHERE k = ? IF c = ?"),
+        [
+            (Some("ts".to_owned()), BindMarkerClause::Using),
+            (None, BindMarkerClause::Using),
+            (None, BindMarkerClause::Assignment),
+            (Some("b".to_owned()), BindMarkerClause::Assignment),
+            (None, BindMarkerClause::Where),
+            (None, BindMarkerClause::Condition),
+        ]
+    );
+    assert_eq!(markers("SELECT * FROM t WHERE k = 1 LIMIT 10"), []);
+}
+
+#[test]
+fn test_statement_classification() {
+    // (cql, ddl, dml, query, authz, conditional)
+    let test_cases = [
+        ("SELECT * FROM t", false, false, true, false, false),
+        (
+            "INSERT INTO t (k) VALUES (1)",
+            false,
+            true,
+            false,
+            false,
+            false,
+        ),
+        (
+            "INSERT INTO t (k) VALUES (1) IF NOT EXISTS",
+            false,
+            true,
+            false,
+            false,
+            true,
+        ),
+        (
+            "UPDATE t SET v = 1 WHERE k = 1",
+            false,
+            true,
+            false,
+            false,
+            false,
+        ),
+        (
+            "UPDATE t SET v = 1 WHERE k = 1 IF v = 0",
+            false,
+            true,
+            false,
+            false,
+            true,
+        ),
+        (
+            "CREATE TABLE IF NOT EXISTS t (k int PRIMARY KEY)",
+            true,
+            false,
+            false,
+            false,
+            false,
+        ),
+        ("CREATE INDEX ON t (v)", true, false, false, false, false),
+        ("TRUNCATE t", false, true, false, false, false),
+    ];
+    for (cql, ddl, dml, query, authz, conditional) in test_cases {
+        let statement = Parser::new(cql).parse().unwrap().remove(0);
+        assert_eq!(statement.is_ddl(), ddl, "{}", cql);
+        assert_eq!(statement.modifies_schema(), ddl, "{}", cql);
+        assert_eq!(statement.is_dml(), dml, "{}", cql);
+        assert_eq!(statement.is_query(), query, "{}", cql);
+        assert_eq!(statement.is_authz(), authz, "{}", cql);
+        assert_eq!(statement.is_conditional(), conditional, "{}", cql);
+    }
+
+    // statements not parsed yet
+    assert!(CqlStatement::Auth(AuthStatement::CreateRole).is_authz());
+    assert!(CqlStatement::Drop(DropStatement::Function).is_ddl());
+    let use_statement = CqlStatement::Use("ks".to_owned());
+    assert!(!use_statement.is_ddl() && !use_statement.is_dml() && !use_statement.is_authz());
+}
+
+#[test]
+fn test_where_clause_relations() {
+    let int = |i| ExpressionKind::Value(Literal::Constant(Constant::Integer(i))).into();
+    let where_clause = |cql: &str| match Parser::new(cql).parse().unwrap().remove(0) {
+        CqlStatement::Select(select) => select.selection.expect("WHERE clause expected"),
+        _ => panic!("SELECT statement expected"),
+    };
+
+    let relations = where_clause("SELECT * FROM t WHERE a = 1 AND b IN (1,2) AND token(pk) > 0");
+    assert_eq!(
+        relations.relations(),
+        [
+            Relation::SingleColumn {
+                column: "a".into(),
+                operator: Operator::Equal,
+                value: int(1),
+            },
+            Relation::SingleColumn {
+                column: "b".into(),
+                operator: Operator::In,
+                value: ExpressionKind::Value(Literal::ConstantList(vec![
+                    Constant::Integer(1),
+                    Constant::Integer(2)
+                ]))
+                .into(),
+            },
+            Relation::Token {
+                columns: vec!["pk".into()],
+                operator: Operator::GreaterThan,
+                value: int(0),
+            },
+        ]
+    );
+    assert_eq!(relations.restrictions("b").count(), 1);
+    assert_eq!(relations.restrictions("pk").count(), 1);
+    assert_eq!(relations.restrictions("c").count(), 0);
+
+    let relations = where_clause(
+        "SELECT * FROM t WHERE (c1, c2) >= (1, 2) AND m CONTAINS KEY 'k' AND s CONTAINS 1
+         AND v IN ? AND expr(idx, 'query') AND c1 IN ()",
+    );
+    assert_eq!(
+        relations.relations(),
+        [
+            Relation::MultiColumn {
+                columns: vec!["c1".into(), "c2".into()],
+                operator: Operator::GreaterThanOrEqual,
+                value: ExpressionKind::Value(Literal::Tuple(vec![int(1), int(2)])).into(),
+            },
+            Relation::SingleColumn {
+                column: "m".into(),
+                operator: Operator::ContainsKey,
+                value: ExpressionKind::Value(Literal::Constant(Constant::StringLiteral(
+                    "k".to_owned()
+                )))
+                .into(),
+            },
+            Relation::SingleColumn {
+                column: "s".into(),
+                operator: Operator::Contains,
+                value: int(1),
+            },
+            Relation::SingleColumn {
+                column: "v".into(),
+                operator: Operator::In,
+                value: ExpressionKind::Value(Literal::Binding(None)).into(),
+            },
+            Relation::CustomIndex {
+                index: "idx".to_owned(),
+                value: ExpressionKind::Value(Literal::Constant(Constant::StringLiteral(
+                    "query".to_owned()
+                )))
+                .into(),
+            },
+            Relation::SingleColumn {
+                column: "c1".into(),
+                operator: Operator::In,
+                value: ExpressionKind::Value(Literal::List(vec![])).into(),
+            },
+        ]
+    );
+    assert_eq!(
+        relations
+            .restrictions("c1")
+            .map(|r| r.operator())
+            .collect::<Vec<_>>(),
+        [Some(&Operator::GreaterThanOrEqual), Some(&Operator::In)]
+    );
+
+    // converted back to AND-joined expression
+    assert_eq!(
+        Expression::from(where_clause("SELECT * FROM t WHERE a = 1 AND b > 2")),
+        ExpressionKind::BinaryOp(BinaryOp::new(
+            Box::new(Expression::new(ExpressionKind::BinaryOp(BinaryOp::new(
+                Box::new(Expression::new(ExpressionKind::Identifier("a".into()))),
+                Operator::Equal,
+                Box::new(int(1)),
+            )))),
+            Operator::And,
+            Box::new(Expression::new(ExpressionKind::BinaryOp(BinaryOp::new(
+                Box::new(Expression::new(ExpressionKind::Identifier("b".into()))),
+                Operator::GreaterThan,
+                Box::new(int(2)),
+            )))),
+        ))
+        .into()
+    );
+
+    for cql in [
+        "SELECT * FROM t WHERE a",
+        "SELECT * FROM t WHERE a IS NULL",
+        "SELECT * FROM t WHERE 1 = a",
+        "SELECT * FROM t WHERE a = 1 AND",
+    ] {
+        assert!(Parser::new(cql).parse().is_err(), "{}", cql);
+    }
+}
+
+#[test]
+fn test_where_clause_parentheses() {
+    // relations in parentheses are the same as without them
+    let test_cases = [
+        ("(a = 1)", "a = 1"),
+        ("(a = 1 AND b = 2)", "a = 1 AND b = 2"),
+        (
+            "((a = 1) AND (b > 2)) AND c < 3",
+            "a = 1 AND b > 2 AND c < 3",
+        ),
+        (
+            "a = 1 AND ((c1, c2) > (1, 2) AND token(pk) > 0)",
+            "a = 1 AND (c1, c2) > (1, 2) AND token(pk) > 0",
+        ),
+        (
+            "(expr(idx, 'q') AND a IN (1, 2))",
+            "expr(idx, 'q') AND a IN (1, 2)",
+        ),
+        // columns of multi-column relations
+        ("(a) = (1)", "(a) = (1)"),
+        ("((a, b) = (1, 2))", "(a, b) = (1, 2)"),
+    ];
+    for (relations, expected) in test_cases.iter() {
+        let cql = format!("SELECT * FROM t WHERE {}", relations);
+        let expected = format!("SELECT * FROM t WHERE {}", expected);
+        assert_eq!(
+            Parser::new(&cql).parse().unwrap(),
+            Parser::new(&expected).parse().unwrap(),
+            "{}",
+            cql
+        );
+    }
+    assert!(
+        Parser::new("DELETE FROM t WHERE (k = 1 AND c = 2) IF EXISTS")
+            .parse()
+            .is_ok()
+    );
+
+    let test_cases = [
+        (
+            "SELECT * FROM t WHERE (a = 1",
+            "1:29: expected ')', found end of input",
+        ),
+        (
+            "SELECT * FROM t WHERE (a = 1))",
+            "1:30: expected a CQL statement, found ')'",
+        ),
+        (
+            "SELECT * FROM t WHERE ()",
+            "1:24: expected column name, found ')'",
+        ),
+    ];
+    for (cql, expected) in test_cases.iter() {
+        let error = Parser::new(cql).parse().unwrap_err();
+        assert_eq!(error.to_string(), *expected, "{}", cql);
+    }
+}
+
+#[test]
+fn test_where_clause_or() {
+    // reported at OR
+    let test_cases = [
+        ("SELECT * FROM t WHERE a = 1 OR b = 2", 28),
+        ("SELECT * FROM t WHERE (a = 1 OR b = 2)", 29),
+        ("SELECT * FROM t WHERE a = 1 AND (b = 2 OR c = 3)", 39),
+        ("SELECT * FROM t WHERE a IN (1, 2) OR a = 3", 34),
+        ("SELECT * FROM t WHERE a = (1 OR 2)", 29),
+        ("UPDATE t SET v = 1 WHERE k = 1 OR k = 2", 31),
+        ("DELETE FROM t WHERE k = 1 or k = 2", 26),
+    ];
+    for (cql, offset) in test_cases.iter() {
+        let error = Parser::new(cql).parse().unwrap_err();
+        assert_eq!(
+            error.message(),
+            "OR is not supported in CQL WHERE clauses",
+            "{}",
+            cql
+        );
+        assert_eq!(error.offset(), Some(*offset), "{}", cql);
+    }
+    let error = Parser::parse_expression_only("a = 1 OR b = 2").unwrap_err();
+    assert_eq!(error.message(), "OR is not supported in CQL WHERE clauses");
+}
+
+#[test]
+fn test_display_types_and_operators() {
+    let test_cases = [
+        ("int", "int"),
+        ("TIMEUUID", "timeuuid"),
+        (
+            "map<text, frozen<list<int>>>",
+            "map<text, frozen<list<int>>>",
+        ),
+        (
+            "set<frozen<tuple<int,text,blob>>>",
+            "set<frozen<tuple<int, text, blob>>>",
+        ),
+        (
+            "list<frozen<ks.\"Address\">>",
+            "list<frozen<ks.\"Address\">>",
+        ),
+        ("frozen<map<uuid, address>>", "frozen<map<uuid, address>>"),
+    ];
+    for (cql, expected) in test_cases {
+        let cql_type = Parser::parse_cql_type_only(cql).unwrap();
+        assert_eq!(cql_type.to_string(), expected);
+        assert_eq!(Parser::parse_cql_type_only(expected).unwrap(), cql_type);
+    }
+
+    assert_eq!(
+        CqlType::Custom(CustomType::parse("org.example.Type's")).to_string(),
+        "'org.example.Type''s'"
+    );
+    assert_eq!(NativeDataType::VarInt.to_string(), "varint");
+    assert_eq!(
+        CollectionType::List(Box::new(CqlType::Native(NativeDataType::Int))).to_string(),
+        "list<int>"
+    );
+
+    assert_eq!(Operator::GreaterThanOrEqual.to_string(), ">=");
+    assert_eq!(Operator::NotEqual.to_string(), "!=");
+    assert_eq!(Operator::ContainsKey.to_string(), "CONTAINS KEY");
+    assert_eq!(Operator::IsNot.to_string(), "IS NOT");
+}
+
+#[test]
+fn test_constant_accessors() {
+    assert_eq!(Constant::Integer(42).as_f64(), Some(42.0));
+    assert_eq!(Constant::Float("1.5e3".to_string()).as_f64(), Some(1500.0));
+    assert_eq!(Constant::Float("1e309".to_string()).as_f64(), None);
+    assert!(Constant::NaN.as_f64().unwrap().is_nan());
+    assert_eq!(Constant::Infinity.as_f64(), Some(f64::INFINITY));
+    assert_eq!(Constant::Boolean(true).as_f64(), None);
+
+    assert_eq!(Constant::Integer(i64::MIN).as_i64(), Some(i64::MIN));
+    assert_eq!(Constant::Float("1.0".to_string()).as_i64(), None);
+
+    assert_eq!(
+        Constant::UUID("00000000-0000-0000-0000-0000000000ff".to_string()).as_uuid_bytes(),
+        Some([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff])
+    );
+    assert_eq!(
+        Constant::UUID("not-a-uuid".to_string()).as_uuid_bytes(),
+        None
+    );
+
+    let year = Constant::Duration("P1Y".to_string());
+    assert_eq!(year.as_duration_months(), Some(12));
+    assert_eq!(year.as_duration_days(), Some(0));
+    assert_eq!(year.as_duration_nanos(), Some(0));
+    let hour_and_half = Constant::Duration("1h30m".to_string());
+    assert_eq!(hour_and_half.as_duration_months(), Some(0));
+    assert_eq!(hour_and_half.as_duration_nanos(), Some(5_400_000_000_000));
+    assert_eq!(
+        Constant::Duration("P2W".to_string()).as_duration_days(),
+        Some(14)
+    );
+    assert_eq!(
+        Constant::Duration("300000000y".to_string()).as_duration(),
+        None
+    );
+    assert_eq!(Constant::Integer(1).as_duration(), None);
+}
+
+#[test]
+fn test_index_targets() {
+    fn target(column: &str, quoted: bool, index_type: IndexType) -> IndexTarget {
+        IndexTarget {
+            column: column.into(),
+            quoted,
+            index_type,
+        }
+    }
+    let tests = vec![
+        (
+            "CREATE INDEX ON t (v)",
+            target("v", false, IndexType::Simple),
+        ),
+        (
+            "CREATE INDEX ON t (\"Value\")",
+            target("Value", true, IndexType::Simple),
+        ),
+        (
+            "CREATE INDEX ON t (values)",
+            target("values", false, IndexType::Simple),
+        ),
+        (
+            "CREATE INDEX ON t (\"values\")",
+            target("values", true, IndexType::Simple),
+        ),
+        (
+            "CREATE INDEX ON t (KEYS)",
+            target("keys", false, IndexType::Simple),
+        ),
+        (
+            "CREATE INDEX ON t (\"full\")",
+            target("full", true, IndexType::Simple),
+        ),
+        (
+            "CREATE INDEX ON t (VALUES(m))",
+            target("m", false, IndexType::Values),
+        ),
+        (
+            "CREATE INDEX ON t (KEYS(m))",
+            target("m", false, IndexType::Keys),
+        ),
+        (
+            "CREATE INDEX ON t (ENTRIES(m))",
+            target("m", false, IndexType::KeysAndValues),
+        ),
+        (
+            "CREATE INDEX ON t (FULL(m))",
+            target("m", false, IndexType::Full),
+        ),
+        (
+            "CREATE INDEX ON t (values(\"Keys\"))",
+            target("Keys", true, IndexType::Values),
+        ),
+        (
+            "CREATE INDEX ON t (keys(values))",
+            target("values", false, IndexType::Keys),
+        ),
+    ];
+    for (cql, expected) in tests {
+        match Parser::new(cql).parse().unwrap().pop() {
+            Some(CqlStatement::CreateIndex(index)) => {
+                assert_eq!(index.index_targets, vec![expected], "{}", cql)
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    match Parser::new("CREATE INDEX ON t (a, values(b))")
+        .parse()
+        .unwrap()
+        .pop()
+    {
+        Some(CqlStatement::CreateIndex(index)) => assert_eq!(
+            index.index_targets,
+            vec![
+                target("a", false, IndexType::Simple),
+                target("b", false, IndexType::Values)
+            ]
+        ),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    // ENTRIES and FULL are reserved keywords
+    assert!(Parser::new("CREATE INDEX ON t (full)").parse().is_err());
+    assert!(Parser::new("CREATE INDEX ON t (entries)").parse().is_err());
+    assert!(Parser::new("CREATE INDEX ON t (values())").parse().is_err());
+    assert!(Parser::new("CREATE INDEX ON t (values(m)").parse().is_err());
+}
+
+#[test]
+fn test_negative_constants() {
+    let constant = |c| ExpressionKind::Value(Literal::Constant(c)).into();
+
+    let cql = "INSERT INTO t (k, a, b) VALUES (-1000, -1.5e-3, -b) USING TIMESTAMP -5";
+    let insert = match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::Insert(insert)) => insert,
+        other => panic!("unexpected result: {:?}", other),
+    };
+    let values = match insert.values {
+        InsertMethod::Normal { values, .. } => values,
+        other => panic!("unexpected values: {:?}", other),
+    };
+    assert_eq!(
+        values,
+        vec![
+            constant(Constant::Integer(-1000)),
+            constant(Constant::Float("-1.5e-3".to_owned())),
+            ExpressionKind::UnaryOp(UnaryOp::new(
+                Operator::Minus,
+                Box::new(Expression::new(ExpressionKind::Identifier("b".into()))),
+            ))
+            .into(),
+        ]
+    );
+    assert_eq!(
+        insert.timestamp,
+        Some(Literal::Constant(Constant::Integer(-5)))
+    );
+
+    // rejected by Cassandra when executed, but valid syntax
+    let cql = "ALTER TABLE t WITH crc_check_chance = -0.5 AND default_time_to_live = -1";
+    let alter = match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::AlterTable(alter)) => alter,
+        other => panic!("unexpected result: {:?}", other),
+    };
+    assert_eq!(
+        alter.operation,
+        AlterTableOperation::WithProperties(vec![
+            Property::new(
+                "crc_check_chance".to_owned(),
+                PropertyValue::Constant(Constant::Float("-0.5".to_owned())),
+            ),
+            Property::new(
+                "default_time_to_live".to_owned(),
+                PropertyValue::Constant(Constant::Integer(-1)),
+            ),
+        ])
+    );
+
+    let test_cases = [
+        // `-` separated from the number is a unary minus
+        ("SELECT * FROM t WHERE a = - 1", "a = - 1"),
+        ("SELECT * FROM t WHERE a = -1", "a = -1"),
+        ("SELECT * FROM t WHERE a = 1-1", "a = 1 - 1"),
+        ("SELECT * FROM t WHERE a = - -1", "a = - -1"),
+        (
+            "SELECT * FROM t WHERE a = -9223372036854775808",
+            "a = -9223372036854775808",
+        ),
+    ];
+    for (cql, relation) in test_cases.iter() {
+        let select = match Parser::new(cql).parse().unwrap().pop() {
+            Some(CqlStatement::Select(select)) => select,
+            other => panic!("unexpected result: {:?}", other),
+        };
+        let where_clause = select.selection.unwrap();
+        assert_eq!(&where_clause.to_string(), relation, "{}", cql);
+    }
+
+    let error = Parser::new("SELECT * FROM t LIMIT -9223372036854775809")
+        .parse()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "1:23: Invalid integer -9223372036854775809: number too small to fit in target type"
+    );
+}
+
+#[test]
+fn test_alter_table() {
+    fn alter_table(cql: &str) -> AlterTableStatement {
+        match Parser::new(cql).parse().unwrap().pop() {
+            Some(CqlStatement::AlterTable(alter_table)) => alter_table,
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    let alter = alter_table("ALTER TABLE IF EXISTS ks.t ADD IF NOT EXISTS c int");
+    assert_eq!(alter.name, "ks.t".parse::<QualifiedName>().unwrap());
+    assert!(alter.if_exists);
+    assert_eq!(
+        alter.operation,
+        AlterTableOperation::Add {
+            if_not_exists: true,
+            columns: vec![ColumnDefinition::new(
+                "c".to_string(),
+                CqlType::Native(NativeDataType::Int)
+            )],
+        }
+    );
+
+    // statement-level and operation-level flags are independent
+    let alter = alter_table("ALTER TABLE t ADD IF NOT EXISTS (a int, b text)");
+    assert!(!alter.if_exists);
+    assert!(matches!(
+        alter.operation,
+        AlterTableOperation::Add { if_not_exists: true, ref columns } if columns.len() == 2
+    ));
+    let alter = alter_table("ALTER TABLE IF EXISTS t ADD a int");
+    assert!(alter.if_exists);
+    assert!(matches!(
+        alter.operation,
+        AlterTableOperation::Add {
+            if_not_exists: false,
+            ..
+        }
+    ));
+
+    assert_eq!(
+        alter_table("ALTER TABLE t DROP IF EXISTS c").operation,
+        AlterTableOperation::Drop {
+            if_exists: true,
+            columns: vec!["c".into()],
+            timestamp: None,
+        }
+    );
+    let alter = alter_table("ALTER TABLE IF EXISTS t DROP (a, b) USING TIMESTAMP 1700000000000000");
+    assert!(alter.if_exists);
+    assert_eq!(
+        alter.operation,
+        AlterTableOperation::Drop {
+            if_exists: false,
+            columns: vec!["a".into(), "b".into()],
+            timestamp: Some(1_700_000_000_000_000),
+        }
+    );
+
+    assert_eq!(
+        alter_table("ALTER TABLE t RENAME IF EXISTS a TO b AND c TO d").operation,
+        AlterTableOperation::Rename {
+            if_exists: true,
+            columns: vec![("a".into(), "b".into()), ("c".into(), "d".into())],
+        }
+    );
+    assert_eq!(
+        alter_table("ALTER TABLE t DROP COMPACT STORAGE").operation,
+        AlterTableOperation::DropCompactStorage
+    );
+    assert!(matches!(
+        alter_table("ALTER TABLE t WITH comment = 'c' AND gc_grace_seconds = 0").operation,
+        AlterTableOperation::WithProperties(ref properties) if properties.len() == 2
+    ));
+
+    assert_eq!(
+        Parser::new("ALTER TABLE t ADD a int PRIMARY KEY")
+            .parse()
+            .unwrap_err()
+            .to_string(),
+        "1:19: PRIMARY KEY cannot be added by ALTER TABLE"
+    );
+    assert_eq!(
+        Parser::new("ALTER TABLE t TRUNCATE")
+            .parse()
+            .unwrap_err()
+            .to_string(),
+        "1:15: expected ADD, ALTER, DROP, RENAME or WITH, found 'TRUNCATE'"
+    );
+}
+
+#[test]
+fn test_alter_type() {
+    fn alter_type(cql: &str) -> AlterTypeStatement {
+        match Parser::new(cql).parse().unwrap().pop() {
+            Some(CqlStatement::AlterType(alter_type)) => alter_type,
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    let alter = alter_type("ALTER TYPE IF EXISTS ks.address ADD IF NOT EXISTS zip text");
+    assert!(alter.if_exists);
+    assert_eq!(
+        alter.operation,
+        AlterTypeOperation::Add {
+            if_not_exists: true,
+            field: "zip".to_string(),
+            data_type: CqlType::Native(NativeDataType::Text),
+        }
+    );
+    let alter = alter_type("ALTER TYPE address RENAME IF EXISTS a TO b");
+    assert!(!alter.if_exists);
+    assert_eq!(
+        alter.operation,
+        AlterTypeOperation::Rename {
+            if_exists: true,
+            fields: vec![("a".to_string(), "b".to_string())],
+        }
+    );
+    assert_eq!(
+        alter_type("ALTER TYPE address RENAME a TO b").operation,
+        AlterTypeOperation::Rename {
+            if_exists: false,
+            fields: vec![("a".to_string(), "b".to_string())],
+        }
+    );
+}
+
+#[test]
+fn test_use() {
+    assert_eq!(
+        Parser::new("USE music; use \"Music\"").parse().unwrap(),
+        [
+            CqlStatement::Use("music".to_owned()),
+            CqlStatement::Use("Music".to_owned()),
+        ]
+    );
+    let error = Parser::new("USE").parse().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "1:4: expected keyspace name, found end of input"
+    );
+}
+
+#[test]
+fn test_drop() {
+    let name = |keyspace: Option<&str>, name: &str| {
+        QualifiedName::new(keyspace.map(str::to_owned), name.to_owned())
+    };
+    assert_eq!(
+        Parser::new(
+            "DROP KEYSPACE IF EXISTS \"Music\";
+             DROP TABLE ks.t;
+             DROP INDEX IF EXISTS ks.t_v_idx;
+             DROP TYPE address;
+             DROP MATERIALIZED VIEW IF EXISTS t_by_v"
+        )
+        .parse()
+        .unwrap(),
+        [
+            CqlStatement::Drop(DropStatement::Keyspace {
+                name: "Music".to_owned(),
+                if_exists: true,
+            }),
+            CqlStatement::Drop(DropStatement::Table {
+                name: name(Some("ks"), "t"),
+                if_exists: false,
+            }),
+            CqlStatement::Drop(DropStatement::Index {
+                name: name(Some("ks"), "t_v_idx"),
+                if_exists: true,
+            }),
+            CqlStatement::Drop(DropStatement::Type {
+                name: name(None, "address"),
+                if_exists: false,
+            }),
+            CqlStatement::Drop(DropStatement::MaterializedView {
+                name: name(None, "t_by_v"),
+                if_exists: true,
+            }),
+        ]
+    );
+    let error = Parser::new("DROP TABLE IF EXISTS").parse().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "1:21: expected identifier, found end of input"
+    );
+}
+
+#[test]
+fn test_truncate() {
+    assert_eq!(
+        Parser::new("TRUNCATE t; TRUNCATE TABLE ks.t; TRUNCATE \"Ks\".\"T\"")
+            .parse()
+            .unwrap(),
+        [
+            CqlStatement::Truncate("t".parse().unwrap()),
+            CqlStatement::Truncate("ks.t".parse().unwrap()),
+            CqlStatement::Truncate("\"Ks\".\"T\"".parse().unwrap()),
+        ]
+    );
+    assert_eq!(
+        Parser::new("TRUNCATE TABLE ks.")
+            .parse()
+            .unwrap_err()
+            .to_string(),
+        "1:19: expected identifier, found end of input"
+    );
+}
+
+#[test]
+fn test_alter_keyspace() {
+    assert_eq!(
+        Parser::new("ALTER KEYSPACE IF EXISTS ks WITH durable_writes = false")
+            .parse()
+            .unwrap(),
+        [CqlStatement::AlterKeyspace(AlterKeyspaceStatement {
+            keyspace_name: "ks".to_owned(),
+            if_exists: true,
+            attributes: vec![Property::new(
+                "durable_writes".to_owned(),
+                PropertyValue::Constant(Constant::Boolean(false)),
+            )],
+            span: Span::default(),
+        })]
+    );
+}
+
+#[test]
+fn test_alter_if_exists_version() {
+    for cql in [
+        "ALTER TABLE t ADD IF NOT EXISTS c int",
+        "ALTER TABLE t DROP IF EXISTS c",
+        "ALTER TABLE t RENAME IF EXISTS a TO b",
+        "ALTER TYPE address ADD IF NOT EXISTS zip text",
+    ] {
+        assert!(Parser::with_version(cql, CqlVersion::V4_1).parse().is_ok());
+        assert!(Parser::with_version(cql, CqlVersion::V4_0).parse().is_err());
+    }
+    // statement-level IF EXISTS is not restricted
+    assert!(
+        Parser::with_version("ALTER TABLE IF EXISTS t ADD c int", CqlVersion::V4_0)
+            .parse()
+            .is_ok()
+    );
+    assert_eq!(
+        Parser::with_version("ALTER TABLE t DROP IF EXISTS c", CqlVersion::V4_0)
+            .parse()
+            .unwrap_err()
+            .to_string(),
+        "1:20: IF EXISTS is not supported in Cassandra 4.0"
+    );
+}
+
+#[test]
+fn test_alter_column_type() {
+    let cql = "ALTER TABLE ks.t ALTER payload TYPE blob";
+    let expected = CqlStatement::AlterTable(AlterTableStatement {
+        name: "ks.t".parse::<QualifiedName>().unwrap(),
+        if_exists: false,
+        operation: AlterTableOperation::AlterType {
+            column: "payload".into(),
+            new_type: CqlType::Native(NativeDataType::Blob),
+        },
+        span: Span::default(),
+    });
+    // parsed without version for old schema dumps
+    assert_eq!(Parser::new(cql).parse().unwrap(), vec![expected]);
+    for version in [CqlVersion::V3_0, CqlVersion::V3_6] {
+        assert!(Parser::with_version(cql, version).parse().is_ok());
+    }
+    for version in [CqlVersion::V4_0, CqlVersion::V4_1, CqlVersion::V5_0] {
+        assert_eq!(
+            Parser::with_version(cql, version)
+                .parse()
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "1:18: ALTER ... TYPE is not supported in Cassandra {} \
+                 (changing column types was removed in Cassandra 4.0)",
+                version
+            )
+        );
+    }
+
+    match Parser::new("ALTER TABLE t ALTER \"Value\" TYPE frozen<list<int>>")
+        .parse()
+        .unwrap()
+        .pop()
+    {
+        Some(CqlStatement::AlterTable(AlterTableStatement {
+            operation: AlterTableOperation::AlterType { column, new_type },
+            ..
+        })) => {
+            assert_eq!(&*column, "Value");
+            assert_eq!(new_type.to_string(), "frozen<list<int>>");
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert_eq!(
+        Parser::new("ALTER TABLE t ALTER v blob")
+            .parse()
+            .unwrap_err()
+            .to_string(),
+        "1:23: expected TYPE, found 'blob'"
+    );
+}
+
+#[test]
+fn test_nan_and_infinity() {
+    match Parser::new("INSERT INTO t (a) VALUES (NaN)")
+        .parse()
+        .unwrap()
+        .pop()
+    {
+        Some(CqlStatement::Insert(insert)) => assert_eq!(
+            insert.values,
+            InsertMethod::Normal {
+                columns: vec![ExpressionKind::Identifier("a".into()).into()],
+                values: vec![ExpressionKind::Value(Literal::Constant(Constant::NaN)).into()],
+            }
+        ),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    match Parser::new("SELECT * FROM t WHERE v = Infinity")
+        .parse()
+        .unwrap()
+        .pop()
+    {
+        Some(CqlStatement::Select(select)) => assert_eq!(
+            select.selection.unwrap().relations(),
+            &[Relation::SingleColumn {
+                column: "v".into(),
+                operator: Operator::Equal,
+                value: ExpressionKind::Value(Literal::Constant(Constant::Infinity)).into(),
+            }]
+        ),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_collection_literals() {
+    fn int(i: i64) -> Expression {
+        ExpressionKind::Value(Literal::Constant(Constant::Integer(i))).into()
+    }
+    fn literal(cql: &str) -> Literal {
+        match Parser::parse_expression_only(cql) {
+            Ok(Expression {
+                kind: ExpressionKind::Value(literal),
+                ..
+            }) => literal,
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    assert_eq!(
+        literal("[1, 2, 3]"),
+        Literal::ConstantList(vec![
+            Constant::Integer(1),
+            Constant::Integer(2),
+            Constant::Integer(3)
+        ])
+    );
+    assert_eq!(
+        literal("[1, 2.5, a]"),
+        Literal::List(vec![
+            int(1),
+            ExpressionKind::Value(Literal::Constant(Constant::Float("2.5".to_owned()))).into(),
+            ExpressionKind::Identifier("a".into()).into()
+        ])
+    );
+    assert_eq!(literal("{1, 2}"), Literal::Set(vec![int(1), int(2)]));
+    assert_eq!(literal("{1: 2}"), Literal::Map(vec![(int(1), int(2))]));
+    // empty literals are unambiguous without types
+    assert_eq!(literal("[]"), Literal::List(Vec::new()));
+    assert_eq!(literal("{}"), Literal::Map(Vec::new()));
+    for cql in ["[1, 2, 3]", "[]", "{'a', 'b'}", "{'a': 1}", "{}"] {
+        assert_eq!(literal(cql).to_string(), cql);
+    }
+    assert!(
+        Parser::new("INSERT INTO t (k, l, s) VALUES (1, [1, 2], {'a'})")
+            .parse()
+            .is_ok()
+    );
+    assert_eq!(
+        Parser::parse_expression_only("[1, 2")
+            .unwrap_err()
+            .to_string(),
+        "1:6: expected ']', found end of input"
+    );
+
+    let list = CollectionType::List(Box::new(CqlType::Native(NativeDataType::Int)));
+    let set = CollectionType::Set(Box::new(CqlType::Native(NativeDataType::Int)));
+    let map = CollectionType::Map {
+        key_type: Box::new(CqlType::Native(NativeDataType::Int)),
+        value_type: Box::new(CqlType::Native(NativeDataType::Int)),
+    };
+    let test_cases = [
+        ("{}", &set, Some(Literal::Set(Vec::new()))),
+        ("{}", &map, Some(Literal::Map(Vec::new()))),
+        ("{}", &list, None),
+        ("{1}", &set, Some(Literal::Set(vec![int(1)]))),
+        ("{1}", &map, None),
+        ("{1: 2}", &set, None),
+        ("[]", &list, Some(Literal::List(Vec::new()))),
+        ("[]", &set, None),
+        ("[1]", &map, None),
+        ("?", &list, Some(Literal::Binding(None))),
+        ("NULL", &set, Some(Literal::Null)),
+        ("1", &list, None),
+    ];
+    for (cql, collection_type, expected) in test_cases {
+        let mut literal = literal(cql);
+        let reinterpreted = literal.reinterpret_as(collection_type);
+        assert_eq!(
+            reinterpreted,
+            expected.is_some(),
+            "{} as {}",
+            cql,
+            collection_type
+        );
+        if let Some(expected) = expected {
+            assert_eq!(literal, expected, "{} as {}", cql, collection_type);
+        }
+    }
+}
+
+#[test]
+fn test_list_literals() {
+    fn int(i: i64) -> Expression {
+        ExpressionKind::Value(Literal::Constant(Constant::Integer(i))).into()
+    }
+    fn text(s: &str) -> Expression {
+        ExpressionKind::Value(Literal::Constant(Constant::StringLiteral(s.to_owned()))).into()
+    }
+    fn list(elements: Vec<Expression>) -> Expression {
+        ExpressionKind::Value(Literal::List(elements)).into()
+    }
+    fn ints(values: &[i64]) -> Expression {
+        ExpressionKind::Value(Literal::ConstantList(
+            values.iter().map(|i| Constant::Integer(*i)).collect(),
+        ))
+        .into()
+    }
+
+    let cql = "INSERT INTO t (k, l) VALUES (1, [1, 2, 3])";
+    match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::Insert(InsertStatement {
+            values: InsertMethod::Normal { values, .. },
+            ..
+        })) => assert_eq!(values[1], ints(&[1, 2, 3])),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let cql = "SELECT * FROM t WHERE k = 1 AND l = ['a','b']";
+    match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::Select(select)) => assert_eq!(
+            select.selection.unwrap().relations()[1],
+            Relation::SingleColumn {
+                column: "l".into(),
+                operator: Operator::Equal,
+                value: ExpressionKind::Value(Literal::ConstantList(vec![
+                    Constant::StringLiteral("a".to_owned()),
+                    Constant::StringLiteral("b".to_owned())
+                ]))
+                .into(),
+            }
+        ),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let test_cases = [
+        ("[]", list(Vec::new())),
+        ("[[]]", list(vec![list(Vec::new())])),
+        ("[[1], [2, 3]]", list(vec![ints(&[1]), ints(&[2, 3])])),
+        (
+            "[(1, 'a'), (2, 'b')]",
+            list(vec![
+                ExpressionKind::Value(Literal::Tuple(vec![int(1), text("a")])).into(),
+                ExpressionKind::Value(Literal::Tuple(vec![int(2), text("b")])).into(),
+            ]),
+        ),
+        (
+            "[?, :v]",
+            list(vec![
+                ExpressionKind::Value(Literal::Binding(None)).into(),
+                ExpressionKind::Value(Literal::Binding(Some("v".to_owned()))).into(),
+            ]),
+        ),
+        // element selection of a list literal
+        (
+            "[1, 2][0]",
+            ExpressionKind::CollectionSubSelection {
+                receiver: Box::new(ints(&[1, 2])),
+                element: Box::new(int(0)),
+                upto: None,
+            }
+            .into(),
+        ),
+    ];
+    for (cql, expected) in test_cases {
+        assert_eq!(
+            Parser::parse_expression_only(cql).unwrap(),
+            expected,
+            "{}",
+            cql
+        );
+    }
+
+    // `[` following a term is still element or slice selection
+    let test_cases = [
+        ("l[0]", "l", int(0), None),
+        ("m['k']", "m", text("k"), None),
+        ("s[1..3]", "s", int(1), Some(int(3))),
+    ];
+    for (cql, receiver, element, upto) in test_cases {
+        assert_eq!(
+            Parser::parse_expression_only(cql).unwrap(),
+            ExpressionKind::CollectionSubSelection {
+                receiver: Box::new(Expression::new(ExpressionKind::Identifier(receiver.into()))),
+                element: Box::new(element),
+                upto: upto.map(Box::new),
+            }
+            .into(),
+            "{}",
+            cql
+        );
+    }
+    assert!(Parser::new("UPDATE t SET l = l + [4], e = [] WHERE k = 1")
+        .parse()
+        .is_ok());
+}
+
+#[test]
+fn test_long_lists() {
+    // generated IN list of constants is held compactly
+    let values: Vec<String> = (0..100_000).map(|i| format!("'id{}'", i)).collect();
+    let cql = format!("SELECT * FROM t WHERE k IN ({})", values.join(", "));
+    match Parser::new(&cql).parse().unwrap().pop() {
+        Some(CqlStatement::Select(select)) => match select.selection.unwrap().relations() {
+            [Relation::SingleColumn {
+                value:
+                    Expression {
+                        kind: ExpressionKind::Value(Literal::ConstantList(constants)),
+                        ..
+                    },
+                ..
+            }] => {
+                assert_eq!(constants.len(), 100_000);
+                assert_eq!(
+                    constants[99_999],
+                    Constant::StringLiteral("id99999".to_owned())
+                );
+            }
+            other => panic!("unexpected relations: {:?}", other),
+        },
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    // constants of different kinds, or other terms, are expressions
+    let test_cases = [
+        ("k IN (1, -2, 3)", true),
+        ("k IN (1, 'a')", false),
+        ("k IN (1, ?)", false),
+        ("k IN (1, 1 + 1)", false),
+        ("k IN ((1, 2), (3, 4))", false),
+    ];
+    for (relation, compact) in test_cases {
+        let cql = format!("SELECT * FROM t WHERE {}", relation);
+        let select = match Parser::new(&cql).parse().unwrap().pop() {
+            Some(CqlStatement::Select(select)) => select,
+            other => panic!("unexpected result: {:?}", other),
+        };
+        let relation_parsed = &select.selection.as_ref().unwrap().relations()[0];
+        assert_eq!(
+            matches!(
+                relation_parsed.value().kind,
+                ExpressionKind::Value(Literal::ConstantList(_))
+            ),
+            compact,
+            "{}",
+            relation
+        );
+        assert_eq!(relation_parsed.to_string(), relation);
+    }
+
+    let options = ParserOptions {
+        max_terms_per_list: 3,
+        ..ParserOptions::default()
+    };
+    let test_cases = [
+        ("SELECT * FROM t WHERE k IN (1, 2, 3)", None),
+        ("SELECT * FROM t WHERE k IN (1, 2, 3, 4)", Some(38)),
+        ("SELECT * FROM t WHERE k IN (1, 2, a, b)", Some(38)),
+        ("INSERT INTO t (a, b, c, d) VALUES (1, 2, 3, 4)", Some(45)),
+        ("UPDATE t SET l = [1, 2, 3, 4] WHERE k = 1", Some(28)),
+        ("UPDATE t SET s = {1, 2, 3, 4} WHERE k = 1", Some(28)),
+        (
+            "UPDATE t SET m = {1: 1, 2: 2, 3: 3, 4: 4} WHERE k = 1",
+            Some(37),
+        ),
+        (
+            "SELECT * FROM t WHERE (a, b, c, d) = (1, 2, 3, 4)",
+            Some(48),
+        ),
+    ];
+    for (cql, column) in test_cases {
+        let result = Parser::with_options(cql, options).parse();
+        match column {
+            None => assert!(result.is_ok(), "{}", cql),
+            Some(column) => {
+                let error = result.unwrap_err();
+                assert_eq!(
+                    error.message(),
+                    "Too many terms in list (max: 3)",
+                    "{}",
+                    cql
+                );
+                assert_eq!(error.column(), Some(column), "{}", cql);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_identifier_interning() {
+    let cql = "CREATE TABLE ks.t (k int PRIMARY KEY, v text);
+        SELECT v FROM ks.t WHERE k = 1;";
+    let statements = Parser::new(cql).parse().unwrap();
+    let (table, select) = match &statements[..] {
+        [CqlStatement::CreateTable(table), CqlStatement::Select(select)] => (table, select),
+        other => panic!("unexpected result: {:?}", other),
+    };
+    // the same column in different statements
+    let v = match &select.projection {
+        Projection::Selectors(selectors) => match &selectors[0].selectable().kind {
+            ExpressionKind::Identifier(v) => v,
+            other => panic!("unexpected selector: {:?}", other),
+        },
+        other => panic!("unexpected projection: {:?}", other),
+    };
+    assert!(Arc::ptr_eq(&table.column_definitions[1].name, v));
+    match &select.selection.as_ref().unwrap().relations()[0] {
+        Relation::SingleColumn { column, .. } => {
+            assert!(Arc::ptr_eq(&table.column_definitions[0].name, column));
+            assert!(Arc::ptr_eq(&table.partition_keys[0][0], column));
+        }
+        other => panic!("unexpected relation: {:?}", other),
+    }
+    // qualified name components
+    assert!(Arc::ptr_eq(&table.name.name, &select.table_name.name));
+    assert!(Arc::ptr_eq(
+        table.name.keyspace.as_ref().unwrap(),
+        select.table_name.keyspace.as_ref().unwrap()
+    ));
+
+    // identifiers are shared across parsers with the same cache
+    let cache = IdentifierCache::new();
+    let name = |cql| match Parser::new(cql)
+        .identifier_cache(cache.clone())
+        .parse()
+        .unwrap()
+        .pop()
+    {
+        Some(CqlStatement::Select(select)) => select.table_name.name,
+        other => panic!("unexpected result: {:?}", other),
+    };
+    let first = name("SELECT * FROM users");
+    let second = name("SELECT * FROM Users");
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(cache.len(), 1);
+    cache.clear();
+    assert!(cache.is_empty());
+    assert!(!Arc::ptr_eq(&first, &name("SELECT * FROM users")));
+}
+
+#[test]
+fn test_malformed_literals() {
+    // Inputs around string literal edges must be reported as errors, not panic
+    let inputs = [
+        "SELECT * FROM t WHERE k = $",
+        "SELECT * FROM t WHERE k = $x$",
+        "SELECT * FROM t WHERE k = $$",
+        "SELECT * FROM t WHERE k = $$$",
+        "SELECT * FROM t WHERE k = $$a$",
+        "SELECT * FROM t WHERE k = '",
+        "SELECT * FROM t WHERE k = 'a''",
+        "INSERT INTO t (k) VALUES ($",
+        "INSERT INTO t JSON $",
+        "CREATE TABLE t (k int PRIMARY KEY) WITH comment = $",
+        "SELECT * FROM \"",
+        "SELECT * FROM t WHERE k = -",
+    ];
+    for input in &inputs {
+        assert!(Parser::new(input).parse().is_err(), "{}", input);
+        let (_, errors) = Parser::new(input).parse_lenient();
+        assert!(!errors.is_empty(), "{}", input);
+    }
+
+    match Parser::new("SELECT * FROM t WHERE k = $$$$ AND v = ''")
+        .parse()
+        .unwrap()
+        .pop()
+    {
+        Some(CqlStatement::Select(select)) => {
+            let empty = || {
+                ExpressionKind::Value(Literal::Constant(Constant::StringLiteral(String::new())))
+                    .into()
+            };
+            assert_eq!(
+                select.selection.unwrap().relations(),
+                &[
+                    Relation::SingleColumn {
+                        column: "k".into(),
+                        operator: Operator::Equal,
+                        value: empty(),
+                    },
+                    Relation::SingleColumn {
+                        column: "v".into(),
+                        operator: Operator::Equal,
+                        value: empty(),
+                    },
+                ]
+            )
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_group_by() {
+    let cql = "SELECT pk, floor(ts, 12h), max(v) FROM t WHERE pk = 1 GROUP BY pk, floor(ts, 12h)";
+    let select = match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::Select(select)) => select,
+        other => panic!("unexpected result: {:?}", other),
+    };
+    assert_eq!(select.group_by.len(), 2);
+    assert_eq!(
+        select.group_by[0],
+        ExpressionKind::Identifier("pk".into()).into()
+    );
+    match &select.group_by[1].kind {
+        ExpressionKind::Function { name, args } => {
+            assert_eq!(name.to_string(), "floor");
+            assert_eq!(args[0], ExpressionKind::Identifier("ts".into()).into());
+            assert_eq!(
+                args[1],
+                ExpressionKind::Value(Literal::Constant(Constant::Duration("12h".to_owned())))
+                    .into()
+            );
+        }
+        other => panic!("unexpected GROUP BY element: {:?}", other),
+    }
+    assert_eq!(
+        select.to_string(),
+        "SELECT pk, floor(ts, 12h), max(v) FROM t WHERE pk = 1 GROUP BY pk, floor(ts, 12h)"
+    );
+
+    let test_cases = [
+        (
+            "SELECT * FROM t GROUP BY floor(ts, 1h), pk",
+            "1:41: Functions are only supported on the last element of the GROUP BY clause",
+        ),
+        (
+            "SELECT * FROM t GROUP BY pk, 1",
+            "1:30: GROUP BY element must be a column or a function call",
+        ),
+        (
+            "SELECT * FROM t GROUP BY",
+            "1:25: expected expression, found end of input",
+        ),
+    ];
+    for (cql, message) in test_cases.iter() {
+        let error = Parser::new(cql).parse().unwrap_err();
+        assert_eq!(&error.to_string(), message, "{}", cql);
+    }
+
+    let cql = "SELECT * FROM t GROUP BY pk, floor(ts, 1h)";
+    assert!(Parser::with_version(cql, CqlVersion::V4_0).parse().is_ok());
+    let error = Parser::with_version(cql, CqlVersion::V3_6)
+        .parse()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "1:30: Function in GROUP BY is not supported in Cassandra 3.6"
+    );
+}
+
+#[test]
+fn test_table_properties() {
+    let cql = "CREATE TABLE t (k int PRIMARY KEY)
+        WITH ID = 5a1c395e-b41f-11e5-9f22-ba0be0483c18
+        AND cdc = true
+        AND comment = 'users'
+        AND gc_grace_seconds = 3600
+        AND compaction = {'class': 'LeveledCompactionStrategy'}";
+    let table = match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::CreateTable(table)) => table,
+        other => panic!("unexpected result: {:?}", other),
+    };
+    let properties = table.properties();
+    assert_eq!(
+        properties.id(),
+        Some("5a1c395e-b41f-11e5-9f22-ba0be0483c18")
+    );
+    assert_eq!(properties.cdc(), Some(true));
+    assert_eq!(properties.comment(), Some("users"));
+    assert_eq!(properties.gc_grace_seconds(), Some(3600));
+    assert_eq!(
+        properties
+            .get(TableProperties::COMPACTION)
+            .map(ToString::to_string),
+        Some("{'class': 'LeveledCompactionStrategy'}".to_owned())
+    );
+    assert_eq!(properties.get(TableProperties::CACHING), None);
+
+    // values of unexpected types
+    let cql = "CREATE MATERIALIZED VIEW v AS SELECT * FROM t WHERE k IS NOT NULL
+        PRIMARY KEY (k) WITH cdc = 'yes' AND comment = 1";
+    let view = match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::CreateMaterializedView(view)) => view,
+        other => panic!("unexpected result: {:?}", other),
+    };
+    assert_eq!(view.properties().cdc(), None);
+    assert_eq!(view.properties().comment(), None);
+    assert!(view.properties().get(TableProperties::COMMENT).is_some());
+
+    let test_cases = [
+        (
+            "ALTER TABLE t WITH cdc = true AND cdc = false",
+            "1:35: Multiple definitions for property cdc",
+        ),
+        (
+            "CREATE KEYSPACE ks WITH durable_writes = true AND durable_writes = false",
+            "1:51: Multiple definitions for property durable_writes",
+        ),
+    ];
+    for (cql, message) in test_cases.iter() {
+        let error = Parser::new(cql).parse().unwrap_err();
+        assert_eq!(&error.to_string(), message, "{}", cql);
+    }
+}
+
+#[test]
+fn test_table_property_values() {
+    // as written by DESCRIBE TABLE in Cassandra 4.0
+    let cql = "CREATE TABLE ks.events (
+    id uuid,
+    ts timestamp,
+    payload text,
+    PRIMARY KEY (id, ts)
+) WITH CLUSTERING ORDER BY (ts DESC)
+    AND additional_write_policy = '99p'
+    AND bloom_filter_fp_chance = 0.01
+    AND caching = {'keys': 'ALL', 'rows_per_partition': 'NONE'}
+    AND cdc = false
+    AND comment = ''
+    AND compaction = {'class': 'org.apache.cassandra.db.compaction.SizeTieredCompactionStrategy', 'max_threshold': '32', 'min_threshold': '4'}
+    AND compression = {'chunk_length_in_kb': '16', 'class': 'org.apache.cassandra.io.compress.LZ4Compressor'}
+    AND crc_check_chance = 1.0
+    AND default_time_to_live = 0
+    AND extensions = {}
+    AND gc_grace_seconds = 864000
+    AND max_index_interval = 2048
+    AND memtable_flush_period_in_ms = 0
+    AND min_index_interval = 128
+    AND read_repair = 'BLOCKING'
+    AND speculative_retry = '99p';";
+    let table = match Parser::new(cql).parse().unwrap().pop() {
+        Some(CqlStatement::CreateTable(table)) => table,
+        other => panic!("unexpected result: {:?}", other),
+    };
+    let properties = table.properties();
+    assert_eq!(properties.cdc(), Some(false));
+    assert_eq!(properties.comment(), Some(""));
+    assert_eq!(properties.gc_grace_seconds(), Some(864000));
+    assert_eq!(
+        properties.get("speculative_retry"),
+        Some(&PropertyValue::Constant(Constant::StringLiteral(
+            "99p".to_owned()
+        )))
+    );
+    assert_eq!(
+        properties.get("memtable_flush_period_in_ms"),
+        Some(&PropertyValue::Constant(Constant::Integer(0)))
+    );
+    assert_eq!(
+        properties.get("extensions"),
+        Some(&PropertyValue::Map(vec![]))
+    );
+
+    // unquoted values are kept apart from string literals
+    let test_cases = [
+        ("ALWAYS", PropertyValue::Identifier("ALWAYS".to_owned())),
+        ("NONE", PropertyValue::Identifier("NONE".to_owned())),
+        (
+            "99PERCENTILE",
+            PropertyValue::NumberWithUnit("99PERCENTILE".to_owned()),
+        ),
+        (
+            "99.9PERCENTILE",
+            PropertyValue::NumberWithUnit("99.9PERCENTILE".to_owned()),
+        ),
+        ("99p", PropertyValue::NumberWithUnit("99p".to_owned())),
+        (
+            "50ms",
+            PropertyValue::Constant(Constant::Duration("50ms".to_owned())),
+        ),
+    ];
+    for (value, expected) in test_cases.iter() {
+        let cql = format!(
+            "ALTER TABLE t WITH speculative_retry = {} AND memtable_flush_period_in_ms = 0",
+            value
+        );
+        let statement = Parser::new(&cql).parse().unwrap().pop().unwrap();
+        match statement {
+            CqlStatement::AlterTable(alter) => match alter.operation {
+                AlterTableOperation::WithProperties(properties) => {
+                    assert_eq!(properties[0].value(), expected, "{}", cql);
+                    assert_eq!(
+                        properties[0].to_string(),
+                        format!("speculative_retry = {}", value)
+                    );
+                }
+                other => panic!("unexpected operation: {:?}", other),
+            },
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_multibyte_characters_in_quotes() {
+    let strings = [
+        ("'日本語''s'", "日本語's"),
+        ("$$🎉'$$", "🎉'"),
+        // combining characters are kept as written, without normalization
+        ("'e\u{301}'", "e\u{301}"),
+        ("'👍\u{200d}🎉'", "👍\u{200d}🎉"),
+        // fullwidth quotes are not delimiters
+        ("'a＇b'", "a＇b"),
+        ("'＇＇'", "＇＇"),
+    ];
+    for (cql, expected) in strings.iter() {
+        assert_eq!(
+            Parser::parse_expression_only(cql),
+            Ok(
+                ExpressionKind::Value(Literal::Constant(Constant::StringLiteral(
+                    expected.to_string()
+                )))
+                .into()
+            ),
+            "{}",
+            cql
+        );
+    }
+    let names = [
+        ("\"é\"\"x\"", "é\"x"),
+        ("\"e\u{301}\"", "e\u{301}"),
+        ("\"q＂\"", "q＂"),
+        ("\"ÉCOLE\"", "ÉCOLE"),
+    ];
+    for (cql, expected) in names.iter() {
+        assert_eq!(
+            Parser::parse_expression_only(cql),
+            Ok(ExpressionKind::Identifier((*expected).into()).into()),
+            "{}",
+            cql
+        );
+        assert_eq!(normalize_identifier(cql), *expected);
+    }
+
+    // column is in chars
+    let test_cases = [
+        (
+            "SELECT ＂a＂ FROM t",
+            "1:8: expected selector or '*', found '＂a' \
+             (non-ASCII character '＂' is only allowed inside quoted identifiers or string literals)",
+        ),
+        (
+            "SELECT * FROM \"tàble\" WHERE k = ＇x＇",
+            "1:33: expected expression, found '＇x' \
+             (non-ASCII character '＇' is only allowed inside quoted identifiers or string literals)",
+        ),
+        (
+            "SELECT * FROM t WHERE k = 'ü＇",
+            "1:27: expected expression, found 'ü＇ (unterminated string literal)",
+        ),
+    ];
+    for (cql, expected) in test_cases.iter() {
+        let error = Parser::new(cql).parse().unwrap_err();
+        assert_eq!(error.to_string(), *expected, "{}", cql);
+    }
+}
+
+#[test]
+fn test_truncated_multibyte_input_does_not_panic() {
+    let cql = "INSERT INTO ks.\"tàble\" (k, \"é\"\"x\") VALUES ('日本''語', $$ü$$) USING TTL 1; \
+               -- コメント\nSELECT \"e\u{301}\", ＂a＂ FROM t WHERE v = 'a＇b' AND m['ключ'] = '값'";
+    // every prefix that is a valid string
+    for (end, _) in cql.char_indices() {
+        let prefix = &cql[..end];
+        if let Err(error) = Parser::new(prefix).parse() {
+            if let Some(offset) = error.offset() {
+                assert!(prefix.is_char_boundary(offset), "{}", prefix);
+            }
+        }
+        let (statements, errors) = Parser::new(prefix).parse_lenient();
+        assert!(statements.len() + errors.len() <= 2, "{}", prefix);
+    }
+}
+
+#[test]
+fn test_create_type() {
+    let cql = "CREATE TYPE IF NOT EXISTS ks.\"Address\" (
+        street text,
+        \"Zip\" frozen<zip_code>,
+        location frozen<ks.point>,
+        tags frozen<set<text>>,
+        history list<frozen<map<date, frozen<\"Address\">>>>,
+        key tuple<int, frozen<point>>
+    )";
+    let statement = match Parser::new(cql).parse().unwrap().remove(0) {
+        CqlStatement::CreateType(statement) => statement,
+        statement => panic!("unexpected statement: {:?}", statement),
+    };
+    assert!(statement.if_not_exists);
+    assert_eq!(
+        statement.name,
+        QualifiedName {
+            keyspace: Some("ks".into()),
+            name: "Address".into(),
+            keyspace_quoted: false,
+            name_quoted: true,
+            keyspace_source: KeyspaceSource::Explicit,
+        }
+    );
+    assert_eq!(statement.name.to_string(), "ks.\"Address\"");
+    let fields: Vec<_> = statement
+        .field_definitions
+        .iter()
+        .map(|(name, cql_type)| (name.as_str(), cql_type.to_string()))
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            ("street", "text".to_owned()),
+            ("Zip", "frozen<zip_code>".to_owned()),
+            ("location", "frozen<ks.point>".to_owned()),
+            ("tags", "frozen<set<text>>".to_owned()),
+            (
+                "history",
+                "list<frozen<map<date, frozen<\"Address\">>>>".to_owned()
+            ),
+            ("key", "tuple<int, frozen<point>>".to_owned()),
+        ]
+    );
+    assert!(matches!(
+        &statement.field_definitions[1].1,
+        CqlType::Frozen(inner) if matches!(inner.as_ref(), CqlType::UserDefinedType(_))
+    ));
+}
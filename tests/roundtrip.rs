@@ -0,0 +1,619 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Property-based round-trip tests: for generated ASTs, `parse(print(ast)) == ast`.
+//!
+//! ASTs are generated by `proptest` strategies, which shrink a failing case to
+//! the smallest failing statement.
+
+use std::mem;
+
+use proptest::collection::vec;
+use proptest::option;
+use proptest::prelude::*;
+use proptest::sample::{select, Index};
+use proptest::strategy::LazyJust;
+
+use cqlparser::ast::*;
+use cqlparser::{fingerprint, format, FormatOptions, Parser};
+
+/// Number of generated cases per property
+const CASES: u32 = 1000;
+
+/// Prints `statement`, parses it back and compares with the original
+fn round_trip(statement: CqlStatement, cql: String) -> Result<(), TestCaseError> {
+    match Parser::new(&cql).parse() {
+        Ok(parsed) => {
+            prop_assert!(parsed == [statement], "{}\n  parsed as: {:?}", cql, parsed);
+            Ok(())
+        }
+        Err(e) => Err(TestCaseError::fail(format!("{}\n  error: {}", cql, e))),
+    }
+}
+
+// Strategies
+
+const IDENTIFIERS: &[&str] = &[
+    "a",
+    "k",
+    "col_1",
+    "v2",
+    "Col",
+    "my col",
+    "a\"b",
+    "1a",
+    "é",
+    "select",
+    "from",
+    "where",
+    "table",
+    "key",
+    "ttl",
+    "type",
+    "text",
+    "int",
+    "map",
+    "frozen",
+    "token",
+    "count",
+    "writetime",
+    "expr",
+    "json",
+    "static",
+    "values",
+    "distinct",
+    "cast",
+];
+
+fn identifier() -> impl Strategy<Value = String> {
+    select(IDENTIFIERS).prop_map(str::to_owned)
+}
+
+fn qualified_name() -> impl Strategy<Value = QualifiedName> {
+    (option::of(identifier()), identifier()).prop_map(|(keyspace, name)| QualifiedName {
+        keyspace_quoted: keyspace.as_ref().is_some_and(|k| k != &k.to_lowercase()),
+        name_quoted: name != name.to_lowercase(),
+        keyspace: keyspace.map(Identifier::from),
+        name: name.into(),
+        keyspace_source: KeyspaceSource::Explicit,
+    })
+}
+
+fn string() -> impl Strategy<Value = String> {
+    const PIECES: &[&str] = &[
+        "", "x", "'", "''", "$", "$$", " ", "\n", "é", "--", "/*", "\"",
+    ];
+    vec(select(PIECES), 0..4).prop_map(|pieces| pieces.concat())
+}
+
+fn constant() -> impl Strategy<Value = Constant> {
+    const FLOATS: &[&str] = &["1.5", "0.25", "1e10", "2.5E-3", "3.", "-1.5e-3"];
+    const UUIDS: &[&str] = &[
+        "123e4567-e89b-12d3-a456-426655440000",
+        "5A1C395E-B41F-11E5-9F22-BA0BE0483C18",
+    ];
+    const DURATIONS: &[&str] = &["1h30m", "P1Y2M", "PT1H", "3d", "P0001-02-03T04:05:06"];
+    prop_oneof![
+        (-1000i64..1000).prop_map(Constant::Integer),
+        select(&[i64::MAX, i64::MIN][..]).prop_map(Constant::Integer),
+        string().prop_map(Constant::StringLiteral),
+        select(FLOATS).prop_map(|f| Constant::Float(f.to_owned())),
+        any::<bool>().prop_map(Constant::Boolean),
+        select(UUIDS).prop_map(|u| Constant::UUID(u.to_owned())),
+        select(DURATIONS).prop_map(|d| Constant::Duration(d.to_owned())),
+        vec(any::<u8>(), 0..4).prop_map(Constant::Bytes),
+        LazyJust::new(|| Constant::NaN),
+        LazyJust::new(|| Constant::Infinity),
+    ]
+}
+
+fn native_type() -> impl Strategy<Value = NativeDataType> {
+    const TYPES: &[NativeDataType] = &[
+        NativeDataType::Int,
+        NativeDataType::Text,
+        NativeDataType::BigInt,
+        NativeDataType::UUID,
+        NativeDataType::Timestamp,
+        NativeDataType::Duration,
+        NativeDataType::Blob,
+        NativeDataType::Boolean,
+    ];
+    select(TYPES)
+}
+
+fn cql_type(depth: u32) -> BoxedStrategy<CqlType> {
+    native_type()
+        .prop_map(CqlType::Native)
+        .prop_recursive(depth, 16, 3, |inner| {
+            prop_oneof![
+                2 => native_type().prop_map(CqlType::Native),
+                1 => inner.clone().prop_map(|t| CqlType::Collection(CollectionType::List(Box::new(t)))),
+                1 => inner.clone().prop_map(|t| CqlType::Collection(CollectionType::Set(Box::new(t)))),
+                1 => (inner.clone(), inner.clone()).prop_map(|(k, v)| {
+                    CqlType::Collection(CollectionType::Map {
+                        key_type: Box::new(k),
+                        value_type: Box::new(v),
+                    })
+                }),
+                1 => inner.clone().prop_map(|t| CqlType::Frozen(Box::new(t))),
+                1 => qualified_name().prop_map(CqlType::UserDefinedType),
+                1 => vec(inner, 1..4).prop_map(CqlType::Tuple),
+            ]
+        })
+        .boxed()
+}
+
+fn literal_leaf() -> impl Strategy<Value = Literal> {
+    prop_oneof![
+        constant().prop_map(Literal::Constant),
+        LazyJust::new(|| Literal::Binding(None)),
+        identifier().prop_map(|name| Literal::Binding(Some(name))),
+    ]
+}
+
+fn literal(term: BoxedStrategy<Expression>) -> impl Strategy<Value = Literal> {
+    prop_oneof![
+        3 => literal_leaf(),
+        // single element tuple is a parenthesized expression
+        1 => vec(term.clone(), 2..4).prop_map(Literal::Tuple),
+        1 => vec(term.clone(), 0..3).prop_map(list),
+        // empty set is written as `{}`, which is an empty map
+        1 => vec(term.clone(), 1..3).prop_map(Literal::Set),
+        1 => vec((term.clone(), term), 0..3).prop_map(Literal::Map),
+    ]
+}
+
+// List literal, held as `ConstantList` if the elements are constants of the same kind
+// as the parser does
+fn list(elements: Vec<Expression>) -> Literal {
+    let constants: Option<Vec<Constant>> = elements
+        .iter()
+        .map(|element| match &element.kind {
+            ExpressionKind::Value(Literal::Constant(constant)) => Some(constant.clone()),
+            _ => None,
+        })
+        .collect();
+    match constants {
+        Some(constants)
+            if !constants.is_empty()
+                && constants
+                    .iter()
+                    .all(|c| mem::discriminant(c) == mem::discriminant(&constants[0])) =>
+        {
+            Literal::ConstantList(constants)
+        }
+        _ => Literal::List(elements),
+    }
+}
+
+const ARITHMETIC: &[fn() -> Operator] = &[
+    || Operator::Plus,
+    || Operator::Minus,
+    || Operator::Multiply,
+    || Operator::Divide,
+    || Operator::Modulus,
+];
+
+fn term(depth: u32) -> BoxedStrategy<Expression> {
+    prop_oneof![
+        literal_leaf().prop_map(|value| ExpressionKind::Value(value).into()),
+        identifier().prop_map(|name| ExpressionKind::Identifier(name.into()).into()),
+    ]
+    .prop_recursive(depth, 64, 3, |term| {
+        prop_oneof![
+            1 => literal(term.clone()).prop_map(|value| ExpressionKind::Value(value).into()),
+            1 => identifier().prop_map(|name| ExpressionKind::Identifier(name.into()).into()),
+            1 => term.clone().prop_map(|t| ExpressionKind::UnaryOp(UnaryOp::new(Operator::Minus, Box::new(t))).into()),
+            2 => (
+                arithmetic_operand(term.clone()),
+                select(ARITHMETIC),
+                arithmetic_operand(term.clone()),
+            )
+                .prop_map(|(left, operator, right)| {
+                    ExpressionKind::BinaryOp(BinaryOp::new(Box::new(left), operator(), Box::new(right))).into()
+                }),
+            1 => (qualified_name(), vec(term.clone(), 0..3))
+                .prop_map(|(name, args)| ExpressionKind::Function { name, args }.into()),
+            1 => (native_type(), term.clone())
+                .prop_map(|(t, e)| ExpressionKind::Cast(CqlType::Native(t), Box::new(e)).into()),
+            1 => (term.clone(), term.clone(), option::of(term.clone())).prop_map(
+                |(receiver, element, upto)| ExpressionKind::CollectionSubSelection {
+                    receiver: Box::new(receiver),
+                    element: Box::new(element),
+                    upto: upto.map(Box::new),
+                }.into()
+            ),
+            1 => (cql_type(2), term).prop_map(|(t, e)| ExpressionKind::TypeCast(t, Box::new(e)).into()),
+        ]
+    })
+    .boxed()
+}
+
+// Term except duration constants, which are rejected as operands of arithmetic operators
+fn arithmetic_operand(term: BoxedStrategy<Expression>) -> impl Strategy<Value = Expression> {
+    (term, identifier()).prop_map(|(term, name)| match term.kind {
+        ExpressionKind::Value(Literal::Constant(Constant::Duration(_))) => {
+            ExpressionKind::Identifier(name.into()).into()
+        }
+        _ => term,
+    })
+}
+
+fn relation() -> impl Strategy<Value = Relation> {
+    const OPERATORS: &[fn() -> Operator] = &[
+        || Operator::Equal,
+        || Operator::NotEqual,
+        || Operator::LessThan,
+        || Operator::LessThanOrEqual,
+        || Operator::GreaterThan,
+        || Operator::GreaterThanOrEqual,
+        || Operator::Contains,
+        || Operator::ContainsKey,
+        || Operator::Like,
+    ];
+    let columns = || vec(identifier().prop_map(Identifier::from), 1..4);
+    prop_oneof![
+        2 => (identifier(), select(OPERATORS), term(2)).prop_map(|(column, operator, value)| {
+            Relation::SingleColumn {
+                column: column.into(),
+                operator: operator(),
+                value,
+            }
+        }),
+        1 => (identifier(), option::of(vec(term(1), 0..3))).prop_map(|(column, values)| {
+            Relation::SingleColumn {
+                column: column.into(),
+                operator: Operator::In,
+                value: match values {
+                    None => ExpressionKind::Value(Literal::Binding(None)).into(),
+                    Some(values) => ExpressionKind::Value(list(values)).into(),
+                },
+            }
+        }),
+        1 => identifier().prop_map(|column| Relation::SingleColumn {
+            column: column.into(),
+            operator: Operator::IsNot,
+            value: ExpressionKind::Value(Literal::Null).into(),
+        }),
+        1 => (columns(), select(&OPERATORS[2..6]), term(2)).prop_map(|(columns, operator, value)| {
+            Relation::Token {
+                columns,
+                operator: operator(),
+                value,
+            }
+        }),
+        1 => (columns(), select(&OPERATORS[..6]), vec(term(1), 2..4)).prop_map(
+            |(columns, operator, values)| Relation::MultiColumn {
+                columns,
+                operator: operator(),
+                value: ExpressionKind::Value(Literal::Tuple(values)).into(),
+            }
+        ),
+    ]
+}
+
+fn limit() -> impl Strategy<Value = Option<Literal>> {
+    prop_oneof![
+        LazyJust::new(|| None),
+        (0i64..100).prop_map(|n| Some(Literal::Constant(Constant::Integer(n)))),
+        LazyJust::new(|| Some(Literal::Binding(None))),
+    ]
+}
+
+fn select_statement() -> impl Strategy<Value = SelectStatement> {
+    let selector =
+        (term(3), option::of(identifier())).prop_map(|(term, alias)| Selector::new(term, alias));
+    let projection = option::of(vec(selector, 1..4)).prop_map(|selectors| match selectors {
+        None => Projection::Wildcard,
+        Some(selectors) => Projection::Selectors(selectors),
+    });
+    let selection = option::weighted(2.0 / 3.0, vec(relation(), 1..4).prop_map(WhereClause::new));
+    // columns, followed by a function call on the last one
+    let group_by =
+        (vec(identifier(), 0..3), option::of(identifier())).prop_map(|(columns, floor)| {
+            let mut group_by: Vec<Expression> = columns
+                .into_iter()
+                .map(|column| ExpressionKind::Identifier(column.into()).into())
+                .collect();
+            if let Some(column) = floor {
+                group_by.push(
+                    ExpressionKind::Function {
+                        name: QualifiedName::new(None, "floor".to_owned()),
+                        args: vec![
+                            ExpressionKind::Identifier(column.into()).into(),
+                            ExpressionKind::Value(Literal::Constant(Constant::Duration(
+                                "1h".to_owned(),
+                            )))
+                            .into(),
+                        ],
+                    }
+                    .into(),
+                );
+            }
+            group_by
+        });
+    (
+        qualified_name(),
+        projection,
+        selection,
+        group_by,
+        limit(),
+        limit(),
+        any::<bool>(),
+    )
+        .prop_map(
+            |(
+                table_name,
+                projection,
+                selection,
+                group_by,
+                per_partition_limit,
+                limit,
+                allow_filtering,
+            )| {
+                SelectStatement {
+                    table_name,
+                    projection,
+                    selection,
+                    group_by,
+                    // SELECT JSON and DISTINCT are not parsed yet
+                    is_json: false,
+                    is_distinct: false,
+                    per_partition_limit,
+                    limit,
+                    allow_filtering,
+                    span: Span::default(),
+                }
+            },
+        )
+}
+
+fn property_value_leaf() -> impl Strategy<Value = PropertyValue> {
+    const IDENTIFIERS: &[&str] = &["ALWAYS", "LeveledCompactionStrategy", "none"];
+    prop_oneof![
+        prop_oneof![
+            string().prop_map(Constant::StringLiteral),
+            (-100i64..100).prop_map(Constant::Integer),
+            LazyJust::new(|| Constant::Float("0.1".to_owned())),
+            any::<bool>().prop_map(Constant::Boolean),
+        ]
+        .prop_map(PropertyValue::Constant),
+        select(IDENTIFIERS).prop_map(|name| PropertyValue::Identifier(name.to_owned())),
+    ]
+}
+
+fn property_value(depth: u32) -> impl Strategy<Value = PropertyValue> {
+    property_value_leaf().prop_recursive(depth, 16, 3, |value| {
+        prop_oneof![
+            2 => property_value_leaf(),
+            1 => vec((property_value_leaf(), value), 0..3).prop_map(PropertyValue::Map),
+        ]
+    })
+}
+
+fn create_table_statement() -> impl Strategy<Value = CreateTableStatement> {
+    // column names are unique
+    let names = vec(identifier(), 1..7).prop_map(|names| {
+        let mut unique: Vec<String> = Vec::new();
+        for name in names {
+            if !unique.contains(&name) {
+                unique.push(name);
+            }
+        }
+        unique
+    });
+    let columns = names.prop_flat_map(|names| {
+        let count = names.len();
+        (
+            Just(names),
+            vec((cql_type(3), prop::bool::weighted(0.25)), count),
+            // column with PRIMARY KEY in its definition, or the columns of PRIMARY KEY
+            option::of(0..count),
+            vec(0..count, 1..=count),
+            any::<Index>(),
+            any::<Index>(),
+            vec(any::<bool>(), count),
+        )
+    });
+    // property names are unique
+    let table_properties = vec((identifier(), property_value(2)), 0..3).prop_map(|properties| {
+        let mut table_properties: Vec<Property> = Vec::new();
+        for (key, value) in properties {
+            if table_properties.iter().all(|p| p.key() != key) {
+                table_properties.push(Property::new(key, value));
+            }
+        }
+        table_properties
+    });
+
+    (
+        columns,
+        table_properties,
+        qualified_name(),
+        any::<bool>(),
+        prop::bool::weighted(0.25),
+    )
+        .prop_map(
+            |(columns, table_properties, name, if_not_exists, compact_storage)| {
+                let (names, types, primary_key, keys, partition_count, clustering_count, orders) =
+                    columns;
+                let mut column_definitions: Vec<ColumnDefinition> = names
+                    .iter()
+                    .zip(types)
+                    .map(|(name, (data_type, is_static))| {
+                        let mut column = ColumnDefinition::new(name.clone(), data_type);
+                        column.is_static = is_static;
+                        column
+                    })
+                    .collect();
+
+                let (partition_keys, clustering_columns) = match primary_key {
+                    Some(index) => {
+                        let column = &mut column_definitions[index];
+                        column.is_primary_key = true;
+                        (vec![column.name.clone()], Vec::new())
+                    }
+                    None => {
+                        let mut keys_: Vec<Identifier> = Vec::new();
+                        for key in keys {
+                            let key = Identifier::from(names[key].as_str());
+                            if !keys_.contains(&key) {
+                                keys_.push(key);
+                            }
+                        }
+                        let clustering_columns =
+                            keys_.split_off(1 + partition_count.index(keys_.len()));
+                        (keys_, clustering_columns)
+                    }
+                };
+                let clustering_order = clustering_columns
+                    .iter()
+                    .take(clustering_count.index(clustering_columns.len() + 1))
+                    .zip(orders)
+                    .map(|(column, order)| (column.clone(), order))
+                    .collect();
+
+                CreateTableStatement {
+                    name,
+                    if_not_exists,
+                    column_definitions,
+                    partition_keys: vec![partition_keys],
+                    clustering_columns,
+                    compact_storage,
+                    clustering_order,
+                    table_properties,
+                    span: Span::default(),
+                }
+            },
+        )
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn test_select_round_trip(select in select_statement()) {
+        let cql = select.to_string();
+        round_trip(CqlStatement::Select(select), cql)?;
+    }
+
+    #[test]
+    fn test_create_table_round_trip(table in create_table_statement()) {
+        let cql = table.to_string();
+        round_trip(CqlStatement::CreateTable(table), cql)?;
+    }
+}
+
+/// Parses `cql`, and checks that it is printed as `expected` and parsed back to the same AST
+fn assert_round_trip(cql: &str, expected: &str) {
+    let parsed = Parser::new(cql).parse().unwrap();
+    let printed = match &parsed[0] {
+        CqlStatement::Select(select) => select.to_string(),
+        CqlStatement::CreateTable(table) => table.to_string(),
+        other => panic!("unexpected statement: {:?}", other),
+    };
+    assert_eq!(printed, expected, "{}", cql);
+    assert_eq!(
+        Parser::new(&printed).parse().unwrap(),
+        parsed,
+        "{}",
+        printed
+    );
+}
+
+// Regressions found by the round-trip properties
+#[test]
+fn test_round_trip_regressions() {
+    // `''` is unescaped in string literal, and escaped again when printed
+    assert_round_trip(
+        "SELECT * FROM t WHERE a = 'it''s' AND b = $$it's$$",
+        "SELECT * FROM t WHERE a = 'it''s' AND b = 'it''s'",
+    );
+    // `%` operator
+    assert_round_trip("SELECT a % 2 FROM t", "SELECT a % 2 FROM t");
+    // empty blob
+    assert_round_trip(
+        "SELECT * FROM t WHERE b = 0x",
+        "SELECT * FROM t WHERE b = 0x",
+    );
+    // `--` starts a comment
+    assert_round_trip("SELECT - -1 FROM t", "SELECT - -1 FROM t");
+    // minus before element selection of a number is not a negative constant
+    assert_round_trip("SELECT -(1[0]) FROM t", "SELECT - 1[0] FROM t");
+    assert_round_trip(
+        "SELECT [1, 2][0], - [1][0] FROM t",
+        "SELECT [1, 2][0], -[1][0] FROM t",
+    );
+    // collection element and slice, where `3...4` is not a range
+    assert_round_trip(
+        "SELECT m['k'], (int) l[0], s[3. .. 4] FROM t",
+        "SELECT m['k'], (int) l[0], s[3. ..4] FROM t",
+    );
+    // parenthesized expressions starting with identifier or collection keyword
+    assert_round_trip("SELECT (a + b) * c FROM t", "SELECT (a + b) * c FROM t");
+    assert_round_trip("SELECT -(map + 0) FROM t", "SELECT -(map + 0) FROM t");
+    assert_round_trip("SELECT ((a)) AS b FROM t", "SELECT a AS b FROM t");
+    assert_round_trip(
+        "SELECT * FROM t WHERE a CONTAINS \"key\" PER PARTITION LIMIT 1",
+        "SELECT * FROM t WHERE a CONTAINS \"key\" PER PARTITION LIMIT 1",
+    );
+    // type hints and casts
+    assert_round_trip("SELECT (int) 1 FROM t", "SELECT (int) 1 FROM t");
+    assert_round_trip(
+        "SELECT cast(a AS text), (int) ? FROM t",
+        "SELECT CAST(a AS text), (int) ? FROM t",
+    );
+    assert_round_trip(
+        "SELECT (frozen<list<int>>) a + 1 FROM t",
+        "SELECT (frozen<list<int>>) a + 1 FROM t",
+    );
+    // `CONTAINS key()` is read as `CONTAINS KEY ()`
+    assert_round_trip(
+        "SELECT * FROM t WHERE a CONTAINS \"key\"()",
+        "SELECT * FROM t WHERE a CONTAINS \"key\"()",
+    );
+    // keywords which cannot be type names, and type keywords
+    assert_round_trip(
+        "CREATE TABLE t (a \"count\", b \"map\".\"list\", c \"int\" PRIMARY KEY)",
+        "CREATE TABLE t (a \"count\", b \"map\".\"list\", c \"int\" PRIMARY KEY)",
+    );
+}
+
+// Type hints are written as `(type) term`, since Cassandra accepts CAST only in selectors
+#[test]
+fn test_type_hint_round_trip() {
+    let test_cases = [
+        (
+            "SELECT * FROM t WHERE a = (int) 5",
+            "SELECT * FROM t WHERE a = (int) ?",
+        ),
+        (
+            "INSERT INTO t (k, v) VALUES (1, (bigint) ?)",
+            "INSERT INTO t (k, v) VALUES (?, (bigint) ?)",
+        ),
+        (
+            "UPDATE t SET v = (text) 'x', n = n + (int) 1 WHERE k = 1",
+            "UPDATE t SET v = (text) ?, n = n + (int) ? WHERE k = ?",
+        ),
+    ];
+    for (cql, fingerprinted) in test_cases {
+        let formatted = format(cql, FormatOptions::default()).unwrap();
+        assert!(!formatted.contains("CAST"), "{}", formatted);
+        assert_eq!(
+            Parser::new(&formatted).parse().unwrap(),
+            Parser::new(cql).parse().unwrap(),
+            "{}",
+            formatted
+        );
+        assert_eq!(fingerprint(cql).unwrap(), fingerprinted);
+    }
+}
@@ -0,0 +1,548 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use cqlparser::ast::{CollectionType, CqlType, IndexType, NativeDataType, Property, QualifiedName};
+use cqlparser::schema::{diff, ColumnKind, Schema, SchemaChange, SchemaError};
+use cqlparser::{format_statement, FormatOptions, Parser};
+
+fn schema(cql: &str) -> Result<Schema, SchemaError> {
+    Schema::from_statements(&Parser::new(cql).parse().unwrap())
+}
+
+fn native(native_type: NativeDataType) -> CqlType {
+    CqlType::Native(native_type)
+}
+
+fn user_type(keyspace: &str, name: &str) -> CqlType {
+    CqlType::UserDefinedType(QualifiedName::new(
+        Some(keyspace.to_owned()),
+        name.to_owned(),
+    ))
+}
+
+#[test]
+fn test_schema_alter_table() {
+    let schema = schema(
+        "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy'};
+         CREATE TABLE ks.events (id uuid, at timestamp, kind text, payload blob,
+                                 PRIMARY KEY (id, at));
+         ALTER TABLE ks.events ADD (source inet, tags map<text, text>);
+         ALTER TABLE ks.events DROP payload;
+         ALTER TABLE ks.events RENAME at TO occurred_at",
+    )
+    .unwrap();
+
+    let table = schema.table("ks", "events").unwrap();
+    let columns: Vec<_> = table
+        .columns()
+        .iter()
+        .map(|c| (c.name.as_str(), c.kind))
+        .collect();
+    assert_eq!(
+        columns,
+        [
+            ("id", ColumnKind::PartitionKey),
+            ("occurred_at", ColumnKind::Clustering),
+            ("kind", ColumnKind::Regular),
+            ("source", ColumnKind::Regular),
+            ("tags", ColumnKind::Regular),
+        ]
+    );
+    assert_eq!(table.partition_keys(), ["id"]);
+    assert_eq!(table.clustering_columns(), ["occurred_at"]);
+    assert_eq!(table.column("source"), Some(&native(NativeDataType::Inet)));
+    assert_eq!(table.column("payload"), None);
+    assert_eq!(table.column("at"), None);
+}
+
+#[test]
+fn test_schema_resolution() {
+    let schema = schema(
+        "CREATE KEYSPACE music WITH replication = {'class': 'SimpleStrategy'};
+         CREATE KEYSPACE \"Other\" WITH replication = {'class': 'SimpleStrategy'};
+         USE music;
+         CREATE TYPE address (street text, zip int);
+         ALTER TYPE address RENAME zip TO zip_code;
+         CREATE TABLE Users (id uuid PRIMARY KEY, home frozen<address>,
+                             past map<int, frozen<music.address>>, \"Nick\" text STATIC);
+         CREATE INDEX ON users (\"Nick\");
+         CREATE INDEX ON users (keys(past));
+         CREATE CUSTOM INDEX users_home ON music.users (full(home));
+         CREATE MATERIALIZED VIEW users_by_nick AS SELECT id, home FROM users
+             WHERE \"Nick\" IS NOT NULL AND id IS NOT NULL PRIMARY KEY (\"Nick\", id);
+         USE \"Other\";
+         CREATE TABLE t (k int PRIMARY KEY);
+         SELECT * FROM t",
+    )
+    .unwrap();
+
+    let keyspaces: Vec<_> = schema.keyspaces().map(|k| k.name()).collect();
+    assert_eq!(keyspaces, ["Other", "music"]);
+    assert!(schema.table("Other", "t").is_some());
+
+    let music = schema.keyspace("music").unwrap();
+    let address = music.user_type("address").unwrap();
+    assert_eq!(
+        address.field("zip_code"),
+        Some(&native(NativeDataType::Int))
+    );
+
+    // user defined types are qualified by their keyspaces
+    let users = music.table("users").unwrap();
+    assert_eq!(
+        users.column("home"),
+        Some(&CqlType::Frozen(Box::new(user_type("music", "address"))))
+    );
+    assert_eq!(
+        users.column("past"),
+        Some(&CqlType::Collection(CollectionType::Map {
+            key_type: Box::new(native(NativeDataType::Int)),
+            value_type: Box::new(CqlType::Frozen(Box::new(user_type("music", "address")))),
+        }))
+    );
+    assert_eq!(users.columns()[3].kind, ColumnKind::Static);
+
+    // index names are generated when not given
+    let indexes: Vec<_> = users
+        .indexes()
+        .iter()
+        .map(|i| (i.name.as_str(), i.targets[0].1))
+        .collect();
+    assert_eq!(
+        indexes,
+        [
+            ("users_Nick_idx", IndexType::Simple),
+            ("users_past_idx", IndexType::Keys),
+            ("users_home", IndexType::Full),
+        ]
+    );
+    assert!(music.index("users_home").unwrap().is_custom);
+
+    let view = music.table("users_by_nick").unwrap();
+    assert_eq!(view.base_table(), Some("users"));
+    let columns: Vec<_> = view
+        .columns()
+        .iter()
+        .map(|c| (c.name.as_str(), c.kind))
+        .collect();
+    assert_eq!(
+        columns,
+        [
+            ("Nick", ColumnKind::PartitionKey),
+            ("id", ColumnKind::Clustering),
+            ("home", ColumnKind::Regular),
+        ]
+    );
+}
+
+#[test]
+fn test_schema_if_exists() {
+    let schema = schema(
+        "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy'};
+         CREATE KEYSPACE IF NOT EXISTS ks WITH replication = {'class': 'SimpleStrategy'};
+         CREATE TABLE ks.t (k int PRIMARY KEY);
+         CREATE TABLE IF NOT EXISTS ks.t (k text PRIMARY KEY, v int);
+         ALTER TABLE IF EXISTS ks.missing ADD v int;
+         ALTER TABLE ks.t ADD IF NOT EXISTS k text;
+         ALTER TABLE ks.t DROP IF EXISTS v;
+         ALTER TABLE ks.t RENAME IF EXISTS v TO w",
+    )
+    .unwrap();
+    let table = schema.table("ks", "t").unwrap();
+    assert_eq!(table.columns().len(), 1);
+    assert_eq!(table.column("k"), Some(&native(NativeDataType::Int)));
+}
+
+#[test]
+fn test_schema_alter_column_type() {
+    let schema = schema(
+        "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy'};
+         CREATE TABLE ks.t (k int PRIMARY KEY, v ascii);
+         ALTER TABLE ks.t ALTER v TYPE text",
+    )
+    .unwrap();
+    let table = schema.table("ks", "t").unwrap();
+    assert_eq!(table.column("v"), Some(&native(NativeDataType::Text)));
+}
+
+#[test]
+fn test_schema_errors() {
+    let keyspace = "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy'};";
+    let cases = [
+        (
+            "CREATE TABLE t (k int PRIMARY KEY)",
+            SchemaError::NoKeyspace { name: "t".into() },
+        ),
+        (
+            "USE ks",
+            SchemaError::UnknownKeyspace {
+                keyspace: "ks".into(),
+            },
+        ),
+        (
+            "CREATE TABLE ks.t (k int PRIMARY KEY)",
+            SchemaError::UnknownKeyspace {
+                keyspace: "ks".into(),
+            },
+        ),
+        (
+            "$ks CREATE KEYSPACE KS WITH replication = {'class': 'SimpleStrategy'}",
+            SchemaError::DuplicateKeyspace {
+                keyspace: "ks".into(),
+            },
+        ),
+        (
+            "$ks CREATE TABLE ks.t (k int PRIMARY KEY); CREATE TABLE ks.T (k int PRIMARY KEY)",
+            SchemaError::DuplicateTable {
+                keyspace: "ks".into(),
+                table: "t".into(),
+            },
+        ),
+        (
+            "$ks CREATE TABLE ks.t (k int PRIMARY KEY, k text)",
+            SchemaError::DuplicateColumn {
+                keyspace: "ks".into(),
+                table: "t".into(),
+                column: "k".into(),
+            },
+        ),
+        (
+            "$ks CREATE TABLE ks.t (k int, PRIMARY KEY (k, c))",
+            SchemaError::UnknownColumn {
+                keyspace: "ks".into(),
+                table: "t".into(),
+                column: "c".into(),
+            },
+        ),
+        (
+            "$ks CREATE TABLE ks.t (k int PRIMARY KEY, a frozen<address>)",
+            SchemaError::UnknownType {
+                keyspace: "ks".into(),
+                name: "address".into(),
+            },
+        ),
+        (
+            "$ks ALTER TABLE ks.t ADD v int",
+            SchemaError::UnknownTable {
+                keyspace: "ks".into(),
+                table: "t".into(),
+            },
+        ),
+        (
+            "$ks CREATE TABLE ks.t (k int PRIMARY KEY); ALTER TABLE ks.t DROP k",
+            SchemaError::PrimaryKeyColumn {
+                keyspace: "ks".into(),
+                table: "t".into(),
+                column: "k".into(),
+            },
+        ),
+        (
+            "$ks CREATE TABLE ks.t (k int PRIMARY KEY, v int); ALTER TABLE ks.t RENAME k TO v",
+            SchemaError::DuplicateColumn {
+                keyspace: "ks".into(),
+                table: "t".into(),
+                column: "v".into(),
+            },
+        ),
+        (
+            "$ks CREATE TABLE ks.t (k int PRIMARY KEY); ALTER TABLE ks.t ALTER v TYPE text",
+            SchemaError::UnknownColumn {
+                keyspace: "ks".into(),
+                table: "t".into(),
+                column: "v".into(),
+            },
+        ),
+        (
+            "$ks CREATE TYPE ks.a (f int); ALTER TYPE ks.a RENAME g TO h",
+            SchemaError::UnknownField {
+                keyspace: "ks".into(),
+                name: "a".into(),
+                field: "g".into(),
+            },
+        ),
+        (
+            "$ks DROP INDEX ks.i",
+            SchemaError::UnknownIndex {
+                keyspace: "ks".into(),
+                index: "i".into(),
+            },
+        ),
+        (
+            "$ks DROP MATERIALIZED VIEW ks.v",
+            SchemaError::UnknownTable {
+                keyspace: "ks".into(),
+                table: "v".into(),
+            },
+        ),
+        (
+            "$ks CREATE TABLE ks.t (k int PRIMARY KEY, v int);
+             CREATE INDEX i ON ks.t (v); CREATE INDEX i ON ks.t (k)",
+            SchemaError::DuplicateIndex {
+                keyspace: "ks".into(),
+                index: "i".into(),
+            },
+        ),
+    ];
+    for (cql, expected) in cases {
+        let cql = cql.replace("$ks", keyspace);
+        assert_eq!(schema(&cql).unwrap_err(), expected, "applying {}", cql);
+    }
+
+    // failed statement leaves the schema unchanged
+    let mut schema = schema(
+        "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy'};
+         CREATE TABLE ks.t (k int PRIMARY KEY)",
+    )
+    .unwrap();
+    let statement = Parser::new("ALTER TABLE ks.t ADD (v int, k int)")
+        .parse()
+        .unwrap()
+        .remove(0);
+    let error = schema.apply(&statement).unwrap_err();
+    assert_eq!(error.to_string(), "Column k already exists in ks.t");
+    assert_eq!(schema.table("ks", "t").unwrap().columns().len(), 1);
+}
+
+#[test]
+fn test_schema_drop() {
+    let schema = schema(
+        "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy'};
+         CREATE KEYSPACE gone WITH replication = {'class': 'SimpleStrategy'};
+         USE ks;
+         CREATE TYPE address (street text);
+         CREATE TABLE t (k int PRIMARY KEY, v int, a frozen<address>);
+         CREATE INDEX ON t (v);
+         CREATE INDEX t_a ON t (a);
+         CREATE MATERIALIZED VIEW t_by_v AS SELECT * FROM t
+             WHERE v IS NOT NULL AND k IS NOT NULL PRIMARY KEY (v, k);
+         DROP INDEX t_v_idx;
+         DROP MATERIALIZED VIEW t_by_v;
+         DROP TABLE IF EXISTS u;
+         DROP TYPE IF EXISTS missing.address;
+         DROP KEYSPACE gone",
+    )
+    .unwrap();
+    let keyspaces: Vec<_> = schema.keyspaces().map(|k| k.name()).collect();
+    assert_eq!(keyspaces, ["ks"]);
+    let ks = schema.keyspace("ks").unwrap();
+    assert!(ks.table("t_by_v").is_none());
+    assert!(ks.index("t_v_idx").is_none());
+    assert!(ks.index("t_a").is_some());
+
+    let mut schema = schema;
+    for cql in ["DROP TABLE ks.t", "DROP TYPE ks.address"] {
+        schema.apply(&Parser::new(cql).parse().unwrap()[0]).unwrap();
+    }
+    let ks = schema.keyspace("ks").unwrap();
+    assert_eq!(ks.tables().count(), 0);
+    assert_eq!(ks.user_types().count(), 0);
+}
+
+#[test]
+fn test_schema_properties() {
+    let schema = schema(
+        "CREATE KEYSPACE ks WITH replication = {'replication_factor': 1, 'class': 'SimpleStrategy'}
+             AND durable_writes = true;
+         ALTER KEYSPACE ks WITH durable_writes = false;
+         CREATE TABLE ks.t (k int, c int, d int, PRIMARY KEY (k, c, d))
+             WITH CLUSTERING ORDER BY (c DESC) AND comment = 'a' AND gc_grace_seconds = 0;
+         ALTER TABLE ks.t WITH comment = 'b'",
+    )
+    .unwrap();
+    let properties = |properties: &[Property]| -> Vec<String> {
+        properties.iter().map(ToString::to_string).collect()
+    };
+    // properties are ordered by their names, and so are the entries of maps
+    let ks = schema.keyspace("ks").unwrap();
+    assert_eq!(
+        properties(ks.properties()),
+        [
+            "durable_writes = false",
+            "replication = {'class': 'SimpleStrategy', 'replication_factor': 1}"
+        ]
+    );
+    let table = ks.table("t").unwrap();
+    assert_eq!(
+        properties(table.properties()),
+        ["comment = 'b'", "gc_grace_seconds = 0"]
+    );
+    assert!(table.is_descending("c"));
+    assert!(!table.is_descending("d"));
+}
+
+#[test]
+fn test_schema_diff() {
+    let old = schema(
+        "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy', 'replication_factor': 1};
+         CREATE KEYSPACE old_ks WITH replication = {'class': 'SimpleStrategy'};
+         USE ks;
+         CREATE TYPE address (street text, zip int);
+         CREATE TYPE legacy (a int);
+         CREATE TABLE users (id uuid PRIMARY KEY, name text, nick text, home frozen<address>)
+             WITH comment = 'users';
+         CREATE INDEX ON users (nick);
+         CREATE TABLE events (id uuid, at timestamp, kind text, PRIMARY KEY (id, at));
+         CREATE TABLE gone (k int PRIMARY KEY, l frozen<legacy>);
+         CREATE MATERIALIZED VIEW users_by_name AS SELECT id, name FROM users
+             WHERE name IS NOT NULL AND id IS NOT NULL PRIMARY KEY (name, id)",
+    )
+    .unwrap();
+    let new = schema(
+        "CREATE KEYSPACE ks WITH replication = {'replication_factor': 3, 'class': 'SimpleStrategy'};
+         CREATE KEYSPACE new_ks WITH replication = {'class': 'SimpleStrategy'};
+         USE ks;
+         CREATE TYPE phone (number text);
+         CREATE TYPE address (street text, zip text, phones list<frozen<phone>>);
+         CREATE TABLE users (id uuid PRIMARY KEY, full_name text, nick text,
+                             home frozen<address>, email text)
+             WITH comment = 'all users' AND gc_grace_seconds = 0;
+         CREATE INDEX users_email ON users (email);
+         CREATE TABLE events (id uuid, at timestamp, kind text, PRIMARY KEY (id, at))
+             WITH CLUSTERING ORDER BY (at DESC);
+         CREATE MATERIALIZED VIEW users_by_nick AS SELECT id, nick FROM users
+             WHERE nick IS NOT NULL AND id IS NOT NULL PRIMARY KEY (nick, id);
+         CREATE TABLE new_ks.t (k int PRIMARY KEY)",
+    )
+    .unwrap();
+
+    let changes = diff(&old, &new);
+    let report: Vec<String> = changes.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        report,
+        [
+            "Property replication of keyspace ks changed from \
+             {'class': 'SimpleStrategy', 'replication_factor': 1} to \
+             {'class': 'SimpleStrategy', 'replication_factor': 3}",
+            "Index ks.users_nick_idx removed from ks.users",
+            "Materialized view ks.users_by_name removed",
+            "Table ks.gone removed",
+            "Type ks.phone added",
+            "Field zip of type ks.address changed from int to text (incompatible)",
+            "Field phones list<frozen<ks.phone>> added to type ks.address",
+            "Primary key of ks.events changed from (id, at) to (id, at DESC) (incompatible)",
+            "Column name removed from ks.users",
+            "Column full_name text added to ks.users",
+            "Column email text added to ks.users",
+            "Property comment of ks.users changed from 'users' to 'all users'",
+            "Property gc_grace_seconds of ks.users set to 0",
+            "Materialized view ks.users_by_nick added",
+            "Index ks.users_email added on ks.users (email)",
+            "Type ks.legacy removed",
+            "Keyspace new_ks added",
+            "Table new_ks.t added",
+            "Keyspace old_ks removed",
+        ]
+    );
+    assert!(matches!(
+        &changes[2],
+        SchemaChange::TableRemoved(view) if view.base_table() == Some("users")
+    ));
+
+    // statements to migrate, except for incompatible changes
+    let options = FormatOptions {
+        column_per_line: false,
+        ..FormatOptions::default()
+    };
+    let migration: Vec<String> = changes
+        .iter()
+        .filter_map(SchemaChange::to_statement)
+        .map(|statement| format_statement(&statement, &options).unwrap())
+        .collect();
+    assert_eq!(
+        migration,
+        [
+            "ALTER KEYSPACE ks WITH replication = {'class': 'SimpleStrategy', \
+             'replication_factor': 3}",
+            "DROP INDEX ks.users_nick_idx",
+            "DROP MATERIALIZED VIEW ks.users_by_name",
+            "DROP TABLE ks.gone",
+            "CREATE TYPE ks.phone (number text)",
+            "ALTER TYPE ks.address ADD phones list<frozen<ks.phone>>",
+            "ALTER TABLE ks.users DROP name",
+            "ALTER TABLE ks.users ADD full_name text",
+            "ALTER TABLE ks.users ADD email text",
+            "ALTER TABLE ks.users WITH comment = 'all users'",
+            "ALTER TABLE ks.users WITH gc_grace_seconds = 0",
+            "CREATE MATERIALIZED VIEW ks.users_by_nick AS\n    \
+             SELECT nick, id\n    \
+             FROM ks.users\n    \
+             WHERE nick IS NOT NULL AND id IS NOT NULL\n    \
+             PRIMARY KEY (nick, id)",
+            "CREATE INDEX users_email ON ks.users (email)",
+            "DROP TYPE ks.legacy",
+            "CREATE KEYSPACE new_ks WITH replication = {'class': 'SimpleStrategy'}",
+            "CREATE TABLE new_ks.t (k int, PRIMARY KEY (k))",
+            "DROP KEYSPACE old_ks",
+        ]
+    );
+
+    // applying the migration to the old schema gives the new schema, except for
+    // the incompatible changes
+    let mut migrated = old.clone();
+    for statement in changes.iter().filter_map(SchemaChange::to_statement) {
+        migrated.apply(&statement).unwrap();
+    }
+    let remaining: Vec<_> = diff(&migrated, &new)
+        .iter()
+        .map(SchemaChange::is_incompatible)
+        .collect();
+    assert_eq!(remaining, [true, true]);
+    assert!(diff(&new, &new).is_empty());
+}
+
+#[test]
+fn test_schema_diff_implicit_frozen() {
+    // as declared, and as reported by `DESCRIBE` of the server
+    let declared = schema(
+        "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy'};
+         USE ks;
+         CREATE TYPE point (x int, y int);
+         CREATE TYPE shape (origin tuple<int, int>, points list<frozen<point>>);
+         CREATE TABLE t (k int PRIMARY KEY, p tuple<int, text>, m map<text, tuple<point, int>>,
+                         l list<frozen<list<tuple<int>>>>)",
+    )
+    .unwrap();
+    let reported = schema(
+        "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy'};
+         CREATE TYPE ks.point (x int, y int);
+         CREATE TYPE ks.shape (origin frozen<tuple<int, int>>, points list<frozen<point>>);
+         CREATE TABLE ks.t (k int PRIMARY KEY, p frozen<tuple<int, text>>,
+                            m map<text, frozen<tuple<frozen<point>, int>>>,
+                            l list<frozen<list<frozen<tuple<int>>>>>)",
+    )
+    .unwrap();
+    assert_eq!(diff(&declared, &reported), []);
+
+    // frozen collections are still different from non-frozen ones
+    let changed = schema(
+        "CREATE KEYSPACE ks WITH replication = {'class': 'SimpleStrategy'};
+         CREATE TYPE ks.point (x int, y int);
+         CREATE TYPE ks.shape (origin frozen<tuple<int, int>>, points frozen<list<frozen<point>>>);
+         CREATE TABLE ks.t (k int PRIMARY KEY, p frozen<tuple<int, text>>,
+                            m frozen<map<text, frozen<tuple<frozen<point>, int>>>>,
+                            l list<frozen<list<frozen<tuple<int>>>>>)",
+    )
+    .unwrap();
+    let report: Vec<String> = diff(&declared, &changed)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        report,
+        [
+            "Field points of type ks.shape changed from list<frozen<ks.point>> \
+             to frozen<list<frozen<ks.point>>> (incompatible)",
+            "Column m of ks.t changed from map<text, tuple<ks.point, int>> \
+             to frozen<map<text, frozen<tuple<frozen<ks.point>, int>>>> (incompatible)",
+        ]
+    );
+}
@@ -0,0 +1,94 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use cqlparser::split_statements;
+
+#[test]
+fn test_split_statements() {
+    let cases: &[(&str, &[&str])] = &[
+        ("", &[]),
+        ("  \n\t", &[]),
+        ("SELECT * FROM t", &["SELECT * FROM t"]),
+        ("SELECT * FROM t;", &["SELECT * FROM t"]),
+        // trailing statement without `;`
+        (
+            "SELECT * FROM t; SELECT * FROM u",
+            &["SELECT * FROM t", "SELECT * FROM u"],
+        ),
+        ("  SELECT *\n  FROM t  ;\n", &["SELECT *\n  FROM t"]),
+        // empty statements
+        (";", &[]),
+        (
+            "SELECT * FROM t;; ;\n;SELECT * FROM u;",
+            &["SELECT * FROM t", "SELECT * FROM u"],
+        ),
+        // `;` in string literals
+        (
+            "INSERT INTO t (k) VALUES ('a;b'); SELECT * FROM t",
+            &["INSERT INTO t (k) VALUES ('a;b')", "SELECT * FROM t"],
+        ),
+        (
+            "INSERT INTO t (k) VALUES ('it''s;'); USE ks",
+            &["INSERT INTO t (k) VALUES ('it''s;')", "USE ks"],
+        ),
+        (
+            "CREATE FUNCTION f() RETURNS NULL ON NULL INPUT RETURNS int \
+             LANGUAGE java AS $$ return 1; $$; DROP FUNCTION f",
+            &[
+                "CREATE FUNCTION f() RETURNS NULL ON NULL INPUT RETURNS int \
+                 LANGUAGE java AS $$ return 1; $$",
+                "DROP FUNCTION f",
+            ],
+        ),
+        // `;` in quoted names
+        (
+            "SELECT \"a;b\" FROM t; TRUNCATE t",
+            &["SELECT \"a;b\" FROM t", "TRUNCATE t"],
+        ),
+        // `;` in comments, and comments around statements
+        (
+            "-- first; statement\nSELECT * FROM t; -- done;\n",
+            &["SELECT * FROM t"],
+        ),
+        (
+            "SELECT * /* all; columns */ FROM t; // next;\nDROP TABLE t;",
+            &["SELECT * /* all; columns */ FROM t", "DROP TABLE t"],
+        ),
+        ("/* only; a comment */", &[]),
+        // statements the parser does not support
+        (
+            "TRUNCATE ks.t; CREATE ROLE r WITH LOGIN = true;",
+            &["TRUNCATE ks.t", "CREATE ROLE r WITH LOGIN = true"],
+        ),
+        // batch
+        (
+            "BEGIN BATCH INSERT INTO t (k) VALUES (1); DELETE FROM t WHERE k = 2; APPLY BATCH; \
+             SELECT * FROM t",
+            &[
+                "BEGIN BATCH INSERT INTO t (k) VALUES (1); DELETE FROM t WHERE k = 2; APPLY BATCH",
+                "SELECT * FROM t",
+            ],
+        ),
+        (
+            "begin unlogged batch update t set v = 1 where k = 1; apply batch",
+            &["begin unlogged batch update t set v = 1 where k = 1; apply batch"],
+        ),
+        // unterminated string literal runs to the end
+        (
+            "SELECT * FROM t; SELECT 'a; SELECT * FROM u;",
+            &["SELECT * FROM t", "SELECT 'a; SELECT * FROM u;"],
+        ),
+    ];
+    for (cql, expected) in cases {
+        assert_eq!(&split_statements(cql), expected, "splitting {:?}", cql);
+    }
+}
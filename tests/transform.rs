@@ -0,0 +1,116 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use cqlparser::ast::transform::parameterize;
+use cqlparser::ast::visit::{walk_expression_mut, VisitorMut};
+use cqlparser::ast::*;
+use cqlparser::Parser;
+
+fn binding() -> Expression {
+    ExpressionKind::Value(Literal::Binding(None)).into()
+}
+
+#[test]
+fn test_parameterize() {
+    let mut statements = Parser::new("SELECT * FROM tbl WHERE k = 5 AND v = 'x' LIMIT 10")
+        .parse()
+        .unwrap();
+    parameterize(&mut statements[0]);
+    assert_eq!(
+        statements,
+        vec![CqlStatement::Select(SelectStatement {
+            table_name: QualifiedName::new(None, String::from("tbl")),
+            projection: Projection::Wildcard,
+            selection: Some(WhereClause::new(vec![
+                Relation::SingleColumn {
+                    column: "k".into(),
+                    operator: Operator::Equal,
+                    value: binding(),
+                },
+                Relation::SingleColumn {
+                    column: "v".into(),
+                    operator: Operator::Equal,
+                    value: binding(),
+                },
+            ])),
+            group_by: vec![],
+            is_json: false,
+            is_distinct: false,
+            per_partition_limit: None,
+            limit: Some(Literal::Binding(None)),
+            allow_filtering: false,
+            span: Span::default(),
+        })]
+    );
+}
+
+#[test]
+fn test_parameterize_nested_literals() {
+    let mut statements = Parser::new("UPDATE tbl SET t = (1, (2, 'x')) WHERE k = 1")
+        .parse()
+        .unwrap();
+    parameterize(&mut statements[0]);
+    match &statements[0] {
+        CqlStatement::Update(update) => assert_eq!(
+            update.assignments,
+            vec![ExpressionKind::BinaryOp(BinaryOp::new(
+                Box::new(Expression::new(ExpressionKind::Identifier("t".into()))),
+                Operator::Equal,
+                Box::new(Expression::new(ExpressionKind::Value(Literal::Tuple(
+                    vec![
+                        ExpressionKind::Value(Literal::Binding(None)).into(),
+                        ExpressionKind::Value(Literal::Tuple(vec![
+                            ExpressionKind::Value(Literal::Binding(None)).into(),
+                            ExpressionKind::Value(Literal::Binding(None)).into(),
+                        ]))
+                        .into(),
+                    ]
+                )))),
+            ))
+            .into()]
+        ),
+        _ => panic!("UPDATE statement expected"),
+    }
+}
+
+/// Renames all identifiers into upper case
+struct Renamer;
+
+impl VisitorMut for Renamer {
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        if let ExpressionKind::Identifier(name) = &mut expression.kind {
+            *name = name.to_uppercase().into();
+        }
+        walk_expression_mut(self, expression);
+    }
+}
+
+#[test]
+fn test_visitor_mut_replaces_nodes() {
+    let mut statements = Parser::new("SELECT a + b FROM tbl").parse().unwrap();
+    Renamer.visit_statement_mut(&mut statements[0]);
+    match &statements[0] {
+        CqlStatement::Select(select) => assert_eq!(
+            select.projection,
+            Projection::Selectors(vec![Selector::new(
+                ExpressionKind::BinaryOp(BinaryOp::new(
+                    Box::new(Expression::new(ExpressionKind::Identifier("A".into()))),
+                    Operator::Plus,
+                    Box::new(Expression::new(ExpressionKind::Identifier("B".into()))),
+                ))
+                .into(),
+                None,
+            )])
+        ),
+        _ => panic!("SELECT statement expected"),
+    }
+}
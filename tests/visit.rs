@@ -0,0 +1,187 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use cqlparser::ast::visit::*;
+use cqlparser::ast::*;
+use cqlparser::Parser;
+
+/// Records every visited node in the visiting order
+#[derive(Default)]
+struct Recorder {
+    visited: Vec<String>,
+}
+
+impl Visitor for Recorder {
+    fn visit_expression(&mut self, expression: &Expression) {
+        let node = match &expression.kind {
+            ExpressionKind::Identifier(name) => format!("ident:{}", name),
+            ExpressionKind::UnaryOp(_) => "unary".to_owned(),
+            ExpressionKind::BinaryOp(_) => "binary".to_owned(),
+            ExpressionKind::Value(_) => "value".to_owned(),
+            ExpressionKind::Function { .. } => "function".to_owned(),
+            ExpressionKind::Cast(_, _) => "cast".to_owned(),
+            ExpressionKind::TypeCast(_, _) => "hint".to_owned(),
+            ExpressionKind::CollectionSubSelection { .. } => "subselection".to_owned(),
+        };
+        self.visited.push(node);
+        walk_expression(self, expression);
+    }
+
+    fn visit_literal(&mut self, literal: &Literal) {
+        let node = match literal {
+            Literal::Constant(Constant::Integer(i)) => format!("int:{}", i),
+            Literal::Constant(Constant::StringLiteral(s)) => format!("string:{}", s),
+            Literal::Constant(_) => "constant".to_owned(),
+            Literal::Null => "null".to_owned(),
+            Literal::Map(_) => "map".to_owned(),
+            Literal::Tuple(_) => "tuple".to_owned(),
+            _ => "literal".to_owned(),
+        };
+        self.visited.push(node);
+        walk_literal(self, literal);
+    }
+
+    fn visit_type(&mut self, cql_type: &CqlType) {
+        let node = match cql_type {
+            CqlType::Native(nt) => format!("type:{:?}", nt).to_ascii_lowercase(),
+            CqlType::Collection(_) => "type:collection".to_owned(),
+            CqlType::Tuple(_) => "type:tuple".to_owned(),
+            CqlType::Frozen(_) => "type:frozen".to_owned(),
+            _ => "type".to_owned(),
+        };
+        self.visited.push(node);
+        walk_type(self, cql_type);
+    }
+
+    fn visit_relation(&mut self, relation: &Relation) {
+        let columns = relation.columns().collect::<Vec<_>>();
+        self.visited.push(format!("relation:{}", columns.join(",")));
+        walk_relation(self, relation);
+    }
+
+    fn visit_column(&mut self, column: &Identifier) {
+        self.visited.push(format!("column:{}", column));
+    }
+
+    fn visit_qualified_name(&mut self, name: &QualifiedName) {
+        self.visited.push(format!("name:{}", name.name));
+    }
+
+    fn visit_property(&mut self, property: &Property) {
+        self.visited.push(format!("property:{}", property.key()));
+    }
+}
+
+fn record(cql: &str) -> Vec<String> {
+    let statements = Parser::new(cql).parse().unwrap();
+    let mut recorder = Recorder::default();
+    for statement in &statements {
+        recorder.visit_statement(statement);
+    }
+    recorder.visited
+}
+
+#[test]
+fn test_visit_select() {
+    assert_eq!(
+        record(
+            "SELECT a, cast(b as int) FROM ks.tbl WHERE c = (1, 'x') AND d > -2 AND e < -f LIMIT 10"
+        ),
+        vec![
+            "ident:a",
+            "cast",
+            "type:int",
+            "ident:b",
+            "name:tbl",
+            "relation:c",
+            "column:c",
+            "value",
+            "tuple",
+            "value",
+            "int:1",
+            "value",
+            "string:x",
+            "relation:d",
+            "column:d",
+            "value",
+            "int:-2",
+            "relation:e",
+            "column:e",
+            "unary",
+            "ident:f",
+            "int:10",
+        ]
+    );
+}
+
+#[test]
+fn test_visit_relation_columns() {
+    assert_eq!(
+        record("SELECT * FROM tbl WHERE token(a, b) > 0 AND (c, d) = (1, 2) AND expr(i, 'x')"),
+        vec![
+            "name:tbl",
+            "relation:a,b",
+            "column:a",
+            "column:b",
+            "value",
+            "int:0",
+            "relation:c,d",
+            "column:c",
+            "column:d",
+            "value",
+            "tuple",
+            "value",
+            "int:1",
+            "value",
+            "int:2",
+            "relation:",
+            "value",
+            "string:x",
+        ]
+    );
+}
+
+#[test]
+fn test_visit_function_arguments() {
+    assert_eq!(
+        record("SELECT func(a, 1 + b) FROM tbl"),
+        vec![
+            "function",
+            "name:func",
+            "ident:a",
+            "binary",
+            "value",
+            "int:1",
+            "ident:b",
+            "name:tbl",
+        ]
+    );
+}
+
+#[test]
+fn test_visit_create_table() {
+    assert_eq!(
+        record(
+            "CREATE TABLE tbl (k int PRIMARY KEY, v frozen<map<text, int>>)
+             WITH caching = {'keys': 'ALL'}"
+        ),
+        vec![
+            "name:tbl",
+            "type:int",
+            "type:frozen",
+            "type:collection",
+            "type:text",
+            "type:int",
+            "property:caching",
+        ]
+    );
+}
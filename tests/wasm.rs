@@ -0,0 +1,177 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests of the JavaScript bindings, run with `wasm-pack test --node`
+#![cfg(target_arch = "wasm32")]
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use cqlparser::{parse, parse_with_options, tokenize, Parser};
+
+/// The shape of errors thrown to JavaScript
+#[derive(Debug, Deserialize, PartialEq)]
+struct Error {
+    message: String,
+    expected: Vec<String>,
+    found: Option<String>,
+    offset: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LenientResult {
+    statements: Vec<serde::de::IgnoredAny>,
+    errors: Vec<Error>,
+}
+
+#[derive(Serialize)]
+struct Options {
+    lenient: bool,
+    version: Option<&'static str>,
+    preserve_comments: bool,
+}
+
+fn options(lenient: bool, version: Option<&'static str>) -> JsValue {
+    let options = Options {
+        lenient,
+        version,
+        preserve_comments: false,
+    };
+    serde_wasm_bindgen::to_value(&options).unwrap()
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Token {
+    #[serde(rename = "type")]
+    token_type: String,
+    value: Option<String>,
+    start: usize,
+    length: usize,
+}
+
+fn token(token_type: &str, value: Option<&str>, start: usize, length: usize) -> Token {
+    Token {
+        token_type: token_type.to_owned(),
+        value: value.map(str::to_owned),
+        start,
+        length,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Spanned {
+    leading_comments: Vec<String>,
+    trailing_comment: Option<String>,
+}
+
+#[wasm_bindgen_test]
+fn test_parse_error() {
+    let error = parse("SELECT * FROM t;\nSELECT FROM t").unwrap_err();
+    let error: Error = serde_wasm_bindgen::from_value(error).unwrap();
+    assert_eq!(
+        error,
+        Error {
+            message: "expected selector or '*', found 'FROM'".to_owned(),
+            expected: vec!["selector".to_owned(), "'*'".to_owned()],
+            found: Some("FROM".to_owned()),
+            offset: Some(24),
+            line: Some(2),
+            column: Some(8),
+        }
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_parse_with_options() {
+    let cql = "SELECT * FROM t1; SELECT FROM t; SELECT * FROM t2";
+    assert!(parse_with_options(cql, JsValue::UNDEFINED).is_err());
+
+    let result = parse_with_options(cql, options(true, None)).unwrap();
+    let result: LenientResult = serde_wasm_bindgen::from_value(result).unwrap();
+    assert_eq!(result.statements.len(), 2);
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.errors[0].column, Some(26));
+
+    let cql = "SELECT * FROM t PER PARTITION LIMIT 1";
+    assert!(parse_with_options(cql, options(false, Some("3.6"))).is_ok());
+    let error = parse_with_options(cql, options(false, Some("3.0"))).unwrap_err();
+    let error: Error = serde_wasm_bindgen::from_value(error).unwrap();
+    assert_eq!(
+        error.message,
+        "PER PARTITION LIMIT is not supported in Cassandra 3.0"
+    );
+    assert_eq!(error.line, Some(1));
+
+    let error = parse_with_options(cql, options(false, Some("9.9"))).unwrap_err();
+    let error: Error = serde_wasm_bindgen::from_value(error).unwrap();
+    assert_eq!(error.message, "Unknown CQL version: 9.9");
+}
+
+#[wasm_bindgen_test]
+fn test_parse_preserving_comments() {
+    let options = Options {
+        lenient: false,
+        version: None,
+        preserve_comments: true,
+    };
+    let options = serde_wasm_bindgen::to_value(&options).unwrap();
+    let result = parse_with_options("-- users\nSELECT * FROM users; -- all", options).unwrap();
+    let statements: Vec<Spanned> = serde_wasm_bindgen::from_value(result).unwrap();
+    assert_eq!(statements[0].leading_comments, ["-- users"]);
+    assert_eq!(statements[0].trailing_comment.as_deref(), Some("-- all"));
+}
+
+#[wasm_bindgen_test]
+fn test_tokenize() {
+    let tokens = tokenize("select * FROM t WHERE k = 'é' -- ü").unwrap();
+    let tokens: Vec<Token> = serde_wasm_bindgen::from_value(tokens).unwrap();
+    assert_eq!(
+        tokens,
+        vec![
+            token("keyword", Some("select"), 0, 6),
+            token("whitespace", None, 6, 1),
+            token("operator", None, 7, 1),
+            token("whitespace", None, 8, 1),
+            token("keyword", Some("from"), 9, 4),
+            token("whitespace", None, 13, 1),
+            token("identifier", None, 14, 1),
+            token("whitespace", None, 15, 1),
+            token("keyword", Some("where"), 16, 5),
+            token("whitespace", None, 21, 1),
+            token("identifier", None, 22, 1),
+            token("whitespace", None, 23, 1),
+            token("operator", None, 24, 1),
+            token("whitespace", None, 25, 1),
+            // offsets are in UTF-16 code units
+            token("string", None, 26, 3),
+            token("whitespace", None, 29, 1),
+            token("comment", None, 30, 4),
+        ]
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_ast_json_snapshot() {
+    // JavaScript consumers depend on this shape, so changes to the snapshot
+    // must be deliberate. See "JSON representation" of `cqlparser::ast`.
+    let cql = "SELECT JSON k, v AS value FROM ks.t WHERE k = ? LIMIT 10; \
+               INSERT INTO ks.t JSON '{\"k\": 1}' DEFAULT UNSET";
+    let statements = Parser::new(cql).parse().unwrap();
+    assert_eq!(
+        serde_json::to_string_pretty(&statements).unwrap(),
+        include_str!("wasm/statements.json").trim_end()
+    );
+}